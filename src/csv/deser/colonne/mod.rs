@@ -0,0 +1,173 @@
+// SPDX-License-Identifier: GPL-3.0-only
+/*
+    Copyright (C) 2024-2026 jgabaut, gioninjo
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU General Public License as published by
+    the Free Software Foundation, version 3 of the License.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU General Public License for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use crate::csv::TipoRecordCsv;
+use crate::error::{ContestoErrore, Error};
+use crate::i18n::Messaggio;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Alias predefiniti per i nomi di colonna (oltre alle varianti di maiuscole, `_`, `-` e spazi)
+const ALIAS_PREDEFINITI: [(&str, &str); 10] = [
+    ("specie", "codiceSpecie"),
+    ("cod", "codiceSpecie"),
+    ("codice", "codiceSpecie"),
+    ("passaggio", "numPassaggio"),
+    ("nPassaggio", "numPassaggio"),
+    ("lt", "lunghezza"),
+    ("lunghezzaTotale", "lunghezza"),
+    ("pesoGrammi", "peso"),
+    ("individui", "numeroIndividui"),
+    ("dataCampionamento", "data"),
+];
+
+/// Forma canonica di un nome di colonna usata per i confronti:
+/// minuscolo, senza spazi, `_` e `-`
+pub fn normalizza_nome_colonna(nome: &str) -> String {
+    nome.trim()
+        .trim_start_matches('\u{feff}')
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '_' && *c != '-')
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Nomi alternativi accettati per le colonne dei file di input
+#[derive(Debug, Clone, PartialEq)]
+pub struct AliasColonne {
+    alias: HashMap<String, String>,
+}
+
+impl Default for AliasColonne {
+    /// Alias predefiniti
+    fn default() -> Self {
+        ALIAS_PREDEFINITI
+            .iter()
+            .fold(Self::vuoto(), |a, (alias, campo)| a.con_alias(alias, campo))
+    }
+}
+
+impl AliasColonne {
+    /// Nessun alias: solo i nomi di colonna standard (senza distinzione di maiuscole)
+    pub fn vuoto() -> Self {
+        Self {
+            alias: HashMap::new(),
+        }
+    }
+
+    /// Accetta `alias` come nome alternativo della colonna standard `campo` (es. "codiceSpecie")
+    pub fn con_alias(mut self, alias: &str, campo: &str) -> Self {
+        self.alias
+            .insert(normalizza_nome_colonna(alias), campo.to_string());
+        self
+    }
+
+    /// Nome della colonna standard corrispondente a un'intestazione, se è un alias noto
+    pub fn risolvi(&self, intestazione: &str) -> Option<&str> {
+        self.alias
+            .get(&normalizza_nome_colonna(intestazione))
+            .map(|s| s.as_str())
+    }
+}
+
+/// Corrispondenza tra le colonne standard di un tipo di file e le colonne effettivamente presenti
+#[derive(Debug, Clone, PartialEq)]
+pub struct MappaturaColonne {
+    tipo_csv: TipoRecordCsv,
+    /// Per ogni colonna standard, l'indice della colonna nel file (se presente)
+    indici: Arc<[Option<usize>]>,
+    /// Colonne non vuote del file che non corrispondono a nessuna colonna standard
+    pub ignorate: Vec<String>,
+}
+
+impl MappaturaColonne {
+    pub fn new(tipo_csv: TipoRecordCsv, intestazione: &[&str], alias: &AliasColonne) -> Self {
+        let campi = tipo_csv.header_fields();
+        let mut indici = vec![None; campi.len()];
+        let mut ignorate = Vec::new();
+        for (j, colonna) in intestazione.iter().enumerate() {
+            let nome = normalizza_nome_colonna(colonna);
+            if nome.is_empty() {
+                continue;
+            }
+            let trovato = campi
+                .iter()
+                .position(|c| normalizza_nome_colonna(c) == nome)
+                .or_else(|| {
+                    alias
+                        .risolvi(colonna)
+                        .and_then(|a| campi.iter().position(|c| *c == a))
+                });
+            match trovato {
+                // In caso di colonne ripetute vale la prima
+                Some(i) if indici[i].is_none() => indici[i] = Some(j),
+                _ => ignorate.push(colonna.trim().to_string()),
+            }
+        }
+        Self {
+            tipo_csv,
+            indici: indici.into(),
+            ignorate,
+        }
+    }
+
+    /// Colonne standard assenti nel file
+    pub fn mancanti(&self) -> Vec<&'static str> {
+        self.tipo_csv
+            .header_fields()
+            .iter()
+            .zip(self.indici.iter())
+            .filter(|(_, i)| i.is_none())
+            .map(|(c, _)| *c)
+            .collect()
+    }
+
+    /// Per ogni colonna standard, l'indice (da 0) della colonna nel file
+    pub fn indici(&self) -> Arc<[Option<usize>]> {
+        Arc::clone(&self.indici)
+    }
+
+    pub fn is_completa(&self) -> bool {
+        self.indici.iter().all(|i| i.is_some())
+    }
+
+    /// Un errore per ogni colonna standard assente
+    pub fn errori(&self) -> Vec<Error> {
+        self.mancanti()
+            .into_iter()
+            .map(|c| {
+                Error::ColonnaMancante(
+                    ContestoErrore::da_messaggio(Messaggio::ColonnaMancante)
+                        .tipo_csv(self.tipo_csv)
+                        .campo(c)
+                        .into(),
+                )
+            })
+            .collect()
+    }
+
+    /// Riordina i campi di un record secondo le colonne standard, scartando quelle ignorate
+    pub fn riordina(&self, record: &csv::StringRecord) -> csv::StringRecord {
+        let mut riordinato =
+            csv::StringRecord::with_capacity(record.as_slice().len(), self.indici.len());
+        for i in self.indici.iter() {
+            riordinato.push_field(i.and_then(|i| record.get(i)).unwrap_or(""));
+        }
+        riordinato.set_position(record.position().cloned());
+        riordinato
+    }
+}
@@ -0,0 +1,244 @@
+// SPDX-License-Identifier: GPL-3.0-only
+/*
+    Copyright (C) 2024-2026 jgabaut, gioninjo
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU General Public License as published by
+    the Free Software Foundation, version 3 of the License.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU General Public License for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use encoding_rs::{Decoder, Encoding, UTF_16BE, UTF_16LE, UTF_8, WINDOWS_1252};
+use std::fmt;
+use std::io::{self, Read};
+
+/// Quanti byte vengono esaminati per riconoscere la codifica
+const CAMPIONE_BYTES: usize = 16 * 1024;
+const BUFFER_BYTES: usize = 8 * 1024;
+
+/// Codifica dei caratteri di un file di input
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CodificaTesto {
+    Utf8,
+    Utf8Bom,
+    Utf16Le,
+    Utf16Be,
+    /// Windows-1252, compatibile con Latin-1 per i caratteri stampabili
+    Windows1252,
+}
+
+impl CodificaTesto {
+    fn encoding(&self) -> &'static Encoding {
+        match self {
+            CodificaTesto::Utf8 | CodificaTesto::Utf8Bom => UTF_8,
+            CodificaTesto::Utf16Le => UTF_16LE,
+            CodificaTesto::Utf16Be => UTF_16BE,
+            CodificaTesto::Windows1252 => WINDOWS_1252,
+        }
+    }
+}
+
+impl fmt::Display for CodificaTesto {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let string_representation = match self {
+            CodificaTesto::Utf8 => "UTF-8",
+            CodificaTesto::Utf8Bom => "UTF-8 (BOM)",
+            CodificaTesto::Utf16Le => "UTF-16LE",
+            CodificaTesto::Utf16Be => "UTF-16BE",
+            CodificaTesto::Windows1252 => "Windows-1252",
+        };
+        write!(f, "{}", string_representation)
+    }
+}
+
+/// Riconosce la codifica dall'inizio del file.
+/// In assenza di BOM, UTF-16 viene dedotto dai byte nulli (i file csv sono quasi solo ASCII),
+/// e tutto ciò che non è UTF-8 valido viene trattato come Windows-1252.
+pub fn rileva_codifica(campione: &[u8]) -> CodificaTesto {
+    if campione.starts_with(&[0xEF, 0xBB, 0xBF]) {
+        return CodificaTesto::Utf8Bom;
+    }
+    if campione.starts_with(&[0xFF, 0xFE]) {
+        return CodificaTesto::Utf16Le;
+    }
+    if campione.starts_with(&[0xFE, 0xFF]) {
+        return CodificaTesto::Utf16Be;
+    }
+
+    let nulli_pari = campione.iter().step_by(2).filter(|b| **b == 0).count();
+    let nulli_dispari = campione
+        .iter()
+        .skip(1)
+        .step_by(2)
+        .filter(|b| **b == 0)
+        .count();
+    let coppie = campione.len() / 2;
+    if coppie > 0 && nulli_dispari * 2 > coppie && nulli_pari == 0 {
+        return CodificaTesto::Utf16Le;
+    }
+    if coppie > 0 && nulli_pari * 2 > coppie && nulli_dispari == 0 {
+        return CodificaTesto::Utf16Be;
+    }
+
+    match std::str::from_utf8(campione) {
+        Ok(_) => CodificaTesto::Utf8,
+        // Sequenza troncata alla fine del campione: se il resto del file non è UTF-8 valido,
+        // Utf8Reader passa a Windows-1252
+        Err(e) if e.error_len().is_none() => CodificaTesto::Utf8,
+        Err(_) => CodificaTesto::Windows1252,
+    }
+}
+
+/// Decodifica un intero buffer in UTF-8, rimuovendo l'eventuale BOM.
+/// La codifica viene riconosciuta dall'intero buffer.
+pub fn decodifica(bytes: &[u8]) -> (String, CodificaTesto) {
+    let codifica = rileva_codifica(bytes);
+    let (testo, _) = codifica.encoding().decode_with_bom_removal(bytes);
+    (testo.into_owned(), codifica)
+}
+
+/// Reader che riconosce la codifica dell'input e lo restituisce transcodificato in UTF-8, senza BOM.
+/// La codifica viene riconosciuta dai primi byte; se un file riconosciuto come UTF-8 (senza BOM)
+/// contiene più avanti una sequenza non valida, da quel punto in poi viene letto come Windows-1252.
+/// Nelle altre codifiche le sequenze non valide vengono sostituite con U+FFFD.
+pub struct Utf8Reader<R: Read> {
+    inner: R,
+    decoder: Option<Decoder>,
+    codifica: Option<CodificaTesto>,
+    input: Vec<u8>,
+    input_pos: usize,
+    output: Vec<u8>,
+    output_pos: usize,
+    eof: bool,
+    finito: bool,
+    /// L'input pendente termina con una sequenza UTF-8 incompleta
+    incompleto: bool,
+}
+
+impl<R: Read> Utf8Reader<R> {
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            decoder: None,
+            codifica: None,
+            input: Vec::new(),
+            input_pos: 0,
+            output: Vec::new(),
+            output_pos: 0,
+            eof: false,
+            finito: false,
+            incompleto: false,
+        }
+    }
+
+    /// Codifica riconosciuta, disponibile dopo la prima lettura.
+    /// Diventa Windows-1252 se il file, riconosciuto come UTF-8, si rivela non esserlo.
+    pub fn codifica(&self) -> Option<CodificaTesto> {
+        self.codifica
+    }
+
+    fn riempi_input(&mut self, quanti: usize) -> io::Result<()> {
+        self.input.drain(..self.input_pos);
+        self.input_pos = 0;
+        let mut letti = 0;
+        while letti < quanti && !self.eof {
+            let start = self.input.len();
+            self.input.resize(start + BUFFER_BYTES, 0);
+            let n = match self.inner.read(&mut self.input[start..]) {
+                Ok(n) => n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {
+                    self.input.truncate(start);
+                    continue;
+                }
+                Err(e) => {
+                    self.input.truncate(start);
+                    return Err(e);
+                }
+            };
+            self.input.truncate(start + n);
+            if n == 0 {
+                self.eof = true;
+            }
+            letti += n;
+        }
+        Ok(())
+    }
+
+    fn decoder(&mut self) -> io::Result<&mut Decoder> {
+        if self.decoder.is_none() {
+            self.riempi_input(CAMPIONE_BYTES)?;
+            let codifica = rileva_codifica(&self.input);
+            self.codifica = Some(codifica);
+            self.decoder = Some(codifica.encoding().new_decoder_with_bom_removal());
+        }
+        Ok(self.decoder.as_mut().expect("decoder initialized above"))
+    }
+
+    /// Copia l'input UTF-8 valido così com'è. Alla prima sequenza non valida passa a Windows-1252,
+    /// che decodifica l'input a partire da quella sequenza.
+    fn copia_utf8(&mut self) {
+        let pendenti = &self.input[self.input_pos..];
+        let (validi, malformato) = match std::str::from_utf8(pendenti) {
+            Ok(_) => (pendenti.len(), false),
+            // sequenza troncata: servono altri byte per deciderla
+            Err(e) if e.error_len().is_none() && !self.eof => (e.valid_up_to(), false),
+            Err(e) => (e.valid_up_to(), true),
+        };
+        self.output.clear();
+        self.output.extend_from_slice(&pendenti[..validi]);
+        self.output_pos = 0;
+        self.input_pos += validi;
+        self.incompleto = !malformato && self.input_pos < self.input.len();
+        if malformato {
+            self.codifica = Some(CodificaTesto::Windows1252);
+            self.decoder = Some(WINDOWS_1252.new_decoder_without_bom_handling());
+        } else if self.eof && self.input_pos == self.input.len() {
+            self.finito = true;
+        }
+    }
+}
+
+impl<R: Read> Read for Utf8Reader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.decoder()?;
+        while self.output_pos == self.output.len() {
+            if self.finito {
+                return Ok(0);
+            }
+            if self.input_pos == self.input.len() || self.incompleto {
+                self.riempi_input(1)?;
+            }
+            if self.codifica == Some(CodificaTesto::Utf8) {
+                self.copia_utf8();
+                continue;
+            }
+            let decoder = self.decoder.as_mut().expect("decoder initialized above");
+            let pendenti = self.input.len() - self.input_pos;
+            let capienza = decoder
+                .max_utf8_buffer_length(pendenti)
+                .unwrap_or(pendenti * 3 + 16)
+                .max(16);
+            self.output.clear();
+            self.output.resize(capienza, 0);
+            self.output_pos = 0;
+            let (_, letti, scritti, _) =
+                decoder.decode_to_utf8(&self.input[self.input_pos..], &mut self.output, self.eof);
+            self.input_pos += letti;
+            self.output.truncate(scritti);
+            if self.eof && self.input_pos == self.input.len() {
+                self.finito = true;
+            }
+        }
+        let n = buf.len().min(self.output.len() - self.output_pos);
+        buf[..n].copy_from_slice(&self.output[self.output_pos..self.output_pos + n]);
+        self.output_pos += n;
+        Ok(n)
+    }
+}
@@ -15,15 +15,395 @@
     along with this program.  If not, see <https://www.gnu.org/licenses/>.
 */
 
-use crate::csv::deser::{
-    check_path_is_file_ends_with_csv, deserialize_comma_f32, process_csv_errors, NormalizerReader,
+use crate::csv::deser::{deserialize_comma_f32, leggi_csv, OpzioniLettura, RecordCsvLeggibile};
+use crate::csv::{
+    RecordCsvAnagraficaHFBI, RecordCsvCampionamentoHFBI, RecordCsvCondizioniRiferimentoHFBI,
+    RecordCsvRiferimentoHFBI, RecordCsvSorgente, RigaSorgente, TipoRecordCsv,
 };
-use crate::csv::{RecordCsvAnagraficaHFBI, RecordCsvCampionamentoHFBI, TipoRecordCsv};
-use std::any::TypeId;
+use crate::diagnostics::Diagnostics;
 use std::fmt;
-use std::fs::File;
-use std::io::{Error, Read};
-use std::path::PathBuf;
+use std::io::Read;
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VeryItalianRecordCsvRiferimentoHFBI {
+    pub nome_comune: String,
+    pub codice_specie: String,
+    pub autoctono: u32,
+    pub gruppo_eco: u32,
+    #[serde(deserialize_with = "deserialize_comma_f32")]
+    pub microbentivori: f32,
+    #[serde(deserialize_with = "deserialize_comma_f32")]
+    pub macrobentivori: f32,
+    #[serde(deserialize_with = "deserialize_comma_f32")]
+    pub iperbentivori: f32,
+    #[serde(deserialize_with = "deserialize_comma_f32")]
+    pub erbivori: f32,
+    #[serde(deserialize_with = "deserialize_comma_f32")]
+    pub detritivori: f32,
+    #[serde(deserialize_with = "deserialize_comma_f32")]
+    pub planctivori: f32,
+    #[serde(deserialize_with = "deserialize_comma_f32")]
+    pub onnivori: f32,
+    #[serde(skip)]
+    pub riga_sorgente: Option<RigaSorgente>,
+}
+
+impl RecordCsvSorgente for VeryItalianRecordCsvRiferimentoHFBI {
+    fn riga_sorgente(&self) -> Option<&RigaSorgente> {
+        self.riga_sorgente.as_ref()
+    }
+    fn set_riga_sorgente(&mut self, riga: RigaSorgente) {
+        self.riga_sorgente = Some(riga);
+    }
+}
+
+impl RecordCsvRiferimentoHFBI for VeryItalianRecordCsvRiferimentoHFBI {
+    fn nome_comune(&self) -> String {
+        self.nome_comune.clone()
+    }
+    fn codice_specie(&self) -> String {
+        self.codice_specie.clone()
+    }
+    fn autoctono(&self) -> u32 {
+        self.autoctono
+    }
+    fn gruppo_eco(&self) -> u32 {
+        self.gruppo_eco
+    }
+    fn microbentivori(&self) -> f32 {
+        self.microbentivori
+    }
+    fn macrobentivori(&self) -> f32 {
+        self.macrobentivori
+    }
+    fn iperbentivori(&self) -> f32 {
+        self.iperbentivori
+    }
+    fn erbivori(&self) -> f32 {
+        self.erbivori
+    }
+    fn detritivori(&self) -> f32 {
+        self.detritivori
+    }
+    fn planctivori(&self) -> f32 {
+        self.planctivori
+    }
+    fn onnivori(&self) -> f32 {
+        self.onnivori
+    }
+}
+
+impl fmt::Display for VeryItalianRecordCsvRiferimentoHFBI {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let string_representation = format!(
+            "RecordCsvRiferimentoHFBI: {{ nome_comune: [{}], codice_specie: [{}], autoctono: [{}], gruppo_eco: [{}], microbentivori: [{}], macrobentivori: [{}], iperbentivori: [{}], erbivori: [{}], detritivori: [{}], planctivori: [{}], onnivori: [{}] }}",
+              self.nome_comune, self.codice_specie, self.autoctono, self.gruppo_eco,
+              self.microbentivori, self.macrobentivori, self.iperbentivori, self.erbivori,
+              self.detritivori, self.planctivori, self.onnivori
+        );
+        write!(f, "{}", string_representation)
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlainRecordCsvRiferimentoHFBI {
+    pub nome_comune: String,
+    pub codice_specie: String,
+    pub autoctono: u32,
+    pub gruppo_eco: u32,
+    pub microbentivori: f32,
+    pub macrobentivori: f32,
+    pub iperbentivori: f32,
+    pub erbivori: f32,
+    pub detritivori: f32,
+    pub planctivori: f32,
+    pub onnivori: f32,
+    #[serde(skip)]
+    pub riga_sorgente: Option<RigaSorgente>,
+}
+
+impl RecordCsvSorgente for PlainRecordCsvRiferimentoHFBI {
+    fn riga_sorgente(&self) -> Option<&RigaSorgente> {
+        self.riga_sorgente.as_ref()
+    }
+    fn set_riga_sorgente(&mut self, riga: RigaSorgente) {
+        self.riga_sorgente = Some(riga);
+    }
+}
+
+impl From<VeryItalianRecordCsvRiferimentoHFBI> for PlainRecordCsvRiferimentoHFBI {
+    fn from(r: VeryItalianRecordCsvRiferimentoHFBI) -> Self {
+        Self {
+            nome_comune: r.nome_comune,
+            codice_specie: r.codice_specie,
+            autoctono: r.autoctono,
+            gruppo_eco: r.gruppo_eco,
+            microbentivori: r.microbentivori,
+            macrobentivori: r.macrobentivori,
+            iperbentivori: r.iperbentivori,
+            erbivori: r.erbivori,
+            detritivori: r.detritivori,
+            planctivori: r.planctivori,
+            onnivori: r.onnivori,
+            riga_sorgente: r.riga_sorgente,
+        }
+    }
+}
+
+impl RecordCsvRiferimentoHFBI for PlainRecordCsvRiferimentoHFBI {
+    fn nome_comune(&self) -> String {
+        self.nome_comune.clone()
+    }
+    fn codice_specie(&self) -> String {
+        self.codice_specie.clone()
+    }
+    fn autoctono(&self) -> u32 {
+        self.autoctono
+    }
+    fn gruppo_eco(&self) -> u32 {
+        self.gruppo_eco
+    }
+    fn microbentivori(&self) -> f32 {
+        self.microbentivori
+    }
+    fn macrobentivori(&self) -> f32 {
+        self.macrobentivori
+    }
+    fn iperbentivori(&self) -> f32 {
+        self.iperbentivori
+    }
+    fn erbivori(&self) -> f32 {
+        self.erbivori
+    }
+    fn detritivori(&self) -> f32 {
+        self.detritivori
+    }
+    fn planctivori(&self) -> f32 {
+        self.planctivori
+    }
+    fn onnivori(&self) -> f32 {
+        self.onnivori
+    }
+}
+
+impl fmt::Display for PlainRecordCsvRiferimentoHFBI {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let string_representation = format!(
+            "RecordCsvRiferimentoHFBI: {{ nome_comune: [{}], codice_specie: [{}], autoctono: [{}], gruppo_eco: [{}], microbentivori: [{}], macrobentivori: [{}], iperbentivori: [{}], erbivori: [{}], detritivori: [{}], planctivori: [{}], onnivori: [{}] }}",
+              self.nome_comune, self.codice_specie, self.autoctono, self.gruppo_eco,
+              self.microbentivori, self.macrobentivori, self.iperbentivori, self.erbivori,
+              self.detritivori, self.planctivori, self.onnivori
+        );
+        write!(f, "{}", string_representation)
+    }
+}
+
+impl RecordCsvLeggibile for VeryItalianRecordCsvRiferimentoHFBI {
+    const TIPO_CSV: TipoRecordCsv = TipoRecordCsv::RiferimentoHFBI;
+    const DELIMITATORE: u8 = b';';
+    type VirgolaDecimale = Self;
+}
+
+impl RecordCsvLeggibile for PlainRecordCsvRiferimentoHFBI {
+    const TIPO_CSV: TipoRecordCsv = TipoRecordCsv::RiferimentoHFBI;
+    const DELIMITATORE: u8 = b',';
+    type VirgolaDecimale = VeryItalianRecordCsvRiferimentoHFBI;
+}
+
+/// Legge un file riferimento_hfbi con il delimitatore del tipo di record.
+/// Per le altre opzioni vedi leggi_csv().
+pub fn check_riferimento_hfbi_reader<R: Read, T>(
+    reader: R,
+    has_headers: bool,
+) -> Result<(Vec<T>, Diagnostics), Diagnostics>
+where
+    T: RecordCsvRiferimentoHFBI + RecordCsvLeggibile,
+{
+    leggi_csv(reader, &OpzioniLettura::default().intestazione(has_headers))
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VeryItalianRecordCsvCondizioniRiferimentoHFBI {
+    pub tipo_laguna: u32,
+    pub stagione: u32,
+    pub habitat: u32,
+    #[serde(deserialize_with = "deserialize_comma_f32")]
+    pub bn: f32,
+    #[serde(deserialize_with = "deserialize_comma_f32")]
+    pub ddom: f32,
+    #[serde(deserialize_with = "deserialize_comma_f32")]
+    pub dmig: f32,
+    #[serde(deserialize_with = "deserialize_comma_f32")]
+    pub bbent: f32,
+    #[serde(deserialize_with = "deserialize_comma_f32")]
+    pub dbent: f32,
+    #[serde(deserialize_with = "deserialize_comma_f32")]
+    pub dhzp: f32,
+    #[serde(skip)]
+    pub riga_sorgente: Option<RigaSorgente>,
+}
+
+impl RecordCsvSorgente for VeryItalianRecordCsvCondizioniRiferimentoHFBI {
+    fn riga_sorgente(&self) -> Option<&RigaSorgente> {
+        self.riga_sorgente.as_ref()
+    }
+    fn set_riga_sorgente(&mut self, riga: RigaSorgente) {
+        self.riga_sorgente = Some(riga);
+    }
+}
+
+impl RecordCsvCondizioniRiferimentoHFBI for VeryItalianRecordCsvCondizioniRiferimentoHFBI {
+    fn tipo_laguna(&self) -> u32 {
+        self.tipo_laguna
+    }
+    fn stagione(&self) -> u32 {
+        self.stagione
+    }
+    fn habitat(&self) -> u32 {
+        self.habitat
+    }
+    fn bn(&self) -> f32 {
+        self.bn
+    }
+    fn ddom(&self) -> f32 {
+        self.ddom
+    }
+    fn dmig(&self) -> f32 {
+        self.dmig
+    }
+    fn bbent(&self) -> f32 {
+        self.bbent
+    }
+    fn dbent(&self) -> f32 {
+        self.dbent
+    }
+    fn dhzp(&self) -> f32 {
+        self.dhzp
+    }
+}
+
+impl fmt::Display for VeryItalianRecordCsvCondizioniRiferimentoHFBI {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let string_representation = format!(
+            "RecordCsvCondizioniRiferimentoHFBI: {{ tipo_laguna: [{}], stagione: [{}], habitat: [{}], bn: [{}], ddom: [{}], dmig: [{}], bbent: [{}], dbent: [{}], dhzp: [{}] }}",
+              self.tipo_laguna, self.stagione, self.habitat,
+              self.bn, self.ddom, self.dmig, self.bbent, self.dbent, self.dhzp
+        );
+        write!(f, "{}", string_representation)
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlainRecordCsvCondizioniRiferimentoHFBI {
+    pub tipo_laguna: u32,
+    pub stagione: u32,
+    pub habitat: u32,
+    pub bn: f32,
+    pub ddom: f32,
+    pub dmig: f32,
+    pub bbent: f32,
+    pub dbent: f32,
+    pub dhzp: f32,
+    #[serde(skip)]
+    pub riga_sorgente: Option<RigaSorgente>,
+}
+
+impl RecordCsvSorgente for PlainRecordCsvCondizioniRiferimentoHFBI {
+    fn riga_sorgente(&self) -> Option<&RigaSorgente> {
+        self.riga_sorgente.as_ref()
+    }
+    fn set_riga_sorgente(&mut self, riga: RigaSorgente) {
+        self.riga_sorgente = Some(riga);
+    }
+}
+
+impl From<VeryItalianRecordCsvCondizioniRiferimentoHFBI>
+    for PlainRecordCsvCondizioniRiferimentoHFBI
+{
+    fn from(r: VeryItalianRecordCsvCondizioniRiferimentoHFBI) -> Self {
+        Self {
+            tipo_laguna: r.tipo_laguna,
+            stagione: r.stagione,
+            habitat: r.habitat,
+            bn: r.bn,
+            ddom: r.ddom,
+            dmig: r.dmig,
+            bbent: r.bbent,
+            dbent: r.dbent,
+            dhzp: r.dhzp,
+            riga_sorgente: r.riga_sorgente,
+        }
+    }
+}
+
+impl RecordCsvCondizioniRiferimentoHFBI for PlainRecordCsvCondizioniRiferimentoHFBI {
+    fn tipo_laguna(&self) -> u32 {
+        self.tipo_laguna
+    }
+    fn stagione(&self) -> u32 {
+        self.stagione
+    }
+    fn habitat(&self) -> u32 {
+        self.habitat
+    }
+    fn bn(&self) -> f32 {
+        self.bn
+    }
+    fn ddom(&self) -> f32 {
+        self.ddom
+    }
+    fn dmig(&self) -> f32 {
+        self.dmig
+    }
+    fn bbent(&self) -> f32 {
+        self.bbent
+    }
+    fn dbent(&self) -> f32 {
+        self.dbent
+    }
+    fn dhzp(&self) -> f32 {
+        self.dhzp
+    }
+}
+
+impl fmt::Display for PlainRecordCsvCondizioniRiferimentoHFBI {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let string_representation = format!(
+            "RecordCsvCondizioniRiferimentoHFBI: {{ tipo_laguna: [{}], stagione: [{}], habitat: [{}], bn: [{}], ddom: [{}], dmig: [{}], bbent: [{}], dbent: [{}], dhzp: [{}] }}",
+              self.tipo_laguna, self.stagione, self.habitat,
+              self.bn, self.ddom, self.dmig, self.bbent, self.dbent, self.dhzp
+        );
+        write!(f, "{}", string_representation)
+    }
+}
+
+impl RecordCsvLeggibile for VeryItalianRecordCsvCondizioniRiferimentoHFBI {
+    const TIPO_CSV: TipoRecordCsv = TipoRecordCsv::CondizioniRiferimentoHFBI;
+    const DELIMITATORE: u8 = b';';
+    type VirgolaDecimale = Self;
+}
+
+impl RecordCsvLeggibile for PlainRecordCsvCondizioniRiferimentoHFBI {
+    const TIPO_CSV: TipoRecordCsv = TipoRecordCsv::CondizioniRiferimentoHFBI;
+    const DELIMITATORE: u8 = b',';
+    type VirgolaDecimale = VeryItalianRecordCsvCondizioniRiferimentoHFBI;
+}
+
+/// Legge un file condizioni_riferimento_hfbi con il delimitatore del tipo di record.
+/// Per le altre opzioni vedi leggi_csv().
+pub fn check_condizioni_riferimento_hfbi_reader<R: Read, T>(
+    reader: R,
+    has_headers: bool,
+) -> Result<(Vec<T>, Diagnostics), Diagnostics>
+where
+    T: RecordCsvCondizioniRiferimentoHFBI + RecordCsvLeggibile,
+{
+    leggi_csv(reader, &OpzioniLettura::default().intestazione(has_headers))
+}
 
 #[derive(Debug, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -32,6 +412,17 @@ pub struct VeryItalianRecordCsvCampionamentoHFBI {
     pub numero_individui: u32,
     #[serde(deserialize_with = "deserialize_comma_f32")]
     pub peso: f32,
+    #[serde(skip)]
+    pub riga_sorgente: Option<RigaSorgente>,
+}
+
+impl RecordCsvSorgente for VeryItalianRecordCsvCampionamentoHFBI {
+    fn riga_sorgente(&self) -> Option<&RigaSorgente> {
+        self.riga_sorgente.as_ref()
+    }
+    fn set_riga_sorgente(&mut self, riga: RigaSorgente) {
+        self.riga_sorgente = Some(riga);
+    }
 }
 
 impl RecordCsvCampionamentoHFBI for VeryItalianRecordCsvCampionamentoHFBI {
@@ -62,6 +453,28 @@ pub struct PlainRecordCsvCampionamentoHFBI {
     pub codice_specie: String,
     pub numero_individui: u32,
     pub peso: f32,
+    #[serde(skip)]
+    pub riga_sorgente: Option<RigaSorgente>,
+}
+
+impl RecordCsvSorgente for PlainRecordCsvCampionamentoHFBI {
+    fn riga_sorgente(&self) -> Option<&RigaSorgente> {
+        self.riga_sorgente.as_ref()
+    }
+    fn set_riga_sorgente(&mut self, riga: RigaSorgente) {
+        self.riga_sorgente = Some(riga);
+    }
+}
+
+impl From<VeryItalianRecordCsvCampionamentoHFBI> for PlainRecordCsvCampionamentoHFBI {
+    fn from(r: VeryItalianRecordCsvCampionamentoHFBI) -> Self {
+        Self {
+            codice_specie: r.codice_specie,
+            numero_individui: r.numero_individui,
+            peso: r.peso,
+            riga_sorgente: r.riga_sorgente,
+        }
+    }
 }
 
 impl RecordCsvCampionamentoHFBI for PlainRecordCsvCampionamentoHFBI {
@@ -86,98 +499,28 @@ impl fmt::Display for PlainRecordCsvCampionamentoHFBI {
     }
 }
 
-pub fn parse_csv_campionamento_hfbi<R, T>(mut rdr: csv::Reader<R>) -> (Vec<T>, Vec<csv::Error>)
-where
-    R: std::io::Read,
-    T: RecordCsvCampionamentoHFBI + 'static,
-{
-    let mut records = Vec::new();
-    let mut errors = Vec::new();
-
-    for result in rdr.deserialize() {
-        match result {
-            Ok(record) => records.push(record),
-            Err(e) => errors.push(e),
-        }
-    }
+impl RecordCsvLeggibile for VeryItalianRecordCsvCampionamentoHFBI {
+    const TIPO_CSV: TipoRecordCsv = TipoRecordCsv::CampionamentoHFBI;
+    const DELIMITATORE: u8 = b';';
+    type VirgolaDecimale = Self;
+}
 
-    (records, errors)
+impl RecordCsvLeggibile for PlainRecordCsvCampionamentoHFBI {
+    const TIPO_CSV: TipoRecordCsv = TipoRecordCsv::CampionamentoHFBI;
+    const DELIMITATORE: u8 = b',';
+    type VirgolaDecimale = VeryItalianRecordCsvCampionamentoHFBI;
 }
 
+/// Legge un file campionamento_hfbi con il delimitatore del tipo di record.
+/// Per le altre opzioni vedi leggi_csv().
 pub fn check_campionamento_hfbi_reader<R: Read, T>(
     reader: R,
     has_headers: bool,
-) -> Result<Vec<T>, Vec<csv::Error>>
+) -> Result<(Vec<T>, Diagnostics), Diagnostics>
 where
-    T: RecordCsvCampionamentoHFBI + 'static,
+    T: RecordCsvCampionamentoHFBI + RecordCsvLeggibile,
 {
-    let normalizing_reader = NormalizerReader::new(reader);
-
-    let type_id = TypeId::of::<T>(); // Get the TypeId of T at runtime
-
-    // Match on the TypeId to determine the actual type of T
-    let delimiter = match type_id {
-        id if id == TypeId::of::<VeryItalianRecordCsvCampionamentoHFBI>() => b';',
-        _ => b',',
-    };
-
-    let rdr = csv::ReaderBuilder::new()
-        .delimiter(delimiter)
-        .has_headers(has_headers)
-        .from_reader(normalizing_reader);
-    let (records, errors) = parse_csv_campionamento_hfbi(rdr);
-
-    println!(
-        "Campionamento HFBI: Numero record csv validi: {}",
-        records.len()
-    );
-    println!(
-        "Campionamento HFBI: Numero record csv non validi: {}",
-        errors.len()
-    );
-
-    if !errors.is_empty() {
-        /*
-        for error in &errors {
-            eprintln!("  {}", error);
-        }
-        */
-        let processed_errors = process_csv_errors(&errors, TipoRecordCsv::CampionamentoHFBI);
-        eprintln!("Errori incontrati durante l'elaborazione csv del campionamento HFBI: {{");
-        for e in processed_errors {
-            eprintln!("{e}");
-        }
-        eprintln!("}}");
-        Err(errors)
-    } else {
-        //TODO: handle verbosity
-        //println!("Tutti i record csv del campionamento HFBI sono stati processati con successo!");
-        /*
-        for record in &records {
-            println!("  Record: {{{record}}}");
-        }
-        */
-        Ok(records)
-    }
-}
-
-pub fn check_campionamento_hfbi_path<T>(
-    path: PathBuf,
-    has_headers: bool,
-) -> Result<Vec<T>, Vec<csv::Error>>
-where
-    T: RecordCsvCampionamentoHFBI + 'static,
-{
-    if !check_path_is_file_ends_with_csv(&path) {
-        eprintln!("Il file {} non è un .csv", path.display());
-        let err = csv::Error::from(Error::other(
-            "Errore campionamento HFBI: il file non è un .csv",
-        ));
-        let err_vec: Vec<csv::Error> = vec![err];
-        return Err(err_vec);
-    }
-    let file = File::open(path).expect("Unable to open file");
-    check_campionamento_hfbi_reader(file, has_headers)
+    leggi_csv(reader, &OpzioniLettura::default().intestazione(has_headers))
 }
 
 #[derive(Debug, serde::Deserialize)]
@@ -195,6 +538,17 @@ pub struct VeryItalianRecordCsvAnagraficaHFBI {
     pub stagione: u32,
     pub habitat: u32,
     pub tipo_laguna: u32,
+    #[serde(skip)]
+    pub riga_sorgente: Option<RigaSorgente>,
+}
+
+impl RecordCsvSorgente for VeryItalianRecordCsvAnagraficaHFBI {
+    fn riga_sorgente(&self) -> Option<&RigaSorgente> {
+        self.riga_sorgente.as_ref()
+    }
+    fn set_riga_sorgente(&mut self, riga: RigaSorgente) {
+        self.riga_sorgente = Some(riga);
+    }
 }
 
 impl RecordCsvAnagraficaHFBI for VeryItalianRecordCsvAnagraficaHFBI {
@@ -265,6 +619,35 @@ pub struct PlainRecordCsvAnagraficaHFBI {
     pub stagione: u32,
     pub habitat: u32,
     pub tipo_laguna: u32,
+    #[serde(skip)]
+    pub riga_sorgente: Option<RigaSorgente>,
+}
+
+impl RecordCsvSorgente for PlainRecordCsvAnagraficaHFBI {
+    fn riga_sorgente(&self) -> Option<&RigaSorgente> {
+        self.riga_sorgente.as_ref()
+    }
+    fn set_riga_sorgente(&mut self, riga: RigaSorgente) {
+        self.riga_sorgente = Some(riga);
+    }
+}
+
+impl From<VeryItalianRecordCsvAnagraficaHFBI> for PlainRecordCsvAnagraficaHFBI {
+    fn from(r: VeryItalianRecordCsvAnagraficaHFBI) -> Self {
+        Self {
+            codice_stazione: r.codice_stazione,
+            corpo_idrico: r.corpo_idrico,
+            regione: r.regione,
+            provincia: r.provincia,
+            data: r.data,
+            lunghezza_stazione: r.lunghezza_stazione,
+            larghezza_stazione: r.larghezza_stazione,
+            stagione: r.stagione,
+            habitat: r.habitat,
+            tipo_laguna: r.tipo_laguna,
+            riga_sorgente: r.riga_sorgente,
+        }
+    }
 }
 
 impl RecordCsvAnagraficaHFBI for PlainRecordCsvAnagraficaHFBI {
@@ -322,96 +705,26 @@ impl fmt::Display for PlainRecordCsvAnagraficaHFBI {
     }
 }
 
-pub fn parse_csv_anagrafica_hfbi<R, T>(mut rdr: csv::Reader<R>) -> (Vec<T>, Vec<csv::Error>)
-where
-    R: std::io::Read,
-    T: RecordCsvAnagraficaHFBI,
-{
-    let mut records = Vec::new();
-    let mut errors = Vec::new();
-
-    for result in rdr.deserialize() {
-        match result {
-            Ok(record) => records.push(record),
-            Err(e) => errors.push(e),
-        }
-    }
+impl RecordCsvLeggibile for VeryItalianRecordCsvAnagraficaHFBI {
+    const TIPO_CSV: TipoRecordCsv = TipoRecordCsv::AnagraficaHFBI;
+    const DELIMITATORE: u8 = b';';
+    type VirgolaDecimale = Self;
+}
 
-    (records, errors)
+impl RecordCsvLeggibile for PlainRecordCsvAnagraficaHFBI {
+    const TIPO_CSV: TipoRecordCsv = TipoRecordCsv::AnagraficaHFBI;
+    const DELIMITATORE: u8 = b',';
+    type VirgolaDecimale = VeryItalianRecordCsvAnagraficaHFBI;
 }
 
+/// Legge un file anagrafica_hfbi con il delimitatore del tipo di record.
+/// Per le altre opzioni vedi leggi_csv().
 pub fn check_anagrafica_hfbi_reader<R: Read, T>(
     reader: R,
     has_headers: bool,
-) -> Result<Vec<T>, Vec<csv::Error>>
-where
-    T: RecordCsvAnagraficaHFBI + 'static,
-{
-    let normalizing_reader = NormalizerReader::new(reader);
-
-    let type_id = TypeId::of::<T>(); // Get the TypeId of T at runtime
-
-    // Match on the TypeId to determine the actual type of T
-    let delimiter = match type_id {
-        id if id == TypeId::of::<VeryItalianRecordCsvAnagraficaHFBI>() => b';',
-        _ => b',',
-    };
-
-    let rdr = csv::ReaderBuilder::new()
-        .delimiter(delimiter)
-        .has_headers(has_headers)
-        .from_reader(normalizing_reader);
-    let (records, errors) = parse_csv_anagrafica_hfbi(rdr);
-
-    println!(
-        "Anagrafica HFBI: Numero record csv validi: {}",
-        records.len()
-    );
-    println!(
-        "Anagrafica HFBI: Numero record csv non validi: {}",
-        errors.len()
-    );
-
-    if !errors.is_empty() {
-        /*
-        for error in &errors {
-            eprintln!("  {}", error);
-        }
-        */
-        let processed_errors = process_csv_errors(&errors, TipoRecordCsv::AnagraficaHFBI);
-        eprintln!("Errori incontrati durante l'elaborazione csv dell' anagrafica HFBI: {{");
-        for e in processed_errors {
-            eprintln!("{e}");
-        }
-        eprintln!("}}");
-        Err(errors)
-    } else {
-        //TODO: handle verbosity
-        //println!("Tutti i record csv dell'anagrafica HFBI sono stati processati con successo!");
-        /*
-        for record in &records {
-            println!("  Record: {{{record}}}");
-        }
-        */
-        Ok(records)
-    }
-}
-
-pub fn check_anagrafica_hfbi_path<T>(
-    path: PathBuf,
-    has_headers: bool,
-) -> Result<Vec<T>, Vec<csv::Error>>
+) -> Result<(Vec<T>, Diagnostics), Diagnostics>
 where
-    T: RecordCsvAnagraficaHFBI + 'static,
+    T: RecordCsvAnagraficaHFBI + RecordCsvLeggibile,
 {
-    if !check_path_is_file_ends_with_csv(&path) {
-        eprintln!("Il file {} non è un .csv", path.display());
-        let err = csv::Error::from(Error::other(
-            "Errore anagrafica HFBI: il file non è un .csv",
-        ));
-        let err_vec: Vec<csv::Error> = vec![err];
-        return Err(err_vec);
-    }
-    let file = File::open(path).expect("Unable to open file");
-    check_anagrafica_hfbi_reader(file, has_headers)
+    leggi_csv(reader, &OpzioniLettura::default().intestazione(has_headers))
 }
@@ -15,45 +15,19 @@
     along with this program.  If not, see <https://www.gnu.org/licenses/>.
 */
 
-use super::{
-    ANAGRAFICA_HFBI_HEADER_FIELDS, ANAGRAFICA_NISECI_HEADER_FIELDS,
-    CAMPIONAMENTO_HFBI_HEADER_FIELDS, CAMPIONAMENTO_NISECI_HEADER_FIELDS,
-    RIFERIMENTO_NISECI_HEADER_FIELDS,
-};
-use crate::csv::TipoRecordCsv;
+use crate::csv::deser::colonne::{AliasColonne, MappaturaColonne};
+use crate::csv::deser::encoding::Utf8Reader;
+use crate::csv::deser::sniff::sniff_reader;
+use crate::csv::{RecordCsvSorgente, RigaSorgente, TipoRecordCsv};
+use crate::diagnostics::{Diagnostics, ValidationMode};
+use crate::error::{ContestoErrore, Error, Posizione};
+use crate::i18n::Messaggio;
+use serde::de::DeserializeOwned;
 use serde::{de, Deserialize, Deserializer};
-use std::io::{self, Read};
+use std::fs::File;
+use std::io::Read;
 use std::path::Path;
-
-struct NormalizerReader<R: Read> {
-    inner: R,
-}
-
-impl<R: Read> NormalizerReader<R> {
-    fn new(inner: R) -> Self {
-        Self { inner }
-    }
-}
-
-impl<R: Read> Read for NormalizerReader<R> {
-    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
-        let size = self.inner.read(buf)?;
-
-        // Change very italian accented characters in place
-        for byte in buf.iter_mut().take(size) {
-            match *byte {
-                b'\xF2' => *byte = b'o',
-                b'\xE0' => *byte = b'a',
-                b'\xE8' => *byte = b'e',
-                b'\xF9' => *byte = b'u',
-                b'\xEC' => *byte = b'i',
-                _ => {}
-            }
-        }
-
-        Ok(size)
-    }
-}
+use std::sync::Arc;
 
 fn deserialize_comma_f32<'de, D>(deserializer: D) -> Result<f32, D::Error>
 where
@@ -64,203 +38,323 @@ where
     s.parse::<f32>().map_err(de::Error::custom)
 }
 
-fn parse_csv_pos(pos: &Option<csv::Position>) -> String {
-    let res;
-    match pos {
-        Some(p) => {
-            // These should be equal. We may show the value only once if they are
-            let line_offset = p.line();
-            let record_offset = p.record();
-
-            if line_offset == record_offset {
-                res = format!("Riga: {}", line_offset);
-            } else {
-                // TODO: How can we hit this branch?
-                res = format!("Riga: {} Record: {}", line_offset, record_offset);
+/// Converte un errore del crate csv nell'errore della libreria.
+/// Se è disponibile il record grezzo, il valore del campo incriminato viene riportato.
+pub fn converti_errore_csv(
+    error: &csv::Error,
+    tipo_csv: TipoRecordCsv,
+    record: Option<&csv::StringRecord>,
+) -> Error {
+    let contesto = ContestoErrore::default().tipo_csv(tipo_csv);
+    match error.kind() {
+        csv::ErrorKind::Deserialize { pos, err } => {
+            let mut contesto = contesto;
+            if let Some(p) = pos {
+                contesto = contesto.posizione(Posizione::from(p));
             }
-
-            // We ignore this since I don't think users may care?
-            // let byte_offset = p.byte();
-            // res = format!("Riga: {} Record: {} Char: {} ", line_offset, record_offset, byte_offset);
+            if let Some(f) = err.field() {
+                // Deduce name for field from index in the header
+                // f is u64 starting from 0
+                let field_idx = f as usize;
+                let header_fields = tipo_csv.header_fields();
+                if field_idx < header_fields.len() {
+                    contesto = contesto.campo(header_fields[field_idx]);
+                } else {
+                    contesto = contesto.campo("???");
+                }
+                if let Some(valore) = record.and_then(|r| r.get(field_idx)) {
+                    contesto = contesto.valore(valore);
+                }
+            }
+            let messaggio = match err.kind() {
+                csv::DeserializeErrorKind::Message(msg) => Messaggio::Esterno(msg.clone()),
+                csv::DeserializeErrorKind::Unsupported(msg) => Messaggio::Esterno(msg.clone()),
+                csv::DeserializeErrorKind::UnexpectedEndOfRow => Messaggio::FineRigaInattesa,
+                csv::DeserializeErrorKind::InvalidUtf8(utf8err) => {
+                    Messaggio::Esterno(utf8err.to_string())
+                }
+                csv::DeserializeErrorKind::ParseBool(boolerr) => {
+                    Messaggio::Esterno(boolerr.to_string())
+                }
+                csv::DeserializeErrorKind::ParseInt(interr) => {
+                    Messaggio::Esterno(interr.to_string())
+                }
+                csv::DeserializeErrorKind::ParseFloat(floaterr) => {
+                    Messaggio::Esterno(floaterr.to_string())
+                }
+            };
+            Error::Deserializzazione(contesto.messaggio(messaggio).into())
+        }
+        csv::ErrorKind::Io(io_error) => {
+            let contesto = contesto.messaggio(Messaggio::Esterno(io_error.to_string()));
+            Error::Io(contesto.into())
         }
-        None => {
-            res = "none".to_string();
+        csv::ErrorKind::Utf8 { pos, err } => {
+            let mut contesto = contesto;
+            if let Some(p) = pos {
+                contesto = contesto.posizione(Posizione::from(p));
+            }
+            contesto = contesto.messaggio(Messaggio::Esterno(err.to_string()));
+            Error::Utf8(contesto.into())
+        }
+        csv::ErrorKind::UnequalLengths {
+            pos,
+            expected_len,
+            len,
+        } => {
+            let mut contesto = contesto;
+            if let Some(p) = pos {
+                contesto = contesto.posizione(Posizione::from(p));
+            }
+            contesto = contesto.messaggio(Messaggio::LunghezzaRecord {
+                attesa: *expected_len,
+                trovata: *len,
+            });
+            Error::NumeroCampi(contesto.into())
+        }
+        _ => {
+            let contesto = contesto.messaggio(Messaggio::Esterno(error.to_string()));
+            Error::Io(contesto.into())
         }
     }
-    res
 }
 
-pub fn process_csv_errors(errors: &Vec<csv::Error>, tipo_csv: TipoRecordCsv) -> Vec<String> {
-    let mut res = Vec::new();
-    for error in errors {
-        match error.kind() {
-            csv::ErrorKind::Deserialize { pos, err } => {
-                let field_str;
-                match err.field() {
-                    Some(f) => {
-                        // Deduce name for field from index in the header
-                        // f is u64 starting from 0
-                        let field_idx = f as usize;
-                        match tipo_csv {
-                            TipoRecordCsv::RiferimentoNISECI => {
-                                if field_idx < RIFERIMENTO_NISECI_HEADER_FIELDS.len() {
-                                    field_str = format!(
-                                        "{} ({})",
-                                        field_idx, RIFERIMENTO_NISECI_HEADER_FIELDS[field_idx]
-                                    );
-                                } else {
-                                    field_str = "???".to_string();
-                                }
-                            }
-                            TipoRecordCsv::CampionamentoNISECI => {
-                                if field_idx < CAMPIONAMENTO_NISECI_HEADER_FIELDS.len() {
-                                    field_str = format!(
-                                        "{} ({})",
-                                        field_idx, CAMPIONAMENTO_NISECI_HEADER_FIELDS[field_idx]
-                                    );
-                                } else {
-                                    field_str = "???".to_string();
-                                }
-                            }
-                            TipoRecordCsv::AnagraficaNISECI => {
-                                if field_idx < ANAGRAFICA_NISECI_HEADER_FIELDS.len() {
-                                    field_str = format!(
-                                        "{} ({})",
-                                        field_idx, ANAGRAFICA_NISECI_HEADER_FIELDS[field_idx]
-                                    );
-                                } else {
-                                    field_str = "???".to_string();
-                                }
-                            }
-                            TipoRecordCsv::CampionamentoHFBI => {
-                                if field_idx < CAMPIONAMENTO_HFBI_HEADER_FIELDS.len() {
-                                    field_str = format!(
-                                        "{} ({})",
-                                        field_idx, CAMPIONAMENTO_HFBI_HEADER_FIELDS[field_idx]
-                                    );
-                                } else {
-                                    field_str = "???".to_string();
-                                }
-                            }
-                            TipoRecordCsv::AnagraficaHFBI => {
-                                if field_idx < ANAGRAFICA_HFBI_HEADER_FIELDS.len() {
-                                    field_str = format!(
-                                        "{} ({})",
-                                        field_idx, ANAGRAFICA_HFBI_HEADER_FIELDS[field_idx]
-                                    );
-                                } else {
-                                    field_str = "???".to_string();
-                                }
-                            }
-                        }
-                    }
-                    None => {
-                        field_str = "none".to_string();
-                    }
-                }
-                let mut curr_err = format!(
-                    "  Errore di deserializzazione alla posizione: {}: campo {}",
-                    parse_csv_pos(pos),
-                    field_str,
-                );
-                match err.kind() {
-                    csv::DeserializeErrorKind::Message(msg) => {
-                        curr_err = format!("{curr_err}: {}", translate_error_message(msg));
-                    }
-                    csv::DeserializeErrorKind::Unsupported(msg) => {
-                        curr_err = format!("{curr_err}: {}", translate_error_message(msg));
-                    }
-                    csv::DeserializeErrorKind::UnexpectedEndOfRow => {
-                        curr_err = format!("{curr_err}: Fine riga inatteso");
-                    }
-                    csv::DeserializeErrorKind::InvalidUtf8(utf8err) => {
-                        curr_err = format!(
-                            "{curr_err}: {}",
-                            translate_error_message(&utf8err.to_string())
-                        );
-                    }
-                    csv::DeserializeErrorKind::ParseBool(boolerr) => {
-                        curr_err = format!(
-                            "{curr_err}: {}",
-                            translate_error_message(&boolerr.to_string())
-                        );
-                    }
-                    csv::DeserializeErrorKind::ParseInt(interr) => {
-                        curr_err = format!(
-                            "{curr_err}: {}",
-                            translate_error_message(&interr.to_string())
-                        );
-                    }
-                    csv::DeserializeErrorKind::ParseFloat(floaterr) => {
-                        curr_err = format!(
-                            "{curr_err}: {}",
-                            translate_error_message(&floaterr.to_string())
-                        );
-                    }
+/// Legge tutti i record dal reader, raccogliendo separatamente record validi ed errori
+fn parse_csv_records<R, T>(
+    mut rdr: csv::Reader<R>,
+    tipo_csv: TipoRecordCsv,
+    alias: &AliasColonne,
+) -> (Vec<T>, Vec<Error>)
+where
+    R: Read,
+    T: serde::de::DeserializeOwned + RecordCsvSorgente,
+{
+    let mut records = Vec::new();
+    let mut errors = Vec::new();
+
+    // Le colonne vengono risolte per nome: i record vengono riportati all'ordine standard
+    // prima della deserializzazione
+    let mappatura = if rdr.has_headers() {
+        match rdr.headers() {
+            Ok(h) => {
+                let intestazione: Vec<&str> = h.iter().collect();
+                let mappatura = MappaturaColonne::new(tipo_csv, &intestazione, alias);
+                if !mappatura.is_completa() {
+                    return (records, mappatura.errori());
                 }
-                res.push(curr_err);
+                let headers = csv::StringRecord::from(tipo_csv.header_fields().to_vec());
+                Some((mappatura, headers))
             }
-            csv::ErrorKind::Io(io_error) => {
-                res.push(format!(
-                    "  Errore di I/O: {}",
-                    translate_error_message(&io_error.to_string())
-                ));
+            Err(e) => {
+                errors.push(converti_errore_csv(&e, tipo_csv, None));
+                return (records, errors);
             }
-            csv::ErrorKind::Utf8 { pos, err } => {
-                res.push(format!(
-                    "  Errore UTF-8 alla posizione: {}: {}",
-                    parse_csv_pos(pos),
-                    translate_error_message(&err.to_string())
-                ));
-            }
-            csv::ErrorKind::UnequalLengths {
-                pos,
-                expected_len,
-                len,
-            } => {
-                res.push(format!(
-                    "  Errore numero campi alla posizione: {}: lunghezza attesa {}, trovata {}",
-                    parse_csv_pos(pos),
-                    expected_len,
-                    len // no translate_error_message() anche se teoricamente lo supporta
-                ));
-            }
-            _ => {
-                res.push(format!(
-                    "  Errore sconosciuto: {}",
-                    translate_error_message(&error.to_string())
-                ));
+        }
+    } else {
+        None
+    };
+
+    // Senza intestazione le colonne sono nell'ordine standard
+    let colonne: Arc<[Option<usize>]> = match &mappatura {
+        Some((m, _)) => m.indici(),
+        None => (0..tipo_csv.header_fields().len()).map(Some).collect(),
+    };
+
+    // Gli errori dei singoli record riportano il numero progressivo del record (da 1)
+    for (idx, result) in rdr.records().enumerate() {
+        let idx = idx + 1;
+        match result {
+            Ok(record) => {
+                let (record, headers) = match &mappatura {
+                    Some((m, headers)) => (m.riordina(&record), Some(headers)),
+                    None => (record, None),
+                };
+                let riga = RigaSorgente {
+                    tipo_csv,
+                    posizione: record.position().map(Posizione::from),
+                    celle: record.iter().map(|c| c.to_string()).collect(),
+                    colonne: Arc::clone(&colonne),
+                };
+                match record.deserialize::<T>(headers) {
+                    Ok(mut r) => {
+                        r.set_riga_sorgente(riga);
+                        records.push(r);
+                    }
+                    Err(e) => errors.push(
+                        converti_errore_csv(&e, tipo_csv, Some(&record))
+                            .con_riga_sorgente(&riga)
+                            .con_record(idx),
+                    ),
+                }
             }
+            Err(e) => match converti_errore_csv(&e, tipo_csv, None) {
+                // Un errore di I/O non riguarda il singolo record
+                err @ Error::Io(_) => errors.push(err),
+                err => errors.push(err.con_record(idx)),
+            },
         }
     }
-    res
+
+    (records, errors)
 }
 
-pub fn check_path_is_file_ends_with_csv(path: &Path) -> bool {
-    if !path.exists() {
-        eprintln!("Error: Passed path does not exist");
-        false
-    } else if !path.is_file() {
-        eprintln!("Error: Passed path is not a regular file");
-        false
+/// Record che si può leggere da un file csv con leggi_csv()
+pub trait RecordCsvLeggibile: DeserializeOwned + RecordCsvSorgente + Sized + 'static {
+    const TIPO_CSV: TipoRecordCsv;
+    /// Delimitatore usato quando OpzioniLettura non ne indica uno
+    const DELIMITATORE: u8;
+    /// Record con cui leggere i file con la virgola decimale in modalità auto
+    type VirgolaDecimale: RecordCsvLeggibile + Into<Self>;
+}
+
+/// Opzioni di lettura di un file csv
+#[derive(Debug, Clone, PartialEq)]
+pub struct OpzioniLettura {
+    /// Il file ha una riga di intestazione
+    pub intestazione: bool,
+    /// None usa il delimitatore del tipo di record (';' per VeryItalian, ',' per Plain)
+    pub delimitatore: Option<u8>,
+    pub alias: AliasColonne,
+    /// In Lenient i record che non si possono deserializzare vengono scartati
+    /// (vedi Diagnostics::scartati)
+    pub modalita: ValidationMode,
+    /// Rileva delimitatore e separatore decimale dall'input, ignorando il delimitatore indicato.
+    /// Il formato rilevato viene riportato in Diagnostics::formato.
+    pub auto: bool,
+}
+
+impl Default for OpzioniLettura {
+    fn default() -> Self {
+        Self {
+            intestazione: true,
+            delimitatore: None,
+            alias: AliasColonne::default(),
+            modalita: ValidationMode::Strict,
+            auto: false,
+        }
+    }
+}
+
+impl OpzioniLettura {
+    pub fn intestazione(mut self, intestazione: bool) -> Self {
+        self.intestazione = intestazione;
+        self
+    }
+
+    pub fn delimitatore(mut self, delimitatore: u8) -> Self {
+        self.delimitatore = Some(delimitatore);
+        self
+    }
+
+    pub fn alias(mut self, alias: AliasColonne) -> Self {
+        self.alias = alias;
+        self
+    }
+
+    pub fn modalita(mut self, modalita: ValidationMode) -> Self {
+        self.modalita = modalita;
+        self
+    }
+
+    pub fn auto(mut self) -> Self {
+        self.auto = true;
+        self
+    }
+}
+
+/// Legge e deserializza i record di un file csv secondo le opzioni indicate.
+/// In modalità auto, se l'input usa la virgola decimale i record vengono letti come
+/// T::VirgolaDecimale e convertiti in T.
+pub fn leggi_csv<R: Read, T: RecordCsvLeggibile>(
+    reader: R,
+    opzioni: &OpzioniLettura,
+) -> Result<(Vec<T>, Diagnostics), Diagnostics> {
+    if !opzioni.auto {
+        let delimitatore = opzioni.delimitatore.unwrap_or(T::DELIMITATORE);
+        return leggi_csv_delimitato(reader, opzioni, delimitatore);
+    }
+
+    let tipo_csv = T::TIPO_CSV;
+    let (formato, reader) = sniff_reader(reader)
+        .map_err(|e| Diagnostics::from_errore(tipo_csv, Error::from(e).con_tipo_csv(tipo_csv)))?;
+    let res = if formato.usa_virgola_decimale() {
+        leggi_csv_delimitato::<_, T::VirgolaDecimale>(reader, opzioni, formato.delimitatore).map(
+            |(records, diagnostics)| (records.into_iter().map(Into::into).collect(), diagnostics),
+        )
     } else {
-        let ext = path.extension();
-        match ext {
-            Some(ex) => {
-                if !(ex == "csv" || ex == "CSV") {
-                    eprintln!("Error: Passed path does not end with .csv");
-                    return false;
-                }
-                true
-            }
-            None => {
-                eprintln!("Error: Passed path does not end with .csv");
-                false
-            }
+        leggi_csv_delimitato::<_, T>(reader, opzioni, formato.delimitatore)
+    };
+    match res {
+        Ok((records, mut diagnostics)) => {
+            diagnostics.set_formato(formato);
+            Ok((records, diagnostics))
+        }
+        Err(mut diagnostics) => {
+            diagnostics.set_formato(formato);
+            Err(diagnostics)
         }
     }
 }
 
+/// Come leggi_csv(), aprendo il file al percorso indicato
+pub fn leggi_csv_path<T: RecordCsvLeggibile>(
+    path: &Path,
+    opzioni: &OpzioniLettura,
+) -> Result<(Vec<T>, Diagnostics), Diagnostics> {
+    let file =
+        open_csv_path(path, T::TIPO_CSV).map_err(|e| Diagnostics::from_errore(T::TIPO_CSV, e))?;
+    leggi_csv(file, opzioni)
+}
+
+fn leggi_csv_delimitato<R: Read, T: RecordCsvLeggibile>(
+    reader: R,
+    opzioni: &OpzioniLettura,
+    delimitatore: u8,
+) -> Result<(Vec<T>, Diagnostics), Diagnostics> {
+    let rdr = csv::ReaderBuilder::new()
+        .delimiter(delimitatore)
+        .has_headers(opzioni.intestazione)
+        .from_reader(Utf8Reader::new(reader));
+    let (records, errors) = parse_csv_records(rdr, T::TIPO_CSV, &opzioni.alias);
+
+    Diagnostics::from_errori(T::TIPO_CSV, records.len(), errors).esito(records, opzioni.modalita)
+}
+
+/// Controlla che il percorso esista, sia un file regolare e abbia estensione .csv
+pub fn check_path_is_file_ends_with_csv(path: &Path, tipo_csv: TipoRecordCsv) -> Result<(), Error> {
+    let percorso = path.display().to_string();
+    let messaggio = if !path.exists() {
+        Messaggio::PercorsoInesistente(percorso)
+    } else if !path.is_file() {
+        Messaggio::PercorsoNonFile(percorso)
+    } else {
+        match path.extension() {
+            Some(ex) if ex == "csv" || ex == "CSV" => return Ok(()),
+            _ => Messaggio::FileNonCsv(percorso),
+        }
+    };
+    Err(Error::FileNonValido(
+        ContestoErrore::da_messaggio(messaggio)
+            .tipo_csv(tipo_csv)
+            .into(),
+    ))
+}
+
+/// Apre il file al percorso indicato, dopo averne verificato la validità
+pub(crate) fn open_csv_path(path: &Path, tipo_csv: TipoRecordCsv) -> Result<File, Error> {
+    check_path_is_file_ends_with_csv(path, tipo_csv)?;
+    File::open(path).map_err(|e| {
+        Error::Io(
+            ContestoErrore::da_messaggio(Messaggio::FileNonApribile {
+                percorso: path.display().to_string(),
+                dettaglio: e.to_string(),
+            })
+            .tipo_csv(tipo_csv)
+            .into(),
+        )
+    })
+}
+
 pub fn translate_error_message(msg: &str) -> String {
     if msg.starts_with("missing field") {
         msg.replace("missing field", "campo mancante")
@@ -294,10 +388,12 @@ pub fn translate_error_message(msg: &str) -> String {
             .replace("but the previous record has", "ma il record precedente ha")
             .replace("fields", "campi")
     } else {
-        eprintln!("Unmatched translation for {msg}");
         msg.to_string() // Default to original message if no match
     }
 }
 
+pub mod colonne;
+pub mod encoding;
 pub mod hfbi;
 pub mod niseci;
+pub mod sniff;
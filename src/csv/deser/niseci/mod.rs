@@ -15,18 +15,15 @@
     along with this program.  If not, see <https://www.gnu.org/licenses/>.
 */
 
-use crate::csv::deser::{
-    check_path_is_file_ends_with_csv, deserialize_comma_f32, process_csv_errors, NormalizerReader,
-};
+use crate::csv::deser::{deserialize_comma_f32, leggi_csv, OpzioniLettura, RecordCsvLeggibile};
 use crate::csv::{
-    RecordCsvAnagraficaNISECI, RecordCsvCampionamentoNISECI, RecordCsvRiferimentoNISECI,
-    TipoRecordCsv,
+    RecordCsvAnagraficaNISECI, RecordCsvCampionamentoNISECI, RecordCsvComunitaRiferimentoNISECI,
+    RecordCsvRiferimentoNISECI, RecordCsvSorgente, RigaSorgente, TipoRecordCsv,
 };
-use std::any::TypeId;
+use crate::diagnostics::Diagnostics;
+use crate::domain::niseci::catalogo::SoglieSpecieNISECI;
 use std::fmt;
-use std::fs::File;
-use std::io::{Error, Read};
-use std::path::PathBuf;
+use std::io::Read;
 
 #[derive(Debug, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -54,6 +51,17 @@ pub struct VeryItalianRecordCsvRiferimentoNISECI {
     pub dens_soglia1: f32,
     #[serde(deserialize_with = "deserialize_comma_f32")]
     pub dens_soglia2: f32,
+    #[serde(skip)]
+    pub riga_sorgente: Option<RigaSorgente>,
+}
+
+impl RecordCsvSorgente for VeryItalianRecordCsvRiferimentoNISECI {
+    fn riga_sorgente(&self) -> Option<&RigaSorgente> {
+        self.riga_sorgente.as_ref()
+    }
+    fn set_riga_sorgente(&mut self, riga: RigaSorgente) {
+        self.riga_sorgente = Some(riga);
+    }
 }
 
 impl RecordCsvRiferimentoNISECI for VeryItalianRecordCsvRiferimentoNISECI {
@@ -144,6 +152,42 @@ pub struct PlainRecordCsvRiferimentoNISECI {
     pub ad_juv_soglia4: f32,
     pub dens_soglia1: f32,
     pub dens_soglia2: f32,
+    #[serde(skip)]
+    pub riga_sorgente: Option<RigaSorgente>,
+}
+
+impl RecordCsvSorgente for PlainRecordCsvRiferimentoNISECI {
+    fn riga_sorgente(&self) -> Option<&RigaSorgente> {
+        self.riga_sorgente.as_ref()
+    }
+    fn set_riga_sorgente(&mut self, riga: RigaSorgente) {
+        self.riga_sorgente = Some(riga);
+    }
+}
+
+impl From<VeryItalianRecordCsvRiferimentoNISECI> for PlainRecordCsvRiferimentoNISECI {
+    fn from(r: VeryItalianRecordCsvRiferimentoNISECI) -> Self {
+        Self {
+            nome_comune: r.nome_comune,
+            nome_latino: r.nome_latino,
+            codice_specie: r.codice_specie,
+            origine: r.origine,
+            tipo_autoctono: r.tipo_autoctono,
+            allo_nocivita: r.allo_nocivita,
+            specie_attesa: r.specie_attesa,
+            cl_soglia1: r.cl_soglia1,
+            cl_soglia2: r.cl_soglia2,
+            cl_soglia3: r.cl_soglia3,
+            cl_soglia4: r.cl_soglia4,
+            ad_juv_soglia1: r.ad_juv_soglia1,
+            ad_juv_soglia2: r.ad_juv_soglia2,
+            ad_juv_soglia3: r.ad_juv_soglia3,
+            ad_juv_soglia4: r.ad_juv_soglia4,
+            dens_soglia1: r.dens_soglia1,
+            dens_soglia2: r.dens_soglia2,
+            riga_sorgente: r.riga_sorgente,
+        }
+    }
 }
 
 impl RecordCsvRiferimentoNISECI for PlainRecordCsvRiferimentoNISECI {
@@ -214,98 +258,160 @@ impl fmt::Display for PlainRecordCsvRiferimentoNISECI {
     }
 }
 
-pub fn parse_csv_riferimento_niseci<R, T>(mut rdr: csv::Reader<R>) -> (Vec<T>, Vec<csv::Error>)
-where
-    R: std::io::Read,
-    T: RecordCsvRiferimentoNISECI,
-{
-    let mut records = Vec::new();
-    let mut errors = Vec::new();
-
-    for result in rdr.deserialize() {
-        match result {
-            Ok(record) => records.push(record),
-            Err(e) => errors.push(e),
-        }
-    }
+impl RecordCsvLeggibile for VeryItalianRecordCsvRiferimentoNISECI {
+    const TIPO_CSV: TipoRecordCsv = TipoRecordCsv::RiferimentoNISECI;
+    const DELIMITATORE: u8 = b';';
+    type VirgolaDecimale = Self;
+}
 
-    (records, errors)
+impl RecordCsvLeggibile for PlainRecordCsvRiferimentoNISECI {
+    const TIPO_CSV: TipoRecordCsv = TipoRecordCsv::RiferimentoNISECI;
+    const DELIMITATORE: u8 = b',';
+    type VirgolaDecimale = VeryItalianRecordCsvRiferimentoNISECI;
 }
 
+/// Legge un file riferimento_niseci con il delimitatore del tipo di record.
+/// Per le altre opzioni vedi leggi_csv().
 pub fn check_riferimento_niseci_reader<R: Read, T>(
     reader: R,
     has_headers: bool,
-) -> Result<Vec<T>, Vec<csv::Error>>
+) -> Result<(Vec<T>, Diagnostics), Diagnostics>
 where
-    T: RecordCsvRiferimentoNISECI + 'static,
+    T: RecordCsvRiferimentoNISECI + RecordCsvLeggibile,
 {
-    let normalizing_reader = NormalizerReader::new(reader);
-
-    let type_id = TypeId::of::<T>(); // Get the TypeId of T at runtime
-
-    // Match on the TypeId to determine the actual type of T
-    let delimiter = match type_id {
-        id if id == TypeId::of::<VeryItalianRecordCsvRiferimentoNISECI>() => b';',
-        _ => b',',
-    };
-
-    let rdr = csv::ReaderBuilder::new()
-        .delimiter(delimiter)
-        .has_headers(has_headers)
-        .from_reader(normalizing_reader);
-    let (records, errors) = parse_csv_riferimento_niseci(rdr);
-
-    println!(
-        "Riferimento NISECI: Numero record csv validi: {}",
-        records.len()
-    );
-    println!(
-        "Riferimento NISECI: Numero record csv non validi: {}",
-        errors.len()
-    );
-
-    if !errors.is_empty() {
-        /*
-        for error in &errors {
-            eprintln!("  {}", error);
-        }
-        */
-        let processed_errors = process_csv_errors(&errors, TipoRecordCsv::RiferimentoNISECI);
-        eprintln!("Errori incontrati durante l'elaborazione csv del riferimento NISECI: {{");
-        for e in processed_errors {
-            eprintln!("{e}");
-        }
-        eprintln!("}}");
-        Err(errors)
-    } else {
-        //TODO: handle verbosity
-        //println!("Tutti i record csv del riferimento NISECI sono stati processati con successo!");
-        /*
-        for record in &records {
-            println!("  Record: {{{record}}}");
+    leggi_csv(reader, &OpzioniLettura::default().intestazione(has_headers))
+}
+
+/// Non ha campi decimali: si distingue da PlainRecordCsvComunitaRiferimentoNISECI solo per il
+/// delimitatore (vedi RecordCsvLeggibile::DELIMITATORE).
+/// Il file csv non ha colonne per le soglie: eventuali colonne come clSoglia1 vengono ignorate e
+/// valgono sempre le soglie di CATALOGO_NISECI
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VeryItalianRecordCsvComunitaRiferimentoNISECI {
+    pub codice_specie: String,
+    pub specie_attesa: u32,
+    pub tipo_autoctono: u32,
+    #[serde(skip)]
+    pub riga_sorgente: Option<RigaSorgente>,
+}
+
+impl RecordCsvSorgente for VeryItalianRecordCsvComunitaRiferimentoNISECI {
+    fn riga_sorgente(&self) -> Option<&RigaSorgente> {
+        self.riga_sorgente.as_ref()
+    }
+    fn set_riga_sorgente(&mut self, riga: RigaSorgente) {
+        self.riga_sorgente = Some(riga);
+    }
+}
+
+impl RecordCsvComunitaRiferimentoNISECI for VeryItalianRecordCsvComunitaRiferimentoNISECI {
+    fn codice_specie(&self) -> String {
+        self.codice_specie.clone()
+    }
+    fn specie_attesa(&self) -> u32 {
+        self.specie_attesa
+    }
+    fn tipo_autoctono(&self) -> u32 {
+        self.tipo_autoctono
+    }
+}
+
+impl fmt::Display for VeryItalianRecordCsvComunitaRiferimentoNISECI {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let string_representation = format!(
+            "RecordCsvComunitaRiferimentoNISECI: {{ codice_specie: [{}], specie_attesa: [{}], tipo_autoctono: [{}] }}",
+              self.codice_specie, self.specie_attesa, self.tipo_autoctono
+        );
+        write!(f, "{}", string_representation)
+    }
+}
+
+/// Nell'input JSON può indicare anche le soglie che sostituiscono quelle del catalogo.
+/// Letto da csv ha sempre soglie = None: per cambiare le soglie da csv serve un file
+/// riferimento_niseci completo
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlainRecordCsvComunitaRiferimentoNISECI {
+    pub codice_specie: String,
+    pub specie_attesa: u32,
+    pub tipo_autoctono: u32,
+    #[serde(default)]
+    pub soglie: Option<SoglieSpecieNISECI>,
+    #[serde(skip)]
+    pub riga_sorgente: Option<RigaSorgente>,
+}
+
+impl RecordCsvSorgente for PlainRecordCsvComunitaRiferimentoNISECI {
+    fn riga_sorgente(&self) -> Option<&RigaSorgente> {
+        self.riga_sorgente.as_ref()
+    }
+    fn set_riga_sorgente(&mut self, riga: RigaSorgente) {
+        self.riga_sorgente = Some(riga);
+    }
+}
+
+impl From<VeryItalianRecordCsvComunitaRiferimentoNISECI>
+    for PlainRecordCsvComunitaRiferimentoNISECI
+{
+    fn from(r: VeryItalianRecordCsvComunitaRiferimentoNISECI) -> Self {
+        Self {
+            codice_specie: r.codice_specie,
+            specie_attesa: r.specie_attesa,
+            tipo_autoctono: r.tipo_autoctono,
+            soglie: None,
+            riga_sorgente: r.riga_sorgente,
         }
-        */
-        Ok(records)
     }
 }
 
-pub fn check_riferimento_niseci_path<T>(
-    path: PathBuf,
+impl RecordCsvComunitaRiferimentoNISECI for PlainRecordCsvComunitaRiferimentoNISECI {
+    fn codice_specie(&self) -> String {
+        self.codice_specie.clone()
+    }
+    fn specie_attesa(&self) -> u32 {
+        self.specie_attesa
+    }
+    fn tipo_autoctono(&self) -> u32 {
+        self.tipo_autoctono
+    }
+    fn soglie(&self) -> Option<&SoglieSpecieNISECI> {
+        self.soglie.as_ref()
+    }
+}
+
+impl fmt::Display for PlainRecordCsvComunitaRiferimentoNISECI {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let string_representation = format!(
+            "RecordCsvComunitaRiferimentoNISECI: {{ codice_specie: [{}], specie_attesa: [{}], tipo_autoctono: [{}] }}",
+              self.codice_specie, self.specie_attesa, self.tipo_autoctono
+        );
+        write!(f, "{}", string_representation)
+    }
+}
+
+impl RecordCsvLeggibile for VeryItalianRecordCsvComunitaRiferimentoNISECI {
+    const TIPO_CSV: TipoRecordCsv = TipoRecordCsv::ComunitaRiferimentoNISECI;
+    const DELIMITATORE: u8 = b';';
+    type VirgolaDecimale = Self;
+}
+
+impl RecordCsvLeggibile for PlainRecordCsvComunitaRiferimentoNISECI {
+    const TIPO_CSV: TipoRecordCsv = TipoRecordCsv::ComunitaRiferimentoNISECI;
+    const DELIMITATORE: u8 = b',';
+    type VirgolaDecimale = VeryItalianRecordCsvComunitaRiferimentoNISECI;
+}
+
+/// Legge un file comunita_riferimento_niseci con il delimitatore del tipo di record.
+/// Per le altre opzioni vedi leggi_csv().
+pub fn check_comunita_riferimento_niseci_reader<R: Read, T>(
+    reader: R,
     has_headers: bool,
-) -> Result<Vec<T>, Vec<csv::Error>>
+) -> Result<(Vec<T>, Diagnostics), Diagnostics>
 where
-    T: RecordCsvRiferimentoNISECI + 'static,
+    T: RecordCsvComunitaRiferimentoNISECI + RecordCsvLeggibile,
 {
-    if !check_path_is_file_ends_with_csv(&path) {
-        eprintln!("Il file {} non è un .csv", path.display());
-        let err = csv::Error::from(Error::other(
-            "Errore riferimento NISECI: il file non è un .csv",
-        ));
-        let err_vec: Vec<csv::Error> = vec![err];
-        return Err(err_vec);
-    }
-    let file = File::open(path).expect("Unable to open file");
-    check_riferimento_niseci_reader(file, has_headers)
+    leggi_csv(reader, &OpzioniLettura::default().intestazione(has_headers))
 }
 
 #[derive(Debug, serde::Deserialize)]
@@ -318,6 +424,17 @@ pub struct VeryItalianRecordCsvCampionamentoNISECI {
     pub lunghezza: u32,
     #[serde(deserialize_with = "deserialize_comma_f32")]
     pub peso: f32,
+    #[serde(skip)]
+    pub riga_sorgente: Option<RigaSorgente>,
+}
+
+impl RecordCsvSorgente for VeryItalianRecordCsvCampionamentoNISECI {
+    fn riga_sorgente(&self) -> Option<&RigaSorgente> {
+        self.riga_sorgente.as_ref()
+    }
+    fn set_riga_sorgente(&mut self, riga: RigaSorgente) {
+        self.riga_sorgente = Some(riga);
+    }
 }
 
 impl RecordCsvCampionamentoNISECI for VeryItalianRecordCsvCampionamentoNISECI {
@@ -361,6 +478,31 @@ pub struct PlainRecordCsvCampionamentoNISECI {
     pub codice_specie: String,
     pub lunghezza: u32,
     pub peso: f32,
+    #[serde(skip)]
+    pub riga_sorgente: Option<RigaSorgente>,
+}
+
+impl RecordCsvSorgente for PlainRecordCsvCampionamentoNISECI {
+    fn riga_sorgente(&self) -> Option<&RigaSorgente> {
+        self.riga_sorgente.as_ref()
+    }
+    fn set_riga_sorgente(&mut self, riga: RigaSorgente) {
+        self.riga_sorgente = Some(riga);
+    }
+}
+
+impl From<VeryItalianRecordCsvCampionamentoNISECI> for PlainRecordCsvCampionamentoNISECI {
+    fn from(r: VeryItalianRecordCsvCampionamentoNISECI) -> Self {
+        Self {
+            data: r.data,
+            stazione: r.stazione,
+            num_passaggio: r.num_passaggio,
+            codice_specie: r.codice_specie,
+            lunghezza: r.lunghezza,
+            peso: r.peso,
+            riga_sorgente: r.riga_sorgente,
+        }
+    }
 }
 
 impl RecordCsvCampionamentoNISECI for PlainRecordCsvCampionamentoNISECI {
@@ -395,98 +537,28 @@ impl fmt::Display for PlainRecordCsvCampionamentoNISECI {
     }
 }
 
-pub fn parse_csv_campionamento_niseci<R, T>(mut rdr: csv::Reader<R>) -> (Vec<T>, Vec<csv::Error>)
-where
-    R: std::io::Read,
-    T: RecordCsvCampionamentoNISECI + 'static,
-{
-    let mut records = Vec::new();
-    let mut errors = Vec::new();
-
-    for result in rdr.deserialize() {
-        match result {
-            Ok(record) => records.push(record),
-            Err(e) => errors.push(e),
-        }
-    }
+impl RecordCsvLeggibile for VeryItalianRecordCsvCampionamentoNISECI {
+    const TIPO_CSV: TipoRecordCsv = TipoRecordCsv::CampionamentoNISECI;
+    const DELIMITATORE: u8 = b';';
+    type VirgolaDecimale = Self;
+}
 
-    (records, errors)
+impl RecordCsvLeggibile for PlainRecordCsvCampionamentoNISECI {
+    const TIPO_CSV: TipoRecordCsv = TipoRecordCsv::CampionamentoNISECI;
+    const DELIMITATORE: u8 = b',';
+    type VirgolaDecimale = VeryItalianRecordCsvCampionamentoNISECI;
 }
 
+/// Legge un file campionamento_niseci con il delimitatore del tipo di record.
+/// Per le altre opzioni vedi leggi_csv().
 pub fn check_campionamento_niseci_reader<R: Read, T>(
     reader: R,
     has_headers: bool,
-) -> Result<Vec<T>, Vec<csv::Error>>
-where
-    T: RecordCsvCampionamentoNISECI + 'static,
-{
-    let normalizing_reader = NormalizerReader::new(reader);
-
-    let type_id = TypeId::of::<T>(); // Get the TypeId of T at runtime
-
-    // Match on the TypeId to determine the actual type of T
-    let delimiter = match type_id {
-        id if id == TypeId::of::<VeryItalianRecordCsvCampionamentoNISECI>() => b';',
-        _ => b',',
-    };
-
-    let rdr = csv::ReaderBuilder::new()
-        .delimiter(delimiter)
-        .has_headers(has_headers)
-        .from_reader(normalizing_reader);
-    let (records, errors) = parse_csv_campionamento_niseci(rdr);
-
-    println!(
-        "Campionamento NISECI: Numero record csv validi: {}",
-        records.len()
-    );
-    println!(
-        "Campionamento NISECI: Numero record csv non validi: {}",
-        errors.len()
-    );
-
-    if !errors.is_empty() {
-        /*
-        for error in &errors {
-            eprintln!("  {}", error);
-        }
-        */
-        let processed_errors = process_csv_errors(&errors, TipoRecordCsv::CampionamentoNISECI);
-        eprintln!("Errori incontrati durante l'elaborazione csv del campionamento NISECI: {{");
-        for e in processed_errors {
-            eprintln!("{e}");
-        }
-        eprintln!("}}");
-        Err(errors)
-    } else {
-        //TODO: handle verbosity
-        //println!("Tutti i record csv del campionamento NISECI sono stati processati con successo!");
-        /*
-        for record in &records {
-            println!("  Record: {{{record}}}");
-        }
-        */
-        Ok(records)
-    }
-}
-
-pub fn check_campionamento_niseci_path<T>(
-    path: PathBuf,
-    has_headers: bool,
-) -> Result<Vec<T>, Vec<csv::Error>>
+) -> Result<(Vec<T>, Diagnostics), Diagnostics>
 where
-    T: RecordCsvCampionamentoNISECI + 'static,
+    T: RecordCsvCampionamentoNISECI + RecordCsvLeggibile,
 {
-    if !check_path_is_file_ends_with_csv(&path) {
-        eprintln!("Il file {} non è un .csv", path.display());
-        let err = csv::Error::from(Error::other(
-            "Errore campionamento NISECI: il file non è un .csv",
-        ));
-        let err_vec: Vec<csv::Error> = vec![err];
-        return Err(err_vec);
-    }
-    let file = File::open(path).expect("Unable to open file");
-    check_campionamento_niseci_reader(file, has_headers)
+    leggi_csv(reader, &OpzioniLettura::default().intestazione(has_headers))
 }
 
 #[derive(Debug, serde::Deserialize)]
@@ -507,6 +579,17 @@ pub struct VeryItalianRecordCsvAnagraficaNISECI {
     pub idro_eco_regione: u32,
     pub area_alpina: u32,
     pub nome_bacino: String,
+    #[serde(skip)]
+    pub riga_sorgente: Option<RigaSorgente>,
+}
+
+impl RecordCsvSorgente for VeryItalianRecordCsvAnagraficaNISECI {
+    fn riga_sorgente(&self) -> Option<&RigaSorgente> {
+        self.riga_sorgente.as_ref()
+    }
+    fn set_riga_sorgente(&mut self, riga: RigaSorgente) {
+        self.riga_sorgente = Some(riga);
+    }
 }
 
 impl RecordCsvAnagraficaNISECI for VeryItalianRecordCsvAnagraficaNISECI {
@@ -593,6 +676,38 @@ pub struct PlainRecordCsvAnagraficaNISECI {
     pub idro_eco_regione: u32,
     pub area_alpina: u32,
     pub nome_bacino: String,
+    #[serde(skip)]
+    pub riga_sorgente: Option<RigaSorgente>,
+}
+
+impl RecordCsvSorgente for PlainRecordCsvAnagraficaNISECI {
+    fn riga_sorgente(&self) -> Option<&RigaSorgente> {
+        self.riga_sorgente.as_ref()
+    }
+    fn set_riga_sorgente(&mut self, riga: RigaSorgente) {
+        self.riga_sorgente = Some(riga);
+    }
+}
+
+impl From<VeryItalianRecordCsvAnagraficaNISECI> for PlainRecordCsvAnagraficaNISECI {
+    fn from(r: VeryItalianRecordCsvAnagraficaNISECI) -> Self {
+        Self {
+            codice_stazione: r.codice_stazione,
+            corpo_idrico: r.corpo_idrico,
+            regione: r.regione,
+            provincia: r.provincia,
+            data: r.data,
+            lunghezza_stazione: r.lunghezza_stazione,
+            larghezza_stazione: r.larghezza_stazione,
+            tipo_comunita: r.tipo_comunita,
+            fonte: r.fonte,
+            numero_protocollo: r.numero_protocollo,
+            idro_eco_regione: r.idro_eco_regione,
+            area_alpina: r.area_alpina,
+            nome_bacino: r.nome_bacino,
+            riga_sorgente: r.riga_sorgente,
+        }
+    }
 }
 
 impl RecordCsvAnagraficaNISECI for PlainRecordCsvAnagraficaNISECI {
@@ -663,96 +778,26 @@ impl fmt::Display for PlainRecordCsvAnagraficaNISECI {
     }
 }
 
-pub fn parse_csv_anagrafica_niseci<R, T>(mut rdr: csv::Reader<R>) -> (Vec<T>, Vec<csv::Error>)
-where
-    R: std::io::Read,
-    T: RecordCsvAnagraficaNISECI,
-{
-    let mut records = Vec::new();
-    let mut errors = Vec::new();
-
-    for result in rdr.deserialize() {
-        match result {
-            Ok(record) => records.push(record),
-            Err(e) => errors.push(e),
-        }
-    }
+impl RecordCsvLeggibile for VeryItalianRecordCsvAnagraficaNISECI {
+    const TIPO_CSV: TipoRecordCsv = TipoRecordCsv::AnagraficaNISECI;
+    const DELIMITATORE: u8 = b';';
+    type VirgolaDecimale = Self;
+}
 
-    (records, errors)
+impl RecordCsvLeggibile for PlainRecordCsvAnagraficaNISECI {
+    const TIPO_CSV: TipoRecordCsv = TipoRecordCsv::AnagraficaNISECI;
+    const DELIMITATORE: u8 = b',';
+    type VirgolaDecimale = VeryItalianRecordCsvAnagraficaNISECI;
 }
 
+/// Legge un file anagrafica_niseci con il delimitatore del tipo di record.
+/// Per le altre opzioni vedi leggi_csv().
 pub fn check_anagrafica_niseci_reader<R: Read, T>(
     reader: R,
     has_headers: bool,
-) -> Result<Vec<T>, Vec<csv::Error>>
-where
-    T: RecordCsvAnagraficaNISECI + 'static,
-{
-    let normalizing_reader = NormalizerReader::new(reader);
-
-    let type_id = TypeId::of::<T>(); // Get the TypeId of T at runtime
-
-    // Match on the TypeId to determine the actual type of T
-    let delimiter = match type_id {
-        id if id == TypeId::of::<VeryItalianRecordCsvAnagraficaNISECI>() => b';',
-        _ => b',',
-    };
-
-    let rdr = csv::ReaderBuilder::new()
-        .delimiter(delimiter)
-        .has_headers(has_headers)
-        .from_reader(normalizing_reader);
-    let (records, errors) = parse_csv_anagrafica_niseci(rdr);
-
-    println!(
-        "Anagrafica NISECI: Numero record csv validi: {}",
-        records.len()
-    );
-    println!(
-        "Anagrafica NISECI: Numero record csv non validi: {}",
-        errors.len()
-    );
-
-    if !errors.is_empty() {
-        /*
-        for error in &errors {
-            eprintln!("  {}", error);
-        }
-        */
-        let processed_errors = process_csv_errors(&errors, TipoRecordCsv::AnagraficaNISECI);
-        eprintln!("Errori incontrati durante l'elaborazione csv dell' anagrafica NISECI: {{");
-        for e in processed_errors {
-            eprintln!("{e}");
-        }
-        eprintln!("}}");
-        Err(errors)
-    } else {
-        //TODO: handle verbosity
-        //println!("Tutti i record csv dell'anagrafica NISECI sono stati processati con successo!");
-        /*
-        for record in &records {
-            println!("  Record: {{{record}}}");
-        }
-        */
-        Ok(records)
-    }
-}
-
-pub fn check_anagrafica_niseci_path<T>(
-    path: PathBuf,
-    has_headers: bool,
-) -> Result<Vec<T>, Vec<csv::Error>>
+) -> Result<(Vec<T>, Diagnostics), Diagnostics>
 where
-    T: RecordCsvAnagraficaNISECI + 'static,
+    T: RecordCsvAnagraficaNISECI + RecordCsvLeggibile,
 {
-    if !check_path_is_file_ends_with_csv(&path) {
-        eprintln!("Il file {} non è un .csv", path.display());
-        let err = csv::Error::from(Error::other(
-            "Errore anagrafica NISECI: il file non è un .csv",
-        ));
-        let err_vec: Vec<csv::Error> = vec![err];
-        return Err(err_vec);
-    }
-    let file = File::open(path).expect("Unable to open file");
-    check_anagrafica_niseci_reader(file, has_headers)
+    leggi_csv(reader, &OpzioniLettura::default().intestazione(has_headers))
 }
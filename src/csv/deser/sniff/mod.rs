@@ -0,0 +1,209 @@
+// SPDX-License-Identifier: GPL-3.0-only
+/*
+    Copyright (C) 2024-2026 jgabaut, gioninjo
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU General Public License as published by
+    the Free Software Foundation, version 3 of the License.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU General Public License for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use crate::csv::deser::colonne::{AliasColonne, MappaturaColonne};
+use crate::csv::deser::encoding::{decodifica, CodificaTesto};
+use crate::csv::TipoRecordCsv;
+use crate::i18n::{Etichetta, Lingua};
+use std::fmt;
+use std::io::{self, Cursor, Read};
+
+/// Quanti byte vengono letti in anticipo per rilevare il formato
+const SNIFF_BYTES: u64 = 16 * 1024;
+/// Quante righe (intestazione compresa) vengono esaminate
+const SNIFF_RIGHE: usize = 20;
+const DELIMITATORI_CANDIDATI: [u8; 3] = [b';', b',', b'\t'];
+const TIPI_RECORD_CSV: [TipoRecordCsv; 8] = [
+    TipoRecordCsv::RiferimentoNISECI,
+    TipoRecordCsv::CampionamentoNISECI,
+    TipoRecordCsv::AnagraficaNISECI,
+    TipoRecordCsv::CampionamentoHFBI,
+    TipoRecordCsv::AnagraficaHFBI,
+    TipoRecordCsv::RiferimentoHFBI,
+    TipoRecordCsv::CondizioniRiferimentoHFBI,
+    TipoRecordCsv::ComunitaRiferimentoNISECI,
+];
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum LocaleCsv {
+    /// `;` come delimitatore, `,` come separatore decimale
+    Italiano,
+    /// `,` come delimitatore, `.` come separatore decimale
+    Internazionale,
+    /// Qualsiasi altra combinazione
+    NonStandard,
+}
+
+impl Etichetta for LocaleCsv {
+    fn etichetta(&self, lingua: Lingua) -> &'static str {
+        match self {
+            LocaleCsv::Italiano => lingua.scegli("Italiano", "Italian"),
+            LocaleCsv::Internazionale => lingua.scegli("Internazionale", "International"),
+            LocaleCsv::NonStandard => lingua.scegli("Non standard", "Non-standard"),
+        }
+    }
+}
+
+impl fmt::Display for LocaleCsv {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let string_representation = self.etichetta(Lingua::default());
+        write!(f, "{}", string_representation)
+    }
+}
+
+/// Formato di un file csv dedotto dall'intestazione e dalle prime righe
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct FormatoRilevato {
+    pub delimitatore: u8,
+    pub separatore_decimale: u8,
+    pub locale: LocaleCsv,
+    /// Tipo di file riconosciuto dall'intestazione, se presente
+    pub tipo_record: Option<TipoRecordCsv>,
+    pub codifica: CodificaTesto,
+}
+
+impl FormatoRilevato {
+    pub fn usa_virgola_decimale(&self) -> bool {
+        self.separatore_decimale == b','
+    }
+
+    /// Descrizione del formato nella lingua richiesta (vedi Display)
+    pub fn descrizione(&self, lingua: Lingua) -> String {
+        let tipo_str = match self.tipo_record {
+            Some(t) => t.etichetta(lingua),
+            None => lingua.scegli("non riconosciuto", "not recognized"),
+        };
+        match lingua {
+            Lingua::Italiano => format!(
+                "locale: {}, delimitatore: {:?}, separatore decimale: {:?}, tipo file: {}, codifica: {}",
+                self.locale.etichetta(lingua),
+                self.delimitatore as char,
+                self.separatore_decimale as char,
+                tipo_str,
+                self.codifica
+            ),
+            Lingua::Inglese => format!(
+                "locale: {}, delimiter: {:?}, decimal separator: {:?}, file type: {}, encoding: {}",
+                self.locale.etichetta(lingua),
+                self.delimitatore as char,
+                self.separatore_decimale as char,
+                tipo_str,
+                self.codifica
+            ),
+        }
+    }
+}
+
+impl fmt::Display for FormatoRilevato {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let string_representation = self.descrizione(Lingua::default());
+        write!(f, "{}", string_representation)
+    }
+}
+
+fn is_decimale(cella: &str, separatore: char) -> bool {
+    let cella = cella.trim().trim_start_matches('-');
+    match cella.split_once(separatore) {
+        Some((intera, decimale)) => {
+            !intera.is_empty()
+                && !decimale.is_empty()
+                && intera.chars().all(|c| c.is_ascii_digit())
+                && decimale.chars().all(|c| c.is_ascii_digit())
+        }
+        None => false,
+    }
+}
+
+/// Riconosce il tipo di file dai nomi delle colonne dell'intestazione (vedi MappaturaColonne)
+pub fn sniff_tipo_record(intestazione: &[&str]) -> Option<TipoRecordCsv> {
+    let alias = AliasColonne::default();
+    TIPI_RECORD_CSV
+        .iter()
+        .filter(|t| MappaturaColonne::new(**t, intestazione, &alias).is_completa())
+        .max_by_key(|t| t.header_fields().len())
+        .copied()
+}
+
+/// Deduce delimitatore, separatore decimale e tipo di file da un campione dell'input
+pub fn sniff_formato(campione: &[u8]) -> FormatoRilevato {
+    let (testo, codifica) = decodifica(campione);
+    let righe: Vec<&str> = testo
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .take(SNIFF_RIGHE)
+        .collect();
+
+    // Il delimitatore scelto è quello più frequente nell'intestazione, a parità quello
+    // che si ripete con lo stesso numero di occorrenze in tutte le righe
+    let mut delimitatore = b';';
+    let mut miglior_punteggio = (0, false);
+    if let Some(intestazione) = righe.first() {
+        for d in DELIMITATORI_CANDIDATI {
+            let conta = |l: &str| l.bytes().filter(|b| *b == d).count();
+            let occorrenze = conta(intestazione);
+            let costante = righe.iter().all(|l| conta(l) == occorrenze);
+            let punteggio = (occorrenze, costante);
+            if occorrenze > 0 && punteggio > miglior_punteggio {
+                miglior_punteggio = punteggio;
+                delimitatore = d;
+            }
+        }
+    }
+
+    let celle: Vec<&str> = righe
+        .iter()
+        .skip(1)
+        .flat_map(|l| l.split(delimitatore as char))
+        .collect();
+    let separatore_decimale = if delimitatore == b',' {
+        b'.'
+    } else if celle.iter().any(|c| is_decimale(c, ',')) {
+        b','
+    } else if celle.iter().any(|c| is_decimale(c, '.')) {
+        b'.'
+    } else {
+        b','
+    };
+
+    let locale = match (delimitatore, separatore_decimale) {
+        (b';', b',') => LocaleCsv::Italiano,
+        (b',', b'.') => LocaleCsv::Internazionale,
+        _ => LocaleCsv::NonStandard,
+    };
+
+    let tipo_record = righe.first().and_then(|intestazione| {
+        let campi: Vec<&str> = intestazione.split(delimitatore as char).collect();
+        sniff_tipo_record(&campi)
+    });
+
+    FormatoRilevato {
+        delimitatore,
+        separatore_decimale,
+        locale,
+        tipo_record,
+        codifica,
+    }
+}
+
+/// Legge l'inizio dell'input per rilevarne il formato.
+/// Restituisce un reader equivalente a quello originale, da cui non è stato consumato nulla.
+pub fn sniff_reader<R: Read>(mut reader: R) -> io::Result<(FormatoRilevato, impl Read)> {
+    let mut campione = Vec::new();
+    (&mut reader).take(SNIFF_BYTES).read_to_end(&mut campione)?;
+    let formato = sniff_formato(&campione);
+    Ok((formato, Cursor::new(campione).chain(reader)))
+}
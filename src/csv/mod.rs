@@ -15,8 +15,14 @@
     along with this program.  If not, see <https://www.gnu.org/licenses/>.
 */
 
+use crate::domain::niseci::catalogo::SoglieSpecieNISECI;
+use crate::error::Posizione;
+use crate::i18n::{Etichetta, Lingua};
+use std::sync::Arc;
+
 pub mod deser;
 pub mod parser;
+pub mod ser;
 
 // This must be kept aligned with RecordCsvRiferimentoNISECI definition.
 // TODO: get this stuff with some macro?
@@ -46,6 +52,14 @@ pub const RIFERIMENTO_NISECI_HEADER_FIELD_TYPES: [&str; 17] = [
 pub const RIFERIMENTO_NISECI_HEADER: &str = "\
 nomeComune;nomeLatino;codiceSpecie;origine;tipoAutoctono;alloNocivita;specieAttesa;clSoglia1;clSoglia2;clSoglia3;clSoglia4;adJuvSoglia1;adJuvSoglia2;adJuvSoglia3;adJuvSoglia4;densSoglia1;densSoglia2";
 
+// This must be kept aligned with RecordCsvComunitaRiferimentoNISECI definition.
+// TODO: get this stuff with some macro?
+pub const COMUNITA_RIFERIMENTO_NISECI_HEADER_FIELDS: [&str; 3] =
+    ["codiceSpecie", "specieAttesa", "tipoAutoctono"];
+pub const COMUNITA_RIFERIMENTO_NISECI_HEADER_FIELD_TYPES: [&str; 3] = ["String", "u32", "u32"];
+pub const COMUNITA_RIFERIMENTO_NISECI_HEADER: &str = "\
+codiceSpecie;specieAttesa;tipoAutoctono";
+
 // This must be kept aligned with RecordCsvCampionamentoNISECI definition.
 // TODO: get this stuff with some macro?
 pub const CAMPIONAMENTO_NISECI_HEADER_FIELDS: [&str; 6] = [
@@ -85,6 +99,46 @@ pub const ANAGRAFICA_NISECI_HEADER_FIELD_TYPES: [&str; 13] = [
 pub const ANAGRAFICA_NISECI_HEADER: &str = "\
 codiceStazione;corpoIdrico;regione;provincia;data;lunghezzaStazione;larghezzaStazione;tipoComunita;fonte;numeroProtocollo;idroEcoRegione;areaAlpina;nomeBacino";
 
+// This must be kept aligned with RecordCsvRiferimentoHFBI definition.
+// TODO: get this stuff with some macro?
+pub const RIFERIMENTO_HFBI_HEADER_FIELDS: [&str; 11] = [
+    "nomeComune",
+    "codiceSpecie",
+    "autoctono",
+    "gruppoEco",
+    "microbentivori",
+    "macrobentivori",
+    "iperbentivori",
+    "erbivori",
+    "detritivori",
+    "planctivori",
+    "onnivori",
+];
+pub const RIFERIMENTO_HFBI_HEADER_FIELD_TYPES: [&str; 11] = [
+    "String", "String", "u32", "u32", "f32", "f32", "f32", "f32", "f32", "f32", "f32",
+];
+pub const RIFERIMENTO_HFBI_HEADER: &str = "\
+nomeComune;codiceSpecie;autoctono;gruppoEco;microbentivori;macrobentivori;iperbentivori;erbivori;detritivori;planctivori;onnivori";
+
+// This must be kept aligned with RecordCsvCondizioniRiferimentoHFBI definition.
+// TODO: get this stuff with some macro?
+pub const CONDIZIONI_RIFERIMENTO_HFBI_HEADER_FIELDS: [&str; 9] = [
+    "tipoLaguna",
+    "stagione",
+    "habitat",
+    "bn",
+    "ddom",
+    "dmig",
+    "bbent",
+    "dbent",
+    "dhzp",
+];
+pub const CONDIZIONI_RIFERIMENTO_HFBI_HEADER_FIELD_TYPES: [&str; 9] = [
+    "u32", "u32", "u32", "f32", "f32", "f32", "f32", "f32", "f32",
+];
+pub const CONDIZIONI_RIFERIMENTO_HFBI_HEADER: &str = "\
+tipoLaguna;stagione;habitat;bn;ddom;dmig;bbent;dbent;dhzp";
+
 // This must be kept aligned with RecordCsvCampionamentoHFBI definition.
 // TODO: get this stuff with some macro?
 pub const CAMPIONAMENTO_HFBI_HEADER_FIELDS: [&str; 3] = ["codiceSpecie", "numeroIndividui", "peso"];
@@ -112,16 +166,120 @@ pub const ANAGRAFICA_HFBI_HEADER_FIELD_TYPES: [&str; 10] = [
 pub const ANAGRAFICA_HFBI_HEADER: &str = "\
 codiceStazione;corpoIdrico;regione;provincia;data;lunghezzaStazione;larghezzaStazione;stagione;habitat;tipoLaguna";
 
-#[derive(Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum TipoRecordCsv {
     RiferimentoNISECI,
     CampionamentoNISECI,
     AnagraficaNISECI,
     CampionamentoHFBI,
     AnagraficaHFBI,
+    RiferimentoHFBI,
+    CondizioniRiferimentoHFBI,
+    ComunitaRiferimentoNISECI,
+}
+
+impl TipoRecordCsv {
+    pub fn header_fields(&self) -> &'static [&'static str] {
+        match self {
+            TipoRecordCsv::RiferimentoNISECI => &RIFERIMENTO_NISECI_HEADER_FIELDS,
+            TipoRecordCsv::CampionamentoNISECI => &CAMPIONAMENTO_NISECI_HEADER_FIELDS,
+            TipoRecordCsv::AnagraficaNISECI => &ANAGRAFICA_NISECI_HEADER_FIELDS,
+            TipoRecordCsv::CampionamentoHFBI => &CAMPIONAMENTO_HFBI_HEADER_FIELDS,
+            TipoRecordCsv::AnagraficaHFBI => &ANAGRAFICA_HFBI_HEADER_FIELDS,
+            TipoRecordCsv::RiferimentoHFBI => &RIFERIMENTO_HFBI_HEADER_FIELDS,
+            TipoRecordCsv::CondizioniRiferimentoHFBI => &CONDIZIONI_RIFERIMENTO_HFBI_HEADER_FIELDS,
+            TipoRecordCsv::ComunitaRiferimentoNISECI => &COMUNITA_RIFERIMENTO_NISECI_HEADER_FIELDS,
+        }
+    }
+    pub fn header_field_types(&self) -> &'static [&'static str] {
+        match self {
+            TipoRecordCsv::RiferimentoNISECI => &RIFERIMENTO_NISECI_HEADER_FIELD_TYPES,
+            TipoRecordCsv::CampionamentoNISECI => &CAMPIONAMENTO_NISECI_HEADER_FIELD_TYPES,
+            TipoRecordCsv::AnagraficaNISECI => &ANAGRAFICA_NISECI_HEADER_FIELD_TYPES,
+            TipoRecordCsv::CampionamentoHFBI => &CAMPIONAMENTO_HFBI_HEADER_FIELD_TYPES,
+            TipoRecordCsv::AnagraficaHFBI => &ANAGRAFICA_HFBI_HEADER_FIELD_TYPES,
+            TipoRecordCsv::RiferimentoHFBI => &RIFERIMENTO_HFBI_HEADER_FIELD_TYPES,
+            TipoRecordCsv::CondizioniRiferimentoHFBI => {
+                &CONDIZIONI_RIFERIMENTO_HFBI_HEADER_FIELD_TYPES
+            }
+            TipoRecordCsv::ComunitaRiferimentoNISECI => {
+                &COMUNITA_RIFERIMENTO_NISECI_HEADER_FIELD_TYPES
+            }
+        }
+    }
+}
+
+impl Etichetta for TipoRecordCsv {
+    fn etichetta(&self, lingua: Lingua) -> &'static str {
+        match self {
+            TipoRecordCsv::RiferimentoNISECI => {
+                lingua.scegli("riferimento NISECI", "NISECI reference")
+            }
+            TipoRecordCsv::CampionamentoNISECI => {
+                lingua.scegli("campionamento NISECI", "NISECI sampling")
+            }
+            TipoRecordCsv::AnagraficaNISECI => {
+                lingua.scegli("anagrafica NISECI", "NISECI station data")
+            }
+            TipoRecordCsv::CampionamentoHFBI => {
+                lingua.scegli("campionamento HFBI", "HFBI sampling")
+            }
+            TipoRecordCsv::AnagraficaHFBI => lingua.scegli("anagrafica HFBI", "HFBI station data"),
+            TipoRecordCsv::RiferimentoHFBI => lingua.scegli("riferimento HFBI", "HFBI reference"),
+            TipoRecordCsv::CondizioniRiferimentoHFBI => lingua.scegli(
+                "condizioni di riferimento HFBI",
+                "HFBI reference conditions",
+            ),
+            TipoRecordCsv::ComunitaRiferimentoNISECI => lingua.scegli(
+                "comunità di riferimento NISECI",
+                "NISECI reference community",
+            ),
+        }
+    }
+}
+
+impl std::fmt::Display for TipoRecordCsv {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let string_representation = self.etichetta(Lingua::default());
+        write!(f, "{}", string_representation)
+    }
 }
 
-pub trait RecordCsvRiferimentoNISECI: serde::de::DeserializeOwned {
+/// Riga del file csv da cui è stato letto un record
+#[derive(Debug, Clone, PartialEq)]
+pub struct RigaSorgente {
+    pub tipo_csv: TipoRecordCsv,
+    /// Posizione fisica della riga nel file
+    pub posizione: Option<Posizione>,
+    /// Celle grezze, nell'ordine di TipoRecordCsv::header_fields()
+    pub celle: Vec<String>,
+    /// Per ogni campo, l'indice (da 0) della colonna corrispondente nel file
+    pub colonne: Arc<[Option<usize>]>,
+}
+
+impl RigaSorgente {
+    /// Valore grezzo e indice di colonna nel file di un campo (es. "codiceSpecie")
+    pub fn cella(&self, campo: &str) -> Option<(&str, Option<usize>)> {
+        let i = self
+            .tipo_csv
+            .header_fields()
+            .iter()
+            .position(|c| *c == campo)?;
+        let valore = self.celle.get(i)?;
+        Some((valore, self.colonne.get(i).copied().flatten()))
+    }
+}
+
+/// Record che conosce la riga del file da cui è stato letto.
+/// L'implementazione di default non conserva nulla.
+pub trait RecordCsvSorgente {
+    fn riga_sorgente(&self) -> Option<&RigaSorgente> {
+        None
+    }
+    fn set_riga_sorgente(&mut self, _riga: RigaSorgente) {}
+}
+
+pub trait RecordCsvRiferimentoNISECI: serde::de::DeserializeOwned + RecordCsvSorgente {
     #[allow(dead_code)]
     fn nome_comune(&self) -> String;
     fn nome_latino(&self) -> String;
@@ -142,10 +300,22 @@ pub trait RecordCsvRiferimentoNISECI: serde::de::DeserializeOwned {
     fn dens_soglia2(&self) -> f32;
 }
 
-pub trait RecordCsvCampionamentoNISECI: serde::de::DeserializeOwned {
-    #[allow(dead_code)]
+/// Specie di una comunità di riferimento scelta da CATALOGO_NISECI.
+/// Le soglie sostitutive si possono indicare solo nell'input JSON (campo "soglie"): il file csv
+/// non ha colonne per le soglie.
+pub trait RecordCsvComunitaRiferimentoNISECI:
+    serde::de::DeserializeOwned + RecordCsvSorgente
+{
+    fn codice_specie(&self) -> String;
+    fn specie_attesa(&self) -> u32;
+    fn tipo_autoctono(&self) -> u32;
+    fn soglie(&self) -> Option<&SoglieSpecieNISECI> {
+        None
+    }
+}
+
+pub trait RecordCsvCampionamentoNISECI: serde::de::DeserializeOwned + RecordCsvSorgente {
     fn data(&self) -> String;
-    #[allow(dead_code)]
     fn stazione(&self) -> String;
     fn num_passaggio(&self) -> u32;
     fn codice_specie(&self) -> String;
@@ -153,7 +323,7 @@ pub trait RecordCsvCampionamentoNISECI: serde::de::DeserializeOwned {
     fn peso(&self) -> f32;
 }
 
-pub trait RecordCsvAnagraficaNISECI: serde::de::DeserializeOwned {
+pub trait RecordCsvAnagraficaNISECI: serde::de::DeserializeOwned + RecordCsvSorgente {
     fn codice_stazione(&self) -> String;
     fn corpo_idrico(&self) -> String;
     fn regione(&self) -> String;
@@ -169,13 +339,41 @@ pub trait RecordCsvAnagraficaNISECI: serde::de::DeserializeOwned {
     fn nome_bacino(&self) -> String;
 }
 
-pub trait RecordCsvCampionamentoHFBI: serde::de::DeserializeOwned {
+pub trait RecordCsvRiferimentoHFBI: serde::de::DeserializeOwned + RecordCsvSorgente {
+    fn nome_comune(&self) -> String;
+    fn codice_specie(&self) -> String;
+    fn autoctono(&self) -> u32;
+    fn gruppo_eco(&self) -> u32;
+    fn microbentivori(&self) -> f32;
+    fn macrobentivori(&self) -> f32;
+    fn iperbentivori(&self) -> f32;
+    fn erbivori(&self) -> f32;
+    fn detritivori(&self) -> f32;
+    fn planctivori(&self) -> f32;
+    fn onnivori(&self) -> f32;
+}
+
+pub trait RecordCsvCondizioniRiferimentoHFBI:
+    serde::de::DeserializeOwned + RecordCsvSorgente
+{
+    fn tipo_laguna(&self) -> u32;
+    fn stagione(&self) -> u32;
+    fn habitat(&self) -> u32;
+    fn bn(&self) -> f32;
+    fn ddom(&self) -> f32;
+    fn dmig(&self) -> f32;
+    fn bbent(&self) -> f32;
+    fn dbent(&self) -> f32;
+    fn dhzp(&self) -> f32;
+}
+
+pub trait RecordCsvCampionamentoHFBI: serde::de::DeserializeOwned + RecordCsvSorgente {
     fn codice_specie(&self) -> String;
     fn numero_individui(&self) -> u32;
     fn peso(&self) -> f32;
 }
 
-pub trait RecordCsvAnagraficaHFBI: serde::de::DeserializeOwned {
+pub trait RecordCsvAnagraficaHFBI: serde::de::DeserializeOwned + RecordCsvSorgente {
     fn codice_stazione(&self) -> String;
     fn corpo_idrico(&self) -> String;
     fn regione(&self) -> String;
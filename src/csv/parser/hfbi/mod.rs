@@ -15,86 +15,425 @@
     along with this program.  If not, see <https://www.gnu.org/licenses/>.
 */
 
-use crate::csv::parser::parse_date;
-use crate::csv::{RecordCsvAnagraficaHFBI, RecordCsvCampionamentoHFBI};
+use crate::csv::parser::{
+    arricchisci_errori, arricchisci_errori_record, cerca_specie, parse_date, OpzioniVerifica,
+};
+use crate::csv::{
+    RecordCsvAnagraficaHFBI, RecordCsvCampionamentoHFBI, RecordCsvCondizioniRiferimentoHFBI,
+    RecordCsvRiferimentoHFBI, TipoRecordCsv,
+};
+use crate::diagnostics::Diagnostics;
 use crate::domain::hfbi::{
-    AnagraficaHFBI, HabitatHFBI, RecordHFBI, StagioneHFBI, TipoLagunaCostieraHFBI, RIFERIMENTO_HFBI,
+    elenco_riferimento_hfbi, indice_riferimento_hfbi, AnagraficaHFBI, CondizioniRiferimentoHFBI,
+    CondizioniRiferimentoKeyHFBI, GruppoEcoHFBI, GruppoTrofHFBI, HabitatHFBI, RecordHFBI,
+    RiferimentoHFBI, SpecieHFBI, StagioneHFBI, TabellaCondizioniRiferimentoHFBI,
+    TipoLagunaCostieraHFBI,
 };
 use crate::domain::location::Location;
-use chrono::format::ParseErrorKind;
-use std::fmt;
+use crate::domain::registro::RegistroStazioni;
+use crate::domain::specie::{CorrezioniSpecie, IndiceSpecie};
+use crate::error::{ContestoErrore, Error};
+use crate::i18n::Messaggio;
+use std::borrow::Cow;
+
+/// Scarto ammesso sulla somma delle frazioni trofiche di una specie
+const TOLLERANZA_SOMMA_FRAZIONI: f32 = 1e-3;
+
+pub fn parse_recordcsv_riferimento_hfbi<T: RecordCsvRiferimentoHFBI>(
+    records: Vec<T>,
+) -> (Vec<SpecieHFBI>, Vec<Error>) {
+    let mut specie = Vec::new();
+    let mut errors = Vec::new();
+    let mut idx = 0;
+    let mut indice = IndiceSpecie::new(); // Rileva i codici ripetuti
+    for r in &records {
+        idx += 1;
+        let codice_specie = r.codice_specie().trim().to_string();
+        if codice_specie.is_empty() {
+            let err = Error::ValoreInvalido(
+                ContestoErrore::da_messaggio(Messaggio::CodiceSpecieVuoto)
+                    .tipo_csv(TipoRecordCsv::RiferimentoHFBI)
+                    .record(idx)
+                    .campo("codiceSpecie")
+                    .valore(r.codice_specie())
+                    .into(),
+            );
+            errors.push(err);
+            continue;
+        }
+
+        if indice.posizione_codice(&codice_specie).is_some() {
+            let err = Error::ValoreInvalido(
+                ContestoErrore::da_messaggio(Messaggio::CodiceSpecieRidefinito)
+                    .tipo_csv(TipoRecordCsv::RiferimentoHFBI)
+                    .record(idx)
+                    .campo("codiceSpecie")
+                    .valore(r.codice_specie())
+                    .into(),
+            );
+            errors.push(err);
+            continue;
+        }
+
+        let autoctono = match r.autoctono() {
+            0 => false,
+            1 => true,
+            _ => {
+                let err = Error::ValoreInvalido(
+                    ContestoErrore::da_messaggio(Messaggio::CodiceFuoriIntervallo {
+                        tipo: "autoctono",
+                        valore: r.autoctono().to_string(),
+                        min: 0,
+                        max: 1,
+                    })
+                    .tipo_csv(TipoRecordCsv::RiferimentoHFBI)
+                    .record(idx)
+                    .campo("autoctono")
+                    .valore(r.autoctono())
+                    .into(),
+                );
+                errors.push(err);
+                continue;
+            }
+        };
 
-#[derive(Debug)]
-pub enum RecordCsvCampionamentoHFBIError {
-    ValoreInvalido { msg: String }, //TODO: add position?
+        let gruppo_eco = match r.gruppo_eco() {
+            0 => GruppoEcoHFBI::MigratoriMarini,
+            1 => GruppoEcoHFBI::Diadromi,
+            2 => GruppoEcoHFBI::ResidentiDiEstuario,
+            3 => GruppoEcoHFBI::OccasionaliMarini,
+            4 => GruppoEcoHFBI::OccasionaliDiAcqueDolci,
+            _ => {
+                let err = Error::ValoreInvalido(
+                    ContestoErrore::da_messaggio(Messaggio::CodiceFuoriIntervallo {
+                        tipo: "GruppoEcoHFBI",
+                        valore: r.gruppo_eco().to_string(),
+                        min: 0,
+                        max: 4,
+                    })
+                    .tipo_csv(TipoRecordCsv::RiferimentoHFBI)
+                    .record(idx)
+                    .campo("gruppoEco")
+                    .valore(r.gruppo_eco())
+                    .into(),
+                );
+                errors.push(err);
+                continue;
+            }
+        };
+
+        let gruppo_trofico = GruppoTrofHFBI {
+            microbentivori: r.microbentivori(),
+            macrobentivori: r.macrobentivori(),
+            iperbentivori: r.iperbentivori(),
+            erbivori: r.erbivori(),
+            detritivori: r.detritivori(),
+            planctivori: r.planctivori(),
+            onnivori: r.onnivori(),
+        };
+        let fuori_intervallo: Vec<Error> = gruppo_trofico
+            .frazioni()
+            .into_iter()
+            .filter(|(_, v)| !(0.0..=1.0).contains(v))
+            .map(|(campo, v)| {
+                Error::ValoreInvalido(
+                    ContestoErrore::da_messaggio(Messaggio::FrazioneTroficaNonValida {
+                        campo,
+                        valore: v.to_string(),
+                    })
+                    .tipo_csv(TipoRecordCsv::RiferimentoHFBI)
+                    .record(idx)
+                    .campo(campo)
+                    .valore(v)
+                    .into(),
+                )
+            })
+            .collect();
+        if !fuori_intervallo.is_empty() {
+            errors.extend(fuori_intervallo);
+            continue;
+        }
+        let somma = gruppo_trofico.somma();
+        if (somma - 1.0).abs() > TOLLERANZA_SOMMA_FRAZIONI {
+            let err = Error::ValoreInvalido(
+                ContestoErrore::da_messaggio(Messaggio::SommaFrazioniTroficheNonValida(
+                    somma.to_string(),
+                ))
+                .tipo_csv(TipoRecordCsv::RiferimentoHFBI)
+                .record(idx)
+                .into(),
+            );
+            errors.push(err);
+            continue;
+        }
+
+        let specie_rec = SpecieHFBI {
+            nome_comune: Cow::Owned(r.nome_comune().trim().to_string()),
+            codice_specie: Cow::Owned(codice_specie),
+            autoctono,
+            gruppo_eco,
+            gruppo_trofico,
+        };
+        let _ = indice.inserisci(specie.len(), &specie_rec);
+        specie.push(specie_rec);
+    }
+
+    let errors = arricchisci_errori(errors, &records);
+    (specie, errors)
 }
 
-impl fmt::Display for RecordCsvCampionamentoHFBIError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let string_representation = match self {
-            RecordCsvCampionamentoHFBIError::ValoreInvalido { msg } => {
-                format!("Errore record campionamento HFBI: {}", msg)
+/// Legge una tabella di condizioni di riferimento. Oltre agli errori dei singoli record,
+/// riporta le combinazioni di tipo di laguna, stagione e habitat rimaste senza condizioni
+pub fn parse_recordcsv_condizioni_riferimento_hfbi<T: RecordCsvCondizioniRiferimentoHFBI>(
+    records: Vec<T>,
+) -> (TabellaCondizioniRiferimentoHFBI, Vec<Error>) {
+    let mut tabella = TabellaCondizioniRiferimentoHFBI::vuota();
+    let mut errors = Vec::new();
+    let mut idx = 0;
+    for r in &records {
+        idx += 1;
+        let mut record_errors = Vec::new();
+
+        let tipo_laguna = match r.tipo_laguna() {
+            1 => Some(TipoLagunaCostieraHFBI::MAt1),
+            2 => Some(TipoLagunaCostieraHFBI::MAt2),
+            3 => Some(TipoLagunaCostieraHFBI::MAt3),
+            _ => {
+                record_errors.push(Error::ValoreInvalido(
+                    ContestoErrore::da_messaggio(Messaggio::CodiceFuoriIntervallo {
+                        tipo: "TipoLagunaCostieraHFBI",
+                        valore: r.tipo_laguna().to_string(),
+                        min: 1,
+                        max: 3,
+                    })
+                    .tipo_csv(TipoRecordCsv::CondizioniRiferimentoHFBI)
+                    .record(idx)
+                    .campo("tipoLaguna")
+                    .valore(r.tipo_laguna())
+                    .into(),
+                ));
+                None
+            }
+        };
+
+        let stagione = match r.stagione() {
+            0 => Some(StagioneHFBI::Primavera),
+            1 => Some(StagioneHFBI::Autunno),
+            _ => {
+                record_errors.push(Error::ValoreInvalido(
+                    ContestoErrore::da_messaggio(Messaggio::CodiceFuoriIntervallo {
+                        tipo: "StagioneHFBI",
+                        valore: r.stagione().to_string(),
+                        min: 0,
+                        max: 1,
+                    })
+                    .tipo_csv(TipoRecordCsv::CondizioniRiferimentoHFBI)
+                    .record(idx)
+                    .campo("stagione")
+                    .valore(r.stagione())
+                    .into(),
+                ));
+                None
             }
         };
-        write!(f, "{}", string_representation)
+
+        let habitat = match r.habitat() {
+            0 => Some(HabitatHFBI::Vegetato),
+            1 => Some(HabitatHFBI::NonVegetato),
+            _ => {
+                record_errors.push(Error::ValoreInvalido(
+                    ContestoErrore::da_messaggio(Messaggio::CodiceFuoriIntervallo {
+                        tipo: "HabitatHFBI",
+                        valore: r.habitat().to_string(),
+                        min: 0,
+                        max: 1,
+                    })
+                    .tipo_csv(TipoRecordCsv::CondizioniRiferimentoHFBI)
+                    .record(idx)
+                    .campo("habitat")
+                    .valore(r.habitat())
+                    .into(),
+                ));
+                None
+            }
+        };
+
+        let condizioni = CondizioniRiferimentoHFBI {
+            bn: r.bn(),
+            ddom: r.ddom(),
+            dmig: r.dmig(),
+            bbent: r.bbent(),
+            dbent: r.dbent(),
+            dhzp: r.dhzp(),
+        };
+        // Le metriche vengono divise per questi valori
+        for (campo, valore) in [
+            ("bn", condizioni.bn),
+            ("ddom", condizioni.ddom),
+            ("dmig", condizioni.dmig),
+            ("bbent", condizioni.bbent),
+            ("dbent", condizioni.dbent),
+            ("dhzp", condizioni.dhzp),
+        ] {
+            if !(valore.is_finite() && valore > 0.0) {
+                record_errors.push(Error::ValoreInvalido(
+                    ContestoErrore::da_messaggio(Messaggio::CondizioneRiferimentoNonValida {
+                        campo,
+                        valore: valore.to_string(),
+                    })
+                    .tipo_csv(TipoRecordCsv::CondizioniRiferimentoHFBI)
+                    .record(idx)
+                    .campo(campo)
+                    .valore(valore)
+                    .into(),
+                ));
+            }
+        }
+
+        match (tipo_laguna, stagione, habitat) {
+            (Some(tipo_laguna), Some(stagione), Some(habitat_vegetato))
+                if record_errors.is_empty() =>
+            {
+                let chiave = CondizioniRiferimentoKeyHFBI {
+                    tipo_laguna,
+                    stagione,
+                    habitat_vegetato,
+                };
+                if tabella.get(&chiave).is_some() {
+                    errors.push(Error::ValoreInvalido(
+                        ContestoErrore::da_messaggio(Messaggio::CondizioniRiferimentoDuplicate(
+                            chiave,
+                        ))
+                        .tipo_csv(TipoRecordCsv::CondizioniRiferimentoHFBI)
+                        .record(idx)
+                        .into(),
+                    ));
+                    continue;
+                }
+                tabella.inserisci(chiave, condizioni);
+            }
+            _ => errors.extend(record_errors),
+        }
     }
+
+    let mancanti = tabella.mancanti();
+    if !mancanti.is_empty() {
+        errors.push(Error::NumeroRecord(
+            ContestoErrore::da_messaggio(Messaggio::CondizioniRiferimentoIncomplete(mancanti))
+                .tipo_csv(TipoRecordCsv::CondizioniRiferimentoHFBI)
+                .into(),
+        ));
+    }
+
+    let errors = arricchisci_errori(errors, &records);
+    (tabella, errors)
 }
 
 pub fn parse_recordcsv_campionamento_hfbi<T: RecordCsvCampionamentoHFBI>(
     records: Vec<T>,
-) -> (Vec<RecordHFBI>, Vec<RecordCsvCampionamentoHFBIError>) {
+) -> (Vec<RecordHFBI>, Vec<Error>) {
+    let (campioni, errors, _) = parse_recordcsv_campionamento_hfbi_corretto(
+        records,
+        elenco_riferimento_hfbi(),
+        indice_riferimento_hfbi(),
+        None,
+    );
+    (campioni, errors)
+}
+
+/// Come parse_recordcsv_campionamento_hfbi(), cercando le specie nell'elenco indicato
+/// invece che in RIFERIMENTO_HFBI
+pub fn parse_recordcsv_campionamento_hfbi_with_riferimento<T: RecordCsvCampionamentoHFBI>(
+    records: Vec<T>,
+    riferimento: &RiferimentoHFBI,
+) -> (Vec<RecordHFBI>, Vec<Error>) {
+    let indice = riferimento.indice();
+    let (campioni, errors, _) = parse_recordcsv_campionamento_hfbi_corretto(
+        records,
+        &riferimento.elenco_specie,
+        &indice,
+        None,
+    );
+    (campioni, errors)
+}
+
+/// Come parse_recordcsv_campionamento_hfbi_with_riferimento(), applicando le correzioni confermate
+/// ai codici specie sconosciuti. Restituisce anche le sostituzioni effettuate
+fn parse_recordcsv_campionamento_hfbi_corretto<T: RecordCsvCampionamentoHFBI>(
+    records: Vec<T>,
+    riferimento_specie: &[SpecieHFBI],
+    indice: &IndiceSpecie,
+    correzioni: Option<&CorrezioniSpecie>,
+) -> (Vec<RecordHFBI>, Vec<Error>, Vec<Messaggio>) {
+    let mut sostituzioni = Vec::new();
     let mut campioni = Vec::new();
     let mut errors = Vec::new();
     let mut idx = 0;
-    for r in records {
+    for r in &records {
         idx += 1;
         if r.codice_specie().is_empty() {
-            let err = RecordCsvCampionamentoHFBIError::ValoreInvalido {
-                msg: format!("Record {idx}: codice_specie non valido (lunghezza < 1)"),
-            };
+            let err = Error::ValoreInvalido(
+                ContestoErrore::da_messaggio(Messaggio::CodiceSpecieVuoto)
+                    .tipo_csv(TipoRecordCsv::CampionamentoHFBI)
+                    .record(idx)
+                    .campo("codiceSpecie")
+                    .valore(r.codice_specie())
+                    .into(),
+            );
             errors.push(err);
             continue;
         }
         let codice_specie = r.codice_specie();
-        let mut opt_matched_specie = None;
-        for s in &RIFERIMENTO_HFBI {
-            // FIXME: this is O(n^2).
-            if s.codice_specie == codice_specie {
-                opt_matched_specie = Some(s);
-                break; // TODO: mmmh
-            }
-        }
-
-        let matched_specie;
-        if let Some(specie) = opt_matched_specie {
-            matched_specie = specie;
-        } else {
-            let err = RecordCsvCampionamentoHFBIError::ValoreInvalido {
-                msg: format!(
-                    "Record {idx}: codice_specie non valido (non presente nel riferimento): {}",
-                    codice_specie
-                ),
+        let matched_specie =
+            match cerca_specie(indice, riferimento_specie, &codice_specie, correzioni) {
+                Ok((specie, None)) => specie,
+                Ok((specie, Some(corretto))) => {
+                    sostituzioni.push(Messaggio::CodiceSpecieCorretto {
+                        record: idx,
+                        errato: codice_specie.to_string(),
+                        corretto,
+                    });
+                    specie
+                }
+                Err(messaggio) => {
+                    let err = Error::ValoreInvalido(
+                        ContestoErrore::da_messaggio(messaggio)
+                            .tipo_csv(TipoRecordCsv::CampionamentoHFBI)
+                            .record(idx)
+                            .campo("codiceSpecie")
+                            .valore(codice_specie)
+                            .into(),
+                    );
+                    errors.push(err);
+                    continue;
+                }
             };
-            errors.push(err);
-            continue;
-        }
 
         //TODO: update this abomination when records change to have an integer directly
         if r.numero_individui() < 1 {
-            let err = RecordCsvCampionamentoHFBIError::ValoreInvalido {
-                msg: format!(
-                    "Record {idx}: numero_individui non valido (<1): {}",
-                    r.numero_individui()
-                ),
-            };
+            let err = Error::ValoreInvalido(
+                ContestoErrore::da_messaggio(Messaggio::NumeroIndividuiNonValido(
+                    r.numero_individui().to_string(),
+                ))
+                .tipo_csv(TipoRecordCsv::CampionamentoHFBI)
+                .record(idx)
+                .campo("numeroIndividui")
+                .valore(r.numero_individui())
+                .into(),
+            );
             errors.push(err);
             continue;
         }
 
         if !r.peso().is_finite() {
-            let err = RecordCsvCampionamentoHFBIError::ValoreInvalido {
-                msg: format!("Record {idx}: peso non valido (not finite): {}", r.peso()),
-            };
+            let err = Error::ValoreInvalido(
+                ContestoErrore::da_messaggio(Messaggio::PesoNonValido(r.peso().to_string()))
+                    .tipo_csv(TipoRecordCsv::CampionamentoHFBI)
+                    .record(idx)
+                    .campo("peso")
+                    .valore(r.peso())
+                    .into(),
+            );
             errors.push(err);
             continue;
         }
@@ -107,145 +446,182 @@ pub fn parse_recordcsv_campionamento_hfbi<T: RecordCsvCampionamentoHFBI>(
         };
         campioni.push(hfbi_rec);
     }
-    (campioni, errors)
-}
-
-#[derive(Debug)]
-pub enum RecordCsvAnagraficaHFBIError {
-    ValoreInvalido { msg: String }, //TODO: add position?
-}
-
-impl fmt::Display for RecordCsvAnagraficaHFBIError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let string_representation = match self {
-            RecordCsvAnagraficaHFBIError::ValoreInvalido { msg } => {
-                format!("Errore record anagrafica HFBI: {}", msg)
-            }
-        };
-        write!(f, "{}", string_representation)
-    }
+    let errors = arricchisci_errori(errors, &records);
+    (campioni, errors, sostituzioni)
 }
 
 pub fn parse_recordcsv_anagrafica_hfbi<T: RecordCsvAnagraficaHFBI>(
     records: Vec<T>,
-) -> Result<AnagraficaHFBI, Vec<RecordCsvAnagraficaHFBIError>> {
+) -> Result<AnagraficaHFBI, Vec<Error>> {
     let mut errors = Vec::new();
     if records.len() > 1 {
-        let err = RecordCsvAnagraficaHFBIError::ValoreInvalido {
-            msg: format!("Troppi record: {}, atteso 1", records.len()),
-        };
+        let err = Error::NumeroRecord(
+            ContestoErrore::da_messaggio(Messaggio::TroppiRecord(records.len()))
+                .tipo_csv(TipoRecordCsv::AnagraficaHFBI)
+                .into(),
+        );
         errors.push(err);
     }
     if records.is_empty() {
-        let err = RecordCsvAnagraficaHFBIError::ValoreInvalido {
-            msg: "Nessun record trovato: atteso 1".to_string(),
-        };
+        let err = Error::NumeroRecord(
+            ContestoErrore::da_messaggio(Messaggio::NessunRecord)
+                .tipo_csv(TipoRecordCsv::AnagraficaHFBI)
+                .into(),
+        );
         errors.push(err);
         return Err(errors);
     }
 
     let r = records.first().unwrap();
 
+    match parse_record_anagrafica_hfbi(r) {
+        Ok(anagrafica) => {
+            if !errors.is_empty() {
+                return Err(errors);
+            }
+            Ok(anagrafica)
+        }
+        Err(record_errors) => {
+            errors.extend(record_errors);
+            Err(arricchisci_errori_record(errors, r))
+        }
+    }
+}
+
+/// Come parse_recordcsv_anagrafica_hfbi(), ma accetta un record per ogni stazione (e data).
+/// Un record con stesso codice stazione e stessa data di uno precedente è un errore.
+pub fn parse_recordcsv_registro_anagrafica_hfbi<T: RecordCsvAnagraficaHFBI>(
+    records: Vec<T>,
+) -> (RegistroStazioni<AnagraficaHFBI>, Vec<Error>) {
+    let mut registro = RegistroStazioni::new();
+    let mut errors = Vec::new();
+    let mut idx = 0;
+    for r in &records {
+        idx += 1;
+        match parse_record_anagrafica_hfbi(r) {
+            Ok(anagrafica) => {
+                if let Err(duplicato) = registro.inserisci(anagrafica) {
+                    let err = Error::ValoreInvalido(
+                        ContestoErrore::da_messaggio(Messaggio::StazioneDuplicata {
+                            data: duplicato.date_string.to_string(),
+                            stazione: duplicato.codice_stazione.to_string(),
+                        })
+                        .tipo_csv(TipoRecordCsv::AnagraficaHFBI)
+                        .record(idx)
+                        .campo("codiceStazione")
+                        .valore(&duplicato.codice_stazione)
+                        .into(),
+                    );
+                    errors.push(err);
+                }
+            }
+            Err(record_errors) => {
+                errors.extend(record_errors.into_iter().map(|e| e.con_record(idx)));
+            }
+        }
+    }
+    let errors = arricchisci_errori(errors, &records);
+    (registro, errors)
+}
+
+/// Valida un singolo record di anagrafica
+fn parse_record_anagrafica_hfbi<T: RecordCsvAnagraficaHFBI>(
+    r: &T,
+) -> Result<AnagraficaHFBI, Vec<Error>> {
+    let mut errors = Vec::new();
+
     if r.codice_stazione().is_empty() {
-        let err = RecordCsvAnagraficaHFBIError::ValoreInvalido {
-            msg: format!("Codice stazione troppo corto: {}", r.codice_stazione()),
-        };
+        let err = Error::ValoreInvalido(
+            ContestoErrore::da_messaggio(Messaggio::CampoTroppoCorto {
+                campo: "codiceStazione",
+                valore: r.codice_stazione().to_string(),
+            })
+            .tipo_csv(TipoRecordCsv::AnagraficaHFBI)
+            .campo("codiceStazione")
+            .valore(r.codice_stazione())
+            .into(),
+        );
         errors.push(err);
     }
 
     if r.corpo_idrico().is_empty() {
-        let err = RecordCsvAnagraficaHFBIError::ValoreInvalido {
-            msg: format!("Corpo idrico troppo corto: {}", r.corpo_idrico()),
-        };
+        let err = Error::ValoreInvalido(
+            ContestoErrore::da_messaggio(Messaggio::CampoTroppoCorto {
+                campo: "corpoIdrico",
+                valore: r.corpo_idrico().to_string(),
+            })
+            .tipo_csv(TipoRecordCsv::AnagraficaHFBI)
+            .campo("corpoIdrico")
+            .valore(r.corpo_idrico())
+            .into(),
+        );
         errors.push(err);
     }
 
     if r.regione().is_empty() {
-        let err = RecordCsvAnagraficaHFBIError::ValoreInvalido {
-            msg: format!("Regione troppo corta: {}", r.regione()),
-        };
+        let err = Error::ValoreInvalido(
+            ContestoErrore::da_messaggio(Messaggio::CampoTroppoCorto {
+                campo: "regione",
+                valore: r.regione().to_string(),
+            })
+            .tipo_csv(TipoRecordCsv::AnagraficaHFBI)
+            .campo("regione")
+            .valore(r.regione())
+            .into(),
+        );
         errors.push(err);
     }
 
     if r.provincia().is_empty() {
-        let err = RecordCsvAnagraficaHFBIError::ValoreInvalido {
-            msg: format!("Provincia troppo corta: {}", r.provincia()),
-        };
+        let err = Error::ValoreInvalido(
+            ContestoErrore::da_messaggio(Messaggio::CampoTroppoCorto {
+                campo: "provincia",
+                valore: r.provincia().to_string(),
+            })
+            .tipo_csv(TipoRecordCsv::AnagraficaHFBI)
+            .campo("provincia")
+            .valore(r.provincia())
+            .into(),
+        );
         errors.push(err);
     }
 
-    match parse_date(&r.data()) {
-        Ok(_) => {}
-        Err(e) => match e.kind() {
-            ParseErrorKind::OutOfRange => {
-                let err = RecordCsvAnagraficaHFBIError::ValoreInvalido {
-                    msg: "Data fornita non valida: fuori range".to_string(),
-                };
-                errors.push(err);
-            }
-            ParseErrorKind::Impossible => {
-                let err = RecordCsvAnagraficaHFBIError::ValoreInvalido {
-                    msg: "Data fornita non valida: valori non possibili".to_string(),
-                };
-                errors.push(err);
-            }
-            ParseErrorKind::NotEnough => {
-                let err = RecordCsvAnagraficaHFBIError::ValoreInvalido {
-                    msg: "Data fornita non valida: specifica insufficiente".to_string(),
-                };
-                errors.push(err);
-            }
-            ParseErrorKind::Invalid => {
-                let err = RecordCsvAnagraficaHFBIError::ValoreInvalido {
-                    msg: "Data fornita non valida: presenza di caratteri non attesi".to_string(),
-                };
-                errors.push(err);
-            }
-            ParseErrorKind::TooShort => {
-                let err = RecordCsvAnagraficaHFBIError::ValoreInvalido {
-                    msg: "Data fornita non valida: terminazione prematura dell'input".to_string(),
-                };
-                errors.push(err);
-            }
-            ParseErrorKind::TooLong => {
-                let err = RecordCsvAnagraficaHFBIError::ValoreInvalido {
-                    msg: "Data fornita non valida: input in eccesso".to_string(),
-                };
-                errors.push(err);
-            }
-            ParseErrorKind::BadFormat => {
-                let err = RecordCsvAnagraficaHFBIError::ValoreInvalido {
-                    msg: "Data fornita non valida: errore nella specifica di formattazione"
-                        .to_string(),
-                };
-                errors.push(err);
-            }
-            _ => {
-                let err = RecordCsvAnagraficaHFBIError::ValoreInvalido {
-                    msg: "Data fornita non valida: errore sconosciuto".to_string(),
-                };
-                errors.push(err);
-            }
-        },
+    if let Err(e) = parse_date(&r.data()) {
+        let err = Error::ValoreInvalido(
+            ContestoErrore::da_messaggio(Messaggio::DataFornitaNonValida(e))
+                .tipo_csv(TipoRecordCsv::AnagraficaHFBI)
+                .campo("data")
+                .valore(r.data())
+                .into(),
+        );
+        errors.push(err);
     }
 
     if r.lunghezza_stazione() < 0.0 {
-        let err = RecordCsvAnagraficaHFBIError::ValoreInvalido {
-            msg: format!(
-                "Lunghezza stazione troppo bassa: {}",
-                r.lunghezza_stazione()
-            ),
-        };
+        let err = Error::ValoreInvalido(
+            ContestoErrore::da_messaggio(Messaggio::MisuraTroppoBassa {
+                campo: "lunghezzaStazione",
+                valore: r.lunghezza_stazione().to_string(),
+            })
+            .tipo_csv(TipoRecordCsv::AnagraficaHFBI)
+            .campo("lunghezzaStazione")
+            .valore(r.lunghezza_stazione())
+            .into(),
+        );
         errors.push(err);
     }
 
     if r.larghezza_stazione() < 0.0 {
-        let err = RecordCsvAnagraficaHFBIError::ValoreInvalido {
-            msg: format!(
-                "Larghezza stazione troppo bassa: {}",
-                r.larghezza_stazione()
-            ),
-        };
+        let err = Error::ValoreInvalido(
+            ContestoErrore::da_messaggio(Messaggio::MisuraTroppoBassa {
+                campo: "larghezzaStazione",
+                valore: r.larghezza_stazione().to_string(),
+            })
+            .tipo_csv(TipoRecordCsv::AnagraficaHFBI)
+            .campo("larghezzaStazione")
+            .valore(r.larghezza_stazione())
+            .into(),
+        );
         errors.push(err);
     }
 
@@ -258,9 +634,18 @@ pub fn parse_recordcsv_anagrafica_hfbi<T: RecordCsvAnagraficaHFBI>(
             stagione = StagioneHFBI::Autunno;
         }
         _ => {
-            let err = RecordCsvAnagraficaHFBIError::ValoreInvalido {
-                msg: format!("Stagione HFBI non valido: {}, atteso [0, 1]", r.stagione()),
-            };
+            let err = Error::ValoreInvalido(
+                ContestoErrore::da_messaggio(Messaggio::CodiceFuoriIntervallo {
+                    tipo: "StagioneHFBI",
+                    valore: r.stagione().to_string(),
+                    min: 0,
+                    max: 1,
+                })
+                .tipo_csv(TipoRecordCsv::AnagraficaHFBI)
+                .campo("stagione")
+                .valore(r.stagione())
+                .into(),
+            );
             errors.push(err);
         }
     }
@@ -269,9 +654,18 @@ pub fn parse_recordcsv_anagrafica_hfbi<T: RecordCsvAnagraficaHFBI>(
         0 => HabitatHFBI::Vegetato,
         1 => HabitatHFBI::NonVegetato,
         _ => {
-            let err = RecordCsvAnagraficaHFBIError::ValoreInvalido {
-                msg: format!("HabitatHFBI non valido: {}, atteso [0, 1]", r.habitat()),
-            };
+            let err = Error::ValoreInvalido(
+                ContestoErrore::da_messaggio(Messaggio::CodiceFuoriIntervallo {
+                    tipo: "HabitatHFBI",
+                    valore: r.habitat().to_string(),
+                    min: 0,
+                    max: 1,
+                })
+                .tipo_csv(TipoRecordCsv::AnagraficaHFBI)
+                .campo("habitat")
+                .valore(r.habitat())
+                .into(),
+            );
             errors.push(err);
             HabitatHFBI::Vegetato // To still assign something by default
         }
@@ -289,12 +683,18 @@ pub fn parse_recordcsv_anagrafica_hfbi<T: RecordCsvAnagraficaHFBI>(
             tipo_laguna = TipoLagunaCostieraHFBI::MAt3;
         }
         _ => {
-            let err = RecordCsvAnagraficaHFBIError::ValoreInvalido {
-                msg: format!(
-                    "TipoLagunaCostieraHFBI non valido: {}, atteso [1, 3]",
-                    r.tipo_laguna()
-                ),
-            };
+            let err = Error::ValoreInvalido(
+                ContestoErrore::da_messaggio(Messaggio::CodiceFuoriIntervallo {
+                    tipo: "TipoLagunaCostieraHFBI",
+                    valore: r.tipo_laguna().to_string(),
+                    min: 1,
+                    max: 3,
+                })
+                .tipo_csv(TipoRecordCsv::AnagraficaHFBI)
+                .campo("tipoLaguna")
+                .valore(r.tipo_laguna())
+                .into(),
+            );
             errors.push(err);
         }
     }
@@ -305,7 +705,8 @@ pub fn parse_recordcsv_anagrafica_hfbi<T: RecordCsvAnagraficaHFBI>(
 
     let res = AnagraficaHFBI {
         codice_stazione: r.codice_stazione(),
-        date_string: r.data(), // Formato gg/mm/aaaa
+        date_string: r.data(),
+        data: parse_date(&r.data()).ok(),
         corpo_idrico: r.corpo_idrico(),
         posizione: Location {
             regione: r.regione(),
@@ -320,71 +721,80 @@ pub fn parse_recordcsv_anagrafica_hfbi<T: RecordCsvAnagraficaHFBI>(
     Ok(res)
 }
 
-pub fn check_records_campionamento_hfbi<T: RecordCsvCampionamentoHFBI>(
+pub fn check_records_riferimento_hfbi<T: RecordCsvRiferimentoHFBI>(
     records: Vec<T>,
-) -> Result<Vec<RecordHFBI>, Vec<RecordCsvCampionamentoHFBIError>> {
-    let (records, errors) = parse_recordcsv_campionamento_hfbi(records);
+    opzioni: &OpzioniVerifica,
+) -> Result<(RiferimentoHFBI, Diagnostics), Diagnostics> {
+    let (specie, errors) = parse_recordcsv_riferimento_hfbi(records);
 
-    println!(
-        "Campionamento HFBI: Numero record validi: {}",
-        records.len()
-    );
-    println!(
-        "Campionamento HFBI: Numero record non validi: {}",
-        errors.len()
-    );
+    Diagnostics::from_errori(TipoRecordCsv::RiferimentoHFBI, specie.len(), errors)
+        .esito(RiferimentoHFBI::new(specie), opzioni.modalita)
+}
 
-    if !errors.is_empty() {
-        eprintln!("Errori incontrati durante l'elaborazione dei record per campionamento HFBI: {{");
-        //TODO: add process_record_campionamentoNISECI_errors()
-        for error in &errors {
-            eprintln!("  {}", error);
-        }
-        eprintln!("}}");
-        Err(errors)
-    } else {
-        //TODO: handle verbosity
-        //println!("Tutti i record del campionamento HFBI sono stati processati con successo!");
-        /*
-        for record in &records {
-            println!("  Record: {{{record}}}");
+pub fn check_records_condizioni_riferimento_hfbi<T: RecordCsvCondizioniRiferimentoHFBI>(
+    records: Vec<T>,
+    opzioni: &OpzioniVerifica,
+) -> Result<(TabellaCondizioniRiferimentoHFBI, Diagnostics), Diagnostics> {
+    let (tabella, errors) = parse_recordcsv_condizioni_riferimento_hfbi(records);
+
+    Diagnostics::from_errori(
+        TipoRecordCsv::CondizioniRiferimentoHFBI,
+        tabella.len(),
+        errors,
+    )
+    .esito(tabella, opzioni.modalita)
+}
+
+pub fn check_records_campionamento_hfbi<T: RecordCsvCampionamentoHFBI>(
+    records: Vec<T>,
+    opzioni: &OpzioniVerifica,
+) -> Result<(Vec<RecordHFBI>, Diagnostics), Diagnostics> {
+    let indice_riferimento;
+    let (elenco, indice) = match opzioni.riferimento {
+        Some(riferimento) => {
+            indice_riferimento = riferimento.indice();
+            (riferimento.elenco_specie.as_slice(), &indice_riferimento)
         }
-        */
-        Ok(records)
-    }
+        None => (elenco_riferimento_hfbi(), indice_riferimento_hfbi()),
+    };
+    let (records, errors, sostituzioni) =
+        parse_recordcsv_campionamento_hfbi_corretto(records, elenco, indice, opzioni.correzioni);
+
+    let mut diagnostics =
+        Diagnostics::from_errori(TipoRecordCsv::CampionamentoHFBI, records.len(), errors);
+    diagnostics.aggiungi_avvisi(sostituzioni);
+    diagnostics.esito(records, opzioni.modalita)
 }
 
 pub fn check_records_anagrafica_hfbi<T: RecordCsvAnagraficaHFBI>(
     records: Vec<T>,
-) -> Result<AnagraficaHFBI, Vec<RecordCsvAnagraficaHFBIError>> {
-    let res = parse_recordcsv_anagrafica_hfbi(records);
+) -> Result<(AnagraficaHFBI, Diagnostics), Diagnostics> {
+    let num_records = records.len();
+    let mut diagnostics = Diagnostics::new(TipoRecordCsv::AnagraficaHFBI);
 
-    match res {
+    match parse_recordcsv_anagrafica_hfbi(records) {
         Ok(anagrafica) => {
-            println!("Anagrafica HFBI: {}", anagrafica);
-            //TODO: handle verbosity
-            //println!("Tutti i record dell'anagrafica HFBI sono stati processati con successo!");
-            /*
-            for record in &records {
-                println!("  Record: {{{record}}}");
-            }
-            */
-            Ok(anagrafica)
+            diagnostics.record_validi = 1;
+            Ok((anagrafica, diagnostics))
         }
         Err(errors) => {
-            println!(
-                "Anagrafica HFBI: Numero record non validi: {}",
-                errors.len()
-            );
-            eprintln!(
-                "Errori incontrati durante l'elaborazione dei record per anagrafica HFBI: {{"
-            );
-            //TODO: add process_record_anagraficaHFBI_errors()
-            for error in &errors {
-                eprintln!("  {}", error);
-            }
-            eprintln!("}}");
-            Err(errors)
+            diagnostics.record_non_validi = num_records;
+            diagnostics.errori = errors;
+            Err(diagnostics)
         }
     }
 }
+
+pub fn check_records_registro_anagrafica_hfbi<T: RecordCsvAnagraficaHFBI>(
+    records: Vec<T>,
+    opzioni: &OpzioniVerifica,
+) -> Result<(RegistroStazioni<AnagraficaHFBI>, Diagnostics), Diagnostics> {
+    let num_records = records.len();
+    let (registro, errors) = parse_recordcsv_registro_anagrafica_hfbi(records);
+
+    let mut diagnostics = Diagnostics::new(TipoRecordCsv::AnagraficaHFBI);
+    diagnostics.record_validi = registro.len();
+    diagnostics.record_non_validi = num_records - registro.len();
+    diagnostics.errori = errors;
+    diagnostics.esito(registro, opzioni.modalita)
+}
@@ -15,12 +15,158 @@
     along with this program.  If not, see <https://www.gnu.org/licenses/>.
 */
 
-use chrono::NaiveDate;
+use crate::csv::RecordCsvSorgente;
+use crate::diagnostics::ValidationMode;
+use crate::domain::hfbi::RiferimentoHFBI;
+use crate::domain::specie::{CorrezioniSpecie, IndiceSpecie, SpecieIndicizzabile};
+use crate::error::Error;
+use crate::i18n::{Messaggio, MotivoDataNonValida};
+use chrono::{Days, NaiveDate};
 
-pub fn parse_date(date_str: &str) -> Result<NaiveDate, chrono::format::ParseError> {
-    let normalized = date_str.replace("/", "-"); // Replace all / with -
-    NaiveDate::parse_from_str(&normalized, "%d-%m-%Y")
+/// Primo numero seriale di Excel accettato (01/01/1950): un numero più piccolo, come un anno
+/// scritto da solo, non è una data esportata senza formato
+const SERIALE_EXCEL_MIN: u64 = 18_264;
+/// Ultimo numero seriale di Excel (31/12/9999)
+const SERIALE_EXCEL_MAX: u64 = 2_958_465;
+
+/// Interpreta una data di input. Formati accettati:
+/// - gg/mm/aaaa, gg-mm-aaaa, gg.mm.aaaa (giorno e mese anche di una cifra)
+/// - gli stessi con anno a due cifre (00-69 -> 2000-2069, 70-99 -> 1970-1999)
+/// - ISO 8601 aaaa-mm-gg, eventualmente seguita dall'ora
+/// - numero seriale di Excel (sistema 1900) dal 1950 in poi, come quando la cella è esportata
+///   senza formato data
+pub fn parse_date(date_str: &str) -> Result<NaiveDate, MotivoDataNonValida> {
+    let s = date_str.trim();
+    if s.is_empty() {
+        return Err(MotivoDataNonValida::InputTroppoCorto);
+    }
+    if let Some(seriale) = seriale_excel(s) {
+        return data_da_seriale_excel(seriale);
+    }
+    // Ignora l'eventuale ora ("2019-07-07T10:00:00", "07/07/2019 10:00")
+    let data = s
+        .split(|c: char| c == 'T' || c.is_whitespace())
+        .next()
+        .unwrap_or(s);
+    let normalized = data.replace(['/', '.'], "-");
+    let formato = match normalized.split('-').collect::<Vec<_>>().as_slice() {
+        [anno, _, _] if anno.len() == 4 => "%Y-%m-%d",
+        [_, _, anno] if anno.len() <= 2 => "%d-%m-%y",
+        _ => "%d-%m-%Y",
+    };
+    NaiveDate::parse_from_str(&normalized, formato).map_err(|e| e.kind().into())
+}
+
+/// Numero seriale di Excel, con l'eventuale parte frazionaria (l'ora) scartata
+fn seriale_excel(s: &str) -> Option<u64> {
+    let (giorni, frazione) = s.split_once(['.', ',']).unwrap_or((s, ""));
+    let solo_cifre = |t: &str| t.chars().all(|c| c.is_ascii_digit());
+    if giorni.is_empty() || !solo_cifre(giorni) || !solo_cifre(frazione) {
+        return None;
+    }
+    giorni.parse().ok()
+}
+
+fn data_da_seriale_excel(seriale: u64) -> Result<NaiveDate, MotivoDataNonValida> {
+    if !(SERIALE_EXCEL_MIN..=SERIALE_EXCEL_MAX).contains(&seriale) {
+        return Err(MotivoDataNonValida::FuoriRange);
+    }
+    // Excel considera bisestile il 1900: dal 01/03/1900 i seriali sono avanti di un giorno
+    NaiveDate::from_ymd_opt(1899, 12, 30)
+        .and_then(|e| e.checked_add_days(Days::new(seriale)))
+        .ok_or(MotivoDataNonValida::FuoriRange)
+}
+
+/// Completa gli errori con la riga del file del record a cui si riferiscono
+/// (numero progressivo del record, a partire da 1)
+pub(crate) fn arricchisci_errori<T: RecordCsvSorgente>(
+    errors: Vec<Error>,
+    records: &[T],
+) -> Vec<Error> {
+    errors
+        .into_iter()
+        .map(|e| {
+            let riga = e
+                .record()
+                .and_then(|idx| idx.checked_sub(1))
+                .and_then(|i| records.get(i))
+                .and_then(|r| r.riga_sorgente());
+            match riga {
+                Some(riga) => e.con_riga_sorgente(riga),
+                None => e,
+            }
+        })
+        .collect()
+}
+
+/// Completa con la riga del file gli errori che riguardano tutti un unico record
+pub(crate) fn arricchisci_errori_record<T: RecordCsvSorgente>(
+    errors: Vec<Error>,
+    record: &T,
+) -> Vec<Error> {
+    match record.riga_sorgente() {
+        Some(riga) => errors
+            .into_iter()
+            .map(|e| e.con_riga_sorgente(riga))
+            .collect(),
+        None => errors,
+    }
 }
 
 pub mod hfbi;
 pub mod niseci;
+
+/// Opzioni dei check_records_*. Ogni funzione usa solo le opzioni che riguardano il suo tipo
+/// di record.
+#[derive(Clone, Copy, Default)]
+pub struct OpzioniVerifica<'a> {
+    /// In Lenient i record non validi vengono scartati (vedi Diagnostics::scartati).
+    /// Una tabella di condizioni di riferimento HFBI incompleta non è mai valida.
+    pub modalita: ValidationMode,
+    /// Correzioni confermate dei codici specie sconosciuti, applicate ai campionamenti.
+    /// Ogni sostituzione è riportata in Diagnostics::avvisi
+    pub correzioni: Option<&'a CorrezioniSpecie>,
+    /// Elenco in cui cercare le specie dei campionamenti HFBI; None usa RIFERIMENTO_HFBI
+    pub riferimento: Option<&'a RiferimentoHFBI>,
+}
+
+impl<'a> OpzioniVerifica<'a> {
+    pub fn modalita(mut self, modalita: ValidationMode) -> Self {
+        self.modalita = modalita;
+        self
+    }
+
+    pub fn correzioni(mut self, correzioni: &'a CorrezioniSpecie) -> Self {
+        self.correzioni = Some(correzioni);
+        self
+    }
+
+    pub fn riferimento(mut self, riferimento: &'a RiferimentoHFBI) -> Self {
+        self.riferimento = Some(riferimento);
+        self
+    }
+}
+
+/// Cerca la specie indicata da un record per codice o nome.
+/// Se non c'è applica le correzioni confermate, restituendo anche il codice usato al posto
+/// di quello del record; altrimenti restituisce il messaggio con la specie più simile.
+pub(crate) fn cerca_specie<'a, S: SpecieIndicizzabile>(
+    indice: &IndiceSpecie,
+    elenco: &'a [S],
+    codice: &str,
+    correzioni: Option<&CorrezioniSpecie>,
+) -> Result<(&'a S, Option<String>), Messaggio> {
+    if let Some(specie) = indice.cerca(elenco, codice) {
+        return Ok((specie, None));
+    }
+    let corretta = correzioni
+        .and_then(|c| c.get(codice))
+        .and_then(|corretto| indice.cerca(elenco, corretto));
+    match corretta {
+        Some(specie) => Ok((specie, Some(specie.codice().trim().to_string()))),
+        None => Err(Messaggio::CodiceSpecieSconosciuto {
+            codice: codice.to_string(),
+            suggerimento: indice.suggerisci(elenco, codice),
+        }),
+    }
+}
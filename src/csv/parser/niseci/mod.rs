@@ -15,70 +15,150 @@
     along with this program.  If not, see <https://www.gnu.org/licenses/>.
 */
 
-use crate::csv::parser::parse_date;
+use crate::csv::parser::{
+    arricchisci_errori, arricchisci_errori_record, cerca_specie, parse_date, OpzioniVerifica,
+};
 use crate::csv::{
-    RecordCsvAnagraficaNISECI, RecordCsvCampionamentoNISECI, RecordCsvRiferimentoNISECI,
+    RecordCsvAnagraficaNISECI, RecordCsvCampionamentoNISECI, RecordCsvComunitaRiferimentoNISECI,
+    RecordCsvRiferimentoNISECI, TipoRecordCsv,
 };
+use crate::diagnostics::{Diagnostics, ValidationMode};
 use crate::domain::location::Location;
+use crate::domain::niseci::catalogo::{
+    elenco_catalogo_niseci, indice_catalogo_niseci, SoglieSpecieNISECI,
+};
 use crate::domain::niseci::{
-    AnagraficaNISECI, AreaNISECI, ComunitaNISECI, IdroEcoRegioneNISECI, RecordNISECI, SpecieNISECI,
-    TipoComunitaNISECI,
+    AnagraficaNISECI, AreaNISECI, CampionamentoNISECI, ChiaveCampionamentoNISECI, ComunitaNISECI,
+    IdroEcoRegioneNISECI, RecordNISECI, SpecieNISECI, TipoComunitaNISECI,
 };
-use chrono::format::ParseErrorKind;
-use std::fmt;
+use crate::domain::registro::{AnagraficaStazione, RegistroStazioni};
+use crate::domain::specie::{CorrezioniSpecie, IndiceSpecie};
+use crate::error::{ContestoErrore, Error};
+use crate::i18n::Messaggio;
+use chrono::NaiveDate;
+use std::collections::BTreeMap;
 
-fn check_soglie_cl<T: RecordCsvRiferimentoNISECI>(r: &T) -> bool {
-    if r.cl_soglia1() < r.cl_soglia2()
-        && r.cl_soglia2() < r.cl_soglia3()
-        && r.cl_soglia3() < r.cl_soglia4()
+fn check_soglie_cl(specie: &SpecieNISECI) -> bool {
+    if specie.cl_soglia1 < specie.cl_soglia2
+        && specie.cl_soglia2 < specie.cl_soglia3
+        && specie.cl_soglia3 < specie.cl_soglia4
     {
         return true;
     }
     false
 }
 
-fn check_soglie_ad_juv<T: RecordCsvRiferimentoNISECI>(r: &T) -> bool {
-    if r.ad_juv_soglia1() < r.ad_juv_soglia2()
-        && r.ad_juv_soglia2() < r.ad_juv_soglia3()
-        && r.ad_juv_soglia3() < r.ad_juv_soglia4()
+fn check_soglie_ad_juv(specie: &SpecieNISECI) -> bool {
+    if specie.ad_juv_soglia1 < specie.ad_juv_soglia2
+        && specie.ad_juv_soglia2 < specie.ad_juv_soglia3
+        && specie.ad_juv_soglia3 < specie.ad_juv_soglia4
     {
         return true;
     }
     false
 }
 
-#[derive(Debug)]
-pub enum RecordCsvRiferimentoNISECIError {
-    ValoreInvalido { msg: String }, //TODO: add position?
-    SoglieCLNonCrescenti { msg: String },
-    SoglieADJUVNonCrescenti { msg: String },
-}
+/// Controlla le soglie di una specie: densità non negative (e non nulle per le specie attese),
+/// crescenti, e soglie di lunghezza e di rapporto adulti/giovani crescenti
+fn check_soglie_specie(
+    specie: &SpecieNISECI,
+    tipo_csv: TipoRecordCsv,
+    idx: usize,
+) -> Option<Error> {
+    let epsilon: f32 = 1e-6;
 
-impl fmt::Display for RecordCsvRiferimentoNISECIError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let string_representation = match self {
-            RecordCsvRiferimentoNISECIError::ValoreInvalido { msg } => {
-                format!("Errore record riferimento NISECI: {}", msg)
-            }
-            RecordCsvRiferimentoNISECIError::SoglieCLNonCrescenti { msg } => {
-                format!("Errore record riferimento NISECI: {}", msg)
-            }
-            RecordCsvRiferimentoNISECIError::SoglieADJUVNonCrescenti { msg } => {
-                format!("Errore record riferimento NISECI: {}", msg)
-            }
-        };
-        write!(f, "{}", string_representation)
+    // Check dens_soglia
+    if specie.dens_soglia1 < 0.0 {
+        let err = Error::ValoreInvalido(
+            ContestoErrore::da_messaggio(Messaggio::DensSogliaNegativa(1))
+                .tipo_csv(tipo_csv)
+                .record(idx)
+                .campo("densSoglia1")
+                .valore(specie.dens_soglia1)
+                .into(),
+        );
+        return Some(err);
+    }
+
+    if specie.dens_soglia1.abs() < epsilon && specie.specie_attesa {
+        let err = Error::ValoreInvalido(
+            ContestoErrore::da_messaggio(Messaggio::DensSogliaNulla(1))
+                .tipo_csv(tipo_csv)
+                .record(idx)
+                .campo("densSoglia1")
+                .valore(specie.dens_soglia1)
+                .into(),
+        );
+        return Some(err);
+    }
+
+    if specie.dens_soglia2 < 0.0 {
+        let err = Error::ValoreInvalido(
+            ContestoErrore::da_messaggio(Messaggio::DensSogliaNegativa(2))
+                .tipo_csv(tipo_csv)
+                .record(idx)
+                .campo("densSoglia2")
+                .valore(specie.dens_soglia2)
+                .into(),
+        );
+        return Some(err);
+    }
+
+    if specie.dens_soglia2.abs() < epsilon && specie.specie_attesa {
+        let err = Error::ValoreInvalido(
+            ContestoErrore::da_messaggio(Messaggio::DensSogliaNulla(2))
+                .tipo_csv(tipo_csv)
+                .record(idx)
+                .campo("densSoglia2")
+                .valore(specie.dens_soglia2)
+                .into(),
+        );
+        return Some(err);
     }
+
+    if specie.dens_soglia1 >= specie.dens_soglia2 && specie.specie_attesa {
+        let err = Error::ValoreInvalido(
+            ContestoErrore::da_messaggio(Messaggio::DensSoglieNonCrescenti)
+                .tipo_csv(tipo_csv)
+                .record(idx)
+                .campo("densSoglia1")
+                .valore(specie.dens_soglia1)
+                .into(),
+        );
+        return Some(err);
+    }
+
+    if !check_soglie_cl(specie) {
+        let err = Error::SoglieCLNonCrescenti(
+            ContestoErrore::da_messaggio(Messaggio::SoglieCLNonCrescenti)
+                .tipo_csv(tipo_csv)
+                .record(idx)
+                .campo("clSoglia1")
+                .into(),
+        );
+        return Some(err);
+    }
+    if !check_soglie_ad_juv(specie) {
+        let err = Error::SoglieADJUVNonCrescenti(
+            ContestoErrore::da_messaggio(Messaggio::SoglieADJUVNonCrescenti)
+                .tipo_csv(tipo_csv)
+                .record(idx)
+                .campo("adJuvSoglia1")
+                .into(),
+        );
+        return Some(err);
+    }
+    None
 }
 
 pub fn parse_recordcsv_riferimento_niseci<T: RecordCsvRiferimentoNISECI>(
     records: Vec<T>,
-) -> (Vec<SpecieNISECI>, Vec<RecordCsvRiferimentoNISECIError>) {
+) -> (Vec<SpecieNISECI>, Vec<Error>) {
     let mut specie = Vec::new();
     let mut errors = Vec::new();
     let mut idx = 0;
-    let mut used_id_specie = Vec::new(); // Stores already-parsed ids to detect doubles
-    for r in records {
+    let mut indice = IndiceSpecie::new(); // Rileva i codici ripetuti
+    for r in &records {
         idx += 1;
         let mut origine_autoctono = true;
         match r.origine().as_str() {
@@ -87,12 +167,16 @@ pub fn parse_recordcsv_riferimento_niseci<T: RecordCsvRiferimentoNISECI>(
             }
             "AUT" => {}
             _ => {
-                let err = RecordCsvRiferimentoNISECIError::ValoreInvalido {
-                    msg: format!(
-                        "Record {idx}: origine invalida (non \"AUT\" o \"ALL\"): {}",
-                        r.origine()
-                    ),
-                };
+                let err = Error::ValoreInvalido(
+                    ContestoErrore::da_messaggio(Messaggio::OrigineNonValida(
+                        r.origine().to_string(),
+                    ))
+                    .tipo_csv(TipoRecordCsv::RiferimentoNISECI)
+                    .record(idx)
+                    .campo("origine")
+                    .valore(r.origine())
+                    .into(),
+                );
                 errors.push(err);
                 continue;
             }
@@ -107,12 +191,16 @@ pub fn parse_recordcsv_riferimento_niseci<T: RecordCsvRiferimentoNISECI>(
                     tipo_autoctono = r.tipo_autoctono() as u8;
                 }
                 _ => {
-                    let err = RecordCsvRiferimentoNISECIError::ValoreInvalido {
-                        msg: format!(
-                            "Record {idx}: tipo_autoctono non valido (non 1 o 2): {}",
-                            r.tipo_autoctono()
-                        ),
-                    };
+                    let err = Error::ValoreInvalido(
+                        ContestoErrore::da_messaggio(Messaggio::TipoAutoctonoNonValido(
+                            r.tipo_autoctono().to_string(),
+                        ))
+                        .tipo_csv(TipoRecordCsv::RiferimentoNISECI)
+                        .record(idx)
+                        .campo("tipoAutoctono")
+                        .valore(r.tipo_autoctono())
+                        .into(),
+                    );
                     errors.push(err);
                     continue;
                 }
@@ -125,12 +213,16 @@ pub fn parse_recordcsv_riferimento_niseci<T: RecordCsvRiferimentoNISECI>(
                     tipo_alloctono = r.allo_nocivita() as u8;
                 }
                 _ => {
-                    let err = RecordCsvRiferimentoNISECIError::ValoreInvalido {
-                        msg: format!(
-                            "Record {idx}: allo_nocivita non valido (non [0..3]): {}",
-                            r.allo_nocivita()
-                        ),
-                    };
+                    let err = Error::ValoreInvalido(
+                        ContestoErrore::da_messaggio(Messaggio::AlloNocivitaNonValida(
+                            r.allo_nocivita().to_string(),
+                        ))
+                        .tipo_csv(TipoRecordCsv::RiferimentoNISECI)
+                        .record(idx)
+                        .campo("alloNocivita")
+                        .valore(r.allo_nocivita())
+                        .into(),
+                    );
                     errors.push(err);
                     continue;
                 }
@@ -138,88 +230,39 @@ pub fn parse_recordcsv_riferimento_niseci<T: RecordCsvRiferimentoNISECI>(
         }
 
         if r.codice_specie().is_empty() {
-            let err = RecordCsvRiferimentoNISECIError::ValoreInvalido {
-                msg: format!("Record {idx}: codice_specie non valido (lunghezza < 1)"),
-            };
+            let err = Error::ValoreInvalido(
+                ContestoErrore::da_messaggio(Messaggio::CodiceSpecieVuoto)
+                    .tipo_csv(TipoRecordCsv::RiferimentoNISECI)
+                    .record(idx)
+                    .campo("codiceSpecie")
+                    .valore(r.codice_specie())
+                    .into(),
+            );
             errors.push(err);
             continue;
         }
 
         let id = r.codice_specie();
 
-        if used_id_specie.contains(&id) {
-            let err = RecordCsvRiferimentoNISECIError::ValoreInvalido {
-                msg: format!("Record {idx}: codice_specie non valido (ridefinizione)"),
-            };
+        if indice.posizione_codice(&id).is_some() {
+            let err = Error::ValoreInvalido(
+                ContestoErrore::da_messaggio(Messaggio::CodiceSpecieRidefinito)
+                    .tipo_csv(TipoRecordCsv::RiferimentoNISECI)
+                    .record(idx)
+                    .campo("codiceSpecie")
+                    .valore(r.codice_specie())
+                    .into(),
+            );
             errors.push(err);
             continue;
         }
 
         let nome = r.nome_latino(); //TODO: controllare se dovrebbe essere nome_comune
 
-        let epsilon: f32 = 1e-6;
-
-        // Check dens_soglia
-        if r.dens_soglia1() < 0.0 {
-            let err = RecordCsvRiferimentoNISECIError::ValoreInvalido {
-                msg: format!("Record {idx}: dens_soglia1 non valido (< 0)"),
-            };
-            errors.push(err);
-            continue;
-        }
-
-        if r.dens_soglia1().abs() < epsilon && specie_attesa {
-            let err = RecordCsvRiferimentoNISECIError::ValoreInvalido {
-                msg: format!("Record {idx}: dens_soglia1 non valido (== 0) per una specie attesa"),
-            };
-            errors.push(err);
-            continue;
-        }
-
-        if r.dens_soglia2() < 0.0 {
-            let err = RecordCsvRiferimentoNISECIError::ValoreInvalido {
-                msg: format!("Record {idx}: dens_soglia2 non valido (< 0)"),
-            };
-            errors.push(err);
-            continue;
-        }
-
-        if r.dens_soglia2().abs() < epsilon && specie_attesa {
-            let err = RecordCsvRiferimentoNISECIError::ValoreInvalido {
-                msg: format!("Record {idx}: dens_soglia2 non valido (== 0) per una specie attesa"),
-            };
-            errors.push(err);
-            continue;
-        }
-
-        if r.dens_soglia1() >= r.dens_soglia2() && specie_attesa {
-            let err = RecordCsvRiferimentoNISECIError::ValoreInvalido {
-                msg: format!(
-                    "Record {idx}: dens_soglia1 maggiore di dens_soglia2 per una specie attesa"
-                ),
-            };
-            errors.push(err);
-            continue;
-        }
-
-        if !check_soglie_cl(&r) {
-            let err = RecordCsvRiferimentoNISECIError::SoglieCLNonCrescenti {
-                msg: format!("Record {idx}: soglie CL non crescenti"),
-            };
-            errors.push(err);
-            continue;
-        }
-        if !check_soglie_ad_juv(&r) {
-            let err = RecordCsvRiferimentoNISECIError::SoglieADJUVNonCrescenti {
-                msg: format!("Record {idx}: soglie AD/JUV non crescenti"),
-            };
-            errors.push(err);
-            continue;
-        }
-
         let specie_rec = SpecieNISECI {
-            id: id.clone(),
+            id,
             nome,
+            nome_comune: r.nome_comune(),
             tipo_autoctono,
             tipo_alloctono,
             specie_attesa,
@@ -234,236 +277,505 @@ pub fn parse_recordcsv_riferimento_niseci<T: RecordCsvRiferimentoNISECI>(
             dens_soglia1: r.dens_soglia1(),
             dens_soglia2: r.dens_soglia2(),
         };
+        if let Some(err) = check_soglie_specie(&specie_rec, TipoRecordCsv::RiferimentoNISECI, idx) {
+            errors.push(err);
+            continue;
+        }
+        let _ = indice.inserisci(specie.len(), &specie_rec);
         specie.push(specie_rec);
-        used_id_specie.push(id);
     }
 
+    let errors = arricchisci_errori(errors, &records);
     (specie, errors)
 }
 
-#[derive(Debug)]
-pub enum RecordCsvCampionamentoNISECIError {
-    ValoreInvalido { msg: String }, //TODO: add position?
-}
-
-impl fmt::Display for RecordCsvCampionamentoNISECIError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let string_representation = match self {
-            RecordCsvCampionamentoNISECIError::ValoreInvalido { msg } => {
-                format!("Errore record campionamento NISECI: {}", msg)
-            }
-        };
-        write!(f, "{}", string_representation)
-    }
-}
-
-pub fn parse_recordcsv_campionamento_niseci<T: RecordCsvCampionamentoNISECI>(
+/// Costruisce la comunità di riferimento dalle specie di CATALOGO_NISECI, con le soglie del
+/// catalogo salvo quelle sostituite dal record. Le soglie risultanti sono controllate come in
+/// parse_recordcsv_riferimento_niseci().
+pub fn parse_recordcsv_comunita_riferimento_niseci<T: RecordCsvComunitaRiferimentoNISECI>(
     records: Vec<T>,
-    riferimento_specie: Vec<SpecieNISECI>,
-) -> (Vec<RecordNISECI>, Vec<RecordCsvCampionamentoNISECIError>) {
-    let mut campioni = Vec::new();
+) -> (Vec<SpecieNISECI>, Vec<Error>) {
+    let mut specie = Vec::new();
     let mut errors = Vec::new();
     let mut idx = 0;
-    for r in records {
+    let mut indice = IndiceSpecie::new(); // Rileva le specie ripetute
+    for r in &records {
         idx += 1;
         if r.codice_specie().is_empty() {
-            let err = RecordCsvCampionamentoNISECIError::ValoreInvalido {
-                msg: format!("Record {idx}: codice_specie non valido (lunghezza < 1)"),
-            };
+            let err = Error::ValoreInvalido(
+                ContestoErrore::da_messaggio(Messaggio::CodiceSpecieVuoto)
+                    .tipo_csv(TipoRecordCsv::ComunitaRiferimentoNISECI)
+                    .record(idx)
+                    .campo("codiceSpecie")
+                    .valore(r.codice_specie())
+                    .into(),
+            );
             errors.push(err);
             continue;
         }
+
         let codice_specie = r.codice_specie();
-        let mut opt_matched_specie = None;
-        for s in &riferimento_specie {
-            // FIXME: this is O(n^2).
-            if s.id == codice_specie {
-                opt_matched_specie = Some(s);
-                break; // TODO: mmmh
+        let voce = match cerca_specie(
+            indice_catalogo_niseci(),
+            elenco_catalogo_niseci(),
+            &codice_specie,
+            None,
+        ) {
+            Ok((voce, _)) => voce,
+            Err(messaggio) => {
+                let err = Error::ValoreInvalido(
+                    ContestoErrore::da_messaggio(messaggio)
+                        .tipo_csv(TipoRecordCsv::ComunitaRiferimentoNISECI)
+                        .record(idx)
+                        .campo("codiceSpecie")
+                        .valore(codice_specie)
+                        .into(),
+                );
+                errors.push(err);
+                continue;
             }
-        }
+        };
 
-        let matched_specie;
-        if let Some(specie) = opt_matched_specie {
-            matched_specie = specie;
-        } else {
-            let err = RecordCsvCampionamentoNISECIError::ValoreInvalido {
-                msg: format!(
-                    "Record {idx}: codice_specie non valido (non presente nel riferimento): {}",
-                    codice_specie
-                ),
-            };
+        // Una specie può essere indicata per codice o per nome: si confronta il codice del catalogo
+        if indice.posizione_codice(voce.codice_specie).is_some() {
+            let err = Error::ValoreInvalido(
+                ContestoErrore::da_messaggio(Messaggio::CodiceSpecieRidefinito)
+                    .tipo_csv(TipoRecordCsv::ComunitaRiferimentoNISECI)
+                    .record(idx)
+                    .campo("codiceSpecie")
+                    .valore(codice_specie)
+                    .into(),
+            );
             errors.push(err);
             continue;
         }
 
-        if r.num_passaggio() < 1 {
-            let err = RecordCsvCampionamentoNISECIError::ValoreInvalido {
-                msg: format!(
-                    "Record {idx}: num_passaggio non valido (<1): {}",
-                    r.num_passaggio()
-                ),
-            };
+        // Come nel file di riferimento, per le specie alloctone tipoAutoctono non viene usato
+        let tipo_autoctono = if voce.autoctona {
+            match r.tipo_autoctono() {
+                1 | 2 => r.tipo_autoctono() as u8,
+                _ => {
+                    let err = Error::ValoreInvalido(
+                        ContestoErrore::da_messaggio(Messaggio::TipoAutoctonoNonValido(
+                            r.tipo_autoctono().to_string(),
+                        ))
+                        .tipo_csv(TipoRecordCsv::ComunitaRiferimentoNISECI)
+                        .record(idx)
+                        .campo("tipoAutoctono")
+                        .valore(r.tipo_autoctono())
+                        .into(),
+                    );
+                    errors.push(err);
+                    continue;
+                }
+            }
+        } else {
+            0
+        };
+
+        let specie_rec = voce.specie(
+            r.specie_attesa() > 0,
+            tipo_autoctono,
+            r.soglie().unwrap_or(&SoglieSpecieNISECI::default()),
+        );
+        if let Some(err) =
+            check_soglie_specie(&specie_rec, TipoRecordCsv::ComunitaRiferimentoNISECI, idx)
+        {
             errors.push(err);
             continue;
         }
-        let passaggio_cattura = r.num_passaggio();
+        let _ = indice.inserisci(specie.len(), &specie_rec);
+        specie.push(specie_rec);
+    }
 
-        if !r.peso().is_finite() {
-            let err = RecordCsvCampionamentoNISECIError::ValoreInvalido {
-                msg: format!("Record {idx}: peso non valido (not finite): {}", r.peso()),
-            };
-            errors.push(err);
-            continue;
+    let errors = arricchisci_errori(errors, &records);
+    (specie, errors)
+}
+
+fn parse_record_campionamento_niseci<T: RecordCsvCampionamentoNISECI>(
+    idx: usize,
+    r: &T,
+    riferimento_specie: &[SpecieNISECI],
+    indice: &IndiceSpecie,
+    correzioni: Option<&CorrezioniSpecie>,
+    sostituzioni: &mut Vec<Messaggio>,
+) -> Result<RecordNISECI, Error> {
+    if r.codice_specie().is_empty() {
+        return Err(Error::ValoreInvalido(
+            ContestoErrore::da_messaggio(Messaggio::CodiceSpecieVuoto)
+                .tipo_csv(TipoRecordCsv::CampionamentoNISECI)
+                .record(idx)
+                .campo("codiceSpecie")
+                .valore(r.codice_specie())
+                .into(),
+        ));
+    }
+    let codice_specie = r.codice_specie();
+    let matched_specie = match cerca_specie(indice, riferimento_specie, &codice_specie, correzioni)
+    {
+        Ok((specie, None)) => specie,
+        Ok((specie, Some(corretto))) => {
+            sostituzioni.push(Messaggio::CodiceSpecieCorretto {
+                record: idx,
+                errato: codice_specie.to_string(),
+                corretto,
+            });
+            specie
+        }
+        Err(messaggio) => {
+            return Err(Error::ValoreInvalido(
+                ContestoErrore::da_messaggio(messaggio)
+                    .tipo_csv(TipoRecordCsv::CampionamentoNISECI)
+                    .record(idx)
+                    .campo("codiceSpecie")
+                    .valore(codice_specie)
+                    .into(),
+            ))
         }
+    };
 
-        let niseci_rec = RecordNISECI {
-            specie: matched_specie.clone(),
-            passaggio_cattura: passaggio_cattura as u8,
-            lunghezza: r.lunghezza(),
-            peso: r.peso(),
-        };
-        campioni.push(niseci_rec);
+    if r.num_passaggio() < 1 {
+        return Err(Error::ValoreInvalido(
+            ContestoErrore::da_messaggio(Messaggio::NumPassaggioNonValido(
+                r.num_passaggio().to_string(),
+            ))
+            .tipo_csv(TipoRecordCsv::CampionamentoNISECI)
+            .record(idx)
+            .campo("numPassaggio")
+            .valore(r.num_passaggio())
+            .into(),
+        ));
     }
+    let passaggio_cattura = r.num_passaggio();
+
+    if !r.peso().is_finite() {
+        return Err(Error::ValoreInvalido(
+            ContestoErrore::da_messaggio(Messaggio::PesoNonValido(r.peso().to_string()))
+                .tipo_csv(TipoRecordCsv::CampionamentoNISECI)
+                .record(idx)
+                .campo("peso")
+                .valore(r.peso())
+                .into(),
+        ));
+    }
+
+    let data = match parse_date(&r.data()) {
+        Ok(data) => data,
+        Err(_) => {
+            return Err(Error::ValoreInvalido(
+                ContestoErrore::da_messaggio(Messaggio::DataNonValida(r.data().to_string()))
+                    .tipo_csv(TipoRecordCsv::CampionamentoNISECI)
+                    .record(idx)
+                    .campo("data")
+                    .valore(r.data())
+                    .into(),
+            ))
+        }
+    };
+
+    Ok(RecordNISECI {
+        specie: matched_specie.clone(),
+        passaggio_cattura: passaggio_cattura as u8,
+        lunghezza: r.lunghezza(),
+        peso: r.peso(),
+        data: Some(data),
+    })
+}
+
+pub fn parse_recordcsv_campionamento_niseci<T: RecordCsvCampionamentoNISECI>(
+    records: Vec<T>,
+    riferimento_specie: Vec<SpecieNISECI>,
+) -> (Vec<RecordNISECI>, Vec<Error>) {
+    let (campioni, errors, _) =
+        parse_recordcsv_campionamento_niseci_corretto(records, riferimento_specie, None);
     (campioni, errors)
 }
 
-#[derive(Debug)]
-pub enum RecordCsvAnagraficaNISECIError {
-    ValoreInvalido { msg: String }, //TODO: add position?
+/// Come parse_recordcsv_campionamento_niseci(), applicando le correzioni confermate ai codici
+/// specie sconosciuti. Restituisce anche le sostituzioni effettuate
+fn parse_recordcsv_campionamento_niseci_corretto<T: RecordCsvCampionamentoNISECI>(
+    records: Vec<T>,
+    riferimento_specie: Vec<SpecieNISECI>,
+    correzioni: Option<&CorrezioniSpecie>,
+) -> (Vec<RecordNISECI>, Vec<Error>, Vec<Messaggio>) {
+    let mut sostituzioni = Vec::new();
+    let indice = IndiceSpecie::da_elenco(&riferimento_specie);
+    let mut campioni = Vec::new();
+    let mut errors = Vec::new();
+    let mut idx = 0;
+    for r in &records {
+        idx += 1;
+        match parse_record_campionamento_niseci(
+            idx,
+            r,
+            &riferimento_specie,
+            &indice,
+            correzioni,
+            &mut sostituzioni,
+        ) {
+            Ok(niseci_rec) => campioni.push(niseci_rec),
+            Err(err) => errors.push(err),
+        }
+    }
+    let errors = arricchisci_errori(errors, &records);
+    (campioni, errors, sostituzioni)
+}
+
+/// Come parse_recordcsv_campionamento_niseci(), ma raggruppa i record per (stazione, data):
+/// ogni gruppo è un campionamento distinto
+pub fn parse_recordcsv_campionamenti_niseci<T: RecordCsvCampionamentoNISECI>(
+    records: Vec<T>,
+    riferimento_specie: Vec<SpecieNISECI>,
+) -> (
+    BTreeMap<ChiaveCampionamentoNISECI, CampionamentoNISECI>,
+    Vec<Error>,
+) {
+    let (campionamenti, errors, _) =
+        parse_recordcsv_campionamenti_niseci_corretto(records, riferimento_specie, None);
+    (campionamenti, errors)
 }
 
-impl fmt::Display for RecordCsvAnagraficaNISECIError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let string_representation = match self {
-            RecordCsvAnagraficaNISECIError::ValoreInvalido { msg } => {
-                format!("Errore record anagrafica NISECI: {}", msg)
+fn parse_recordcsv_campionamenti_niseci_corretto<T: RecordCsvCampionamentoNISECI>(
+    records: Vec<T>,
+    riferimento_specie: Vec<SpecieNISECI>,
+    correzioni: Option<&CorrezioniSpecie>,
+) -> (
+    BTreeMap<ChiaveCampionamentoNISECI, CampionamentoNISECI>,
+    Vec<Error>,
+    Vec<Messaggio>,
+) {
+    let mut sostituzioni = Vec::new();
+    let indice = IndiceSpecie::da_elenco(&riferimento_specie);
+    let mut campionamenti: BTreeMap<ChiaveCampionamentoNISECI, CampionamentoNISECI> =
+        BTreeMap::new();
+    let mut errors = Vec::new();
+    let mut idx = 0;
+    for r in &records {
+        idx += 1;
+        let stazione = r.stazione().trim().to_string();
+        if stazione.is_empty() {
+            let err = Error::ValoreInvalido(
+                ContestoErrore::da_messaggio(Messaggio::StazioneVuota)
+                    .tipo_csv(TipoRecordCsv::CampionamentoNISECI)
+                    .record(idx)
+                    .campo("stazione")
+                    .valore(r.stazione())
+                    .into(),
+            );
+            errors.push(err);
+            continue;
+        }
+        let data = match parse_date(&r.data()) {
+            Ok(data) => data,
+            Err(_) => {
+                let err = Error::ValoreInvalido(
+                    ContestoErrore::da_messaggio(Messaggio::DataNonValida(r.data().to_string()))
+                        .tipo_csv(TipoRecordCsv::CampionamentoNISECI)
+                        .record(idx)
+                        .campo("data")
+                        .valore(r.data())
+                        .into(),
+                );
+                errors.push(err);
+                continue;
             }
         };
-        write!(f, "{}", string_representation)
+        match parse_record_campionamento_niseci(
+            idx,
+            r,
+            &riferimento_specie,
+            &indice,
+            correzioni,
+            &mut sostituzioni,
+        ) {
+            Ok(niseci_rec) => {
+                campionamenti
+                    .entry(ChiaveCampionamentoNISECI { stazione, data })
+                    .or_insert_with(|| CampionamentoNISECI {
+                        campionamento: Vec::new(),
+                    })
+                    .campionamento
+                    .push(niseci_rec);
+            }
+            Err(err) => errors.push(err),
+        }
     }
+    let errors = arricchisci_errori(errors, &records);
+    (campionamenti, errors, sostituzioni)
 }
 
 pub fn parse_recordcsv_anagrafica_niseci<T: RecordCsvAnagraficaNISECI>(
     records: Vec<T>,
-) -> Result<AnagraficaNISECI, Vec<RecordCsvAnagraficaNISECIError>> {
+) -> Result<AnagraficaNISECI, Vec<Error>> {
     let mut errors = Vec::new();
     if records.len() > 1 {
-        let err = RecordCsvAnagraficaNISECIError::ValoreInvalido {
-            msg: format!("Troppi record: {}, atteso 1", records.len()),
-        };
+        let err = Error::NumeroRecord(
+            ContestoErrore::da_messaggio(Messaggio::TroppiRecord(records.len()))
+                .tipo_csv(TipoRecordCsv::AnagraficaNISECI)
+                .into(),
+        );
         errors.push(err);
     }
     if records.is_empty() {
-        let err = RecordCsvAnagraficaNISECIError::ValoreInvalido {
-            msg: "Nessun record trovato: atteso 1".to_string(),
-        };
+        let err = Error::NumeroRecord(
+            ContestoErrore::da_messaggio(Messaggio::NessunRecord)
+                .tipo_csv(TipoRecordCsv::AnagraficaNISECI)
+                .into(),
+        );
         errors.push(err);
         return Err(errors);
     }
 
     let r = records.first().unwrap();
 
+    match parse_record_anagrafica_niseci(r) {
+        Ok(anagrafica) => {
+            if !errors.is_empty() {
+                return Err(errors);
+            }
+            Ok(anagrafica)
+        }
+        Err(record_errors) => {
+            errors.extend(record_errors);
+            Err(arricchisci_errori_record(errors, r))
+        }
+    }
+}
+
+/// Come parse_recordcsv_anagrafica_niseci(), ma accetta un record per ogni stazione (e data).
+/// Un record con stesso codice stazione e stessa data di uno precedente è un errore.
+pub fn parse_recordcsv_registro_anagrafica_niseci<T: RecordCsvAnagraficaNISECI>(
+    records: Vec<T>,
+) -> (RegistroStazioni<AnagraficaNISECI>, Vec<Error>) {
+    let mut registro = RegistroStazioni::new();
+    let mut errors = Vec::new();
+    let mut idx = 0;
+    for r in &records {
+        idx += 1;
+        match parse_record_anagrafica_niseci(r) {
+            Ok(anagrafica) => {
+                if let Err(duplicato) = registro.inserisci(anagrafica) {
+                    let err = Error::ValoreInvalido(
+                        ContestoErrore::da_messaggio(Messaggio::StazioneDuplicata {
+                            data: duplicato.date_string.to_string(),
+                            stazione: duplicato.codice_stazione.to_string(),
+                        })
+                        .tipo_csv(TipoRecordCsv::AnagraficaNISECI)
+                        .record(idx)
+                        .campo("codiceStazione")
+                        .valore(&duplicato.codice_stazione)
+                        .into(),
+                    );
+                    errors.push(err);
+                }
+            }
+            Err(record_errors) => {
+                errors.extend(record_errors.into_iter().map(|e| e.con_record(idx)));
+            }
+        }
+    }
+    let errors = arricchisci_errori(errors, &records);
+    (registro, errors)
+}
+
+/// Valida un singolo record di anagrafica
+fn parse_record_anagrafica_niseci<T: RecordCsvAnagraficaNISECI>(
+    r: &T,
+) -> Result<AnagraficaNISECI, Vec<Error>> {
+    let mut errors = Vec::new();
+
     if r.codice_stazione().is_empty() {
-        let err = RecordCsvAnagraficaNISECIError::ValoreInvalido {
-            msg: format!("Codice stazione troppo corto: {}", r.codice_stazione()),
-        };
+        let err = Error::ValoreInvalido(
+            ContestoErrore::da_messaggio(Messaggio::CampoTroppoCorto {
+                campo: "codiceStazione",
+                valore: r.codice_stazione().to_string(),
+            })
+            .tipo_csv(TipoRecordCsv::AnagraficaNISECI)
+            .campo("codiceStazione")
+            .valore(r.codice_stazione())
+            .into(),
+        );
         errors.push(err);
     }
 
     if r.corpo_idrico().is_empty() {
-        let err = RecordCsvAnagraficaNISECIError::ValoreInvalido {
-            msg: format!("Corpo idrico troppo corto: {}", r.corpo_idrico()),
-        };
+        let err = Error::ValoreInvalido(
+            ContestoErrore::da_messaggio(Messaggio::CampoTroppoCorto {
+                campo: "corpoIdrico",
+                valore: r.corpo_idrico().to_string(),
+            })
+            .tipo_csv(TipoRecordCsv::AnagraficaNISECI)
+            .campo("corpoIdrico")
+            .valore(r.corpo_idrico())
+            .into(),
+        );
         errors.push(err);
     }
 
     if r.regione().is_empty() {
-        let err = RecordCsvAnagraficaNISECIError::ValoreInvalido {
-            msg: format!("Regione troppo corta: {}", r.regione()),
-        };
+        let err = Error::ValoreInvalido(
+            ContestoErrore::da_messaggio(Messaggio::CampoTroppoCorto {
+                campo: "regione",
+                valore: r.regione().to_string(),
+            })
+            .tipo_csv(TipoRecordCsv::AnagraficaNISECI)
+            .campo("regione")
+            .valore(r.regione())
+            .into(),
+        );
         errors.push(err);
     }
 
     if r.provincia().is_empty() {
-        let err = RecordCsvAnagraficaNISECIError::ValoreInvalido {
-            msg: format!("Provincia troppo corta: {}", r.provincia()),
-        };
+        let err = Error::ValoreInvalido(
+            ContestoErrore::da_messaggio(Messaggio::CampoTroppoCorto {
+                campo: "provincia",
+                valore: r.provincia().to_string(),
+            })
+            .tipo_csv(TipoRecordCsv::AnagraficaNISECI)
+            .campo("provincia")
+            .valore(r.provincia())
+            .into(),
+        );
         errors.push(err);
     }
 
-    match parse_date(&r.data()) {
-        Ok(_) => {}
-        Err(e) => match e.kind() {
-            ParseErrorKind::OutOfRange => {
-                let err = RecordCsvAnagraficaNISECIError::ValoreInvalido {
-                    msg: "Data fornita non valida: fuori range".to_string(),
-                };
-                errors.push(err);
-            }
-            ParseErrorKind::Impossible => {
-                let err = RecordCsvAnagraficaNISECIError::ValoreInvalido {
-                    msg: "Data fornita non valida: valori non possibili".to_string(),
-                };
-                errors.push(err);
-            }
-            ParseErrorKind::NotEnough => {
-                let err = RecordCsvAnagraficaNISECIError::ValoreInvalido {
-                    msg: "Data fornita non valida: specifica insufficiente".to_string(),
-                };
-                errors.push(err);
-            }
-            ParseErrorKind::Invalid => {
-                let err = RecordCsvAnagraficaNISECIError::ValoreInvalido {
-                    msg: "Data fornita non valida: presenza di caratteri non attesi".to_string(),
-                };
-                errors.push(err);
-            }
-            ParseErrorKind::TooShort => {
-                let err = RecordCsvAnagraficaNISECIError::ValoreInvalido {
-                    msg: "Data fornita non valida: terminazione prematura dell'input".to_string(),
-                };
-                errors.push(err);
-            }
-            ParseErrorKind::TooLong => {
-                let err = RecordCsvAnagraficaNISECIError::ValoreInvalido {
-                    msg: "Data fornita non valida: input in eccesso".to_string(),
-                };
-                errors.push(err);
-            }
-            ParseErrorKind::BadFormat => {
-                let err = RecordCsvAnagraficaNISECIError::ValoreInvalido {
-                    msg: "Data fornita non valida: errore nella specifica di formattazione"
-                        .to_string(),
-                };
-                errors.push(err);
-            }
-            _ => {
-                let err = RecordCsvAnagraficaNISECIError::ValoreInvalido {
-                    msg: "Data fornita non valida: errore sconosciuto".to_string(),
-                };
-                errors.push(err);
-            }
-        },
+    if let Err(e) = parse_date(&r.data()) {
+        let err = Error::ValoreInvalido(
+            ContestoErrore::da_messaggio(Messaggio::DataFornitaNonValida(e))
+                .tipo_csv(TipoRecordCsv::AnagraficaNISECI)
+                .campo("data")
+                .valore(r.data())
+                .into(),
+        );
+        errors.push(err);
     }
 
     if r.lunghezza_stazione() < 0.0 {
-        let err = RecordCsvAnagraficaNISECIError::ValoreInvalido {
-            msg: format!(
-                "Lunghezza stazione troppo bassa: {}",
-                r.lunghezza_stazione()
-            ),
-        };
+        let err = Error::ValoreInvalido(
+            ContestoErrore::da_messaggio(Messaggio::MisuraTroppoBassa {
+                campo: "lunghezzaStazione",
+                valore: r.lunghezza_stazione().to_string(),
+            })
+            .tipo_csv(TipoRecordCsv::AnagraficaNISECI)
+            .campo("lunghezzaStazione")
+            .valore(r.lunghezza_stazione())
+            .into(),
+        );
         errors.push(err);
     }
 
     if r.larghezza_stazione() < 0.0 {
-        let err = RecordCsvAnagraficaNISECIError::ValoreInvalido {
-            msg: format!(
-                "Larghezza stazione troppo bassa: {}",
-                r.larghezza_stazione()
-            ),
-        };
+        let err = Error::ValoreInvalido(
+            ContestoErrore::da_messaggio(Messaggio::MisuraTroppoBassa {
+                campo: "larghezzaStazione",
+                valore: r.larghezza_stazione().to_string(),
+            })
+            .tipo_csv(TipoRecordCsv::AnagraficaNISECI)
+            .campo("larghezzaStazione")
+            .valore(r.larghezza_stazione())
+            .into(),
+        );
         errors.push(err);
     }
 
@@ -480,32 +792,48 @@ pub fn parse_recordcsv_anagrafica_niseci<T: RecordCsvAnagraficaNISECI>(
             tipo_comunita = TipoComunitaNISECI::AffinataDalMase;
         }
         _ => {
-            let err = RecordCsvAnagraficaNISECIError::ValoreInvalido {
-                msg: format!(
-                    "Tipo comunita NISECI non valido: {}, atteso [0, 3]",
-                    r.tipo_comunita()
-                ),
-            };
+            let err = Error::ValoreInvalido(
+                ContestoErrore::da_messaggio(Messaggio::CodiceFuoriIntervallo {
+                    tipo: "TipoComunitaNISECI",
+                    valore: r.tipo_comunita().to_string(),
+                    min: 0,
+                    max: 3,
+                })
+                .tipo_csv(TipoRecordCsv::AnagraficaNISECI)
+                .campo("tipoComunita")
+                .valore(r.tipo_comunita())
+                .into(),
+            );
             errors.push(err);
         }
     }
 
     match tipo_comunita {
-        TipoComunitaNISECI::Recuperata => {
-            if r.fonte().is_empty() {
-                let err = RecordCsvAnagraficaNISECIError::ValoreInvalido {
-                    msg: format!("Fonte troppo corta: {}", r.fonte()),
-                };
-                errors.push(err);
-            }
+        TipoComunitaNISECI::Recuperata if r.fonte().is_empty() => {
+            let err = Error::ValoreInvalido(
+                ContestoErrore::da_messaggio(Messaggio::CampoTroppoCorto {
+                    campo: "fonte",
+                    valore: r.fonte().to_string(),
+                })
+                .tipo_csv(TipoRecordCsv::AnagraficaNISECI)
+                .campo("fonte")
+                .valore(r.fonte())
+                .into(),
+            );
+            errors.push(err);
         }
-        TipoComunitaNISECI::AffinataDalMase => {
-            if r.numero_protocollo().is_empty() {
-                let err = RecordCsvAnagraficaNISECIError::ValoreInvalido {
-                    msg: format!("Numero protocollo troppo corto: {}", r.numero_protocollo()),
-                };
-                errors.push(err);
-            }
+        TipoComunitaNISECI::AffinataDalMase if r.numero_protocollo().is_empty() => {
+            let err = Error::ValoreInvalido(
+                ContestoErrore::da_messaggio(Messaggio::CampoTroppoCorto {
+                    campo: "numeroProtocollo",
+                    valore: r.numero_protocollo().to_string(),
+                })
+                .tipo_csv(TipoRecordCsv::AnagraficaNISECI)
+                .campo("numeroProtocollo")
+                .valore(r.numero_protocollo())
+                .into(),
+            );
+            errors.push(err);
         }
         _ => {}
     }
@@ -533,12 +861,18 @@ pub fn parse_recordcsv_anagrafica_niseci<T: RecordCsvAnagraficaNISECI>(
         19 => IdroEcoRegioneNISECI::Sicilia,
         20 => IdroEcoRegioneNISECI::Sardegna,
         _ => {
-            let err = RecordCsvAnagraficaNISECIError::ValoreInvalido {
-                msg: format!(
-                    "IdroEcoRegioneNISECI non valido: {}, atteso [0, 20]",
-                    r.idro_eco_regione()
-                ),
-            };
+            let err = Error::ValoreInvalido(
+                ContestoErrore::da_messaggio(Messaggio::CodiceFuoriIntervallo {
+                    tipo: "IdroEcoRegioneNISECI",
+                    valore: r.idro_eco_regione().to_string(),
+                    min: 0,
+                    max: 20,
+                })
+                .tipo_csv(TipoRecordCsv::AnagraficaNISECI)
+                .campo("idroEcoRegione")
+                .valore(r.idro_eco_regione())
+                .into(),
+            );
             errors.push(err);
             IdroEcoRegioneNISECI::Toscana // To still assign something by default
         }
@@ -550,9 +884,16 @@ pub fn parse_recordcsv_anagrafica_niseci<T: RecordCsvAnagraficaNISECI>(
     }
 
     if r.nome_bacino().is_empty() {
-        let err = RecordCsvAnagraficaNISECIError::ValoreInvalido {
-            msg: format!("Nome bacino troppo corto: {}", r.nome_bacino()),
-        };
+        let err = Error::ValoreInvalido(
+            ContestoErrore::da_messaggio(Messaggio::CampoTroppoCorto {
+                campo: "nomeBacino",
+                valore: r.nome_bacino().to_string(),
+            })
+            .tipo_csv(TipoRecordCsv::AnagraficaNISECI)
+            .campo("nomeBacino")
+            .valore(r.nome_bacino())
+            .into(),
+        );
         errors.push(err);
     }
 
@@ -567,7 +908,8 @@ pub fn parse_recordcsv_anagrafica_niseci<T: RecordCsvAnagraficaNISECI>(
             numero_protocollo: Some(r.numero_protocollo()),
         },
         codice_stazione: r.codice_stazione(),
-        date_string: r.data(), // Formato gg/mm/aaaa
+        date_string: r.data(),
+        data: parse_date(&r.data()).ok(),
         area,
         corpo_idrico: r.corpo_idrico(),
         bacino_appartenenza: r.nome_bacino(),
@@ -584,106 +926,256 @@ pub fn parse_recordcsv_anagrafica_niseci<T: RecordCsvAnagraficaNISECI>(
 
 pub fn check_records_riferimento_niseci<T: RecordCsvRiferimentoNISECI>(
     records: Vec<T>,
-) -> Result<Vec<SpecieNISECI>, Vec<RecordCsvRiferimentoNISECIError>> {
+    opzioni: &OpzioniVerifica,
+) -> Result<(Vec<SpecieNISECI>, Diagnostics), Diagnostics> {
     let (records, errors) = parse_recordcsv_riferimento_niseci(records);
 
-    println!(
-        "Riferimento NISECI: Numero record validi: {}",
-        records.len()
-    );
-    println!(
-        "Riferimento NISECI: Numero record non validi: {}",
-        errors.len()
-    );
+    Diagnostics::from_errori(TipoRecordCsv::RiferimentoNISECI, records.len(), errors)
+        .esito(records, opzioni.modalita)
+}
 
-    if !errors.is_empty() {
-        eprintln!("Errori incontrati durante l'elaborazione dei record per riferimento NISECI: {{");
-        //TODO: add process_record_riferimentoNISECI_errors()
-        for error in &errors {
-            eprintln!("  {}", error);
-        }
-        eprintln!("}}");
-        Err(errors)
-    } else {
-        //TODO: handle verbosity
-        //println!("Tutti i record del riferimento NISECI sono stati processati con successo!");
-        /*
-        for record in &records {
-            println!("  Record: {{{record}}}");
-        }
-        */
-        Ok(records)
-    }
+pub fn check_records_comunita_riferimento_niseci<T: RecordCsvComunitaRiferimentoNISECI>(
+    records: Vec<T>,
+    opzioni: &OpzioniVerifica,
+) -> Result<(Vec<SpecieNISECI>, Diagnostics), Diagnostics> {
+    let (records, errors) = parse_recordcsv_comunita_riferimento_niseci(records);
+
+    Diagnostics::from_errori(
+        TipoRecordCsv::ComunitaRiferimentoNISECI,
+        records.len(),
+        errors,
+    )
+    .esito(records, opzioni.modalita)
 }
 
 pub fn check_records_campionamento_niseci<T: RecordCsvCampionamentoNISECI>(
     records: Vec<T>,
     riferimento_specie: Vec<SpecieNISECI>,
-) -> Result<Vec<RecordNISECI>, Vec<RecordCsvCampionamentoNISECIError>> {
-    let (records, errors) = parse_recordcsv_campionamento_niseci(records, riferimento_specie);
-
-    println!(
-        "Campionamento NISECI: Numero record validi: {}",
-        records.len()
+    opzioni: &OpzioniVerifica,
+) -> Result<(Vec<RecordNISECI>, Diagnostics), Diagnostics> {
+    let (records, errors, sostituzioni) = parse_recordcsv_campionamento_niseci_corretto(
+        records,
+        riferimento_specie,
+        opzioni.correzioni,
     );
-    println!(
-        "Campionamento NISECI: Numero record non validi: {}",
-        errors.len()
+
+    let mut diagnostics =
+        Diagnostics::from_errori(TipoRecordCsv::CampionamentoNISECI, records.len(), errors);
+    diagnostics.aggiungi_avvisi(sostituzioni);
+    diagnostics.esito(records, opzioni.modalita)
+}
+
+pub fn check_records_campionamenti_niseci<T: RecordCsvCampionamentoNISECI>(
+    records: Vec<T>,
+    riferimento_specie: Vec<SpecieNISECI>,
+    opzioni: &OpzioniVerifica,
+) -> Result<
+    (
+        BTreeMap<ChiaveCampionamentoNISECI, CampionamentoNISECI>,
+        Diagnostics,
+    ),
+    Diagnostics,
+> {
+    let (campionamenti, errors, sostituzioni) = parse_recordcsv_campionamenti_niseci_corretto(
+        records,
+        riferimento_specie,
+        opzioni.correzioni,
     );
 
-    if !errors.is_empty() {
-        eprintln!(
-            "Errori incontrati durante l'elaborazione dei record per campionamento NISECI: {{"
-        );
-        //TODO: add process_record_campionamentoNISECI_errors()
-        for error in &errors {
-            eprintln!("  {}", error);
+    let record_validi = campionamenti.values().map(|c| c.campionamento.len()).sum();
+    let mut diagnostics =
+        Diagnostics::from_errori(TipoRecordCsv::CampionamentoNISECI, record_validi, errors);
+    diagnostics.aggiungi_avvisi(sostituzioni);
+    diagnostics.esito(campionamenti, opzioni.modalita)
+}
+
+pub fn check_records_anagrafica_niseci<T: RecordCsvAnagraficaNISECI>(
+    records: Vec<T>,
+) -> Result<(AnagraficaNISECI, Diagnostics), Diagnostics> {
+    let num_records = records.len();
+    let mut diagnostics = Diagnostics::new(TipoRecordCsv::AnagraficaNISECI);
+
+    match parse_recordcsv_anagrafica_niseci(records) {
+        Ok(anagrafica) => {
+            diagnostics.record_validi = 1;
+            Ok((anagrafica, diagnostics))
         }
-        eprintln!("}}");
-        Err(errors)
-    } else {
-        //TODO: handle verbosity
-        //println!("Tutti i record del campionamento NISECI sono stati processati con successo!");
-        /*
-        for record in &records {
-            println!("  Record: {{{record}}}");
+        Err(errors) => {
+            diagnostics.record_non_validi = num_records;
+            diagnostics.errori = errors;
+            Err(diagnostics)
         }
-        */
-        Ok(records)
     }
 }
 
-pub fn check_records_anagrafica_niseci<T: RecordCsvAnagraficaNISECI>(
+pub fn check_records_registro_anagrafica_niseci<T: RecordCsvAnagraficaNISECI>(
     records: Vec<T>,
-) -> Result<AnagraficaNISECI, Vec<RecordCsvAnagraficaNISECIError>> {
-    let res = parse_recordcsv_anagrafica_niseci(records);
+    opzioni: &OpzioniVerifica,
+) -> Result<(RegistroStazioni<AnagraficaNISECI>, Diagnostics), Diagnostics> {
+    let num_records = records.len();
+    let (registro, errors) = parse_recordcsv_registro_anagrafica_niseci(records);
 
-    match res {
-        Ok(anagrafica) => {
-            println!("Anagrafica NISECI: {}", anagrafica);
-            //TODO: handle verbosity
-            //println!("Tutti i record dell'anagrafica NISECI sono stati processati con successo!");
-            /*
-            for record in &records {
-                println!("  Record: {{{record}}}");
+    let mut diagnostics = Diagnostics::new(TipoRecordCsv::AnagraficaNISECI);
+    diagnostics.record_validi = registro.len();
+    diagnostics.record_non_validi = num_records - registro.len();
+    diagnostics.errori = errors;
+    diagnostics.esito(registro, opzioni.modalita)
+}
+
+/// Controlla che il campionamento appartenga alla stazione e alla data dell'anagrafica.
+/// Segnala ogni stazione o data diversa da quella dell'anagrafica (sul primo record in cui
+/// compare) e i campionamenti con più stazioni. Restituisce anche il numero di record non coerenti.
+pub fn parse_coerenza_niseci<T: RecordCsvCampionamentoNISECI>(
+    anagrafica: &AnagraficaNISECI,
+    records: &[T],
+) -> (usize, Vec<Error>) {
+    let mut errors = Vec::new();
+    let mut non_coerenti = 0;
+    let stazione_anagrafica = anagrafica.codice_stazione.trim();
+    let data_anagrafica = AnagraficaStazione::data(anagrafica);
+    let mut stazioni: Vec<String> = Vec::new();
+    let mut date_segnalate: Vec<NaiveDate> = Vec::new();
+    let mut idx = 0;
+    for r in records {
+        idx += 1;
+        let mut coerente = true;
+
+        let stazione = r.stazione().trim().to_string();
+        if stazione != stazione_anagrafica {
+            coerente = false;
+            if !stazioni.contains(&stazione) {
+                let err = Error::ValoreInvalido(
+                    ContestoErrore::da_messaggio(Messaggio::StazioneNonCorrispondente {
+                        campionamento: stazione.clone(),
+                        anagrafica: stazione_anagrafica.to_string(),
+                    })
+                    .tipo_csv(TipoRecordCsv::CampionamentoNISECI)
+                    .record(idx)
+                    .campo("stazione")
+                    .valore(r.stazione())
+                    .into(),
+                );
+                errors.push(err);
             }
-            */
-            Ok(anagrafica)
         }
-        Err(errors) => {
-            println!(
-                "Anagrafica NISECI: Numero record non validi: {}",
-                errors.len()
+        if !stazioni.contains(&stazione) {
+            stazioni.push(stazione);
+        }
+
+        // Le date non valide sono già segnalate da parse_recordcsv_campionamento_niseci()
+        if let (Ok(data), Some(data_anagrafica)) = (parse_date(&r.data()), data_anagrafica) {
+            if data != data_anagrafica {
+                coerente = false;
+                if !date_segnalate.contains(&data) {
+                    let err = Error::ValoreInvalido(
+                        ContestoErrore::da_messaggio(Messaggio::DataNonCorrispondente {
+                            campionamento: r.data().trim().to_string(),
+                            anagrafica: anagrafica.date_string.trim().to_string(),
+                        })
+                        .tipo_csv(TipoRecordCsv::CampionamentoNISECI)
+                        .record(idx)
+                        .campo("data")
+                        .valore(r.data())
+                        .into(),
+                    );
+                    errors.push(err);
+                    date_segnalate.push(data);
+                }
+            }
+        }
+
+        if !coerente {
+            non_coerenti += 1;
+        }
+    }
+
+    if stazioni.len() > 1 {
+        let err = Error::ValoreInvalido(
+            ContestoErrore::da_messaggio(Messaggio::StazioniMultiple(stazioni))
+                .tipo_csv(TipoRecordCsv::CampionamentoNISECI)
+                .campo("stazione")
+                .into(),
+        );
+        errors.push(err);
+    }
+
+    (non_coerenti, arricchisci_errori(errors, records))
+}
+
+/// Come parse_coerenza_niseci(), per più campionamenti e un registro di anagrafiche:
+/// segnala le stazioni senza anagrafica e i campionamenti la cui data non corrisponde
+/// a nessuna anagrafica della stazione. Restituisce anche il numero di campionamenti non coerenti.
+pub fn parse_coerenza_campionamenti_niseci<V>(
+    registro: &RegistroStazioni<AnagraficaNISECI>,
+    campionamenti: &BTreeMap<ChiaveCampionamentoNISECI, V>,
+) -> (usize, Vec<Error>) {
+    let mut errors = Vec::new();
+    for chiave in campionamenti.keys() {
+        let anagrafiche = registro.get(&chiave.stazione);
+        if anagrafiche.is_empty() {
+            let err = Error::ValoreInvalido(
+                ContestoErrore::da_messaggio(Messaggio::AnagraficaMancante(
+                    chiave.stazione.clone(),
+                ))
+                .tipo_csv(TipoRecordCsv::CampionamentoNISECI)
+                .campo("stazione")
+                .valore(&chiave.stazione)
+                .into(),
             );
-            eprintln!(
-                "Errori incontrati durante l'elaborazione dei record per anagrafica NISECI: {{"
+            errors.push(err);
+        } else if !anagrafiche
+            .iter()
+            .any(|a| AnagraficaStazione::data(a) == Some(chiave.data))
+        {
+            let date_anagrafica: Vec<&str> =
+                anagrafiche.iter().map(|a| a.date_string.trim()).collect();
+            let data = chiave.data.format("%d/%m/%Y").to_string();
+            let err = Error::ValoreInvalido(
+                ContestoErrore::da_messaggio(Messaggio::DataNonCorrispondente {
+                    campionamento: format!("{} ({})", data, chiave.stazione),
+                    anagrafica: date_anagrafica.join(", "),
+                })
+                .tipo_csv(TipoRecordCsv::CampionamentoNISECI)
+                .campo("data")
+                .valore(data)
+                .into(),
             );
-            //TODO: add process_record_anagraficaNISECI_errors()
-            for error in &errors {
-                eprintln!("  {}", error);
-            }
-            eprintln!("}}");
-            Err(errors)
+            errors.push(err);
         }
     }
+    (errors.len(), errors)
+}
+
+/// Verifica incrociata tra anagrafica e campionamento NISECI, da eseguire prima del calcolo:
+/// fallisce se il campionamento riporta stazioni o date diverse da quelle dell'anagrafica
+pub fn check_coerenza_niseci<T: RecordCsvCampionamentoNISECI>(
+    anagrafica: &AnagraficaNISECI,
+    records: &[T],
+) -> Result<Diagnostics, Diagnostics> {
+    let (non_coerenti, errors) = parse_coerenza_niseci(anagrafica, records);
+
+    let mut diagnostics = Diagnostics::new(TipoRecordCsv::CampionamentoNISECI);
+    diagnostics.record_validi = records.len() - non_coerenti;
+    diagnostics.record_non_validi = non_coerenti;
+    diagnostics.errori = errors;
+    diagnostics
+        .esito((), ValidationMode::Strict)
+        .map(|(_, d)| d)
+}
+
+/// Come check_coerenza_niseci(), tra un registro di anagrafiche e i campionamenti di
+/// check_records_campionamenti_niseci(); i conteggi si riferiscono ai campionamenti
+pub fn check_coerenza_campionamenti_niseci<V>(
+    registro: &RegistroStazioni<AnagraficaNISECI>,
+    campionamenti: &BTreeMap<ChiaveCampionamentoNISECI, V>,
+) -> Result<Diagnostics, Diagnostics> {
+    let (non_coerenti, errors) = parse_coerenza_campionamenti_niseci(registro, campionamenti);
+
+    let mut diagnostics = Diagnostics::new(TipoRecordCsv::CampionamentoNISECI);
+    diagnostics.record_validi = campionamenti.len() - non_coerenti;
+    diagnostics.record_non_validi = non_coerenti;
+    diagnostics.errori = errors;
+    diagnostics
+        .esito((), ValidationMode::Strict)
+        .map(|(_, d)| d)
 }
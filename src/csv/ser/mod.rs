@@ -0,0 +1,246 @@
+// SPDX-License-Identifier: GPL-3.0-only
+/*
+    Copyright (C) 2024-2026 jgabaut, gioninjo
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU General Public License as published by
+    the Free Software Foundation, version 3 of the License.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU General Public License for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use crate::csv::deser::sniff::{FormatoRilevato, LocaleCsv};
+use crate::domain::hfbi::{AnagraficaHFBI, RisultatoHFBI};
+use crate::domain::niseci::{AnagraficaNISECI, RisultatoNISECI};
+use crate::engines::hfbi::full::calculate_stato_ecologico_hfbi;
+use crate::engines::niseci::full::calculate_stato_ecologico_niseci;
+use crate::error::{ContestoErrore, Error};
+use crate::i18n::Messaggio;
+use std::io::Write;
+
+pub const RIEPILOGO_NISECI_HEADER_FIELDS: [&str; 8] = [
+    "codiceStazione",
+    "data",
+    "niseci",
+    "rqe",
+    "statoEcologico",
+    "x1",
+    "x2",
+    "x3",
+];
+
+pub const SPECIE_NISECI_HEADER_FIELDS: [&str; 18] = [
+    "codiceStazione",
+    "data",
+    "codiceSpecie",
+    "nomeLatino",
+    "tipoAutoctono",
+    "tipoAlloctono",
+    "specieAttesa",
+    "cl1",
+    "cl2",
+    "cl3",
+    "cl4",
+    "cl5",
+    "densitaStimata",
+    "quantitaStimata",
+    "x2b",
+    "rapportoAdJuv",
+    "x2aA",
+    "x2aB",
+];
+
+pub const RIEPILOGO_HFBI_HEADER_FIELDS: [&str; 11] = [
+    "codiceStazione",
+    "data",
+    "hfbi",
+    "statoEcologico",
+    "mmi",
+    "bn",
+    "ddom",
+    "dmig",
+    "bbent",
+    "dbent",
+    "dhzp",
+];
+
+/// Formato dei file csv prodotti
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct FormatoOutput {
+    pub delimitatore: u8,
+    pub separatore_decimale: u8,
+}
+
+impl FormatoOutput {
+    /// `;` come delimitatore, `,` come separatore decimale (Excel con locale italiano)
+    pub fn italiano() -> Self {
+        Self {
+            delimitatore: b';',
+            separatore_decimale: b',',
+        }
+    }
+    /// `,` come delimitatore, `.` come separatore decimale
+    pub fn internazionale() -> Self {
+        Self {
+            delimitatore: b',',
+            separatore_decimale: b'.',
+        }
+    }
+
+    fn format_f32(&self, v: f32) -> String {
+        let s = format!("{v}");
+        if self.separatore_decimale == b'.' {
+            s
+        } else {
+            s.replace('.', &(self.separatore_decimale as char).to_string())
+        }
+    }
+
+    fn format_opt_f32(&self, v: Option<f32>) -> String {
+        match v {
+            Some(v) => self.format_f32(v),
+            None => "NC".to_string(),
+        }
+    }
+}
+
+impl From<&FormatoRilevato> for FormatoOutput {
+    /// Stesso formato del file di input (un locale non standard viene scritto come internazionale)
+    fn from(formato: &FormatoRilevato) -> Self {
+        match formato.locale {
+            LocaleCsv::Italiano => Self::italiano(),
+            LocaleCsv::Internazionale | LocaleCsv::NonStandard => Self::internazionale(),
+        }
+    }
+}
+
+fn converti_errore_scrittura(e: csv::Error) -> Error {
+    Error::Io(ContestoErrore::da_messaggio(Messaggio::Esterno(e.to_string())).into())
+}
+
+fn csv_writer<W: Write>(writer: W, formato: &FormatoOutput) -> csv::Writer<W> {
+    csv::WriterBuilder::new()
+        .delimiter(formato.delimitatore)
+        .from_writer(writer)
+}
+
+/// Scrive il riepilogo dei risultati NISECI, una riga per campionamento:
+/// stazione, data, valore NISECI, RQE, stato ecologico e metriche x1/x2/x3
+pub fn write_riepilogo_niseci<W: Write>(
+    writer: W,
+    risultati: &[(&AnagraficaNISECI, &RisultatoNISECI)],
+    formato: &FormatoOutput,
+) -> Result<(), Error> {
+    let mut wtr = csv_writer(writer, formato);
+    wtr.write_record(RIEPILOGO_NISECI_HEADER_FIELDS)
+        .map_err(converti_errore_scrittura)?;
+    for (anagrafica, risultato) in risultati {
+        let stato_str =
+            match calculate_stato_ecologico_niseci(risultato.get_valore(), &anagrafica.area) {
+                Some(s) => s.to_string(),
+                None => "NC".to_string(),
+            };
+        wtr.write_record([
+            anagrafica.codice_stazione.clone(),
+            anagrafica.date_string.clone(),
+            formato.format_opt_f32(risultato.get_valore()),
+            formato.format_opt_f32(risultato.get_rqe()),
+            stato_str,
+            formato.format_f32(risultato.get_x1()),
+            formato.format_opt_f32(risultato.get_x2()),
+            formato.format_f32(risultato.get_x3()),
+        ])
+        .map_err(converti_errore_scrittura)?;
+    }
+    wtr.flush()?;
+    Ok(())
+}
+
+/// Scrive i valori intermedi di ogni specie (specie_specifici), una riga per specie e campionamento.
+/// Le specie sono ordinate per codice.
+pub fn write_specie_niseci<W: Write>(
+    writer: W,
+    risultati: &[(&AnagraficaNISECI, &RisultatoNISECI)],
+    formato: &FormatoOutput,
+) -> Result<(), Error> {
+    let mut wtr = csv_writer(writer, formato);
+    wtr.write_record(SPECIE_NISECI_HEADER_FIELDS)
+        .map_err(converti_errore_scrittura)?;
+    for (anagrafica, risultato) in risultati {
+        let specie_specifici = &risultato.get_valori_intermedi().specie_specifici;
+        let mut codici: Vec<&String> = specie_specifici.keys().collect();
+        codici.sort();
+        for codice in codici {
+            let v = &specie_specifici[codice];
+            let specie = &v.classi_eta.specie;
+            let specie_attesa_str = match specie.specie_attesa {
+                true => "SI".to_string(),
+                false => "NO".to_string(),
+            };
+            wtr.write_record([
+                anagrafica.codice_stazione.clone(),
+                anagrafica.date_string.clone(),
+                codice.clone(),
+                specie.nome.clone(),
+                specie.tipo_autoctono.to_string(),
+                specie.tipo_alloctono.to_string(),
+                specie_attesa_str,
+                v.classi_eta.cl1.to_string(),
+                v.classi_eta.cl2.to_string(),
+                v.classi_eta.cl3.to_string(),
+                v.classi_eta.cl4.to_string(),
+                v.classi_eta.cl5.to_string(),
+                formato.format_f32(v.densita_stimata),
+                v.quantita_stimata.to_string(),
+                formato.format_f32(v.x2_b),
+                formato.format_opt_f32(v.rapporto_ad_juv),
+                v.x2_a_a.to_string(),
+                v.x2_a_b.to_string(),
+            ])
+            .map_err(converti_errore_scrittura)?;
+        }
+    }
+    wtr.flush()?;
+    Ok(())
+}
+
+/// Scrive il riepilogo dei risultati HFBI, una riga per campionamento:
+/// stazione, data, valore HFBI, stato ecologico, MMI e le sei metriche
+pub fn write_riepilogo_hfbi<W: Write>(
+    writer: W,
+    risultati: &[(&AnagraficaHFBI, &RisultatoHFBI)],
+    formato: &FormatoOutput,
+) -> Result<(), Error> {
+    let mut wtr = csv_writer(writer, formato);
+    wtr.write_record(RIEPILOGO_HFBI_HEADER_FIELDS)
+        .map_err(converti_errore_scrittura)?;
+    for (anagrafica, risultato) in risultati {
+        let stato_str = match calculate_stato_ecologico_hfbi(risultato.get_valore()) {
+            Some(s) => s.to_string(),
+            None => "NC".to_string(),
+        };
+        let intermedi = risultato.get_intermediates();
+        wtr.write_record([
+            anagrafica.codice_stazione.clone(),
+            anagrafica.date_string.clone(),
+            formato.format_opt_f32(risultato.get_valore()),
+            stato_str,
+            formato.format_f32(intermedi.mmi),
+            formato.format_f32(intermedi.bn),
+            formato.format_f32(intermedi.ddom),
+            formato.format_f32(intermedi.dmig),
+            formato.format_f32(intermedi.bbent),
+            formato.format_f32(intermedi.dbent),
+            formato.format_f32(intermedi.dhzp),
+        ])
+        .map_err(converti_errore_scrittura)?;
+    }
+    wtr.flush()?;
+    Ok(())
+}
@@ -0,0 +1,270 @@
+// SPDX-License-Identifier: GPL-3.0-only
+/*
+    Copyright (C) 2024-2026 jgabaut, gioninjo
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU General Public License as published by
+    the Free Software Foundation, version 3 of the License.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU General Public License for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use crate::csv::deser::sniff::{FormatoRilevato, LocaleCsv};
+use crate::csv::TipoRecordCsv;
+use crate::error::{Error, Posizione};
+use crate::i18n::{Etichetta, Lingua, Messaggio};
+use std::fmt;
+
+/// Come trattare i record non validi durante la lettura e la validazione
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum ValidationMode {
+    /// Qualsiasi errore fa fallire l'intera fase
+    #[default]
+    Strict,
+    /// I record non validi vengono scartati e riportati in Diagnostics::scartati,
+    /// i record validi vengono restituiti
+    Lenient,
+}
+
+/// Record scartato in modalità Lenient, con i motivi dello scarto
+#[derive(Debug, Clone, PartialEq)]
+pub struct RigaScartata {
+    pub tipo_csv: Option<TipoRecordCsv>,
+    /// Numero progressivo del record (a partire da 1)
+    pub record: usize,
+    /// Posizione fisica nel file, se nota
+    pub posizione: Option<Posizione>,
+    pub errori: Vec<Error>,
+}
+
+/// Resoconto di una fase di elaborazione (lettura csv o validazione dei record).
+/// La libreria non stampa nulla: il chiamante decide cosa farne.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Diagnostics {
+    pub sorgente: Option<TipoRecordCsv>,
+    pub record_validi: usize,
+    pub record_non_validi: usize,
+    /// Avvisi non bloccanti, scritti nella lingua richiesta da descrizione() (in italiano da Display)
+    pub avvisi: Vec<Messaggio>,
+    pub errori: Vec<Error>,
+    /// Formato del file, quando è stato rilevato automaticamente
+    pub formato: Option<FormatoRilevato>,
+    /// Record scartati in modalità Lenient
+    pub scartati: Vec<RigaScartata>,
+}
+
+impl Diagnostics {
+    pub fn new(sorgente: TipoRecordCsv) -> Self {
+        Self {
+            sorgente: Some(sorgente),
+            ..Default::default()
+        }
+    }
+
+    pub fn from_errori(sorgente: TipoRecordCsv, record_validi: usize, errori: Vec<Error>) -> Self {
+        Self {
+            sorgente: Some(sorgente),
+            record_validi,
+            // Le colonne mancanti riguardano l'intestazione, non i singoli record
+            record_non_validi: errori
+                .iter()
+                .filter(|e| !matches!(e, Error::ColonnaMancante(_)))
+                .count(),
+            avvisi: Vec::new(),
+            errori,
+            formato: None,
+            scartati: Vec::new(),
+        }
+    }
+
+    /// Resoconto per un errore che impedisce di leggere qualsiasi record (es. file non valido)
+    pub fn from_errore(sorgente: TipoRecordCsv, errore: Error) -> Self {
+        Self {
+            sorgente: Some(sorgente),
+            errori: vec![errore],
+            ..Default::default()
+        }
+    }
+
+    /// Registra il formato rilevato, con un avviso se il file non è nel formato atteso
+    pub fn set_formato(&mut self, formato: FormatoRilevato) {
+        if formato.locale == LocaleCsv::NonStandard {
+            self.avvisi.push(Messaggio::FormatoNonStandard(formato));
+        }
+        match (self.sorgente, formato.tipo_record) {
+            (Some(atteso), Some(trovato)) if atteso != trovato => {
+                self.avvisi
+                    .push(Messaggio::TipoFileDiverso { trovato, atteso });
+            }
+            _ => {}
+        }
+        self.formato = Some(formato);
+    }
+
+    /// Aggiunge gli avvisi indicati
+    pub fn aggiungi_avvisi(&mut self, avvisi: Vec<Messaggio>) {
+        self.avvisi.extend(avvisi);
+    }
+
+    pub fn has_errori(&self) -> bool {
+        !self.errori.is_empty()
+    }
+
+    /// Sposta in `scartati` gli errori che riguardano un singolo record, raggruppandoli per record.
+    /// Restano in `errori` solo quelli che non dipendono da un record (es. colonne mancanti).
+    pub fn scarta_record_non_validi(&mut self) {
+        let mut restanti = Vec::new();
+        for errore in std::mem::take(&mut self.errori) {
+            let idx = match errore.record() {
+                Some(idx) if !matches!(errore, Error::ColonnaMancante(_)) => idx,
+                _ => {
+                    restanti.push(errore);
+                    continue;
+                }
+            };
+            match self.scartati.iter_mut().find(|r| r.record == idx) {
+                Some(riga) => {
+                    if riga.posizione.is_none() {
+                        riga.posizione = errore.posizione().cloned();
+                    }
+                    riga.errori.push(errore);
+                }
+                None => self.scartati.push(RigaScartata {
+                    tipo_csv: errore.tipo_csv().or(self.sorgente),
+                    record: idx,
+                    posizione: errore.posizione().cloned(),
+                    errori: vec![errore],
+                }),
+            }
+        }
+        self.scartati.sort_by_key(|r| r.record);
+        self.errori = restanti;
+        self.record_non_validi = self.scartati.len()
+            + self
+                .errori
+                .iter()
+                .filter(|e| !matches!(e, Error::ColonnaMancante(_)))
+                .count();
+    }
+
+    /// Esito di una fase secondo la modalità di validazione:
+    /// in Strict fallisce con qualsiasi errore, in Lenient solo con errori non legati a un record
+    pub fn esito<T>(
+        mut self,
+        valori: T,
+        mode: ValidationMode,
+    ) -> Result<(T, Diagnostics), Diagnostics> {
+        if mode == ValidationMode::Lenient {
+            self.scarta_record_non_validi();
+        }
+        if self.has_errori() {
+            Err(self)
+        } else {
+            Ok((valori, self))
+        }
+    }
+
+    /// Colonne attese che mancano nell'intestazione del file
+    pub fn colonne_mancanti(&self) -> Vec<&str> {
+        self.errori
+            .iter()
+            .filter(|e| matches!(e, Error::ColonnaMancante(_)))
+            .filter_map(|e| e.campo())
+            .collect()
+    }
+
+    /// Accoda il contenuto di un altro resoconto a questo
+    pub fn merge(&mut self, other: Diagnostics) {
+        if self.sorgente.is_none() {
+            self.sorgente = other.sorgente;
+        }
+        if self.formato.is_none() {
+            self.formato = other.formato;
+        }
+        self.record_validi += other.record_validi;
+        self.record_non_validi += other.record_non_validi;
+        self.avvisi.extend(other.avvisi);
+        self.errori.extend(other.errori);
+        self.scartati.extend(other.scartati);
+    }
+
+    /// Inoltra il resoconto alla facade `log` (target "esox")
+    #[cfg(feature = "log")]
+    pub fn emit(&self) {
+        let sorgente = match self.sorgente {
+            Some(s) => s.to_string(),
+            None => "esox".to_string(),
+        };
+        log::info!(
+            target: "esox",
+            "{}: Numero record validi: {}, non validi: {}",
+            sorgente,
+            self.record_validi,
+            self.record_non_validi
+        );
+        for avviso in &self.avvisi {
+            log::warn!(target: "esox", "{}: {}", sorgente, avviso);
+        }
+        for errore in &self.errori {
+            log::error!(target: "esox", "{}", errore);
+        }
+        for riga in &self.scartati {
+            for errore in &riga.errori {
+                log::warn!(target: "esox", "{}: {}", sorgente, errore);
+            }
+        }
+    }
+}
+
+impl Diagnostics {
+    /// Resoconto leggibile nella lingua richiesta (vedi Display)
+    pub fn descrizione(&self, lingua: Lingua) -> String {
+        let sorgente = match self.sorgente {
+            Some(s) => s.etichetta(lingua).to_string(),
+            None => "none".to_string(),
+        };
+        let mut string_representation = match lingua {
+            Lingua::Italiano => format!(
+                "Diagnostica {}: {{ record validi: {}, record non validi: {}, avvisi: {}, errori: {} }}",
+                sorgente,
+                self.record_validi,
+                self.record_non_validi,
+                self.avvisi.len(),
+                self.errori.len()
+            ),
+            Lingua::Inglese => format!(
+                "Diagnostics {}: {{ valid records: {}, invalid records: {}, warnings: {}, errors: {} }}",
+                sorgente,
+                self.record_validi,
+                self.record_non_validi,
+                self.avvisi.len(),
+                self.errori.len()
+            ),
+        };
+        let avviso_str = lingua.scegli("Avviso", "Warning");
+        for avviso in &self.avvisi {
+            string_representation = format!(
+                "{string_representation}\n  {avviso_str}: {}",
+                avviso.testo(lingua)
+            );
+        }
+        for errore in &self.errori {
+            string_representation =
+                format!("{string_representation}\n  {}", errore.descrizione(lingua));
+        }
+        string_representation
+    }
+}
+
+impl fmt::Display for Diagnostics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let string_representation = self.descrizione(Lingua::default());
+        write!(f, "{}", string_representation)
+    }
+}
@@ -15,36 +15,50 @@
     along with this program.  If not, see <https://www.gnu.org/licenses/>.
 */
 
+use crate::domain::specie::{IndiceSpecie, SpecieIndicizzabile};
+use crate::i18n::{Etichetta, Lingua};
+use chrono::NaiveDate;
 use once_cell::sync::Lazy;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 
 use crate::domain::location::Location;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum GruppoEcoHFBI {
     MigratoriMarini,
     Diadromi,
     ResidentiDiEstuario,
-    #[allow(dead_code)]
     OccasionaliMarini,
-    #[allow(dead_code)]
     OccasionaliDiAcqueDolci,
 }
 
+impl Etichetta for GruppoEcoHFBI {
+    fn etichetta(&self, lingua: Lingua) -> &'static str {
+        match self {
+            GruppoEcoHFBI::MigratoriMarini => lingua.scegli("MigratoriMarini", "Marine migrants"),
+            GruppoEcoHFBI::Diadromi => lingua.scegli("Diadromi", "Diadromous"),
+            GruppoEcoHFBI::ResidentiDiEstuario => {
+                lingua.scegli("ResidentiDiEstuario", "Estuarine residents")
+            }
+            GruppoEcoHFBI::OccasionaliMarini => {
+                lingua.scegli("OccasionaliMarini", "Marine stragglers")
+            }
+            GruppoEcoHFBI::OccasionaliDiAcqueDolci => {
+                lingua.scegli("OccasionaliDiAcqueDolci", "Freshwater stragglers")
+            }
+        }
+    }
+}
+
 impl fmt::Display for GruppoEcoHFBI {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let string_representation = match *self {
-            GruppoEcoHFBI::MigratoriMarini => "MigratoriMarini",
-            GruppoEcoHFBI::Diadromi => "Diadromi",
-            GruppoEcoHFBI::ResidentiDiEstuario => "ResidentiDiEstuario",
-            GruppoEcoHFBI::OccasionaliMarini => "OccasionaliMarini",
-            GruppoEcoHFBI::OccasionaliDiAcqueDolci => "OccasionaliDiAcqueDolci",
-        };
+        let string_representation = self.etichetta(Lingua::default());
         write!(f, "{}", string_representation)
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GruppoTrofHFBI {
     pub microbentivori: f32,
     pub macrobentivori: f32,
@@ -55,6 +69,26 @@ pub struct GruppoTrofHFBI {
     pub onnivori: f32,
 }
 
+impl GruppoTrofHFBI {
+    /// Frazioni per gruppo trofico, con il nome della colonna corrispondente nel file di riferimento
+    pub fn frazioni(&self) -> [(&'static str, f32); 7] {
+        [
+            ("microbentivori", self.microbentivori),
+            ("macrobentivori", self.macrobentivori),
+            ("iperbentivori", self.iperbentivori),
+            ("erbivori", self.erbivori),
+            ("detritivori", self.detritivori),
+            ("planctivori", self.planctivori),
+            ("onnivori", self.onnivori),
+        ]
+    }
+
+    /// Somma delle frazioni, che per una specie valida è 1
+    pub fn somma(&self) -> f32 {
+        self.frazioni().iter().map(|(_, v)| v).sum()
+    }
+}
+
 impl fmt::Display for GruppoTrofHFBI {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let string_representation = format!(
@@ -71,10 +105,10 @@ impl fmt::Display for GruppoTrofHFBI {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SpecieHFBI {
-    pub nome_comune: &'static str,
-    pub codice_specie: &'static str,
+    pub nome_comune: Cow<'static, str>,
+    pub codice_specie: Cow<'static, str>,
     pub autoctono: bool,
     pub gruppo_eco: GruppoEcoHFBI,
     pub gruppo_trofico: GruppoTrofHFBI,
@@ -100,8 +134,8 @@ impl fmt::Display for SpecieHFBI {
 
 pub const RIFERIMENTO_HFBI: [SpecieHFBI; 31] = [
     SpecieHFBI {
-        nome_comune: "Cheppia",
-        codice_specie: "CH",
+        nome_comune: Cow::Borrowed("Cheppia"),
+        codice_specie: Cow::Borrowed("CH"),
         autoctono: true,
         gruppo_eco: GruppoEcoHFBI::Diadromi,
         gruppo_trofico: GruppoTrofHFBI {
@@ -115,8 +149,8 @@ pub const RIFERIMENTO_HFBI: [SpecieHFBI; 31] = [
         },
     },
     SpecieHFBI {
-        nome_comune: "Anguilla",
-        codice_specie: "AN",
+        nome_comune: Cow::Borrowed("Anguilla"),
+        codice_specie: Cow::Borrowed("AN"),
         autoctono: true,
         gruppo_eco: GruppoEcoHFBI::Diadromi,
         gruppo_trofico: GruppoTrofHFBI {
@@ -130,8 +164,8 @@ pub const RIFERIMENTO_HFBI: [SpecieHFBI; 31] = [
         },
     },
     SpecieHFBI {
-        nome_comune: "Nono",
-        codice_specie: "NO",
+        nome_comune: Cow::Borrowed("Nono"),
+        codice_specie: Cow::Borrowed("NO"),
         autoctono: true,
         gruppo_eco: GruppoEcoHFBI::ResidentiDiEstuario,
         gruppo_trofico: GruppoTrofHFBI {
@@ -145,8 +179,8 @@ pub const RIFERIMENTO_HFBI: [SpecieHFBI; 31] = [
         },
     },
     SpecieHFBI {
-        nome_comune: "Latterino di lago",
-        codice_specie: "LAT",
+        nome_comune: Cow::Borrowed("Latterino di lago"),
+        codice_specie: Cow::Borrowed("LAT"),
         autoctono: true,
         gruppo_eco: GruppoEcoHFBI::ResidentiDiEstuario,
         gruppo_trofico: GruppoTrofHFBI {
@@ -160,8 +194,8 @@ pub const RIFERIMENTO_HFBI: [SpecieHFBI; 31] = [
         },
     },
     SpecieHFBI {
-        nome_comune: "Aguglia",
-        codice_specie: "BBE",
+        nome_comune: Cow::Borrowed("Aguglia"),
+        codice_specie: Cow::Borrowed("BBE"),
         autoctono: true,
         gruppo_eco: GruppoEcoHFBI::MigratoriMarini,
         gruppo_trofico: GruppoTrofHFBI {
@@ -175,8 +209,8 @@ pub const RIFERIMENTO_HFBI: [SpecieHFBI; 31] = [
         },
     },
     SpecieHFBI {
-        nome_comune: "Gallinella",
-        codice_specie: "CLU",
+        nome_comune: Cow::Borrowed("Gallinella"),
+        codice_specie: Cow::Borrowed("CLU"),
         autoctono: true,
         gruppo_eco: GruppoEcoHFBI::MigratoriMarini,
         gruppo_trofico: GruppoTrofHFBI {
@@ -190,8 +224,8 @@ pub const RIFERIMENTO_HFBI: [SpecieHFBI; 31] = [
         },
     },
     SpecieHFBI {
-        nome_comune: "Muggine labbrone",
-        codice_specie: "CEL",
+        nome_comune: Cow::Borrowed("Muggine labbrone"),
+        codice_specie: Cow::Borrowed("CEL"),
         autoctono: true,
         gruppo_eco: GruppoEcoHFBI::MigratoriMarini,
         gruppo_trofico: GruppoTrofHFBI {
@@ -205,8 +239,8 @@ pub const RIFERIMENTO_HFBI: [SpecieHFBI; 31] = [
         },
     },
     SpecieHFBI {
-        nome_comune: "Spigola branzino",
-        codice_specie: "DIC",
+        nome_comune: Cow::Borrowed("Spigola branzino"),
+        codice_specie: Cow::Borrowed("DIC"),
         autoctono: true,
         gruppo_eco: GruppoEcoHFBI::MigratoriMarini,
         gruppo_trofico: GruppoTrofHFBI {
@@ -220,8 +254,8 @@ pub const RIFERIMENTO_HFBI: [SpecieHFBI; 31] = [
         },
     },
     SpecieHFBI {
-        nome_comune: "Alice (Acciuga Europea)",
-        codice_specie: "DIC",
+        nome_comune: Cow::Borrowed("Alice (Acciuga Europea)"),
+        codice_specie: Cow::Borrowed("ENG"),
         autoctono: true,
         gruppo_eco: GruppoEcoHFBI::MigratoriMarini,
         gruppo_trofico: GruppoTrofHFBI {
@@ -235,8 +269,8 @@ pub const RIFERIMENTO_HFBI: [SpecieHFBI; 31] = [
         },
     },
     SpecieHFBI {
-        nome_comune: "Ghiozzo nero",
-        codice_specie: "GHN",
+        nome_comune: Cow::Borrowed("Ghiozzo nero"),
+        codice_specie: Cow::Borrowed("GHN"),
         autoctono: true,
         gruppo_eco: GruppoEcoHFBI::ResidentiDiEstuario,
         gruppo_trofico: GruppoTrofHFBI {
@@ -250,8 +284,8 @@ pub const RIFERIMENTO_HFBI: [SpecieHFBI; 31] = [
         },
     },
     SpecieHFBI {
-        nome_comune: "Cavalluccio marino",
-        codice_specie: "HGU",
+        nome_comune: Cow::Borrowed("Cavalluccio marino"),
+        codice_specie: Cow::Borrowed("HGU"),
         autoctono: true,
         gruppo_eco: GruppoEcoHFBI::ResidentiDiEstuario,
         gruppo_trofico: GruppoTrofHFBI {
@@ -265,8 +299,8 @@ pub const RIFERIMENTO_HFBI: [SpecieHFBI; 31] = [
         },
     },
     SpecieHFBI {
-        nome_comune: "Cavalluccio camuso",
-        codice_specie: "HHI",
+        nome_comune: Cow::Borrowed("Cavalluccio camuso"),
+        codice_specie: Cow::Borrowed("HHI"),
         autoctono: true,
         gruppo_eco: GruppoEcoHFBI::ResidentiDiEstuario,
         gruppo_trofico: GruppoTrofHFBI {
@@ -280,8 +314,8 @@ pub const RIFERIMENTO_HFBI: [SpecieHFBI; 31] = [
         },
     },
     SpecieHFBI {
-        nome_comune: "Ghiozzetto di laguna",
-        codice_specie: "GHL",
+        nome_comune: Cow::Borrowed("Ghiozzetto di laguna"),
+        codice_specie: Cow::Borrowed("GHL"),
         autoctono: true,
         gruppo_eco: GruppoEcoHFBI::ResidentiDiEstuario,
         gruppo_trofico: GruppoTrofHFBI {
@@ -295,8 +329,8 @@ pub const RIFERIMENTO_HFBI: [SpecieHFBI; 31] = [
         },
     },
     SpecieHFBI {
-        nome_comune: "Muggine dorato",
-        codice_specie: "CED",
+        nome_comune: Cow::Borrowed("Muggine dorato"),
+        codice_specie: Cow::Borrowed("CED"),
         autoctono: true,
         gruppo_eco: GruppoEcoHFBI::MigratoriMarini,
         gruppo_trofico: GruppoTrofHFBI {
@@ -310,8 +344,8 @@ pub const RIFERIMENTO_HFBI: [SpecieHFBI; 31] = [
         },
     },
     SpecieHFBI {
-        nome_comune: "Muggine calamita",
-        codice_specie: "CEC",
+        nome_comune: Cow::Borrowed("Muggine calamita"),
+        codice_specie: Cow::Borrowed("CEC"),
         autoctono: true,
         gruppo_eco: GruppoEcoHFBI::Diadromi,
         gruppo_trofico: GruppoTrofHFBI {
@@ -325,8 +359,8 @@ pub const RIFERIMENTO_HFBI: [SpecieHFBI; 31] = [
         },
     },
     SpecieHFBI {
-        nome_comune: "Muggine musino",
-        codice_specie: "MUS",
+        nome_comune: Cow::Borrowed("Muggine musino"),
+        codice_specie: Cow::Borrowed("MUS"),
         autoctono: true,
         gruppo_eco: GruppoEcoHFBI::MigratoriMarini,
         gruppo_trofico: GruppoTrofHFBI {
@@ -340,8 +374,8 @@ pub const RIFERIMENTO_HFBI: [SpecieHFBI; 31] = [
         },
     },
     SpecieHFBI {
-        nome_comune: "Cefalo",
-        codice_specie: "MUG",
+        nome_comune: Cow::Borrowed("Cefalo"),
+        codice_specie: Cow::Borrowed("MUG"),
         autoctono: true,
         gruppo_eco: GruppoEcoHFBI::Diadromi,
         gruppo_trofico: GruppoTrofHFBI {
@@ -355,8 +389,8 @@ pub const RIFERIMENTO_HFBI: [SpecieHFBI; 31] = [
         },
     },
     SpecieHFBI {
-        nome_comune: "Triglia di scoglio",
-        codice_specie: "MSU",
+        nome_comune: Cow::Borrowed("Triglia di scoglio"),
+        codice_specie: Cow::Borrowed("MSU"),
         autoctono: true,
         gruppo_eco: GruppoEcoHFBI::MigratoriMarini,
         gruppo_trofico: GruppoTrofHFBI {
@@ -370,8 +404,8 @@ pub const RIFERIMENTO_HFBI: [SpecieHFBI; 31] = [
         },
     },
     SpecieHFBI {
-        nome_comune: "Pesce ago sottile",
-        codice_specie: "NOP",
+        nome_comune: Cow::Borrowed("Pesce ago sottile"),
+        codice_specie: Cow::Borrowed("NOP"),
         autoctono: true,
         gruppo_eco: GruppoEcoHFBI::ResidentiDiEstuario,
         gruppo_trofico: GruppoTrofHFBI {
@@ -385,8 +419,8 @@ pub const RIFERIMENTO_HFBI: [SpecieHFBI; 31] = [
         },
     },
     SpecieHFBI {
-        nome_comune: "Passera pianuzza",
-        codice_specie: "PFL",
+        nome_comune: Cow::Borrowed("Passera pianuzza"),
+        codice_specie: Cow::Borrowed("PFL"),
         autoctono: true,
         gruppo_eco: GruppoEcoHFBI::MigratoriMarini,
         gruppo_trofico: GruppoTrofHFBI {
@@ -400,8 +434,8 @@ pub const RIFERIMENTO_HFBI: [SpecieHFBI; 31] = [
         },
     },
     SpecieHFBI {
-        nome_comune: "Ghiozzetto cenerino",
-        codice_specie: "GHC",
+        nome_comune: Cow::Borrowed("Ghiozzetto cenerino"),
+        codice_specie: Cow::Borrowed("GHC"),
         autoctono: true,
         gruppo_eco: GruppoEcoHFBI::ResidentiDiEstuario,
         gruppo_trofico: GruppoTrofHFBI {
@@ -415,8 +449,8 @@ pub const RIFERIMENTO_HFBI: [SpecieHFBI; 31] = [
         },
     },
     SpecieHFBI {
-        nome_comune: "Ghiozzetto marmorizzato",
-        codice_specie: "GHM",
+        nome_comune: Cow::Borrowed("Ghiozzetto marmorizzato"),
+        codice_specie: Cow::Borrowed("GHM"),
         autoctono: true,
         gruppo_eco: GruppoEcoHFBI::ResidentiDiEstuario,
         gruppo_trofico: GruppoTrofHFBI {
@@ -430,8 +464,8 @@ pub const RIFERIMENTO_HFBI: [SpecieHFBI; 31] = [
         },
     },
     SpecieHFBI {
-        nome_comune: "Ghiozzetto minuto",
-        codice_specie: "GHE",
+        nome_comune: Cow::Borrowed("Ghiozzetto minuto"),
+        codice_specie: Cow::Borrowed("GHE"),
         autoctono: true,
         gruppo_eco: GruppoEcoHFBI::MigratoriMarini,
         gruppo_trofico: GruppoTrofHFBI {
@@ -445,8 +479,8 @@ pub const RIFERIMENTO_HFBI: [SpecieHFBI; 31] = [
         },
     },
     SpecieHFBI {
-        nome_comune: "Bavosa pavone",
-        codice_specie: "BAP",
+        nome_comune: Cow::Borrowed("Bavosa pavone"),
+        codice_specie: Cow::Borrowed("BAP"),
         autoctono: true,
         gruppo_eco: GruppoEcoHFBI::ResidentiDiEstuario,
         gruppo_trofico: GruppoTrofHFBI {
@@ -460,8 +494,8 @@ pub const RIFERIMENTO_HFBI: [SpecieHFBI; 31] = [
         },
     },
     SpecieHFBI {
-        nome_comune: "Sardina",
-        codice_specie: "SPI",
+        nome_comune: Cow::Borrowed("Sardina"),
+        codice_specie: Cow::Borrowed("SPI"),
         autoctono: true,
         gruppo_eco: GruppoEcoHFBI::MigratoriMarini,
         gruppo_trofico: GruppoTrofHFBI {
@@ -475,8 +509,8 @@ pub const RIFERIMENTO_HFBI: [SpecieHFBI; 31] = [
         },
     },
     SpecieHFBI {
-        nome_comune: "Sogliola comune",
-        codice_specie: "SSO",
+        nome_comune: Cow::Borrowed("Sogliola comune"),
+        codice_specie: Cow::Borrowed("SSO"),
         autoctono: true,
         gruppo_eco: GruppoEcoHFBI::MigratoriMarini,
         gruppo_trofico: GruppoTrofHFBI {
@@ -490,8 +524,8 @@ pub const RIFERIMENTO_HFBI: [SpecieHFBI; 31] = [
         },
     },
     SpecieHFBI {
-        nome_comune: "Orata",
-        codice_specie: "SAU",
+        nome_comune: Cow::Borrowed("Orata"),
+        codice_specie: Cow::Borrowed("SAU"),
         autoctono: true,
         gruppo_eco: GruppoEcoHFBI::MigratoriMarini,
         gruppo_trofico: GruppoTrofHFBI {
@@ -505,8 +539,8 @@ pub const RIFERIMENTO_HFBI: [SpecieHFBI; 31] = [
         },
     },
     SpecieHFBI {
-        nome_comune: "Pesce ago di rio",
-        codice_specie: "PAR",
+        nome_comune: Cow::Borrowed("Pesce ago di rio"),
+        codice_specie: Cow::Borrowed("PAR"),
         autoctono: true,
         gruppo_eco: GruppoEcoHFBI::ResidentiDiEstuario,
         gruppo_trofico: GruppoTrofHFBI {
@@ -520,8 +554,8 @@ pub const RIFERIMENTO_HFBI: [SpecieHFBI; 31] = [
         },
     },
     SpecieHFBI {
-        nome_comune: "Pesce ago adriatico",
-        codice_specie: "STA",
+        nome_comune: Cow::Borrowed("Pesce ago adriatico"),
+        codice_specie: Cow::Borrowed("STA"),
         autoctono: true,
         gruppo_eco: GruppoEcoHFBI::ResidentiDiEstuario,
         gruppo_trofico: GruppoTrofHFBI {
@@ -535,8 +569,8 @@ pub const RIFERIMENTO_HFBI: [SpecieHFBI; 31] = [
         },
     },
     SpecieHFBI {
-        nome_comune: "Pesce ago cavallino",
-        codice_specie: "STY",
+        nome_comune: Cow::Borrowed("Pesce ago cavallino"),
+        codice_specie: Cow::Borrowed("STY"),
         autoctono: true,
         gruppo_eco: GruppoEcoHFBI::ResidentiDiEstuario,
         gruppo_trofico: GruppoTrofHFBI {
@@ -550,8 +584,8 @@ pub const RIFERIMENTO_HFBI: [SpecieHFBI; 31] = [
         },
     },
     SpecieHFBI {
-        nome_comune: "Ghiozzo gò",
-        codice_specie: "GHG",
+        nome_comune: Cow::Borrowed("Ghiozzo gò"),
+        codice_specie: Cow::Borrowed("GHG"),
         autoctono: true,
         gruppo_eco: GruppoEcoHFBI::ResidentiDiEstuario,
         gruppo_trofico: GruppoTrofHFBI {
@@ -566,9 +600,80 @@ pub const RIFERIMENTO_HFBI: [SpecieHFBI; 31] = [
     },
 ];
 
+impl SpecieIndicizzabile for SpecieHFBI {
+    fn codice(&self) -> &str {
+        &self.codice_specie
+    }
+    fn nomi(&self) -> Vec<&str> {
+        vec![&self.nome_comune]
+    }
+}
+
+static ELENCO_RIFERIMENTO_HFBI: [SpecieHFBI; 31] = RIFERIMENTO_HFBI;
+
+static INDICE_RIFERIMENTO_HFBI: Lazy<IndiceSpecie> =
+    Lazy::new(|| IndiceSpecie::da_elenco(&ELENCO_RIFERIMENTO_HFBI));
+
+/// Elenco delle specie di RIFERIMENTO_HFBI, nell'ordine della tabella
+pub fn elenco_riferimento_hfbi() -> &'static [SpecieHFBI] {
+    &ELENCO_RIFERIMENTO_HFBI
+}
+
+/// Indice di RIFERIMENTO_HFBI per codice e nome comune
+pub fn indice_riferimento_hfbi() -> &'static IndiceSpecie {
+    &INDICE_RIFERIMENTO_HFBI
+}
+
+/// Cerca una specie di RIFERIMENTO_HFBI per codice o nome comune, ignorando maiuscole e spazi
+pub fn cerca_specie_hfbi(chiave: &str) -> Option<&'static SpecieHFBI> {
+    INDICE_RIFERIMENTO_HFBI.cerca(&ELENCO_RIFERIMENTO_HFBI, chiave)
+}
+
 use std::{collections::HashMap, fmt};
 
-#[derive(Debug, Clone)]
+/// Elenco delle specie per il calcolo dell'HFBI.
+/// Il predefinito è RIFERIMENTO_HFBI; un elenco aggiornato si può leggere da file con
+/// csv::parser::hfbi::check_records_riferimento_hfbi()
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RiferimentoHFBI {
+    pub elenco_specie: Vec<SpecieHFBI>,
+}
+
+impl Default for RiferimentoHFBI {
+    fn default() -> Self {
+        Self::new(RIFERIMENTO_HFBI.to_vec())
+    }
+}
+
+impl fmt::Display for RiferimentoHFBI {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut string_representation = "RiferimentoHFBI: {".to_string();
+        for s in &self.elenco_specie {
+            string_representation = format!("{string_representation}\n  {{{s}}},");
+        }
+        string_representation = format!("{string_representation}\n}}");
+        write!(f, "{}", string_representation)
+    }
+}
+
+impl RiferimentoHFBI {
+    pub fn new(elenco_specie: Vec<SpecieHFBI>) -> Self {
+        Self { elenco_specie }
+    }
+
+    /// Indice delle specie per codice e nome comune
+    pub fn indice(&self) -> IndiceSpecie {
+        IndiceSpecie::da_elenco(&self.elenco_specie)
+    }
+
+    /// Cerca una specie per codice o nome comune, ignorando maiuscole e spazi.
+    /// Per ricerche ripetute conviene costruire una volta l'indice con indice().
+    pub fn cerca_specie(&self, chiave: &str) -> Option<&SpecieHFBI> {
+        self.indice().cerca(&self.elenco_specie, chiave)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RecordHFBI {
     pub specie: SpecieHFBI,
     pub numero_individui: u32, // in millimetri
@@ -585,7 +690,7 @@ impl fmt::Display for RecordHFBI {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct CampionamentoHFBI {
     pub campionamento: Vec<RecordHFBI>,
 }
@@ -607,20 +712,27 @@ impl CampionamentoHFBI {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum TipoLagunaCostieraHFBI {
     MAt1,
     MAt2,
     MAt3,
 }
 
-impl fmt::Display for TipoLagunaCostieraHFBI {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let string_representation = match *self {
+impl Etichetta for TipoLagunaCostieraHFBI {
+    /// Codici ufficiali, uguali in tutte le lingue
+    fn etichetta(&self, _lingua: Lingua) -> &'static str {
+        match self {
             TipoLagunaCostieraHFBI::MAt1 => "M-AT-1",
             TipoLagunaCostieraHFBI::MAt2 => "M-AT-2",
             TipoLagunaCostieraHFBI::MAt3 => "M-AT-3",
-        };
+        }
+    }
+}
+
+impl fmt::Display for TipoLagunaCostieraHFBI {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let string_representation = self.etichetta(Lingua::default());
         write!(f, "{}", string_representation)
     }
 }
@@ -638,18 +750,24 @@ impl TryFrom<i32> for TipoLagunaCostieraHFBI {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum StagioneHFBI {
     Primavera,
     Autunno,
 }
 
+impl Etichetta for StagioneHFBI {
+    fn etichetta(&self, lingua: Lingua) -> &'static str {
+        match self {
+            StagioneHFBI::Primavera => lingua.scegli("Primavera", "Spring"),
+            StagioneHFBI::Autunno => lingua.scegli("Autunno", "Autumn"),
+        }
+    }
+}
+
 impl fmt::Display for StagioneHFBI {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let string_representation = match *self {
-            StagioneHFBI::Primavera => "Primavera",
-            StagioneHFBI::Autunno => "Autunno",
-        };
+        let string_representation = self.etichetta(Lingua::default());
         write!(f, "{}", string_representation)
     }
 }
@@ -666,18 +784,24 @@ impl TryFrom<i32> for StagioneHFBI {
     }
 }
 
-#[derive(Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum HabitatHFBI {
     Vegetato,
     NonVegetato,
 }
 
+impl Etichetta for HabitatHFBI {
+    fn etichetta(&self, lingua: Lingua) -> &'static str {
+        match self {
+            HabitatHFBI::Vegetato => lingua.scegli("Vegetato", "Vegetated"),
+            HabitatHFBI::NonVegetato => lingua.scegli("Non Vegetato", "Unvegetated"),
+        }
+    }
+}
+
 impl fmt::Display for HabitatHFBI {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let string_representation = match *self {
-            HabitatHFBI::Vegetato => "Vegetato",
-            HabitatHFBI::NonVegetato => "Non Vegetato",
-        };
+        let string_representation = self.etichetta(Lingua::default());
         write!(f, "{}", string_representation)
     }
 }
@@ -707,12 +831,15 @@ pub struct AnagraficaHFBIDraft {
     pub larghezza_media_transetto: String,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct AnagraficaHFBI {
     pub codice_stazione: String,
     pub corpo_idrico: String,
     pub posizione: Location,
-    pub date_string: String, // Formato gg/mm/aaaa
+    pub date_string: String, // Come riportata nel file
+    /// Data interpretata da date_string (None se non valida)
+    #[serde(default)]
+    pub data: Option<NaiveDate>,
     pub tipo_laguna: TipoLagunaCostieraHFBI,
     pub stagione: StagioneHFBI,
     pub habitat_vegetato: HabitatHFBI,
@@ -769,10 +896,12 @@ impl fmt::Display for ValoriIntermediHFBI {
 }
 
 impl ValoriIntermediHFBI {
+    /// Inoltra i valori intermedi alla facade `log` (target "esox")
+    #[cfg(feature = "log")]
     pub fn log(&self) {
         //TODO: a proper format? we count on the embedded newlines to leverage the
         //chopping on newlines from add_console_message()
-        println!("Valori intermedi: {{{self}}}");
+        log::info!(target: "esox", "Valori intermedi: {{{self}}}");
     }
 }
 
@@ -811,14 +940,53 @@ impl RisultatoHFBI {
     }
 }
 
-#[derive(Hash, PartialEq, Eq)]
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub struct CondizioniRiferimentoKeyHFBI {
     pub tipo_laguna: TipoLagunaCostieraHFBI,
     pub stagione: StagioneHFBI,
     pub habitat_vegetato: HabitatHFBI,
 }
 
-#[derive(Clone)]
+impl CondizioniRiferimentoKeyHFBI {
+    /// Tutte le combinazioni di tipo di laguna, stagione e habitat (12)
+    pub fn tutte() -> Vec<CondizioniRiferimentoKeyHFBI> {
+        let mut chiavi = Vec::new();
+        for tipo_laguna in [
+            TipoLagunaCostieraHFBI::MAt1,
+            TipoLagunaCostieraHFBI::MAt2,
+            TipoLagunaCostieraHFBI::MAt3,
+        ] {
+            for stagione in [StagioneHFBI::Primavera, StagioneHFBI::Autunno] {
+                for habitat_vegetato in [HabitatHFBI::Vegetato, HabitatHFBI::NonVegetato] {
+                    chiavi.push(CondizioniRiferimentoKeyHFBI {
+                        tipo_laguna: tipo_laguna.clone(),
+                        stagione: stagione.clone(),
+                        habitat_vegetato,
+                    });
+                }
+            }
+        }
+        chiavi
+    }
+
+    pub fn descrizione(&self, lingua: Lingua) -> String {
+        format!(
+            "{}/{}/{}",
+            self.tipo_laguna.etichetta(lingua),
+            self.stagione.etichetta(lingua),
+            self.habitat_vegetato.etichetta(lingua)
+        )
+    }
+}
+
+impl fmt::Display for CondizioniRiferimentoKeyHFBI {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let string_representation = self.descrizione(Lingua::default());
+        write!(f, "{}", string_representation)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct CondizioniRiferimentoHFBI {
     pub bn: f32,
     pub ddom: f32,
@@ -829,14 +997,87 @@ pub struct CondizioniRiferimentoHFBI {
 }
 
 impl CondizioniRiferimentoHFBI {
+    /// Condizioni di riferimento della tabella ufficiale per la stazione
     pub fn get_cond_riferimento(
         anagrafica: &AnagraficaHFBI,
     ) -> Option<&'static CondizioniRiferimentoHFBI> {
-        let key = anagrafica.get_cond_riferimento_key();
-        CONDIZIONI_RIFERIMENTO_HFBI_HASHMAP.get(&key)
+        TabellaCondizioniRiferimentoHFBI::ufficiale().get_cond_riferimento(anagrafica)
+    }
+}
+
+/// Condizioni di riferimento per ogni combinazione di tipo di laguna, stagione e habitat.
+/// La predefinita è la tabella ufficiale ISPRA; una tabella ricalibrata si può leggere da file con
+/// csv::parser::hfbi::check_records_condizioni_riferimento_hfbi()
+#[derive(Debug, Clone, PartialEq)]
+pub struct TabellaCondizioniRiferimentoHFBI {
+    condizioni: HashMap<CondizioniRiferimentoKeyHFBI, CondizioniRiferimentoHFBI>,
+}
+
+impl Default for TabellaCondizioniRiferimentoHFBI {
+    fn default() -> Self {
+        Self::ufficiale().clone()
+    }
+}
+
+impl TabellaCondizioniRiferimentoHFBI {
+    /// Tabella vuota, da completare con inserisci()
+    pub fn vuota() -> Self {
+        Self {
+            condizioni: HashMap::new(),
+        }
+    }
+
+    /// Tabella ufficiale, usata da calculate_mmi()
+    pub fn ufficiale() -> &'static TabellaCondizioniRiferimentoHFBI {
+        &TABELLA_CONDIZIONI_RIFERIMENTO_HFBI
+    }
+
+    /// Imposta le condizioni per una combinazione, restituendo quelle che sostituisce
+    pub fn inserisci(
+        &mut self,
+        chiave: CondizioniRiferimentoKeyHFBI,
+        condizioni: CondizioniRiferimentoHFBI,
+    ) -> Option<CondizioniRiferimentoHFBI> {
+        self.condizioni.insert(chiave, condizioni)
+    }
+
+    pub fn get(&self, chiave: &CondizioniRiferimentoKeyHFBI) -> Option<&CondizioniRiferimentoHFBI> {
+        self.condizioni.get(chiave)
+    }
+
+    pub fn get_cond_riferimento(
+        &self,
+        anagrafica: &AnagraficaHFBI,
+    ) -> Option<&CondizioniRiferimentoHFBI> {
+        self.get(&anagrafica.get_cond_riferimento_key())
+    }
+
+    /// Combinazioni senza condizioni di riferimento, nell'ordine di CondizioniRiferimentoKeyHFBI::tutte()
+    pub fn mancanti(&self) -> Vec<CondizioniRiferimentoKeyHFBI> {
+        CondizioniRiferimentoKeyHFBI::tutte()
+            .into_iter()
+            .filter(|k| !self.condizioni.contains_key(k))
+            .collect()
+    }
+
+    pub fn is_completa(&self) -> bool {
+        self.mancanti().is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.condizioni.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.condizioni.is_empty()
     }
 }
 
+static TABELLA_CONDIZIONI_RIFERIMENTO_HFBI: Lazy<TabellaCondizioniRiferimentoHFBI> =
+    Lazy::new(|| TabellaCondizioniRiferimentoHFBI {
+        condizioni: CONDIZIONI_RIFERIMENTO_HFBI_HASHMAP.clone(),
+    });
+
 static CONDIZIONI_RIFERIMENTO_HFBI_HASHMAP: Lazy<
     HashMap<CondizioniRiferimentoKeyHFBI, CondizioniRiferimentoHFBI>,
 > = Lazy::new(|| {
@@ -1037,15 +1278,21 @@ pub enum StatoEcologicoHFBI {
     Cattivo,
 }
 
+impl Etichetta for StatoEcologicoHFBI {
+    fn etichetta(&self, lingua: Lingua) -> &'static str {
+        match self {
+            StatoEcologicoHFBI::Eccellente => lingua.scegli("Eccellente", "High"),
+            StatoEcologicoHFBI::Buono => lingua.scegli("Buono", "Good"),
+            StatoEcologicoHFBI::Sufficiente => lingua.scegli("Sufficiente", "Moderate"),
+            StatoEcologicoHFBI::Scarso => lingua.scegli("Scarso", "Poor"),
+            StatoEcologicoHFBI::Cattivo => lingua.scegli("Cattivo", "Bad"),
+        }
+    }
+}
+
 impl fmt::Display for StatoEcologicoHFBI {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let string_representation = match *self {
-            StatoEcologicoHFBI::Eccellente => "Eccellente",
-            StatoEcologicoHFBI::Buono => "Buono",
-            StatoEcologicoHFBI::Sufficiente => "Sufficiente",
-            StatoEcologicoHFBI::Scarso => "Scarso",
-            StatoEcologicoHFBI::Cattivo => "Cattivo",
-        };
+        let string_representation = self.etichetta(Lingua::default());
         write!(f, "{}", string_representation)
     }
 }
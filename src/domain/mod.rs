@@ -19,3 +19,5 @@ pub mod hfbi;
 pub mod index;
 pub mod location;
 pub mod niseci;
+pub mod registro;
+pub mod specie;
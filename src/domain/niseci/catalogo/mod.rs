@@ -0,0 +1,1388 @@
+// SPDX-License-Identifier: GPL-3.0-only
+/*
+    Copyright (C) 2024-2026 jgabaut, gioninjo
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU General Public License as published by
+    the Free Software Foundation, version 3 of the License.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU General Public License for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use crate::domain::niseci::SpecieNISECI;
+use crate::domain::specie::{IndiceSpecie, SpecieIndicizzabile};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Versione di CATALOGO_NISECI, da aggiornare a ogni modifica delle specie o delle soglie
+pub const VERSIONE_CATALOGO_NISECI: &str = "1";
+
+/// Specie del catalogo NISECI, con le soglie del manuale ISPRA.
+/// Le soglie di densità sono valorizzate solo per alcune specie: per le altre vanno indicate
+/// nella comunità di riferimento (vedi SoglieSpecieNISECI).
+#[derive(Debug, Clone, Serialize)]
+pub struct VoceCatalogoNISECI {
+    pub codice_specie: &'static str,
+    pub nome_latino: &'static str,
+    pub nome_comune: &'static str,
+    pub autoctona: bool,
+    pub allo_nocivita: u8,   // 0 per le specie autoctone
+    pub cl_soglie: [u32; 4], // in mm
+    pub ad_juv_soglie: [f32; 4],
+    pub dens_soglie: [f32; 2],
+}
+
+impl fmt::Display for VoceCatalogoNISECI {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let origine_str = match self.autoctona {
+            true => "AUT".to_string(),
+            false => "ALL".to_string(),
+        };
+        let string_representation = format!(
+            "{}; {}; {}; {}",
+            self.codice_specie, self.nome_latino, self.nome_comune, origine_str
+        );
+        write!(f, "{}", string_representation)
+    }
+}
+
+impl SpecieIndicizzabile for VoceCatalogoNISECI {
+    fn codice(&self) -> &str {
+        self.codice_specie
+    }
+    fn nomi(&self) -> Vec<&str> {
+        vec![self.nome_comune, self.nome_latino]
+    }
+}
+
+impl VoceCatalogoNISECI {
+    /// Specie della comunità di riferimento, con le soglie del catalogo salvo quelle sostituite
+    pub fn specie(
+        &self,
+        specie_attesa: bool,
+        tipo_autoctono: u8,
+        soglie: &SoglieSpecieNISECI,
+    ) -> SpecieNISECI {
+        let cl_soglie = soglie.cl_soglie.unwrap_or(self.cl_soglie);
+        let ad_juv_soglie = soglie.ad_juv_soglie.unwrap_or(self.ad_juv_soglie);
+        let dens_soglie = soglie.dens_soglie.unwrap_or(self.dens_soglie);
+        SpecieNISECI {
+            id: self.codice_specie.to_string(),
+            nome: self.nome_latino.to_string(),
+            nome_comune: self.nome_comune.to_string(),
+            tipo_autoctono,
+            tipo_alloctono: self.allo_nocivita,
+            specie_attesa,
+            cl_soglia1: cl_soglie[0],
+            cl_soglia2: cl_soglie[1],
+            cl_soglia3: cl_soglie[2],
+            cl_soglia4: cl_soglie[3],
+            ad_juv_soglia1: ad_juv_soglie[0],
+            ad_juv_soglia2: ad_juv_soglie[1],
+            ad_juv_soglia3: ad_juv_soglie[2],
+            ad_juv_soglia4: ad_juv_soglie[3],
+            dens_soglia1: dens_soglie[0],
+            dens_soglia2: dens_soglie[1],
+        }
+    }
+}
+
+/// Soglie che sostituiscono quelle del catalogo per una singola comunità di riferimento
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SoglieSpecieNISECI {
+    pub cl_soglie: Option<[u32; 4]>,
+    pub ad_juv_soglie: Option<[f32; 4]>,
+    pub dens_soglie: Option<[f32; 2]>,
+}
+
+/// Le specie e le soglie di templates/riferimento_niseci.csv, con i nomi senza spazi in coda
+pub const CATALOGO_NISECI: [VoceCatalogoNISECI; 126] = [
+    VoceCatalogoNISECI {
+        codice_specie: "AL",
+        nome_latino: "Alburnus alborella",
+        nome_comune: "Alborella",
+        autoctona: true,
+        allo_nocivita: 0,
+        cl_soglie: [45, 90, 130, 150],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.05224649, 0.086500525],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "ALM",
+        nome_latino: "Alburnus albidus",
+        nome_comune: "Alborella meridionale",
+        autoctona: true,
+        allo_nocivita: 0,
+        cl_soglie: [45, 90, 130, 150],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "AN",
+        nome_latino: "Anguilla anguilla",
+        nome_comune: "Anguilla",
+        autoctona: true,
+        allo_nocivita: 0,
+        cl_soglie: [250, 450, 650, 800],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.00135, 0.001971895],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "BC",
+        nome_latino: "Barbus caninus",
+        nome_comune: "Barbo canino",
+        autoctona: true,
+        allo_nocivita: 0,
+        cl_soglie: [45, 90, 130, 150],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.003275133, 0.003365552],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "BA",
+        nome_latino: "Barbus plebejus",
+        nome_comune: "Barbo comune",
+        autoctona: true,
+        allo_nocivita: 0,
+        cl_soglie: [80, 170, 210, 300],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.00720942, 0.036430374],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "BT",
+        nome_latino: "Barbus tyberinus",
+        nome_comune: "Barbo tiberino",
+        autoctona: true,
+        allo_nocivita: 0,
+        cl_soglie: [45, 90, 130, 150],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "BAP",
+        nome_latino: "Salaria pavo",
+        nome_comune: "Bavosa pavone",
+        autoctona: true,
+        allo_nocivita: 0,
+        cl_soglie: [45, 90, 130, 150],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "BOG",
+        nome_latino: "Boops boops",
+        nome_comune: "Boga",
+        autoctona: true,
+        allo_nocivita: 0,
+        cl_soglie: [45, 90, 130, 150],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "BOT",
+        nome_latino: "Lota lota",
+        nome_comune: "Bottatrice",
+        autoctona: true,
+        allo_nocivita: 0,
+        cl_soglie: [250, 450, 650, 800],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "SAL",
+        nome_latino: "Salaria fluviatilis",
+        nome_comune: "Cagnetta",
+        autoctona: true,
+        allo_nocivita: 0,
+        cl_soglie: [45, 90, 130, 150],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "CP",
+        nome_latino: "Cyprinus carpio",
+        nome_comune: "Carpa",
+        autoctona: true,
+        allo_nocivita: 0,
+        cl_soglie: [250, 450, 650, 800],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.005879365, 0.02344597],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "CAF",
+        nome_latino: "Salmo fibreni",
+        nome_comune: "Carpione del Fibreno",
+        autoctona: true,
+        allo_nocivita: 0,
+        cl_soglie: [45, 90, 130, 150],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "CAG",
+        nome_latino: "Salmo carpio",
+        nome_comune: "Carpione del Garda",
+        autoctona: true,
+        allo_nocivita: 0,
+        cl_soglie: [80, 170, 210, 300],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "CV",
+        nome_latino: "Squalius squalus",
+        nome_comune: "Cavedano italico",
+        autoctona: true,
+        allo_nocivita: 0,
+        cl_soglie: [80, 170, 210, 300],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.060369022, 0.17619738],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "MUG",
+        nome_latino: "Mugil cephalus",
+        nome_comune: "Cefalo",
+        autoctona: true,
+        allo_nocivita: 0,
+        cl_soglie: [80, 170, 210, 300],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "CH",
+        nome_latino: "Alosa fallax",
+        nome_comune: "Cheppia (Alosa Agone)",
+        autoctona: true,
+        allo_nocivita: 0,
+        cl_soglie: [80, 170, 210, 300],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "BAR",
+        nome_latino: "Barbatula barbatula",
+        nome_comune: "Cobite barbatello",
+        autoctona: true,
+        allo_nocivita: 0,
+        cl_soglie: [35, 45, 60, 80],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "CO",
+        nome_latino: "Cobitis taenia bilineata",
+        nome_comune: "Cobite comune",
+        autoctona: true,
+        allo_nocivita: 0,
+        cl_soglie: [35, 45, 60, 80],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.003779876, 0.010428674],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "COM",
+        nome_latino: "Sabanejewia larvata",
+        nome_comune: "Cobite mascherato",
+        autoctona: true,
+        allo_nocivita: 0,
+        cl_soglie: [35, 45, 60, 80],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "GHC",
+        nome_latino: "Pomatoschistus canestrini",
+        nome_comune: "Ghiozzetto cenerino",
+        autoctona: true,
+        allo_nocivita: 0,
+        cl_soglie: [35, 45, 60, 80],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "CHT",
+        nome_latino: "Pomatoschistus tortonesei",
+        nome_comune: "Ghiozzetto del Tortonese",
+        autoctona: true,
+        allo_nocivita: 0,
+        cl_soglie: [35, 45, 60, 80],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "GHL",
+        nome_latino: "Knipowitschia panizzae",
+        nome_comune: "Ghiozzetto di laguna",
+        autoctona: true,
+        allo_nocivita: 0,
+        cl_soglie: [35, 45, 60, 80],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "GHM",
+        nome_latino: "Pomatoschistus marmoratus",
+        nome_comune: "Ghiozzetto marmoreggiato",
+        autoctona: true,
+        allo_nocivita: 0,
+        cl_soglie: [35, 45, 60, 80],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "GHE",
+        nome_latino: "Pomatoschistus minutus elongatus",
+        nome_comune: "Ghiozzetto minuto",
+        autoctona: true,
+        allo_nocivita: 0,
+        cl_soglie: [35, 45, 60, 80],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "GHR",
+        nome_latino: "Gobius nigricans",
+        nome_comune: "Ghiozzo di ruscello",
+        autoctona: true,
+        allo_nocivita: 0,
+        cl_soglie: [35, 45, 60, 80],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "GHG",
+        nome_latino: "Zosterisessor ophiocephalus",
+        nome_comune: "Ghiozzo Gò",
+        autoctona: true,
+        allo_nocivita: 0,
+        cl_soglie: [35, 45, 60, 80],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "GHN",
+        nome_latino: "Gobius niger jozo",
+        nome_comune: "Ghiozzo nero",
+        autoctona: true,
+        allo_nocivita: 0,
+        cl_soglie: [35, 45, 60, 80],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "GH",
+        nome_latino: "Padogobius martensii",
+        nome_comune: "Ghiozzo padano",
+        autoctona: true,
+        allo_nocivita: 0,
+        cl_soglie: [35, 45, 60, 80],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.002610741, 0.010291667],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "GO",
+        nome_latino: "Gobio benacensis",
+        nome_comune: "Gobione",
+        autoctona: true,
+        allo_nocivita: 0,
+        cl_soglie: [35, 45, 60, 80],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.001317622, 0.00159442],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "LAF",
+        nome_latino: "Lampetra fluviatilis",
+        nome_comune: "Lampreda di fiume",
+        autoctona: true,
+        allo_nocivita: 0,
+        cl_soglie: [80, 170, 210, 300],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "LAM",
+        nome_latino: "Petromyzon marinus",
+        nome_comune: "Lampreda di mare",
+        autoctona: true,
+        allo_nocivita: 0,
+        cl_soglie: [45, 90, 130, 150],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "LAR",
+        nome_latino: "Lampetra planeri",
+        nome_comune: "Lampreda di ruscello",
+        autoctona: true,
+        allo_nocivita: 0,
+        cl_soglie: [45, 90, 130, 150],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "LAP",
+        nome_latino: "Lampetra zanandreai",
+        nome_comune: "Lampreda padana",
+        autoctona: true,
+        allo_nocivita: 0,
+        cl_soglie: [45, 90, 130, 150],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "LA",
+        nome_latino: "Chondrostoma genei",
+        nome_comune: "Lasca",
+        autoctona: true,
+        allo_nocivita: 0,
+        cl_soglie: [45, 90, 130, 150],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.007285714, 0.046040025],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "LAT",
+        nome_latino: "Atherina boyeri",
+        nome_comune: "Latterino (Aterina) di lago",
+        autoctona: true,
+        allo_nocivita: 0,
+        cl_soglie: [35, 45, 60, 80],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "LU",
+        nome_latino: "Esox lucius",
+        nome_comune: "Luccio",
+        autoctona: true,
+        allo_nocivita: 0,
+        cl_soglie: [250, 450, 650, 800],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "CEC",
+        nome_latino: "Liza ramada",
+        nome_comune: "Muggine calamita",
+        autoctona: true,
+        allo_nocivita: 0,
+        cl_soglie: [80, 170, 210, 300],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "CED",
+        nome_latino: "Liza aurata",
+        nome_comune: "Muggine dorato",
+        autoctona: true,
+        allo_nocivita: 0,
+        cl_soglie: [80, 170, 210, 300],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "CEL",
+        nome_latino: "Chelon labrosus",
+        nome_comune: "Muggine labbrone",
+        autoctona: true,
+        allo_nocivita: 0,
+        cl_soglie: [45, 90, 130, 150],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "MUS",
+        nome_latino: "Liza saliens",
+        nome_comune: "Muggine musino",
+        autoctona: true,
+        allo_nocivita: 0,
+        cl_soglie: [80, 170, 210, 300],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "NO",
+        nome_latino: "Aphanius fasciatus",
+        nome_comune: "Nono",
+        autoctona: true,
+        allo_nocivita: 0,
+        cl_soglie: [35, 45, 60, 80],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "OR",
+        nome_latino: "Sparus auratus",
+        nome_comune: "Orata",
+        autoctona: true,
+        allo_nocivita: 0,
+        cl_soglie: [80, 170, 210, 300],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "PAN",
+        nome_latino: "Knipowitschia punctatissima",
+        nome_comune: "Panzarolo",
+        autoctona: true,
+        allo_nocivita: 0,
+        cl_soglie: [35, 45, 60, 80],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "PA",
+        nome_latino: "Platichthys flesus italicus",
+        nome_comune: "Passera di mare",
+        autoctona: true,
+        allo_nocivita: 0,
+        cl_soglie: [80, 170, 210, 300],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "PR",
+        nome_latino: "Perca fluviatilis",
+        nome_comune: "Persico reale",
+        autoctona: true,
+        allo_nocivita: 0,
+        cl_soglie: [80, 170, 210, 300],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.004285714, 0.004285714],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "PAG",
+        nome_latino: "Syngnathus acus",
+        nome_comune: "Pesce ago",
+        autoctona: true,
+        allo_nocivita: 0,
+        cl_soglie: [80, 170, 210, 300],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "PAR",
+        nome_latino: "Syngnathus abaster",
+        nome_comune: "Pesce ago di rio",
+        autoctona: true,
+        allo_nocivita: 0,
+        cl_soglie: [45, 90, 130, 150],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "PI",
+        nome_latino: "Rutilius pigus",
+        nome_comune: "Pigo",
+        autoctona: true,
+        allo_nocivita: 0,
+        cl_soglie: [45, 90, 130, 150],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "RV",
+        nome_latino: "Sarmarutilius rubilio",
+        nome_comune: "Rovella",
+        autoctona: true,
+        allo_nocivita: 0,
+        cl_soglie: [45, 90, 130, 150],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "SA",
+        nome_latino: "Salvelinus alpinus",
+        nome_comune: "Salmerino",
+        autoctona: true,
+        allo_nocivita: 0,
+        cl_soglie: [250, 450, 650, 800],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "SN",
+        nome_latino: "Phoxinus phoxinus",
+        nome_comune: "Sanguinerola",
+        autoctona: true,
+        allo_nocivita: 0,
+        cl_soglie: [35, 45, 60, 80],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "SV",
+        nome_latino: "Chondrostoma soetta",
+        nome_comune: "Savetta",
+        autoctona: true,
+        allo_nocivita: 0,
+        cl_soglie: [45, 90, 130, 150],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.02300552, 0.1771073],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "SC",
+        nome_latino: "Scardinius erythrophthalmus",
+        nome_comune: "Scardola",
+        autoctona: true,
+        allo_nocivita: 0,
+        cl_soglie: [80, 170, 210, 300],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.004048, 0.010333333],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "SZ",
+        nome_latino: "Cottus gobio",
+        nome_comune: "Scazzone",
+        autoctona: true,
+        allo_nocivita: 0,
+        cl_soglie: [45, 90, 130, 150],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.001523117, 0.002053333],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "DIC",
+        nome_latino: "Dicentrarchus labrax",
+        nome_comune: "Spigola Branzino",
+        autoctona: true,
+        allo_nocivita: 0,
+        cl_soglie: [80, 170, 210, 300],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "SP",
+        nome_latino: "Gasterosteus aculeatus",
+        nome_comune: "Spinarello",
+        autoctona: true,
+        allo_nocivita: 0,
+        cl_soglie: [35, 45, 60, 80],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "AC",
+        nome_latino: "Acipenser sturio",
+        nome_comune: "Storione",
+        autoctona: true,
+        allo_nocivita: 0,
+        cl_soglie: [250, 450, 650, 800],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "ACN",
+        nome_latino: "Acipenser naccarii",
+        nome_comune: "Storione cobice",
+        autoctona: true,
+        allo_nocivita: 0,
+        cl_soglie: [250, 450, 650, 800],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "HH",
+        nome_latino: "Huso huso",
+        nome_comune: "Storione ladano",
+        autoctona: true,
+        allo_nocivita: 0,
+        cl_soglie: [250, 450, 650, 800],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "TM",
+        nome_latino: "Thymallus thymallus",
+        nome_comune: "Temolo",
+        autoctona: true,
+        allo_nocivita: 0,
+        cl_soglie: [45, 90, 130, 150],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "TI",
+        nome_latino: "Tinca tinca",
+        nome_comune: "Tinca",
+        autoctona: true,
+        allo_nocivita: 0,
+        cl_soglie: [80, 170, 210, 300],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "TR",
+        nome_latino: "Rutilius erythrophthalmus",
+        nome_comune: "Triotto",
+        autoctona: true,
+        allo_nocivita: 0,
+        cl_soglie: [45, 90, 130, 150],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.001806394, 0.002214141],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "TF",
+        nome_latino: "Salmo ghigi",
+        nome_comune: "Trota appenninica",
+        autoctona: true,
+        allo_nocivita: 0,
+        cl_soglie: [80, 170, 210, 300],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.031225078, 0.10508852],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "TFL",
+        nome_latino: "Salmo (trutta) lacustris",
+        nome_comune: "Trota di lago",
+        autoctona: true,
+        allo_nocivita: 0,
+        cl_soglie: [250, 450, 650, 800],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "TMA",
+        nome_latino: "Salmo (trutta) macrostigma",
+        nome_comune: "Trota macrostigma",
+        autoctona: true,
+        allo_nocivita: 0,
+        cl_soglie: [80, 170, 210, 300],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "TMR",
+        nome_latino: "Salmo (trutta) marmoratus",
+        nome_comune: "Trota marmorata",
+        autoctona: true,
+        allo_nocivita: 0,
+        cl_soglie: [80, 170, 210, 300],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "VA",
+        nome_latino: "Leuciscus souffia muticellus",
+        nome_comune: "Vairone",
+        autoctona: true,
+        allo_nocivita: 0,
+        cl_soglie: [45, 90, 130, 150],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.026356261, 0.07716246],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "AB",
+        nome_latino: "Abramis brama",
+        nome_comune: "Abramide comune",
+        autoctona: false,
+        allo_nocivita: 2,
+        cl_soglie: [80, 170, 210, 300],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "ABZ",
+        nome_latino: "Ballerus ballerus",
+        nome_comune: "Abramide medio o Zope",
+        autoctona: false,
+        allo_nocivita: 0,
+        cl_soglie: [80, 170, 210, 300],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "ACE",
+        nome_latino: "Gymnocephalus cernuus",
+        nome_comune: "Acerina",
+        autoctona: false,
+        allo_nocivita: 2,
+        cl_soglie: [80, 170, 210, 300],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "ALF",
+        nome_latino: "Alburnoides bipunctatus",
+        nome_comune: "Alborella di fiume",
+        autoctona: false,
+        allo_nocivita: 0,
+        cl_soglie: [35, 45, 60, 80],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "ALE",
+        nome_latino: "Alburnus alburnus",
+        nome_comune: "Alborella europea",
+        autoctona: false,
+        allo_nocivita: 3,
+        cl_soglie: [35, 45, 60, 80],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "ANA",
+        nome_latino: "Anguilla rostrata",
+        nome_comune: "Anguilla americana - Anguilla rostrata",
+        autoctona: false,
+        allo_nocivita: 3,
+        cl_soglie: [250, 450, 650, 800],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "ASP",
+        nome_latino: "Aspius aspius",
+        nome_comune: "Aspio",
+        autoctona: false,
+        allo_nocivita: 1,
+        cl_soglie: [250, 450, 650, 800],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "BBA",
+        nome_latino: "Barbus balcanicus",
+        nome_comune: "Barbo balcanico",
+        autoctona: false,
+        allo_nocivita: 0,
+        cl_soglie: [45, 90, 130, 150],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "BS",
+        nome_latino: "Barbus graellsii",
+        nome_comune: "Barbo di Graells - Barbo dell'Ebro",
+        autoctona: false,
+        allo_nocivita: 2,
+        cl_soglie: [80, 170, 210, 300],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "BE",
+        nome_latino: "Barbus barbus",
+        nome_comune: "Barbo europeo",
+        autoctona: false,
+        allo_nocivita: 2,
+        cl_soglie: [250, 450, 650, 800],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "BBR",
+        nome_latino: "Barbus haasi",
+        nome_comune: "Barbo pinnerosse",
+        autoctona: false,
+        allo_nocivita: 0,
+        cl_soglie: [45, 90, 130, 150],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "BER",
+        nome_latino: "Rutilus arcasii",
+        nome_comune: "Bermejuela",
+        autoctona: false,
+        allo_nocivita: 0,
+        cl_soglie: [35, 45, 60, 80],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "BLK",
+        nome_latino: "Blicca bjoerkna",
+        nome_comune: "Blicca",
+        autoctona: false,
+        allo_nocivita: 2,
+        cl_soglie: [45, 90, 130, 150],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "BOR",
+        nome_latino: "Leuciscus carolitertii",
+        nome_comune: "Bordallo",
+        autoctona: false,
+        allo_nocivita: 0,
+        cl_soglie: [45, 90, 130, 150],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "CC",
+        nome_latino: "Carassius carassius",
+        nome_comune: "Carassio",
+        autoctona: false,
+        allo_nocivita: 2,
+        cl_soglie: [80, 170, 210, 300],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "CS",
+        nome_latino: "Carassius auratus",
+        nome_comune: "Carassio dorato",
+        autoctona: false,
+        allo_nocivita: 2,
+        cl_soglie: [80, 170, 210, 300],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "CPA",
+        nome_latino: "Hypophthalmichthys molitrix",
+        nome_comune: "Carpa argentata",
+        autoctona: false,
+        allo_nocivita: 3,
+        cl_soglie: [250, 450, 650, 800],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "CPP",
+        nome_latino: "Carassius gibelio",
+        nome_comune: "Carpa di Prussia",
+        autoctona: false,
+        allo_nocivita: 0,
+        cl_soglie: [80, 170, 210, 300],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "AMU",
+        nome_latino: "Ctenopharyngodon idellus",
+        nome_comune: "Carpa erbivora - Amur",
+        autoctona: false,
+        allo_nocivita: 3,
+        cl_soglie: [250, 450, 650, 800],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "CPT",
+        nome_latino: "Hypophthalmichthys nobilis",
+        nome_comune: "Carpa testa grossa",
+        autoctona: false,
+        allo_nocivita: 3,
+        cl_soglie: [250, 450, 650, 800],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "CVV",
+        nome_latino: "Squalius cephalus",
+        nome_comune: "Cavedano europeo",
+        autoctona: false,
+        allo_nocivita: 2,
+        cl_soglie: [45, 90, 130, 150],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "COS",
+        nome_latino: "Misgurnus anguillicaudatus",
+        nome_comune: "Cobite di stagno orientale",
+        autoctona: false,
+        allo_nocivita: 2,
+        cl_soglie: [45, 90, 130, 150],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "COR",
+        nome_latino: "Coregonus oxyrhynchus",
+        nome_comune: "Coregone nasello",
+        autoctona: false,
+        allo_nocivita: 3,
+        cl_soglie: [80, 170, 210, 300],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "GAM",
+        nome_latino: "Gambusia holbrooki",
+        nome_comune: "Gambusia",
+        autoctona: false,
+        allo_nocivita: 2,
+        cl_soglie: [35, 45, 60, 80],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "GOE",
+        nome_latino: "Gobio gobio",
+        nome_comune: "Gobione europeo",
+        autoctona: false,
+        allo_nocivita: 2,
+        cl_soglie: [35, 45, 60, 80],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "IDO",
+        nome_latino: "Leuciscus idus",
+        nome_comune: "Ido",
+        autoctona: false,
+        allo_nocivita: 0,
+        cl_soglie: [80, 170, 210, 300],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "LAS",
+        nome_latino: "Chondrostoma polylepis",
+        nome_comune: "Lasca iberica",
+        autoctona: false,
+        allo_nocivita: 0,
+        cl_soglie: [80, 170, 210, 300],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "LAV",
+        nome_latino: "Coregonus lavaretus",
+        nome_comune: "Lavarello - Coregone",
+        autoctona: false,
+        allo_nocivita: 3,
+        cl_soglie: [80, 170, 210, 300],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "MOR",
+        nome_latino: "Pachychilon pictum",
+        nome_comune: "Leucisco d'Albania - Moranec",
+        autoctona: false,
+        allo_nocivita: 2,
+        cl_soglie: [35, 45, 60, 80],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "LP",
+        nome_latino: "Sander lucioperca",
+        nome_comune: "Lucioperca - Luccioperca - Sandra",
+        autoctona: false,
+        allo_nocivita: 2,
+        cl_soglie: [80, 170, 210, 300],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "NA",
+        nome_latino: "Chondrostoma nasus",
+        nome_comune: "Naso",
+        autoctona: false,
+        allo_nocivita: 2,
+        cl_soglie: [80, 170, 210, 300],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "PS",
+        nome_latino: "Lepomis gibbosus",
+        nome_comune: "Persico sole",
+        autoctona: false,
+        allo_nocivita: 2,
+        cl_soglie: [45, 90, 130, 150],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "PT",
+        nome_latino: "Micropterus salmoides",
+        nome_comune: "Persico trota",
+        autoctona: false,
+        allo_nocivita: 2,
+        cl_soglie: [80, 170, 210, 300],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "PGA",
+        nome_latino: "Ameiurus melas",
+        nome_comune: "Pesce gatto - Pesce gatto nero",
+        autoctona: false,
+        allo_nocivita: 2,
+        cl_soglie: [80, 170, 210, 300],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "PGC",
+        nome_latino: "Clarias gariepinus",
+        nome_comune: "Pesce gatto africano",
+        autoctona: false,
+        allo_nocivita: 2,
+        cl_soglie: [250, 450, 650, 800],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "PGM",
+        nome_latino: "Ictalurus punctatus",
+        nome_comune: "Pesce gatto maculato",
+        autoctona: false,
+        allo_nocivita: 2,
+        cl_soglie: [250, 450, 650, 800],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "PGN",
+        nome_latino: "Ameiurus nebulosus",
+        nome_comune: "Pesce gatto nebuloso",
+        autoctona: false,
+        allo_nocivita: 2,
+        cl_soglie: [80, 170, 210, 300],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "PG",
+        nome_latino: "Ictalurus melas",
+        nome_comune: "Pesce gatto2",
+        autoctona: false,
+        allo_nocivita: 0,
+        cl_soglie: [80, 170, 210, 300],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "PRE",
+        nome_latino: "Odonthestes bonariensis",
+        nome_comune: "Pesce re",
+        autoctona: false,
+        allo_nocivita: 3,
+        cl_soglie: [80, 170, 210, 300],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "PRB",
+        nome_latino: "Pseudorasbora parva",
+        nome_comune: "Pseudorasbora - Cebacek",
+        autoctona: false,
+        allo_nocivita: 2,
+        cl_soglie: [35, 45, 60, 80],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "RD",
+        nome_latino: "Rhodeus sericeus",
+        nome_comune: "Rodeo sericeo",
+        autoctona: false,
+        allo_nocivita: 2,
+        cl_soglie: [35, 45, 60, 80],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "GA",
+        nome_latino: "Rutilus rutilus",
+        nome_comune: "Rutilo - Gardon",
+        autoctona: false,
+        allo_nocivita: 2,
+        cl_soglie: [80, 170, 210, 300],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "SALF",
+        nome_latino: "Salvelinus fontinalis",
+        nome_comune: "Salmerino di fontana",
+        autoctona: false,
+        allo_nocivita: 2,
+        cl_soglie: [80, 170, 210, 300],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "SALA",
+        nome_latino: "Oncorhynchus kisutch",
+        nome_comune: "Salmone argentato",
+        autoctona: false,
+        allo_nocivita: 2,
+        cl_soglie: [80, 170, 210, 300],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "SALT",
+        nome_latino: "Salmo salar",
+        nome_comune: "Salmone dell'Atlantico",
+        autoctona: false,
+        allo_nocivita: 0,
+        cl_soglie: [250, 450, 650, 800],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "SZS",
+        nome_latino: "Cottus poecilopus",
+        nome_comune: "Scazzone siberiano",
+        autoctona: false,
+        allo_nocivita: 0,
+        cl_soglie: [35, 45, 60, 80],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "SL",
+        nome_latino: "Silurus glanis",
+        nome_comune: "Siluro",
+        autoctona: false,
+        allo_nocivita: 1,
+        cl_soglie: [250, 450, 650, 800],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "ACB",
+        nome_latino: "Acipenser transmontanus",
+        nome_comune: "Storione bianco",
+        autoctona: false,
+        allo_nocivita: 3,
+        cl_soglie: [250, 450, 650, 800],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "TMD",
+        nome_latino: "Thymallus thymallus (dan)",
+        nome_comune: "Temolo danubiano",
+        autoctona: false,
+        allo_nocivita: 2,
+        cl_soglie: [45, 90, 130, 150],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "TIL",
+        nome_latino: "Oreochromis niloticus",
+        nome_comune: "Tilapia nilotica - Tilapia del Nilo",
+        autoctona: false,
+        allo_nocivita: 3,
+        cl_soglie: [80, 170, 210, 300],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "TFA",
+        nome_latino: "Salmo (trutta) trutta (atl)",
+        nome_comune: "Trota fario atlantica",
+        autoctona: false,
+        allo_nocivita: 2,
+        cl_soglie: [80, 170, 210, 300],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "OM",
+        nome_latino: "Oncorhynchus mykiss",
+        nome_comune: "Trota iridea",
+        autoctona: false,
+        allo_nocivita: 2,
+        cl_soglie: [250, 450, 650, 800],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "OM2",
+        nome_latino: "Salmo gairdneri",
+        nome_comune: "Trota iridea2",
+        autoctona: false,
+        allo_nocivita: 0,
+        cl_soglie: [250, 450, 650, 800],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "VIM",
+        nome_latino: "Vimba vimba",
+        nome_comune: "Vimba o Abramide russo",
+        autoctona: false,
+        allo_nocivita: 0,
+        cl_soglie: [45, 90, 130, 150],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "XBA",
+        nome_latino: "Xbarbus",
+        nome_comune: "Ibrido Barbus",
+        autoctona: false,
+        allo_nocivita: 3,
+        cl_soglie: [80, 170, 210, 300],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "XLU",
+        nome_latino: "Xesox",
+        nome_comune: "Ibrido Esox",
+        autoctona: false,
+        allo_nocivita: 3,
+        cl_soglie: [250, 450, 650, 800],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "XRV",
+        nome_latino: "Xrutilus",
+        nome_comune: "Ibrido Rutilus",
+        autoctona: false,
+        allo_nocivita: 3,
+        cl_soglie: [45, 90, 130, 150],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "XTF",
+        nome_latino: "Xsalmo",
+        nome_comune: "Ibrido Salmo",
+        autoctona: false,
+        allo_nocivita: 3,
+        cl_soglie: [80, 170, 210, 300],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+    VoceCatalogoNISECI {
+        codice_specie: "XTM",
+        nome_latino: "Xthymalus",
+        nome_comune: "Ibrido Thymallus",
+        autoctona: false,
+        allo_nocivita: 3,
+        cl_soglie: [45, 90, 130, 150],
+        ad_juv_soglie: [0.5, 0.67, 1.5, 2.0],
+        dens_soglie: [0.0, 0.0],
+    },
+];
+
+static ELENCO_CATALOGO_NISECI: [VoceCatalogoNISECI; 126] = CATALOGO_NISECI;
+
+static INDICE_CATALOGO_NISECI: Lazy<IndiceSpecie> =
+    Lazy::new(|| IndiceSpecie::da_elenco(&ELENCO_CATALOGO_NISECI));
+
+/// Elenco delle specie di CATALOGO_NISECI, nell'ordine della tabella
+pub fn elenco_catalogo_niseci() -> &'static [VoceCatalogoNISECI] {
+    &ELENCO_CATALOGO_NISECI
+}
+
+/// Indice di CATALOGO_NISECI per codice, nome latino e nome comune
+pub fn indice_catalogo_niseci() -> &'static IndiceSpecie {
+    &INDICE_CATALOGO_NISECI
+}
+
+/// Cerca una specie di CATALOGO_NISECI per codice, nome latino o nome comune, ignorando maiuscole e spazi
+pub fn cerca_specie_catalogo_niseci(chiave: &str) -> Option<&'static VoceCatalogoNISECI> {
+    INDICE_CATALOGO_NISECI.cerca(&ELENCO_CATALOGO_NISECI, chiave)
+}
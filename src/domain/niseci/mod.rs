@@ -15,7 +15,13 @@
     along with this program.  If not, see <https://www.gnu.org/licenses/>.
 */
 
-use serde::Serialize;
+pub mod catalogo;
+pub mod traccia;
+
+use crate::domain::specie::{IndiceSpecie, SpecieIndicizzabile};
+use crate::i18n::{Etichetta, Lingua};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
 use std::collections::hash_map::Entry;
 use std::collections::HashMap;
 use std::fmt;
@@ -23,14 +29,18 @@ use std::vec::Vec;
 
 use super::location::Location;
 
+use crate::engines::niseci::stimatori::{AvvisoRimozione, StimaPopolazione};
+
 #[cfg(test)]
 use crate::engines::niseci::linear_regression::Point; // Needed by fishes_for_every_passage() only
                                                       // in test builds
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SpecieNISECI {
     pub id: String,
-    pub nome: String,
+    pub nome: String, // Nome latino
+    #[serde(default)]
+    pub nome_comune: String,
     pub tipo_autoctono: u8,
     pub tipo_alloctono: u8,
     pub specie_attesa: bool,
@@ -60,11 +70,21 @@ impl fmt::Display for SpecieNISECI {
     }
 }
 
+impl SpecieIndicizzabile for SpecieNISECI {
+    fn codice(&self) -> &str {
+        &self.id
+    }
+    fn nomi(&self) -> Vec<&str> {
+        vec![&self.nome_comune, &self.nome]
+    }
+}
+
 impl SpecieNISECI {
     pub fn new_dummy_specie() -> SpecieNISECI {
         SpecieNISECI {
             id: "0".to_string(),
             nome: "dummy".to_string(),
+            nome_comune: "dummy".to_string(),
             tipo_autoctono: 0,
             tipo_alloctono: 0,
             specie_attesa: true,
@@ -82,7 +102,7 @@ impl SpecieNISECI {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct RiferimentoNISECI {
     pub elenco_specie: Vec<SpecieNISECI>,
 }
@@ -102,15 +122,29 @@ impl RiferimentoNISECI {
     pub fn new(elenco_specie: Vec<SpecieNISECI>) -> Self {
         Self { elenco_specie }
     }
+
+    /// Indice delle specie per codice, nome latino e nome comune
+    pub fn indice(&self) -> IndiceSpecie {
+        IndiceSpecie::da_elenco(&self.elenco_specie)
+    }
+
+    /// Cerca una specie per codice, nome latino o nome comune, ignorando maiuscole e spazi.
+    /// Per ricerche ripetute conviene costruire una volta l'indice con indice().
+    pub fn cerca_specie(&self, chiave: &str) -> Option<&SpecieNISECI> {
+        self.indice().cerca(&self.elenco_specie, chiave)
+    }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RecordNISECI {
     pub specie: SpecieNISECI,
     pub passaggio_cattura: u8,
     pub lunghezza: u32,
     /// in millimetri
     pub peso: f32, // in grammi
+    /// Data del campionamento in cui è stato catturato l'esemplare
+    #[serde(default)]
+    pub data: Option<NaiveDate>,
 }
 
 impl fmt::Display for RecordNISECI {
@@ -121,7 +155,22 @@ impl fmt::Display for RecordNISECI {
     }
 }
 
-#[derive(Clone)]
+/// Identifica un campionamento all'interno di un file che ne contiene più di uno
+/// (più stazioni e/o più date)
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+pub struct ChiaveCampionamentoNISECI {
+    pub stazione: String,
+    pub data: NaiveDate,
+}
+
+impl fmt::Display for ChiaveCampionamentoNISECI {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let string_representation = format!("{} ({})", self.stazione, self.data.format("%d/%m/%Y"));
+        write!(f, "{}", string_representation)
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct CampionamentoNISECI {
     pub campionamento: Vec<RecordNISECI>,
 }
@@ -168,6 +217,28 @@ impl CampionamentoNISECI {
         Self { campionamento }
     }
 
+    /// Date distinte dei record, in ordine cronologico
+    pub fn get_date(&self) -> Vec<NaiveDate> {
+        let mut date: Vec<NaiveDate> = self.campionamento.iter().filter_map(|r| r.data).collect();
+        date.sort();
+        date.dedup();
+        date
+    }
+
+    /// Data del campionamento (la prima, se i record ne riportano più d'una)
+    pub fn get_data(&self) -> Option<NaiveDate> {
+        self.campionamento.iter().filter_map(|r| r.data).min()
+    }
+
+    /// Numero di passaggi del campionamento: l'ultimo passaggio con catture di qualsiasi specie
+    pub fn get_numero_passaggi(&self) -> u8 {
+        self.campionamento
+            .iter()
+            .map(|r| r.passaggio_cattura)
+            .max()
+            .unwrap_or(0)
+    }
+
     pub fn get_numero_pesci_alieni_e_indigeni(&self) -> AlieniIndigeni {
         let mut alieni_indigeni = AlieniIndigeni {
             alieni: 0,
@@ -210,7 +281,7 @@ pub struct AlieniIndigeni {
     pub indigeni: u32,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub enum TipoComunitaNISECI {
     Redatta,
     Recuperata,
@@ -218,14 +289,27 @@ pub enum TipoComunitaNISECI {
     AffinataDalMase,
 }
 
+impl Etichetta for TipoComunitaNISECI {
+    fn etichetta(&self, lingua: Lingua) -> &'static str {
+        match self {
+            TipoComunitaNISECI::Redatta => {
+                lingua.scegli("Redatta dall'operatore", "Written by the operator")
+            }
+            TipoComunitaNISECI::Recuperata => lingua.scegli(
+                "Recuperata da fonti bibliografiche",
+                "Retrieved from bibliographic sources",
+            ),
+            TipoComunitaNISECI::Dm260_2010 => "DM 260/2010",
+            TipoComunitaNISECI::AffinataDalMase => {
+                lingua.scegli("Affinata dal Mase", "Refined by MASE")
+            }
+        }
+    }
+}
+
 impl fmt::Display for TipoComunitaNISECI {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let string_representation = match *self {
-            TipoComunitaNISECI::Redatta => "Redatta dall'operatore",
-            TipoComunitaNISECI::Recuperata => "Recuperata da fonti bibliografiche",
-            TipoComunitaNISECI::Dm260_2010 => "DM 260/2010",
-            TipoComunitaNISECI::AffinataDalMase => "Affinata dal Mase",
-        };
+        let string_representation = self.etichetta(Lingua::default());
         write!(f, "{}", string_representation)
     }
 }
@@ -246,7 +330,7 @@ impl TryFrom<i32> for TipoComunitaNISECI {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct ComunitaNISECI {
     pub tipo: TipoComunitaNISECI,
     pub fonte: Option<String>,
@@ -292,18 +376,29 @@ impl fmt::Display for ComunitaNISECI {
     }
 }
 
-#[derive(Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum AreaNISECI {
     Alpina,
     Mediterranea,
 }
 
+impl Etichetta for AreaNISECI {
+    fn etichetta(&self, lingua: Lingua) -> &'static str {
+        match self {
+            AreaNISECI::Alpina => lingua.scegli("Alpina", "Alpine"),
+            AreaNISECI::Mediterranea => lingua.scegli("Mediterranea", "Mediterranean"),
+        }
+    }
+}
+
 impl fmt::Display for AreaNISECI {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let string_representation = match *self {
-            AreaNISECI::Alpina => "Area NISECI: Alpina".to_string(),
-            AreaNISECI::Mediterranea => "Area NISECI: Mediterranea".to_string(),
-        };
+        let lingua = Lingua::default();
+        let string_representation = format!(
+            "{}: {}",
+            lingua.scegli("Area NISECI", "NISECI area"),
+            self.etichetta(lingua)
+        );
         write!(f, "{}", string_representation)
     }
 }
@@ -334,11 +429,14 @@ pub struct AnagraficaNISECIDraft {
     pub larghezza_media_stazione: String,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct AnagraficaNISECI {
     pub comunita: ComunitaNISECI,
     pub codice_stazione: String,
-    pub date_string: String, // Formato gg/mm/aaaa
+    pub date_string: String, // Come riportata nel file
+    /// Data interpretata da date_string (None se non valida)
+    #[serde(default)]
+    pub data: Option<NaiveDate>,
     pub area: AreaNISECI,
     pub corpo_idrico: String,
     pub bacino_appartenenza: String,
@@ -365,7 +463,7 @@ impl fmt::Display for AnagraficaNISECI {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub enum IdroEcoRegioneNISECI {
     AlpiOccidentali,
     PrealpiDolomiti,
@@ -390,31 +488,59 @@ pub enum IdroEcoRegioneNISECI {
     Sardegna,
 }
 
-impl fmt::Display for IdroEcoRegioneNISECI {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let string_representation = match *self {
-            IdroEcoRegioneNISECI::AlpiOccidentali => "Alpi Occidentali",
-            IdroEcoRegioneNISECI::PrealpiDolomiti => "Prealpi Dolomiti",
-            IdroEcoRegioneNISECI::AlpiCentroOrientali => "Alpi Centro-orientali",
-            IdroEcoRegioneNISECI::AlpiMeridionali => "Alpi Meridionali",
+impl Etichetta for IdroEcoRegioneNISECI {
+    fn etichetta(&self, lingua: Lingua) -> &'static str {
+        match self {
+            IdroEcoRegioneNISECI::AlpiOccidentali => {
+                lingua.scegli("Alpi Occidentali", "Western Alps")
+            }
+            IdroEcoRegioneNISECI::PrealpiDolomiti => {
+                lingua.scegli("Prealpi Dolomiti", "Pre-Alps Dolomites")
+            }
+            IdroEcoRegioneNISECI::AlpiCentroOrientali => {
+                lingua.scegli("Alpi Centro-orientali", "Central-Eastern Alps")
+            }
+            IdroEcoRegioneNISECI::AlpiMeridionali => {
+                lingua.scegli("Alpi Meridionali", "Southern Alps")
+            }
             IdroEcoRegioneNISECI::Monferrato => "Monferrato",
-            IdroEcoRegioneNISECI::PianuraPadana => "Pianura Padana",
-            IdroEcoRegioneNISECI::Carso => "Carso",
-            IdroEcoRegioneNISECI::AppenninoPiemontese => "Appennino Piemontese",
-            IdroEcoRegioneNISECI::AlpiMediterranee => "Alpi Mediterranee",
-            IdroEcoRegioneNISECI::AppenninoSettentrionale => "Appennino Settentrionale",
-            IdroEcoRegioneNISECI::Toscana => "Toscana",
-            IdroEcoRegioneNISECI::CostaAdriatica => "Costa Adriatica",
-            IdroEcoRegioneNISECI::AppenninoCentrale => "Appennino Centrale",
-            IdroEcoRegioneNISECI::RomaViterbese => "Roma-Viterbese",
-            IdroEcoRegioneNISECI::BassoLazio => "Basso Lazio",
-            IdroEcoRegioneNISECI::Vesuvio => "Vesuvio",
+            IdroEcoRegioneNISECI::PianuraPadana => lingua.scegli("Pianura Padana", "Po Plain"),
+            IdroEcoRegioneNISECI::Carso => lingua.scegli("Carso", "Karst"),
+            IdroEcoRegioneNISECI::AppenninoPiemontese => {
+                lingua.scegli("Appennino Piemontese", "Piedmontese Apennines")
+            }
+            IdroEcoRegioneNISECI::AlpiMediterranee => {
+                lingua.scegli("Alpi Mediterranee", "Maritime Alps")
+            }
+            IdroEcoRegioneNISECI::AppenninoSettentrionale => {
+                lingua.scegli("Appennino Settentrionale", "Northern Apennines")
+            }
+            IdroEcoRegioneNISECI::Toscana => lingua.scegli("Toscana", "Tuscany"),
+            IdroEcoRegioneNISECI::CostaAdriatica => {
+                lingua.scegli("Costa Adriatica", "Adriatic Coast")
+            }
+            IdroEcoRegioneNISECI::AppenninoCentrale => {
+                lingua.scegli("Appennino Centrale", "Central Apennines")
+            }
+            IdroEcoRegioneNISECI::RomaViterbese => lingua.scegli("Roma-Viterbese", "Rome-Viterbo"),
+            IdroEcoRegioneNISECI::BassoLazio => lingua.scegli("Basso Lazio", "Lower Lazio"),
+            IdroEcoRegioneNISECI::Vesuvio => lingua.scegli("Vesuvio", "Vesuvius"),
             IdroEcoRegioneNISECI::BasilicataTavoliere => "Basilicata Tavoliere",
-            IdroEcoRegioneNISECI::PugliaCarsica => "Puglia Carsica",
-            IdroEcoRegioneNISECI::AppenninoMeridionale => "Appennino Meridionale",
-            IdroEcoRegioneNISECI::Sicilia => "Sicilia",
-            IdroEcoRegioneNISECI::Sardegna => "Sardegna",
-        };
+            IdroEcoRegioneNISECI::PugliaCarsica => {
+                lingua.scegli("Puglia Carsica", "Karstic Apulia")
+            }
+            IdroEcoRegioneNISECI::AppenninoMeridionale => {
+                lingua.scegli("Appennino Meridionale", "Southern Apennines")
+            }
+            IdroEcoRegioneNISECI::Sicilia => lingua.scegli("Sicilia", "Sicily"),
+            IdroEcoRegioneNISECI::Sardegna => lingua.scegli("Sardegna", "Sardinia"),
+        }
+    }
+}
+
+impl fmt::Display for IdroEcoRegioneNISECI {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let string_representation = self.etichetta(Lingua::default());
         write!(f, "{}", string_representation)
     }
 }
@@ -486,6 +612,10 @@ impl TryFrom<i32> for IdroEcoRegioneNISECI {
 pub struct ValoriIntermediSpecieNISECI {
     pub densita_stimata: f32,
     pub quantita_stimata: u32,
+    /// Stima della popolazione da cui derivano quantita_stimata e densita_stimata
+    pub stima_popolazione: Option<StimaPopolazione>,
+    /// Ipotesi del campionamento per rimozione non rispettate: la densità stimata è poco affidabile
+    pub avvisi_rimozione: Vec<AvvisoRimozione>,
     pub classi_eta: ClassiEtaSpecieNISECI,
     pub rapporto_ad_juv: Option<f32>,
     pub x2_a_a: u8,
@@ -554,10 +684,17 @@ impl fmt::Display for ValoriIntermediNISECI {
 }
 
 impl ValoriIntermediNISECI {
+    /// Inoltra i valori intermedi alla facade `log` (target "esox")
+    #[cfg(feature = "log")]
     pub fn log(&self) {
         //TODO: a proper format? we count on the embedded newlines to leverage the
         //chopping on newlines from add_console_message()
-        println!("Valori intermedi: {{{self}}}");
+        log::info!(target: "esox", "Valori intermedi: {{{self}}}");
+        for (specie, valori) in self.specie_specifici.iter() {
+            for avviso in &valori.avvisi_rimozione {
+                log::warn!(target: "esox", "{specie}: {avviso}");
+            }
+        }
     }
 }
 
@@ -610,6 +747,9 @@ impl RisultatoNISECI {
     pub fn get_x3(&self) -> f32 {
         self.valori_intermedi.x3
     }
+    pub fn get_valori_intermedi(&self) -> &ValoriIntermediNISECI {
+        &self.valori_intermedi
+    }
 }
 
 #[derive(Clone, Copy)]
@@ -680,6 +820,12 @@ impl fmt::Display for ClassiEtaSpecieNISECI {
     }
 }
 
+impl Default for ClassiEtaSpecieNISECI {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl ClassiEtaSpecieNISECI {
     pub fn new() -> ClassiEtaSpecieNISECI {
         ClassiEtaSpecieNISECI {
@@ -856,6 +1002,12 @@ pub struct InfoPopolazioniNISECI {
     pub intermediates_map: HashMap<String, InfoIntermediePopolazioniNISECI>,
 }
 
+impl Default for InfoPopolazioniNISECI {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl InfoPopolazioniNISECI {
     pub fn new() -> InfoPopolazioniNISECI {
         InfoPopolazioniNISECI {
@@ -929,6 +1081,13 @@ pub struct InfoPopolazioniAlieneNISECI {
     pub tot_specie_autoctone: usize,
 }
 
+#[cfg(test)]
+impl Default for InfoPopolazioniAlieneNISECI {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl InfoPopolazioniAlieneNISECI {
     #[cfg(test)]
     pub fn new() -> InfoPopolazioniAlieneNISECI {
@@ -979,6 +1138,12 @@ pub struct ClassiEtaAlieniNISECI {
     pub tot_specie_autoctone: usize,
 }
 
+impl Default for ClassiEtaAlieniNISECI {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl ClassiEtaAlieniNISECI {
     pub fn new() -> ClassiEtaAlieniNISECI {
         ClassiEtaAlieniNISECI {
@@ -1026,6 +1191,7 @@ impl EsemplariPerCattura {
 
 /// enum per il risultato finale di un calcolo niseci
 /// (vedi calculate_stato_ecologico)
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize)]
 pub enum StatoEcologicoNISECI {
     Elevato,
     Buono,
@@ -1034,15 +1200,21 @@ pub enum StatoEcologicoNISECI {
     Cattivo,
 }
 
+impl Etichetta for StatoEcologicoNISECI {
+    fn etichetta(&self, lingua: Lingua) -> &'static str {
+        match self {
+            StatoEcologicoNISECI::Elevato => lingua.scegli("Elevato", "High"),
+            StatoEcologicoNISECI::Buono => lingua.scegli("Buono", "Good"),
+            StatoEcologicoNISECI::Moderato => lingua.scegli("Moderato", "Moderate"),
+            StatoEcologicoNISECI::Scadente => lingua.scegli("Scadente", "Poor"),
+            StatoEcologicoNISECI::Cattivo => lingua.scegli("Cattivo", "Bad"),
+        }
+    }
+}
+
 impl fmt::Display for StatoEcologicoNISECI {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let string_representation = match *self {
-            StatoEcologicoNISECI::Elevato => "Elevato",
-            StatoEcologicoNISECI::Buono => "Buono",
-            StatoEcologicoNISECI::Moderato => "Moderato",
-            StatoEcologicoNISECI::Scadente => "Scadente",
-            StatoEcologicoNISECI::Cattivo => "Cattivo",
-        };
+        let string_representation = self.etichetta(Lingua::default());
         write!(f, "{}", string_representation)
     }
 }
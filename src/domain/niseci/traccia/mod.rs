@@ -0,0 +1,346 @@
+// SPDX-License-Identifier: GPL-3.0-only
+/*
+    Copyright (C) 2024-2026 jgabaut, gioninjo
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU General Public License as published by
+    the Free Software Foundation, version 3 of the License.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU General Public License for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use crate::domain::niseci::{AreaNISECI, StatoEcologicoNISECI};
+use crate::engines::niseci::stimatori::{AvvisoRimozione, StimaPopolazione, StimatorePopolazione};
+use crate::i18n::{Etichetta, Lingua};
+use serde::Serialize;
+use std::fmt;
+
+/// Gruppo di specie per cui viene calcolato x2. Solo le specie attese concorrono a x2,
+/// per le altre i valori servono come valori intermedi.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize)]
+pub enum GruppoSpecieNISECI {
+    Attese,
+    NonAttese,
+    Alloctone,
+}
+
+impl Etichetta for GruppoSpecieNISECI {
+    fn etichetta(&self, lingua: Lingua) -> &'static str {
+        match self {
+            GruppoSpecieNISECI::Attese => lingua.scegli("specie attese", "expected species"),
+            GruppoSpecieNISECI::NonAttese => {
+                lingua.scegli("specie non attese", "unexpected species")
+            }
+            GruppoSpecieNISECI::Alloctone => lingua.scegli("specie alloctone", "alien species"),
+        }
+    }
+}
+
+/// Condizione del calcolo di x3 che ha determinato il risultato
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub enum CondizioneX3 {
+    /// Nessun esemplare alieno: x3 = 1
+    NessunAlieno,
+    /// Esemplari alieni non meno degli indigeni: x3 = 0
+    AlieniNonMinoritari,
+    /// Una specie alloctona di tipo 1 ha una popolazione strutturata: x3 = 0
+    AlloctonaTipo1Strutturata,
+    /// x3 = 0.5 · (a + b)
+    Formula { a: f32, b: f32 },
+}
+
+/// Passo del calcolo del NISECI
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "evento", rename_all = "camelCase")]
+pub enum EventoNISECI {
+    Stimatore {
+        stimatore: StimatorePopolazione,
+    },
+    /// Specie che concorrono a n_i, n_a, m_i e m_a
+    X1 {
+        campionate_importanti: Vec<String>,
+        campionate_altre: Vec<String>,
+        attese_importanti: Vec<String>,
+        attese_altre: Vec<String>,
+        x1: f32,
+    },
+    /// Classi di età e criteri a/b di x2_a
+    StrutturaPopolazione {
+        gruppo: GruppoSpecieNISECI,
+        specie: String,
+        classi_eta: [u32; 5],
+        criterio_a: u8,
+        criterio_b: u8,
+        rapporto_ad_juv: Option<f32>,
+    },
+    /// Stima della popolazione e della densità, confrontata con le soglie della specie
+    Densita {
+        gruppo: GruppoSpecieNISECI,
+        specie: String,
+        catture_per_passaggio: Vec<u32>,
+        stima: Option<StimaPopolazione>,
+        quantita_stimata: u32,
+        superficie: f32,
+        densita_stimata: f32,
+        soglie: [f32; 2],
+        x2_b: f32,
+        avvisi: Vec<AvvisoRimozione>,
+    },
+    /// Densità di una specie valutata solo da x3 che non si è potuta stimare: il calcolo prosegue
+    /// con densita_stimata = -1
+    DensitaNonStimata {
+        specie: String,
+        motivo: String,
+    },
+    /// x2 = (0.6 · Σx2_a + 0.4 · Σx2_b) / specie attese campionate
+    MediaX2 {
+        sommatoria_x2_a: f32,
+        sommatoria_x2_b: f32,
+        specie: usize,
+        x2: Option<f32>,
+    },
+    X3 {
+        alieni: u32,
+        indigeni: u32,
+        condizione: CondizioneX3,
+        x3: f32,
+    },
+    /// NISECI = (0.1·√x1 + 0.1·√x2 + 0.8·x1·x2) · (1 - 0.1·(1 - x3))
+    Formula {
+        x1: f32,
+        x2: Option<f32>,
+        x3: f32,
+        niseci: Option<f32>,
+    },
+    Classificazione {
+        area: AreaNISECI,
+        rqe: Option<f32>,
+        stato: Option<StatoEcologicoNISECI>,
+    },
+}
+
+fn opzionale(valore: Option<f32>) -> String {
+    match valore {
+        Some(v) => format!("{v}"),
+        None => "NC".to_string(),
+    }
+}
+
+impl EventoNISECI {
+    pub fn descrizione(&self, lingua: Lingua) -> String {
+        let l = lingua;
+        match self {
+            EventoNISECI::Stimatore { stimatore } => {
+                format!("{}: {:?}", l.scegli("stimatore", "estimator"), stimatore)
+            }
+            EventoNISECI::X1 {
+                campionate_importanti,
+                campionate_altre,
+                attese_importanti,
+                attese_altre,
+                x1,
+            } => format!(
+                "x1 = (1.2·n_i + 0.8·n_a) / (1.2·m_i + 0.8·m_a) = (1.2·{} + 0.8·{}) / (1.2·{} + 0.8·{}) = {}; n_i [{}], n_a [{}], m_i [{}], m_a [{}]",
+                campionate_importanti.len(),
+                campionate_altre.len(),
+                attese_importanti.len(),
+                attese_altre.len(),
+                x1,
+                campionate_importanti.join(", "),
+                campionate_altre.join(", "),
+                attese_importanti.join(", "),
+                attese_altre.join(", ")
+            ),
+            EventoNISECI::StrutturaPopolazione {
+                gruppo,
+                specie,
+                classi_eta,
+                criterio_a,
+                criterio_b,
+                rapporto_ad_juv,
+            } => format!(
+                "{} ({}): {} {:?}, {} a = {}, {} b = {}, ad/juv = {}",
+                specie,
+                gruppo.etichetta(l),
+                l.scegli("classi di età", "age classes"),
+                classi_eta,
+                l.scegli("criterio", "criterion"),
+                criterio_a,
+                l.scegli("criterio", "criterion"),
+                criterio_b,
+                opzionale(*rapporto_ad_juv)
+            ),
+            EventoNISECI::Densita {
+                gruppo,
+                specie,
+                catture_per_passaggio,
+                stima,
+                quantita_stimata,
+                superficie,
+                densita_stimata,
+                soglie,
+                x2_b,
+                avvisi,
+            } => {
+                let mut testo = format!(
+                    "{} ({}): {} {:?}, N = {}",
+                    specie,
+                    gruppo.etichetta(l),
+                    l.scegli("catture per passaggio", "catches per pass"),
+                    catture_per_passaggio,
+                    opzionale(stima.as_ref().map(|s| s.popolazione))
+                );
+                if let Some((inferiore, superiore)) =
+                    stima.as_ref().and_then(|s| s.intervallo_confidenza)
+                {
+                    testo = format!("{} [{}, {}]", testo, inferiore, superiore);
+                }
+                if let Some(retta) = stima.as_ref().and_then(|s| s.retta.as_ref()) {
+                    testo = format!(
+                        "{} ({} y = {}x + {}, R² = {})",
+                        testo,
+                        l.scegli("retta", "line"),
+                        retta.m,
+                        retta.b,
+                        retta.r_quadro
+                    );
+                }
+                testo = format!(
+                    "{}, {} = {} / {} = {}, {} {} / {}, x2_b = {}",
+                    testo,
+                    l.scegli("densità", "density"),
+                    quantita_stimata,
+                    superficie,
+                    densita_stimata,
+                    l.scegli("soglie", "thresholds"),
+                    soglie[0],
+                    soglie[1],
+                    x2_b
+                );
+                for avviso in avvisi {
+                    testo = format!("{}; {}", testo, avviso.descrizione(l));
+                }
+                testo
+            }
+            EventoNISECI::DensitaNonStimata { specie, motivo } => format!(
+                "{}: {}: {}",
+                specie,
+                l.scegli("densità non stimata", "density not estimated"),
+                motivo
+            ),
+            EventoNISECI::MediaX2 {
+                sommatoria_x2_a,
+                sommatoria_x2_b,
+                specie,
+                x2,
+            } => format!(
+                "x2 = (0.6·Σx2_a + 0.4·Σx2_b) / {} = (0.6·{} + 0.4·{}) / {} = {}",
+                l.scegli("specie", "species"),
+                sommatoria_x2_a,
+                sommatoria_x2_b,
+                specie,
+                opzionale(*x2)
+            ),
+            EventoNISECI::X3 {
+                alieni,
+                indigeni,
+                condizione,
+                x3,
+            } => {
+                let condizione_str = match condizione {
+                    CondizioneX3::NessunAlieno => {
+                        l.scegli("nessun esemplare alieno", "no alien fish").to_string()
+                    }
+                    CondizioneX3::AlieniNonMinoritari => l
+                        .scegli(
+                            "esemplari alieni non inferiori agli indigeni",
+                            "alien fish not fewer than native fish",
+                        )
+                        .to_string(),
+                    CondizioneX3::AlloctonaTipo1Strutturata => l
+                        .scegli(
+                            "specie alloctona di tipo 1 con popolazione strutturata",
+                            "type 1 alien species with a structured population",
+                        )
+                        .to_string(),
+                    CondizioneX3::Formula { a, b } => {
+                        format!("x3 = 0.5·(a + b) = 0.5·({} + {})", a, b)
+                    }
+                };
+                format!(
+                    "x3: {} {}, {} {}; {} => x3 = {}",
+                    alieni,
+                    l.scegli("esemplari alieni", "alien fish"),
+                    indigeni,
+                    l.scegli("indigeni", "native"),
+                    condizione_str,
+                    x3
+                )
+            }
+            EventoNISECI::Formula { x1, x2, x3, niseci } => format!(
+                "NISECI = (0.1·√x1 + 0.1·√x2 + 0.8·x1·x2) · (1 - 0.1·(1 - x3)), x1 = {}, x2 = {}, x3 = {} => {}",
+                x1,
+                opzionale(*x2),
+                x3,
+                opzionale(*niseci)
+            ),
+            EventoNISECI::Classificazione { area, rqe, stato } => format!(
+                "RQE = {}, {} ({}) = {}",
+                opzionale(*rqe),
+                l.scegli("stato ecologico", "ecological status"),
+                area.etichetta(l),
+                stato.map_or("NC", |s| s.etichetta(l))
+            ),
+        }
+    }
+}
+
+impl fmt::Display for EventoNISECI {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let string_representation = self.descrizione(Lingua::default());
+        write!(f, "{}", string_representation)
+    }
+}
+
+/// Traccia del calcolo del NISECI, nell'ordine in cui sono stati eseguiti i passi
+/// (vedi calculate_niseci_with_traccia)
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct TracciaNISECI {
+    pub eventi: Vec<EventoNISECI>,
+}
+
+impl TracciaNISECI {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn registra(&mut self, evento: EventoNISECI) {
+        self.eventi.push(evento);
+    }
+
+    /// Una riga per evento
+    pub fn testo(&self, lingua: Lingua) -> String {
+        self.eventi
+            .iter()
+            .map(|e| e.descrizione(lingua))
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    pub fn json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("TracciaNISECI has only string keys")
+    }
+}
+
+impl fmt::Display for TracciaNISECI {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let string_representation = self.testo(Lingua::default());
+        write!(f, "{}", string_representation)
+    }
+}
@@ -0,0 +1,176 @@
+// SPDX-License-Identifier: GPL-3.0-only
+/*
+    Copyright (C) 2024-2026 jgabaut, gioninjo
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU General Public License as published by
+    the Free Software Foundation, version 3 of the License.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU General Public License for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use super::hfbi::AnagraficaHFBI;
+use super::niseci::{AnagraficaNISECI, ChiaveCampionamentoNISECI};
+use crate::csv::parser::parse_date;
+use chrono::NaiveDate;
+use std::collections::BTreeMap;
+
+/// Anagrafica identificabile da codice stazione e data
+pub trait AnagraficaStazione {
+    fn codice_stazione(&self) -> &str;
+    fn date_string(&self) -> &str;
+    /// Data interpretata, se date_string è una data valida
+    fn data(&self) -> Option<NaiveDate>;
+}
+
+impl AnagraficaStazione for AnagraficaNISECI {
+    fn codice_stazione(&self) -> &str {
+        &self.codice_stazione
+    }
+    fn date_string(&self) -> &str {
+        &self.date_string
+    }
+    fn data(&self) -> Option<NaiveDate> {
+        self.data.or_else(|| parse_date(&self.date_string).ok())
+    }
+}
+
+impl AnagraficaStazione for AnagraficaHFBI {
+    fn codice_stazione(&self) -> &str {
+        &self.codice_stazione
+    }
+    fn date_string(&self) -> &str {
+        &self.date_string
+    }
+    fn data(&self) -> Option<NaiveDate> {
+        self.data.or_else(|| parse_date(&self.date_string).ok())
+    }
+}
+
+/// Confronta le date interpretate; se una delle due non è valida confronta il testo
+fn stessa_data<A: AnagraficaStazione>(a: &A, data: Option<NaiveDate>, date_string: &str) -> bool {
+    match (a.data(), data) {
+        (Some(d1), Some(d2)) => d1 == d2,
+        _ => a.date_string().trim() == date_string.trim(),
+    }
+}
+
+/// L'anagrafica di una stazione che ne ha una sola
+fn unica<A>(anagrafiche: &[A]) -> Option<&A> {
+    match anagrafiche {
+        [anagrafica] => Some(anagrafica),
+        _ => None,
+    }
+}
+
+/// Registro delle anagrafiche di più stazioni, indicizzato per codice stazione (senza spazi
+/// iniziali e finali). La stessa stazione può comparire più volte, purché con date diverse.
+#[derive(Clone)]
+pub struct RegistroStazioni<A: AnagraficaStazione> {
+    elenco: BTreeMap<String, Vec<A>>,
+}
+
+impl<A: AnagraficaStazione> Default for RegistroStazioni<A> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<A: AnagraficaStazione> RegistroStazioni<A> {
+    pub fn new() -> Self {
+        Self {
+            elenco: BTreeMap::new(),
+        }
+    }
+
+    /// Aggiunge un'anagrafica al registro.
+    /// Se esiste già un'anagrafica con stesso codice stazione e stessa data, la restituisce come errore.
+    pub fn inserisci(&mut self, anagrafica: A) -> Result<(), A> {
+        let stazione = self
+            .elenco
+            .entry(anagrafica.codice_stazione().trim().to_string())
+            .or_default();
+        if stazione
+            .iter()
+            .any(|a| stessa_data(a, anagrafica.data(), anagrafica.date_string()))
+        {
+            return Err(anagrafica);
+        }
+        stazione.push(anagrafica);
+        Ok(())
+    }
+
+    /// Tutte le anagrafiche di una stazione
+    pub fn get(&self, codice_stazione: &str) -> &[A] {
+        match self.elenco.get(codice_stazione.trim()) {
+            Some(v) => v,
+            None => &[],
+        }
+    }
+
+    /// Cerca l'anagrafica di una stazione per la data indicata.
+    /// Se nessuna anagrafica ha la stessa data (o la data manca) e la stazione ne ha una sola,
+    /// restituisce quella; con più anagrafiche della stazione restituisce None.
+    pub fn find(&self, codice_stazione: &str, data: &str) -> Option<&A> {
+        let data_parsed = parse_date(data).ok();
+        let anagrafiche = self.get(codice_stazione);
+        anagrafiche
+            .iter()
+            .find(|a| stessa_data(*a, data_parsed, data))
+            .or_else(|| unica(anagrafiche))
+    }
+
+    /// Come find(), con la data già interpretata
+    pub fn find_data(&self, codice_stazione: &str, data: NaiveDate) -> Option<&A> {
+        let anagrafiche = self.get(codice_stazione);
+        anagrafiche
+            .iter()
+            .find(|a| a.data() == Some(data))
+            .or_else(|| unica(anagrafiche))
+    }
+
+    pub fn contains_stazione(&self, codice_stazione: &str) -> bool {
+        self.elenco.contains_key(codice_stazione.trim())
+    }
+
+    /// Numero totale di anagrafiche
+    pub fn len(&self) -> usize {
+        self.elenco.values().map(|v| v.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.elenco.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &A> {
+        self.elenco.values().flatten()
+    }
+
+    /// Abbina ogni campionamento all'anagrafica della sua stazione e data (None se manca)
+    pub fn abbina<'a, V>(
+        &'a self,
+        campionamenti: &'a BTreeMap<ChiaveCampionamentoNISECI, V>,
+    ) -> Vec<(&'a ChiaveCampionamentoNISECI, &'a V, Option<&'a A>)> {
+        campionamenti
+            .iter()
+            .map(|(chiave, v)| (chiave, v, self.find_data(&chiave.stazione, chiave.data)))
+            .collect()
+    }
+}
+
+impl<A: AnagraficaStazione> FromIterator<A> for RegistroStazioni<A> {
+    /// Costruisce il registro ignorando i duplicati (vince il primo)
+    fn from_iter<I: IntoIterator<Item = A>>(iter: I) -> Self {
+        let mut registro = Self::new();
+        for a in iter {
+            let _ = registro.inserisci(a);
+        }
+        registro
+    }
+}
@@ -0,0 +1,290 @@
+// SPDX-License-Identifier: GPL-3.0-only
+/*
+    Copyright (C) 2024-2026 jgabaut, gioninjo
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU General Public License as published by
+    the Free Software Foundation, version 3 of the License.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU General Public License for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use crate::error::Error;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+/// Specie che può essere cercata per codice e per nome
+pub trait SpecieIndicizzabile {
+    fn codice(&self) -> &str;
+    /// Nomi con cui la specie può essere cercata, a partire da quello da mostrare all'utente
+    fn nomi(&self) -> Vec<&str>;
+}
+
+/// Specie proposta al posto di un codice non presente nel riferimento
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpecieSuggerita {
+    pub codice: String,
+    pub nome: String,
+}
+
+impl fmt::Display for SpecieSuggerita {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let string_representation = match self.nome.is_empty() {
+            true => self.codice.clone(),
+            false => format!("{} ({})", self.codice, self.nome),
+        };
+        write!(f, "{}", string_representation)
+    }
+}
+
+/// Distanza di Levenshtein tra due stringhe (in caratteri)
+fn distanza(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut riga: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonale = riga[0];
+        riga[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let sopra = riga[j + 1];
+            let costo = if ca == *cb { 0 } else { 1 };
+            riga[j + 1] = (diagonale + costo).min(sopra + 1).min(riga[j] + 1);
+            diagonale = sopra;
+        }
+    }
+    riga[b.len()]
+}
+
+/// Forma normalizzata di un codice o nome per la ricerca:
+/// minuscolo, senza spazi iniziali e finali, spazi interni ridotti a uno
+pub fn normalizza_nome(nome: &str) -> String {
+    nome.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// Indice di un elenco di specie per codice, nome latino e nome comune.
+/// La ricerca ignora maiuscole e spazi; i codici hanno la precedenza sui nomi.
+/// Un nome condiviso da più specie è ambiguo e non viene trovato.
+#[derive(Debug, Clone, Default)]
+pub struct IndiceSpecie {
+    codici: HashMap<String, usize>,
+    /// None se il nome è di più specie
+    nomi: HashMap<String, Option<usize>>,
+}
+
+impl IndiceSpecie {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Indicizza un elenco; a parità di codice vince la prima specie (vedi duplicati())
+    pub fn da_elenco<S: SpecieIndicizzabile>(elenco: &[S]) -> Self {
+        let mut indice = Self::new();
+        for (posizione, specie) in elenco.iter().enumerate() {
+            let _ = indice.inserisci(posizione, specie);
+        }
+        indice
+    }
+
+    /// Aggiunge la specie che si trova in posizione nell'elenco.
+    /// Se il codice è già presente la specie non viene aggiunta
+    /// e viene restituita la posizione della specie con lo stesso codice.
+    pub fn inserisci<S: SpecieIndicizzabile>(
+        &mut self,
+        posizione: usize,
+        specie: &S,
+    ) -> Result<(), usize> {
+        let codice = normalizza_nome(specie.codice());
+        if let Some(esistente) = self.codici.get(&codice) {
+            return Err(*esistente);
+        }
+        self.codici.insert(codice, posizione);
+        for nome in specie.nomi() {
+            let nome = normalizza_nome(nome);
+            if nome.is_empty() {
+                continue;
+            }
+            self.nomi
+                .entry(nome)
+                .and_modify(|p| {
+                    if *p != Some(posizione) {
+                        *p = None;
+                    }
+                })
+                .or_insert(Some(posizione));
+        }
+        Ok(())
+    }
+
+    /// Posizione della specie con il codice indicato
+    pub fn posizione_codice(&self, codice: &str) -> Option<usize> {
+        self.codici.get(&normalizza_nome(codice)).copied()
+    }
+
+    /// Posizione della specie con il codice o il nome indicato
+    pub fn posizione(&self, chiave: &str) -> Option<usize> {
+        let chiave = normalizza_nome(chiave);
+        self.codici
+            .get(&chiave)
+            .copied()
+            .or_else(|| self.nomi.get(&chiave).copied().flatten())
+    }
+
+    /// Specie dell'elenco con il codice o il nome indicato
+    pub fn cerca<'a, S>(&self, elenco: &'a [S], chiave: &str) -> Option<&'a S> {
+        self.posizione(chiave).and_then(|p| elenco.get(p))
+    }
+
+    /// Posizione della specie con il codice o il nome più simile alla chiave, se abbastanza vicino
+    /// (al massimo 2 modifiche, o una ogni 4 caratteri per i nomi lunghi).
+    /// A parità di distanza sono preferiti i codici, poi l'ordine dell'elenco.
+    pub fn posizione_simile(&self, chiave: &str) -> Option<usize> {
+        let chiave = normalizza_nome(chiave);
+        if chiave.is_empty() {
+            return None;
+        }
+        let soglia = (chiave.chars().count() / 4).max(2);
+        let codici = self.codici.iter().map(|(k, p)| (k, Some(*p), 0));
+        let nomi = self.nomi.iter().map(|(k, p)| (k, *p, 1));
+        codici
+            .chain(nomi)
+            .filter_map(|(voce, posizione, tipo)| {
+                let d = distanza(&chiave, voce);
+                // Una distanza pari alla lunghezza vuol dire che non c'è niente in comune
+                let lunghezza = chiave.chars().count().max(voce.chars().count());
+                match posizione {
+                    Some(p) if d <= soglia && d < lunghezza => Some((d, tipo, p)),
+                    _ => None,
+                }
+            })
+            .min()
+            .map(|(_, _, p)| p)
+    }
+
+    /// Specie da suggerire per una chiave non trovata (vedi posizione_simile())
+    pub fn suggerisci<S: SpecieIndicizzabile>(
+        &self,
+        elenco: &[S],
+        chiave: &str,
+    ) -> Option<SpecieSuggerita> {
+        let specie = self.posizione_simile(chiave).and_then(|p| elenco.get(p))?;
+        let nome = specie
+            .nomi()
+            .into_iter()
+            .map(str::trim)
+            .find(|n| !n.is_empty())
+            .unwrap_or_default();
+        Some(SpecieSuggerita {
+            codice: specie.codice().trim().to_string(),
+            nome: nome.to_string(),
+        })
+    }
+
+    /// Numero di specie indicizzate
+    pub fn len(&self) -> usize {
+        self.codici.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.codici.is_empty()
+    }
+
+    /// Coppie (posizione, posizione della prima specie con lo stesso codice)
+    /// delle specie dell'elenco che ripetono un codice
+    pub fn duplicati<S: SpecieIndicizzabile>(elenco: &[S]) -> Vec<(usize, usize)> {
+        let mut indice = Self::new();
+        elenco
+            .iter()
+            .enumerate()
+            .filter_map(|(posizione, specie)| {
+                indice
+                    .inserisci(posizione, specie)
+                    .err()
+                    .map(|esistente| (posizione, esistente))
+            })
+            .collect()
+    }
+}
+
+/// Correzioni confermate dei codici specie, da applicare automaticamente nelle importazioni
+/// successive (es. ANG -> AN). Si salva e si ricarica come oggetto JSON { errato: corretto };
+/// al caricamento i codici errati vengono normalizzati come in conferma().
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(from = "BTreeMap<String, String>", into = "BTreeMap<String, String>")]
+pub struct CorrezioniSpecie {
+    correzioni: BTreeMap<String, String>,
+}
+
+impl CorrezioniSpecie {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registra che il codice errato va sostituito con quello corretto
+    pub fn conferma(&mut self, errato: &str, corretto: &str) {
+        self.correzioni
+            .insert(normalizza_nome(errato), corretto.trim().to_string());
+    }
+
+    /// Conferma il suggerimento di un errore di codice specie sconosciuto.
+    /// Restituisce false se l'errore non porta un suggerimento.
+    pub fn conferma_suggerimento(&mut self, errore: &Error) -> bool {
+        match (errore.valore(), errore.suggerimento()) {
+            (Some(errato), Some(suggerimento)) => {
+                let corretto = suggerimento.codice.clone();
+                self.conferma(errato, &corretto);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn rimuovi(&mut self, errato: &str) -> Option<String> {
+        self.correzioni.remove(&normalizza_nome(errato))
+    }
+
+    /// Codice con cui sostituire quello indicato, se c'è una correzione
+    pub fn get(&self, errato: &str) -> Option<&str> {
+        self.correzioni
+            .get(&normalizza_nome(errato))
+            .map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.correzioni.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.correzioni.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.correzioni
+            .iter()
+            .map(|(errato, corretto)| (errato.as_str(), corretto.as_str()))
+    }
+}
+
+impl From<BTreeMap<String, String>> for CorrezioniSpecie {
+    fn from(correzioni: BTreeMap<String, String>) -> Self {
+        let mut res = Self::new();
+        for (errato, corretto) in &correzioni {
+            res.conferma(errato, corretto);
+        }
+        res
+    }
+}
+
+impl From<CorrezioniSpecie> for BTreeMap<String, String> {
+    fn from(correzioni: CorrezioniSpecie) -> Self {
+        correzioni.correzioni
+    }
+}
@@ -71,6 +71,7 @@ mod bbent_private_tests {
             habitat_vegetato: HabitatHFBI::NonVegetato,
             lunghezza_media_transetto: lunghezza,
             larghezza_media_transetto: larghezza,
+            data: None,
         }
     }
 
@@ -83,8 +84,8 @@ mod bbent_private_tests {
     ) -> RecordHFBI {
         RecordHFBI {
             specie: SpecieHFBI {
-                nome_comune: "Test Specie",
-                codice_specie: "TS",
+                nome_comune: "Test Specie".into(),
+                codice_specie: "TS".into(),
                 autoctono: true,
                 gruppo_eco,
                 gruppo_trofico: GruppoTrofHFBI {
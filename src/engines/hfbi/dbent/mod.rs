@@ -77,6 +77,7 @@ mod dbent_private_tests {
             habitat_vegetato: HabitatHFBI::NonVegetato,
             lunghezza_media_transetto: lunghezza,
             larghezza_media_transetto: larghezza,
+            data: None,
         }
     }
 
@@ -89,8 +90,8 @@ mod dbent_private_tests {
     ) -> RecordHFBI {
         RecordHFBI {
             specie: SpecieHFBI {
-                nome_comune: "Test Specie",
-                codice_specie: "TS",
+                nome_comune: "Test Specie".into(),
+                codice_specie: "TS".into(),
                 autoctono: true,
                 gruppo_eco,
                 // The "..Default::default()" has been removed and all fields are now set explicitly
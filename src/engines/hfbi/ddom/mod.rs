@@ -74,6 +74,7 @@ mod ddom_private_tests {
             habitat_vegetato: HabitatHFBI::NonVegetato,
             lunghezza_media_transetto: lunghezza,
             larghezza_media_transetto: larghezza,
+            data: None,
         }
     }
 
@@ -81,8 +82,8 @@ mod ddom_private_tests {
     fn create_dummy_record(peso: f32) -> RecordHFBI {
         RecordHFBI {
             specie: SpecieHFBI {
-                nome_comune: "Dummy",
-                codice_specie: "DM",
+                nome_comune: "Dummy".into(),
+                codice_specie: "DM".into(),
                 autoctono: true,
                 gruppo_eco: GruppoEcoHFBI::ResidentiDiEstuario,
                 gruppo_trofico: GruppoTrofHFBI {
@@ -88,6 +88,7 @@ mod dhzp_private_tests {
             habitat_vegetato: HabitatHFBI::NonVegetato,
             lunghezza_media_transetto: lunghezza,
             larghezza_media_transetto: larghezza,
+            data: None,
         }
     }
 
@@ -99,8 +100,8 @@ mod dhzp_private_tests {
     ) -> RecordHFBI {
         RecordHFBI {
             specie: SpecieHFBI {
-                nome_comune: "Test Specie",
-                codice_specie: "TS",
+                nome_comune: "Test Specie".into(),
+                codice_specie: "TS".into(),
                 autoctono: true,
                 gruppo_eco,
                 gruppo_trofico: GruppoTrofHFBI {
@@ -90,6 +90,7 @@ mod dmig_private_tests {
             habitat_vegetato: HabitatHFBI::NonVegetato,
             lunghezza_media_transetto: lunghezza,
             larghezza_media_transetto: larghezza,
+            data: None,
         }
     }
 
@@ -101,8 +102,8 @@ mod dmig_private_tests {
     ) -> RecordHFBI {
         RecordHFBI {
             specie: SpecieHFBI {
-                nome_comune: "Test Specie",
-                codice_specie: codice_specie,
+                nome_comune: "Test Specie".into(),
+                codice_specie: codice_specie.into(),
                 autoctono: true,
                 gruppo_eco,
                 gruppo_trofico: GruppoTrofHFBI {
@@ -1,11 +1,13 @@
 use crate::domain::hfbi::{
-    AnagraficaHFBI, CampionamentoHFBI, CondizioniRiferimentoHFBI, StatoEcologicoHFBI,
+    AnagraficaHFBI, CampionamentoHFBI, StatoEcologicoHFBI, TabellaCondizioniRiferimentoHFBI,
     ValoriIntermediHFBI,
 };
 use crate::engines::hfbi::{
     bbent::calc_bbent, bn::calc_bn, dbent::calc_dbent, ddom::calc_ddom, dhzp::calc_dhzp,
     dmig::calc_dmig,
 };
+use crate::error::{ContestoErrore, Error};
+use crate::i18n::Messaggio;
 
 // WEIGHTS
 const W_DDOM: f32 = 1.0;
@@ -27,10 +29,27 @@ const STATO_ECOLOGICO_HFBI_SOGLIA_SCARSO: f32 = 0.11;
 pub fn calculate_mmi(
     campionamento: &CampionamentoHFBI,
     anagrafica: &AnagraficaHFBI,
-) -> Result<ValoriIntermediHFBI, String> {
-    let condizioni_riferimento = match CondizioniRiferimentoHFBI::get_cond_riferimento(anagrafica) {
+) -> Result<ValoriIntermediHFBI, Error> {
+    calculate_mmi_with_condizioni(
+        campionamento,
+        anagrafica,
+        TabellaCondizioniRiferimentoHFBI::ufficiale(),
+    )
+}
+
+/// Come calculate_mmi(), con le condizioni di riferimento prese dalla tabella indicata
+pub fn calculate_mmi_with_condizioni(
+    campionamento: &CampionamentoHFBI,
+    anagrafica: &AnagraficaHFBI,
+    condizioni: &TabellaCondizioniRiferimentoHFBI,
+) -> Result<ValoriIntermediHFBI, Error> {
+    let condizioni_riferimento = match condizioni.get_cond_riferimento(anagrafica) {
         Some(cond) => cond,
-        None => return Err(String::from("Errore condizioni di riferimento non trovate")),
+        None => {
+            return Err(Error::Calcolo(
+                ContestoErrore::da_messaggio(Messaggio::CondizioniRiferimentoMancanti).into(),
+            ))
+        }
     };
 
     let bbent: f32 = calc_bbent(campionamento, anagrafica);
@@ -78,8 +97,21 @@ pub fn calculate_mmi(
 pub fn calculate_hfbi(
     campionamento: &CampionamentoHFBI,
     anagrafica: &AnagraficaHFBI,
-) -> Result<(f32, ValoriIntermediHFBI), String> {
-    match calculate_mmi(campionamento, anagrafica) {
+) -> Result<(f32, ValoriIntermediHFBI), Error> {
+    calculate_hfbi_with_condizioni(
+        campionamento,
+        anagrafica,
+        TabellaCondizioniRiferimentoHFBI::ufficiale(),
+    )
+}
+
+/// Come calculate_hfbi(), con le condizioni di riferimento prese dalla tabella indicata
+pub fn calculate_hfbi_with_condizioni(
+    campionamento: &CampionamentoHFBI,
+    anagrafica: &AnagraficaHFBI,
+    condizioni: &TabellaCondizioniRiferimentoHFBI,
+) -> Result<(f32, ValoriIntermediHFBI), Error> {
+    match calculate_mmi_with_condizioni(campionamento, anagrafica, condizioni) {
         Ok(intermediates) => {
             let hfbi = (intermediates.mmi + HFBI_T) / HFBI_S;
             let rounded_hfbi = (1000.0 * hfbi).round() / 1000.0;
@@ -115,8 +147,9 @@ mod full_hfbi_private_tests {
     // We use the structs from the domain, but NOT the functions from the parent module.
     // By defining our own mock functions with the same names, they will be used instead.
     use crate::domain::hfbi::{
-        AnagraficaHFBI, CampionamentoHFBI, GruppoEcoHFBI, GruppoTrofHFBI, HabitatHFBI, RecordHFBI,
-        SpecieHFBI, StagioneHFBI, TipoLagunaCostieraHFBI,
+        AnagraficaHFBI, CampionamentoHFBI, CondizioniRiferimentoHFBI, CondizioniRiferimentoKeyHFBI,
+        GruppoEcoHFBI, GruppoTrofHFBI, HabitatHFBI, RecordHFBI, SpecieHFBI, StagioneHFBI,
+        TabellaCondizioniRiferimentoHFBI, TipoLagunaCostieraHFBI,
     };
 
     const EPSILON: f32 = 1e-6;
@@ -128,8 +161,8 @@ mod full_hfbi_private_tests {
     ) -> RecordHFBI {
         RecordHFBI {
             specie: SpecieHFBI {
-                nome_comune: "Test Specie",
-                codice_specie: codice_specie,
+                nome_comune: "Test Specie".into(),
+                codice_specie: codice_specie.into(),
                 autoctono: true,
                 gruppo_eco,
                 gruppo_trofico: GruppoTrofHFBI {
@@ -148,8 +181,9 @@ mod full_hfbi_private_tests {
     }
 
     // Bring in the functions we actually want to test from the parent module.
-    use super::{calculate_hfbi, calculate_mmi};
-    use crate::domain::location::Location; // Assuming path is correct
+    use super::{calculate_hfbi, calculate_mmi, calculate_mmi_with_condizioni};
+    use crate::domain::location::Location;
+    use crate::error::Error; // Assuming path is correct
 
     // Test helper to create a minimal Anagrafica struct.
     fn create_test_anagrafica(codice_stazione: &str) -> AnagraficaHFBI {
@@ -166,6 +200,7 @@ mod full_hfbi_private_tests {
             habitat_vegetato: HabitatHFBI::NonVegetato,
             lunghezza_media_transetto: 100.0,
             larghezza_media_transetto: 100.0,
+            data: None,
         }
     }
 
@@ -221,4 +256,50 @@ mod full_hfbi_private_tests {
             hfbi
         );
     }
+
+    #[test]
+    fn test_mmi_with_condizioni() {
+        let anagrafica = create_test_anagrafica("OK_STATION_CONDIZIONI");
+        let campione = CampionamentoHFBI {
+            campionamento: vec![
+                create_specie_record("SP1", GruppoEcoHFBI::Diadromi, 500.0),
+                create_specie_record("SP2", GruppoEcoHFBI::MigratoriMarini, 200.0),
+                create_specie_record("SP3", GruppoEcoHFBI::ResidentiDiEstuario, 100.0),
+            ],
+        };
+
+        // Doubling every reference value halves each RQE, and so the MMI
+        let mut condizioni = TabellaCondizioniRiferimentoHFBI::ufficiale().clone();
+        let ufficiali = condizioni
+            .get_cond_riferimento(&anagrafica)
+            .unwrap()
+            .clone();
+        let chiave = CondizioniRiferimentoKeyHFBI {
+            tipo_laguna: anagrafica.tipo_laguna.clone(),
+            stagione: anagrafica.stagione.clone(),
+            habitat_vegetato: anagrafica.habitat_vegetato.clone(),
+        };
+        condizioni.inserisci(
+            chiave,
+            CondizioniRiferimentoHFBI {
+                bn: 2.0 * ufficiali.bn,
+                ddom: 2.0 * ufficiali.ddom,
+                dmig: 2.0 * ufficiali.dmig,
+                bbent: 2.0 * ufficiali.bbent,
+                dbent: 2.0 * ufficiali.dbent,
+                dhzp: 2.0 * ufficiali.dhzp,
+            },
+        );
+        let intermediates = calculate_mmi_with_condizioni(&campione, &anagrafica, &condizioni)
+            .expect("calculate_mmi_with_condizioni should succeed with a complete table");
+        assert!((intermediates.mmi - 0.276).abs() < EPSILON);
+
+        // A table without the station's combination cannot be used
+        let result = calculate_mmi_with_condizioni(
+            &campione,
+            &anagrafica,
+            &TabellaCondizioniRiferimentoHFBI::vuota(),
+        );
+        assert!(matches!(result, Err(Error::Calcolo(_))));
+    }
 }
@@ -0,0 +1,270 @@
+// SPDX-License-Identifier: GPL-3.0-only
+/*
+    Copyright (C) 2024-2026 jgabaut, gioninjo
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU General Public License as published by
+    the Free Software Foundation, version 3 of the License.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU General Public License for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::domain::niseci::{
+    AnagraficaNISECI, CampionamentoNISECI, RecordNISECI, RiferimentoNISECI, StatoEcologicoNISECI,
+};
+use crate::error::{ContestoErrore, Error};
+use crate::i18n::Messaggio;
+
+use super::full::{
+    calculate_niseci_with_stimatore, calculate_rqe_niseci, calculate_stato_ecologico_niseci,
+};
+use super::stimatori::StimatorePopolazione;
+
+const STATI_ECOLOGICI_NISECI: [StatoEcologicoNISECI; 5] = [
+    StatoEcologicoNISECI::Elevato,
+    StatoEcologicoNISECI::Buono,
+    StatoEcologicoNISECI::Moderato,
+    StatoEcologicoNISECI::Scadente,
+    StatoEcologicoNISECI::Cattivo,
+];
+
+/// Parametri del bootstrap di calculate_niseci_bootstrap()
+#[derive(Debug, Clone, PartialEq)]
+pub struct OpzioniBootstrapNISECI {
+    /// Numero di campionamenti ricampionati
+    pub repliche: usize,
+    /// Oltre agli esemplari, ricampiona anche i passaggi
+    pub ricampiona_passaggi: bool,
+    /// Livello degli intervalli percentili, tra 0 e 1
+    pub livello_confidenza: f32,
+    /// Seme del generatore: a parità di seme il risultato è riproducibile
+    pub seme: u64,
+    pub stimatore: StimatorePopolazione,
+}
+
+impl Default for OpzioniBootstrapNISECI {
+    fn default() -> Self {
+        Self {
+            repliche: 1000,
+            ricampiona_passaggi: false,
+            livello_confidenza: 0.95,
+            seme: 2024,
+            stimatore: StimatorePopolazione::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProbabilitaStatoNISECI {
+    pub stato: StatoEcologicoNISECI,
+    pub probabilita: f32,
+}
+
+/// Incertezza del NISECI e dell'RQE stimata con il bootstrap
+#[derive(Debug, Clone, Serialize)]
+pub struct BootstrapNISECI {
+    /// NISECI del campionamento originale
+    pub niseci: Option<f32>,
+    /// RQE del campionamento originale
+    pub rqe: Option<f32>,
+    /// NISECI di ogni replica calcolabile, in ordine crescente
+    pub distribuzione_niseci: Vec<f32>,
+    /// RQE di ogni replica calcolabile, in ordine crescente
+    pub distribuzione_rqe: Vec<f32>,
+    pub intervallo_niseci: Option<(f32, f32)>,
+    pub intervallo_rqe: Option<(f32, f32)>,
+    /// Frazione delle repliche calcolabili assegnata a ciascuno stato, da Elevato a Cattivo
+    pub probabilita_stato: Vec<ProbabilitaStatoNISECI>,
+    /// Repliche senza NISECI (nessuna specie attesa ricampionata) o con errori di calcolo
+    pub repliche_non_calcolabili: usize,
+}
+
+impl BootstrapNISECI {
+    pub fn probabilita(&self, stato: StatoEcologicoNISECI) -> f32 {
+        self.probabilita_stato
+            .iter()
+            .find(|p| p.stato == stato)
+            .map_or(0.0, |p| p.probabilita)
+    }
+
+    /// Stato assegnato dal maggior numero di repliche
+    pub fn stato_piu_probabile(&self) -> Option<StatoEcologicoNISECI> {
+        self.probabilita_stato
+            .iter()
+            .filter(|p| p.probabilita > 0.0)
+            .max_by(|a, b| a.probabilita.total_cmp(&b.probabilita))
+            .map(|p| p.stato)
+    }
+}
+
+/// Calcola il NISECI sul campionamento e su `opzioni.repliche` campionamenti ricampionati.
+/// Ogni replica estrae con reinserimento, per ciascun passaggio, tanti esemplari quanti ne sono
+/// stati catturati nel passaggio. Con `ricampiona_passaggi` vengono prima estratti con reinserimento
+/// i passaggi, che mantengono l'ordine originale.
+pub fn calculate_niseci_bootstrap(
+    campionamento: &CampionamentoNISECI,
+    riferimento: &RiferimentoNISECI,
+    anagrafica: &AnagraficaNISECI,
+    opzioni: &OpzioniBootstrapNISECI,
+) -> Result<BootstrapNISECI, Vec<Error>> {
+    if !(opzioni.livello_confidenza > 0.0 && opzioni.livello_confidenza < 1.0) {
+        return Err(vec![Error::ValoreInvalido(
+            ContestoErrore::da_messaggio(Messaggio::LivelloConfidenzaNonValido(
+                opzioni.livello_confidenza,
+            ))
+            .campo("livello_confidenza")
+            .valore(opzioni.livello_confidenza)
+            .into(),
+        )]);
+    }
+
+    let (niseci, _) =
+        calculate_niseci_with_stimatore(campionamento, riferimento, anagrafica, opzioni.stimatore)?;
+
+    let mut generatore = GeneratoreCasuale::new(opzioni.seme);
+    let mut distribuzione_niseci = Vec::with_capacity(opzioni.repliche);
+    let mut conteggio_stati = [0_usize; STATI_ECOLOGICI_NISECI.len()];
+    let mut repliche_non_calcolabili = 0;
+
+    for _ in 0..opzioni.repliche {
+        let replica = ricampiona(campionamento, opzioni.ricampiona_passaggi, &mut generatore);
+        match calculate_niseci_with_stimatore(&replica, riferimento, anagrafica, opzioni.stimatore)
+        {
+            Ok((Some(valore), _)) => {
+                distribuzione_niseci.push(valore);
+                if let Some(stato) =
+                    calculate_stato_ecologico_niseci(Some(valore), &anagrafica.area)
+                {
+                    let indice = STATI_ECOLOGICI_NISECI
+                        .iter()
+                        .position(|s| *s == stato)
+                        .expect("STATI_ECOLOGICI_NISECI lists every state");
+                    conteggio_stati[indice] += 1;
+                }
+            }
+            _ => repliche_non_calcolabili += 1,
+        }
+    }
+
+    distribuzione_niseci.sort_by(f32::total_cmp);
+    // l'RQE cresce con il NISECI: la distribuzione resta ordinata
+    let distribuzione_rqe: Vec<f32> = distribuzione_niseci
+        .iter()
+        .filter_map(|v| calculate_rqe_niseci(Some(*v)))
+        .collect();
+
+    let calcolabili = distribuzione_niseci.len();
+    let probabilita_stato = STATI_ECOLOGICI_NISECI
+        .iter()
+        .zip(conteggio_stati)
+        .map(|(stato, conteggio)| ProbabilitaStatoNISECI {
+            stato: *stato,
+            probabilita: match calcolabili {
+                0 => 0.0,
+                _ => conteggio as f32 / calcolabili as f32,
+            },
+        })
+        .collect();
+
+    Ok(BootstrapNISECI {
+        niseci,
+        rqe: calculate_rqe_niseci(niseci),
+        intervallo_niseci: intervallo_percentile(&distribuzione_niseci, opzioni.livello_confidenza),
+        intervallo_rqe: intervallo_percentile(&distribuzione_rqe, opzioni.livello_confidenza),
+        distribuzione_niseci,
+        distribuzione_rqe,
+        probabilita_stato,
+        repliche_non_calcolabili,
+    })
+}
+
+fn ricampiona(
+    campionamento: &CampionamentoNISECI,
+    ricampiona_passaggi: bool,
+    generatore: &mut GeneratoreCasuale,
+) -> CampionamentoNISECI {
+    let mut per_passaggio: BTreeMap<u8, Vec<&RecordNISECI>> = BTreeMap::new();
+    for record in &campionamento.campionamento {
+        per_passaggio
+            .entry(record.passaggio_cattura)
+            .or_default()
+            .push(record);
+    }
+    let passaggi: Vec<u8> = per_passaggio.keys().copied().collect();
+
+    let estratti: Vec<u8> = match ricampiona_passaggi {
+        true => {
+            let mut estratti: Vec<u8> = passaggi
+                .iter()
+                .map(|_| passaggi[generatore.indice(passaggi.len())])
+                .collect();
+            estratti.sort();
+            estratti
+        }
+        false => passaggi.clone(),
+    };
+
+    let mut records = Vec::with_capacity(campionamento.campionamento.len());
+    // l'i-esimo passaggio estratto prende il numero dell'i-esimo passaggio originale
+    for (passaggio, estratto) in passaggi.iter().zip(estratti) {
+        let esemplari = &per_passaggio[&estratto];
+        for _ in 0..esemplari.len() {
+            let mut record = esemplari[generatore.indice(esemplari.len())].clone();
+            record.passaggio_cattura = *passaggio;
+            records.push(record);
+        }
+    }
+
+    CampionamentoNISECI {
+        campionamento: records,
+    }
+}
+
+/// Percentili (1 - livello) / 2 e (1 + livello) / 2 di valori ordinati, con il metodo nearest-rank
+fn intervallo_percentile(ordinati: &[f32], livello: f32) -> Option<(f32, f32)> {
+    if ordinati.is_empty() {
+        return None;
+    }
+    let percentile = |q: f32| {
+        let rango = (q * ordinati.len() as f32).ceil() as usize;
+        ordinati[rango.clamp(1, ordinati.len()) - 1]
+    };
+    Some((
+        percentile((1.0 - livello) / 2.0),
+        percentile((1.0 + livello) / 2.0),
+    ))
+}
+
+/// SplitMix64: basta per il ricampionamento e non richiede dipendenze
+struct GeneratoreCasuale {
+    stato: u64,
+}
+
+impl GeneratoreCasuale {
+    fn new(seme: u64) -> Self {
+        Self { stato: seme }
+    }
+
+    fn successivo(&mut self) -> u64 {
+        self.stato = self.stato.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.stato;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Intero uniforme in [0, n)
+    fn indice(&mut self, n: usize) -> usize {
+        ((self.successivo() as u128 * n as u128) >> 64) as usize
+    }
+}
@@ -15,17 +15,23 @@
     along with this program.  If not, see <https://www.gnu.org/licenses/>.
 */
 
+use crate::domain::niseci::traccia::{EventoNISECI, GruppoSpecieNISECI, TracciaNISECI};
 use crate::domain::niseci::{
-    AnagraficaNISECI, AreaNISECI, CampionamentoNISECI, RiferimentoNISECI, StatoEcologicoNISECI,
-    ValoriIntermediNISECI, ValoriIntermediSpecieNISECI,
+    AnagraficaNISECI, AreaNISECI, CampionamentoNISECI, ChiaveCampionamentoNISECI,
+    RiferimentoNISECI, StatoEcologicoNISECI, ValoriIntermediNISECI, ValoriIntermediSpecieNISECI,
 };
+use crate::domain::registro::RegistroStazioni;
 use crate::engines::niseci::x2::MetricheX2;
-use std::collections::{hash_map::Entry, HashMap};
+use crate::error::{ContestoErrore, Error};
+use crate::i18n::Messaggio;
+use std::collections::{hash_map::Entry, BTreeMap, HashMap};
 
-use super::x1::calculate_x1;
-use super::x2::calculate_x2;
-use super::x2::calculate_x2_per_alloctone;
-use super::x3::calculate_x3;
+use super::stimatori::{catture_per_passaggio, StimatorePopolazione};
+use super::x1::calculate_x1_with_traccia;
+use super::x2::calculate_metriche_x2_b_specie;
+use super::x2::calculate_x2_per_alloctone_with_stimatore;
+use super::x2::calculate_x2_with_stimatore;
+use super::x3::calculate_x3_with_traccia;
 
 const RQE_NISECI_MAGIC_ADDEND: f32 = std::f32::consts::FRAC_2_SQRT_PI;
 const RQE_NISECI_MAGIC_QUOTIENT: f32 = 1.0603;
@@ -39,29 +45,106 @@ pub fn calculate_niseci(
     campionamento: &CampionamentoNISECI,
     riferimento: &RiferimentoNISECI,
     anagrafica: &AnagraficaNISECI,
-) -> Result<(Option<f32>, ValoriIntermediNISECI), Vec<String>> {
+) -> Result<(Option<f32>, ValoriIntermediNISECI), Vec<Error>> {
+    calculate_niseci_with_stimatore(
+        campionamento,
+        riferimento,
+        anagrafica,
+        StimatorePopolazione::default(),
+    )
+}
+
+/// Come calculate_niseci(), stimando la popolazione di ogni specie (e quindi densita_stimata)
+/// con lo stimatore indicato. La stima, con la sua incertezza, è riportata nei valori intermedi di ogni specie.
+pub fn calculate_niseci_with_stimatore(
+    campionamento: &CampionamentoNISECI,
+    riferimento: &RiferimentoNISECI,
+    anagrafica: &AnagraficaNISECI,
+    stimatore: StimatorePopolazione,
+) -> Result<(Option<f32>, ValoriIntermediNISECI), Vec<Error>> {
+    calculate_niseci_absolute(campionamento, riferimento, anagrafica, stimatore, None)
+}
+
+/// Risultato di calculate_niseci()
+pub type RisultatoCalcoloNISECI = Result<(Option<f32>, ValoriIntermediNISECI), Vec<Error>>;
+
+/// Come calculate_niseci_with_stimatore(), registrando ogni passo del calcolo.
+/// In caso di errore la traccia riporta i passi eseguiti fino all'errore.
+pub fn calculate_niseci_with_traccia(
+    campionamento: &CampionamentoNISECI,
+    riferimento: &RiferimentoNISECI,
+    anagrafica: &AnagraficaNISECI,
+    stimatore: StimatorePopolazione,
+) -> (RisultatoCalcoloNISECI, TracciaNISECI) {
+    let mut traccia = TracciaNISECI::new();
+    let res = calculate_niseci_absolute(
+        campionamento,
+        riferimento,
+        anagrafica,
+        stimatore,
+        Some(&mut traccia),
+    );
+    (res, traccia)
+}
+
+fn calculate_niseci_absolute(
+    campionamento: &CampionamentoNISECI,
+    riferimento: &RiferimentoNISECI,
+    anagrafica: &AnagraficaNISECI,
+    stimatore: StimatorePopolazione,
+    mut traccia: Option<&mut TracciaNISECI>,
+) -> Result<(Option<f32>, ValoriIntermediNISECI), Vec<Error>> {
     let mut errors = Vec::new();
-    let x1 = calculate_x1(campionamento, riferimento);
+    registra(&mut traccia, || EventoNISECI::Stimatore { stimatore });
+
+    let x1 = calculate_x1_with_traccia(campionamento, riferimento, traccia.as_deref_mut());
 
-    let x2 = calculate_x2(campionamento, anagrafica, true);
+    let x2 = calculate_x2_with_stimatore(campionamento, anagrafica, true, stimatore);
     match x2 {
         Ok(_) => {}
         Err(x2_errors) => {
             for e in x2_errors {
-                errors.push(format!("Errore durante calcolo x2: {}", e));
+                errors.push(Error::Calcolo(
+                    ContestoErrore::da_messaggio(Messaggio::CalcoloMetrica {
+                        metrica: "x2",
+                        dettaglio: e,
+                    })
+                    .campo("x2")
+                    .into(),
+                ));
             }
             return Err(errors);
         }
     }
     let (x2, criteri_x2) = x2.expect("calc_niseci() returned earlier on Err match");
+    registra_specie(
+        &mut traccia,
+        GruppoSpecieNISECI::Attese,
+        &criteri_x2,
+        campionamento,
+        anagrafica,
+    );
+    registra(&mut traccia, || EventoNISECI::MediaX2 {
+        sommatoria_x2_a: criteri_x2.get_criterio_a(),
+        sommatoria_x2_b: criteri_x2.get_criterio_b(),
+        specie: criteri_x2.get_submetriche_map().len(),
+        x2,
+    });
 
     // calculate x2 for specie non attese
-    let x2_non_attese = calculate_x2(campionamento, anagrafica, false);
+    let x2_non_attese = calculate_x2_with_stimatore(campionamento, anagrafica, false, stimatore);
     match x2_non_attese {
         Ok(_) => {}
         Err(x2_non_attese_errors) => {
             for e in x2_non_attese_errors {
-                errors.push(format!("Errore durante calcolo x2_non_attese: {}", e));
+                errors.push(Error::Calcolo(
+                    ContestoErrore::da_messaggio(Messaggio::CalcoloMetrica {
+                        metrica: "x2_non_attese",
+                        dettaglio: e,
+                    })
+                    .campo("x2")
+                    .into(),
+                ));
             }
             return Err(errors);
         }
@@ -69,14 +152,29 @@ pub fn calculate_niseci(
 
     let (_x2_non_attese, criteri_x2_non_attese) =
         x2_non_attese.expect("calc_niseci() returned earlier on Err match");
+    registra_specie(
+        &mut traccia,
+        GruppoSpecieNISECI::NonAttese,
+        &criteri_x2_non_attese,
+        campionamento,
+        anagrafica,
+    );
 
     // calculate x2 for specie alloctone
-    let x2_per_alloctone = calculate_x2_per_alloctone(campionamento, anagrafica);
+    let x2_per_alloctone =
+        calculate_x2_per_alloctone_with_stimatore(campionamento, anagrafica, stimatore);
     match x2_per_alloctone {
         Ok(_) => {}
         Err(x2_per_alloctone_errors) => {
             for e in x2_per_alloctone_errors {
-                errors.push(format!("Errore durante calcolo x2_per_alloctone: {}", e));
+                errors.push(Error::Calcolo(
+                    ContestoErrore::da_messaggio(Messaggio::CalcoloMetrica {
+                        metrica: "x2_per_alloctone",
+                        dettaglio: e,
+                    })
+                    .campo("x2_per_alloctone")
+                    .into(),
+                ));
             }
             return Err(errors);
         }
@@ -84,6 +182,13 @@ pub fn calculate_niseci(
 
     let (_x2_per_alloctone, criteri_x2_per_alloctone) =
         x2_per_alloctone.expect("calc_niseci() returned earlier on Err match");
+    registra_specie(
+        &mut traccia,
+        GruppoSpecieNISECI::Alloctone,
+        &criteri_x2_per_alloctone,
+        campionamento,
+        anagrafica,
+    );
 
     let mut valori_intermedi_specie: HashMap<String, ValoriIntermediSpecieNISECI> = HashMap::new();
 
@@ -95,12 +200,19 @@ pub fn calculate_niseci(
     // add valori intermedi specie alloctone
     valori_intermedi_specie.extend(get_valori_intermedi_specie(&criteri_x2_per_alloctone));
 
-    let x3 = calculate_x3(campionamento);
+    let x3 = calculate_x3_with_traccia(campionamento, traccia.as_deref_mut());
     match x3 {
         Ok(_) => {}
         Err(x3_errors) => {
             for e in x3_errors {
-                errors.push(format!("Errore durante calcolo x3: {}", e));
+                errors.push(Error::Calcolo(
+                    ContestoErrore::da_messaggio(Messaggio::CalcoloMetrica {
+                        metrica: "x3",
+                        dettaglio: e,
+                    })
+                    .campo("x3")
+                    .into(),
+                ));
             }
             return Err(errors);
         }
@@ -110,36 +222,62 @@ pub fn calculate_niseci(
     if let Some(ref crit) = criteri_x3 {
         let submetriche_map_x3 = crit.get_submetriche_map();
         for (key, val) in submetriche_map_x3 {
-            let classi_eta = val.get_classi_eta();
-            let specie = key.clone();
-            let densita_stimata = -1.0; //TODO: check if this is correct
-            let subvalue_a = val.get_criterio_a();
-            let subvalue_b = val.get_criterio_b();
+            let Entry::Vacant(vacant_entry) = valori_intermedi_specie.entry(key.clone()) else {
+                continue;
+            };
+            // specie valutata solo da x3: la densità viene stimata come per x2. Se la stima
+            // non riesce la specie resta con densita_stimata = -1 e il calcolo prosegue
+            let metriche_x2_b =
+                match calculate_metriche_x2_b_specie(campionamento, anagrafica, &key, stimatore) {
+                    Ok(metriche) => metriche,
+                    Err(x3_errors) => {
+                        registra(&mut traccia, || EventoNISECI::DensitaNonStimata {
+                            specie: key.clone(),
+                            motivo: x3_errors.join("; "),
+                        });
+                        None
+                    }
+                };
             let val = ValoriIntermediSpecieNISECI {
-                classi_eta,
-                densita_stimata,
-                quantita_stimata: 0,
-                x2_b: 0.0,
+                classi_eta: val.get_classi_eta(),
+                densita_stimata: metriche_x2_b
+                    .as_ref()
+                    .map_or(-1.0, |m| m.get_densita_stimata()),
+                quantita_stimata: metriche_x2_b
+                    .as_ref()
+                    .map_or(0, |m| m.get_quantita_stimata()),
+                stima_popolazione: metriche_x2_b
+                    .as_ref()
+                    .and_then(|m| m.get_stima_popolazione()),
+                avvisi_rimozione: metriche_x2_b
+                    .as_ref()
+                    .map_or(Vec::new(), |m| m.get_avvisi_rimozione()),
+                x2_b: metriche_x2_b.as_ref().map_or(0.0, |m| m.get_x2_b()),
                 rapporto_ad_juv: val.get_rapporto_ad_juv(),
-                x2_a_a: subvalue_a,
-                x2_a_b: subvalue_b,
+                x2_a_a: val.get_criterio_a(),
+                x2_a_b: val.get_criterio_b(),
             };
-            match valori_intermedi_specie.entry(specie) {
-                Entry::Occupied(_) => {}
-                Entry::Vacant(vacant_entry) => {
-                    vacant_entry.insert(val);
-                }
-            }
+            vacant_entry.insert(val);
         }
     }
 
     let mut x1_x2_errors = Vec::new();
     if x1 < 0.0 {
-        x1_x2_errors.push(format!("Errore risultato x1: valore negativo: {}", x1));
+        x1_x2_errors.push(Error::Calcolo(
+            ContestoErrore::da_messaggio(Messaggio::RisultatoNegativo("x1"))
+                .campo("x1")
+                .valore(x1)
+                .into(),
+        ));
     }
     if let Some(val) = x2 {
         if val < 0.0 {
-            x1_x2_errors.push(format!("Errore risultato x2: valore negativo: {}", val));
+            x1_x2_errors.push(Error::Calcolo(
+                ContestoErrore::da_messaggio(Messaggio::RisultatoNegativo("x2"))
+                    .campo("x2")
+                    .valore(val)
+                    .into(),
+            ));
         }
     }
     if !x1_x2_errors.is_empty() {
@@ -163,15 +301,137 @@ pub fn calculate_niseci(
                 - ((0.1 * (1.0 - x3))
                     * ((0.1 * x1.sqrt()) + (0.1 * x2_val.sqrt()) + (0.8 * (x1 * x2_val))));
             let rounded_niseci = (1000.0 * niseci).round() / 1000.0;
+            registra_risultato(
+                &mut traccia,
+                &intermediates,
+                Some(rounded_niseci),
+                anagrafica,
+            );
             Ok((Some(rounded_niseci), intermediates))
         }
         None => {
             // Nel caso in cui nessuna specie attesa sia presente nel campionamento
+            registra_risultato(&mut traccia, &intermediates, None, anagrafica);
             Ok((None, intermediates))
         }
     }
 }
 
+/// Aggiunge un evento alla traccia, se richiesta
+fn registra(traccia: &mut Option<&mut TracciaNISECI>, evento: impl FnOnce() -> EventoNISECI) {
+    if let Some(traccia) = traccia.as_deref_mut() {
+        traccia.registra(evento());
+    }
+}
+
+fn registra_specie(
+    traccia: &mut Option<&mut TracciaNISECI>,
+    gruppo: GruppoSpecieNISECI,
+    criteri: &MetricheX2,
+    campionamento: &CampionamentoNISECI,
+    anagrafica: &AnagraficaNISECI,
+) {
+    let Some(traccia) = traccia.as_deref_mut() else {
+        return;
+    };
+    let superficie = anagrafica.get_larghezza_media() * anagrafica.get_lunghezza_media();
+    let submetriche_map = criteri.get_submetriche_map();
+    let mut codici: Vec<&String> = submetriche_map.keys().collect();
+    codici.sort();
+
+    for codice in codici {
+        let submetriche = &submetriche_map[codice];
+        let classi_eta = submetriche.get_classi_eta();
+        let metriche_x2_a = submetriche.get_metriche_x2_a();
+        let metriche_x2_b = submetriche.get_metriche_x2_b();
+
+        let mut passaggi: HashMap<u8, u32> = HashMap::new();
+        for record in &campionamento.campionamento {
+            if &record.specie.id == codice {
+                *passaggi.entry(record.passaggio_cattura).or_insert(0) += 1;
+            }
+        }
+
+        traccia.registra(EventoNISECI::StrutturaPopolazione {
+            gruppo,
+            specie: codice.clone(),
+            classi_eta: [
+                classi_eta.cl1,
+                classi_eta.cl2,
+                classi_eta.cl3,
+                classi_eta.cl4,
+                classi_eta.cl5,
+            ],
+            criterio_a: metriche_x2_a.get_criterio_a(),
+            criterio_b: metriche_x2_a.get_criterio_b(),
+            rapporto_ad_juv: metriche_x2_a.get_rapporto_ad_juv(),
+        });
+        traccia.registra(EventoNISECI::Densita {
+            gruppo,
+            specie: codice.clone(),
+            catture_per_passaggio: catture_per_passaggio(
+                &passaggi,
+                campionamento.get_numero_passaggi(),
+            ),
+            stima: metriche_x2_b.get_stima_popolazione(),
+            quantita_stimata: metriche_x2_b.get_quantita_stimata(),
+            superficie,
+            densita_stimata: metriche_x2_b.get_densita_stimata(),
+            soglie: [
+                classi_eta.specie.dens_soglia1,
+                classi_eta.specie.dens_soglia2,
+            ],
+            x2_b: metriche_x2_b.get_x2_b(),
+            avvisi: metriche_x2_b.get_avvisi_rimozione(),
+        });
+    }
+}
+
+fn registra_risultato(
+    traccia: &mut Option<&mut TracciaNISECI>,
+    intermediates: &ValoriIntermediNISECI,
+    niseci: Option<f32>,
+    anagrafica: &AnagraficaNISECI,
+) {
+    registra(traccia, || EventoNISECI::Formula {
+        x1: intermediates.x1,
+        x2: intermediates.x2,
+        x3: intermediates.x3,
+        niseci,
+    });
+    registra(traccia, || EventoNISECI::Classificazione {
+        area: anagrafica.area,
+        rqe: calculate_rqe_niseci(niseci),
+        stato: calculate_stato_ecologico_niseci(niseci, &anagrafica.area),
+    });
+}
+
+/// Risultato di calculate_niseci() per ogni campionamento di un file con più stazioni/date
+pub type RisultatiNISECIPerCampionamento =
+    BTreeMap<ChiaveCampionamentoNISECI, Result<(Option<f32>, ValoriIntermediNISECI), Vec<Error>>>;
+
+/// Calcola il NISECI per ogni campionamento, abbinando a ciascuno l'anagrafica della sua stazione
+pub fn calculate_niseci_per_campionamento(
+    campionamenti: &BTreeMap<ChiaveCampionamentoNISECI, CampionamentoNISECI>,
+    riferimento: &RiferimentoNISECI,
+    anagrafiche: &RegistroStazioni<AnagraficaNISECI>,
+) -> RisultatiNISECIPerCampionamento {
+    let mut res = BTreeMap::new();
+    for (chiave, campionamento, opt_anagrafica) in anagrafiche.abbina(campionamenti) {
+        let risultato = match opt_anagrafica {
+            Some(anagrafica) => calculate_niseci(campionamento, riferimento, anagrafica),
+            None => Err(vec![Error::Calcolo(
+                ContestoErrore::da_messaggio(Messaggio::AnagraficaMancante(chiave.to_string()))
+                    .campo("codiceStazione")
+                    .valore(&chiave.stazione)
+                    .into(),
+            )]),
+        };
+        res.insert(chiave.clone(), risultato);
+    }
+    res
+}
+
 pub fn calculate_rqe_niseci(niseci: Option<f32>) -> Option<f32> {
     let rqe =
         niseci.map(|val| (val.log(10.0) + RQE_NISECI_MAGIC_ADDEND) / RQE_NISECI_MAGIC_QUOTIENT);
@@ -234,6 +494,8 @@ fn get_valori_intermedi_specie(
             classi_eta,
             densita_stimata,
             quantita_stimata,
+            stima_popolazione: val.get_metriche_x2_b().get_stima_popolazione(),
+            avvisi_rimozione: val.get_metriche_x2_b().get_avvisi_rimozione(),
             x2_b,
             x2_a_a: criteri_x2_a.get_criterio_a(),
             x2_a_b: criteri_x2_a.get_criterio_b(),
@@ -16,6 +16,11 @@
 */
 
 use core::f32;
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::i18n::Lingua;
 
 #[derive(Debug, PartialEq)]
 pub struct Point<T> {
@@ -29,9 +34,95 @@ impl<T> Point<T> {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum LinearRegressionError {
     SameValues,
+    /// Tutti i punti hanno la stessa x: la retta sarebbe verticale
+    SameXValues,
+    /// Nessun punto da interpolare
+    NoPoints,
+    /// La retta interseca l'asse x prima dell'origine
+    NegativeQuantity(i32),
+}
+
+impl fmt::Display for LinearRegressionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let l = Lingua::default();
+        let string_representation = match self {
+            LinearRegressionError::SameValues => l
+                .scegli(
+                    "catture uguali in ogni passaggio",
+                    "equal catches in every pass",
+                )
+                .to_string(),
+            LinearRegressionError::SameXValues => l
+                .scegli(
+                    "catture cumulate uguali in ogni passaggio",
+                    "equal cumulative catches in every pass",
+                )
+                .to_string(),
+            LinearRegressionError::NoPoints => l
+                .scegli("nessun passaggio da interpolare", "no passes to fit")
+                .to_string(),
+            LinearRegressionError::NegativeQuantity(quantita) => format!(
+                "{} {}",
+                l.scegli("quantita stimata negativa", "negative estimated quantity"),
+                quantita
+            ),
+        };
+        write!(f, "{}", string_representation)
+    }
+}
+
+/// Metodo usato per stimare la retta di rimozione
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default, Serialize)]
+pub enum MetodoRegressione {
+    /// Minimi quadrati, in forma chiusa
+    #[default]
+    MinimiQuadrati,
+    /// Discesa del gradiente sui punti normalizzati, usata fino alla versione 0.1.2.
+    /// Resta disponibile per riprodurre i risultati già calcolati.
+    DiscesaGradiente,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize)]
+pub enum SegnoPendenza {
+    Negativa,
+    Nulla,
+    Positiva,
+}
+
+/// Retta stimata, con gli indici di bontà dell'adattamento
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AdattamentoRetta {
+    pub metodo: MetodoRegressione,
+    pub m: f32,
+    pub b: f32,
+    /// Coefficiente di determinazione
+    pub r_quadro: f32,
+    /// y osservata meno y stimata, nell'ordine dei punti
+    pub residui: Vec<f32>,
+}
+
+impl AdattamentoRetta {
+    pub fn segno_pendenza(&self) -> SegnoPendenza {
+        let epsilon: f32 = 1e-6;
+        if self.m.abs() < epsilon {
+            SegnoPendenza::Nulla
+        } else if self.m > 0.0 {
+            SegnoPendenza::Positiva
+        } else {
+            SegnoPendenza::Negativa
+        }
+    }
+
+    /// Intersezione della retta con l'asse x, se la pendenza non è nulla
+    pub fn intersezione_asse_x(&self) -> Option<f32> {
+        match self.segno_pendenza() {
+            SegnoPendenza::Nulla => None,
+            _ => Some(-(self.b / self.m)),
+        }
+    }
 }
 
 fn gradient_descent(m_now: f32, b_now: f32, points: &[Point<f32>], step: f32) -> (f32, f32) {
@@ -79,33 +170,107 @@ pub fn gradient_descent_iterate(
     Ok((m_final, b_final))
 }
 
+/// Retta dei minimi quadrati, calcolata in forma chiusa
+pub fn minimi_quadrati(points: &[Point<i32>]) -> Result<(f32, f32), LinearRegressionError> {
+    if points.is_empty() {
+        return Err(LinearRegressionError::NoPoints);
+    }
+    let n = points.len() as f64;
+    let media_x = points.iter().map(|p| p.x as f64).sum::<f64>() / n;
+    let media_y = points.iter().map(|p| p.y as f64).sum::<f64>() / n;
+
+    let mut sxx: f64 = 0.0;
+    let mut sxy: f64 = 0.0;
+    let mut syy: f64 = 0.0;
+    for p in points {
+        let dx = p.x as f64 - media_x;
+        let dy = p.y as f64 - media_y;
+        sxx += dx * dx;
+        sxy += dx * dy;
+        syy += dy * dy;
+    }
+
+    // Stesse condizioni di normalize_points(), più il caso delle x tutte uguali
+    let epsilon: f64 = 1e-6;
+    if syy < epsilon {
+        return Err(LinearRegressionError::SameValues);
+    }
+    if sxx < epsilon {
+        return Err(LinearRegressionError::SameXValues);
+    }
+
+    let m = sxy / sxx;
+    let b = media_y - m * media_x;
+    Ok((m as f32, b as f32))
+}
+
+/// Stima la retta con il metodo indicato e ne calcola R² e residui
+pub fn adatta_retta(
+    points: &[Point<i32>],
+    metodo: MetodoRegressione,
+) -> Result<AdattamentoRetta, LinearRegressionError> {
+    let (m, b) = match metodo {
+        MetodoRegressione::MinimiQuadrati => minimi_quadrati(points)?,
+        MetodoRegressione::DiscesaGradiente => gradient_descent_iterate(points)?,
+    };
+
+    let residui: Vec<f32> = points
+        .iter()
+        .map(|p| p.y as f32 - (m * p.x as f32 + b))
+        .collect();
+    let media_y = points.iter().map(|p| p.y as f32).sum::<f32>() / points.len() as f32;
+    let ss_tot: f32 = points.iter().map(|p| (p.y as f32 - media_y).powi(2)).sum();
+    let ss_res: f32 = residui.iter().map(|r| r.powi(2)).sum();
+
+    Ok(AdattamentoRetta {
+        metodo,
+        m,
+        b,
+        r_quadro: 1.0 - ss_res / ss_tot,
+        residui,
+    })
+}
+
 pub fn calculate_quantita_with_regression(campionamenti: &[Point<i32>]) -> Result<u32, String> {
+    calculate_quantita_with_metodo(campionamenti, MetodoRegressione::default())
+        .map_err(|error| error.to_string())
+}
+
+/// Come calculate_quantita_with_regression(), con il metodo di stima della retta indicato
+pub fn calculate_quantita_with_metodo(
+    campionamenti: &[Point<i32>],
+    metodo: MetodoRegressione,
+) -> Result<u32, LinearRegressionError> {
+    stima_quantita_con_retta(campionamenti, metodo).map(|(quantita, _)| quantita)
+}
+
+/// Come calculate_quantita_with_metodo(), restituendo anche la retta stimata con R² e residui.
+/// La retta manca quando non si può stimare e viene usata la somma delle catture.
+pub fn stima_quantita_con_retta(
+    campionamenti: &[Point<i32>],
+    metodo: MetodoRegressione,
+) -> Result<(u32, Option<AdattamentoRetta>), LinearRegressionError> {
     // trova m e b della retta
-    let (m, b) = match gradient_descent_iterate(campionamenti) {
-        Ok((m, b)) => (m, b),
-        Err(error) => {
-            match error {
-                LinearRegressionError::SameValues => return Ok(get_sum(campionamenti)), // come da accordi ritorno la somma
-            }
+    let adattamento = match adatta_retta(campionamenti, metodo) {
+        Ok(adattamento) => adattamento,
+        Err(LinearRegressionError::SameValues | LinearRegressionError::SameXValues) => {
+            return Ok((get_sum(campionamenti), None)); // come da accordi ritorno la somma
         }
+        Err(error) => return Err(error),
     };
 
-    let epsilon: f32 = 1e-6;
-    if m.abs() < epsilon {
-        // In questo caso, come da accordi, ritorniamo la somma dei campionamenti
-        return Ok(get_sum(campionamenti));
-    }
-
-    if m > 0.0 {
-        return Ok(get_sum(campionamenti));
-    }
-
     // l'incorcio della retta con l'asse x ci da la quantita stimata
-    let quantita_stimata = (-(b / m)) as i32;
+    let quantita_stimata = match adattamento.segno_pendenza() {
+        // In questi casi, come da accordi, ritorniamo la somma dei campionamenti
+        SegnoPendenza::Nulla | SegnoPendenza::Positiva => {
+            return Ok((get_sum(campionamenti), Some(adattamento)));
+        }
+        SegnoPendenza::Negativa => (-(adattamento.b / adattamento.m)) as i32,
+    };
     if quantita_stimata < 0 {
-        return Err(format!("quantita stimata negativa {}", quantita_stimata));
+        return Err(LinearRegressionError::NegativeQuantity(quantita_stimata));
     }
-    Ok(quantita_stimata as u32)
+    Ok((quantita_stimata as u32, Some(adattamento)))
 }
 
 /// La denormalizzazione riporta la retta normalizzata (rappresentata da m_norm e b_norm)
@@ -124,6 +289,10 @@ fn denormalize_retta(m_norm: f32, b_norm: f32, points: &[Point<i32>]) -> (f32, f
 }
 
 fn normalize_points(points: &[Point<i32>]) -> Result<Vec<Point<f32>>, LinearRegressionError> {
+    if points.is_empty() {
+        return Err(LinearRegressionError::NoPoints);
+    }
+
     let max_x = points.iter().map(|p| p.x).max().unwrap() as f32;
     let min_x = points.iter().map(|p| p.x).min().unwrap() as f32;
 
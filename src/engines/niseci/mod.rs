@@ -15,8 +15,10 @@
     along with this program.  If not, see <https://www.gnu.org/licenses/>.
 */
 
+pub mod bootstrap;
 pub mod full;
 pub mod linear_regression;
+pub mod stimatori;
 pub mod x1;
 pub mod x2;
 pub mod x3;
@@ -0,0 +1,358 @@
+// SPDX-License-Identifier: GPL-3.0-only
+/*
+    Copyright (C) 2024-2026 jgabaut, gioninjo
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU General Public License as published by
+    the Free Software Foundation, version 3 of the License.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU General Public License for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::Serialize;
+
+use super::linear_regression::{AdattamentoRetta, MetodoRegressione};
+use crate::i18n::{Lingua, Messaggio};
+
+/// Quantile della normale standard per l'intervallo di confidenza al 95%
+const QUANTILE_NORMALE_95: f64 = 1.959964;
+/// Oltre questa popolazione la ricerca di Carle–Strub viene interrotta
+const LIMITE_POPOLAZIONE_CARLE_STRUB: u64 = 10_000_000;
+
+/// Sotto questo numero di esemplari catturati la stima della popolazione non è affidabile
+pub const ESEMPLARI_MINIMI_STIMA: u32 = 10;
+
+/// Stimatore della popolazione di una specie a partire dalle catture di ogni passaggio
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize)]
+pub enum StimatorePopolazione {
+    /// Somma delle catture per un passaggio, Seber–LeCren per due passaggi,
+    /// retta di rimozione per tre o più. Non fornisce l'incertezza della stima.
+    Classico(MetodoRegressione),
+    /// Massima verosimiglianza (Zippin, 1956)
+    Zippin,
+    /// Massima verosimiglianza pesata con prior Beta(1, 1) sulla catturabilità (Carle e Strub, 1978)
+    CarleStrub,
+}
+
+impl Default for StimatorePopolazione {
+    fn default() -> Self {
+        StimatorePopolazione::Classico(MetodoRegressione::default())
+    }
+}
+
+/// Popolazione stimata con la sua incertezza
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StimaPopolazione {
+    pub stimatore: StimatorePopolazione,
+    /// N̂
+    pub popolazione: f32,
+    pub errore_standard: Option<f32>,
+    /// Intervallo di confidenza al 95%, con estremo inferiore non minore delle catture totali
+    pub intervallo_confidenza: Option<(f32, f32)>,
+    /// p̂, probabilità di cattura di un esemplare in un passaggio
+    pub catturabilita: Option<f32>,
+    /// La rimozione non ha permesso una stima e N̂ è la somma delle catture
+    pub somma_catture: bool,
+    /// Retta di rimozione dello stimatore classico, con R² e residui
+    pub retta: Option<AdattamentoRetta>,
+}
+
+impl StimaPopolazione {
+    /// Stima senza incertezza, come quella dello stimatore classico
+    pub fn puntuale(stimatore: StimatorePopolazione, quantita: u32) -> Self {
+        Self {
+            stimatore,
+            popolazione: quantita as f32,
+            errore_standard: None,
+            intervallo_confidenza: None,
+            catturabilita: None,
+            somma_catture: false,
+            retta: None,
+        }
+    }
+
+    /// Somma delle catture adottata al posto di una stima non calcolabile
+    pub fn somma(stimatore: StimatorePopolazione, quantita: u32) -> Self {
+        Self {
+            somma_catture: true,
+            ..Self::puntuale(stimatore, quantita)
+        }
+    }
+
+    /// N̂ arrotondata all'intero più vicino
+    pub fn get_quantita(&self) -> u32 {
+        self.popolazione.round() as u32
+    }
+}
+
+/// Catture in ordine di passaggio per `numero_passaggi` passaggi (vedi
+/// CampionamentoNISECI::get_numero_passaggi()), con 0 per i passaggi senza catture
+pub fn catture_per_passaggio(passaggi: &HashMap<u8, u32>, numero_passaggi: u8) -> Vec<u32> {
+    let ultimo_passaggio = passaggi
+        .keys()
+        .max()
+        .copied()
+        .unwrap_or(0)
+        .max(numero_passaggi);
+    let mut catture = vec![0_u32; ultimo_passaggio as usize];
+    for (passaggio, esemplari) in passaggi {
+        if *passaggio > 0 {
+            catture[(*passaggio - 1) as usize] = *esemplari;
+        }
+    }
+    catture
+}
+
+/// Stima di Zippin: p̂ risolve Σ(i-1)cᵢ / T = q/p - k·qᵏ/(1-qᵏ), con q = 1 - p, e N̂ = T / (1-qᵏ).
+/// Se le catture non diminuiscono N̂ è la somma delle catture, senza incertezza.
+pub fn stima_zippin(catture: &[u32]) -> Result<StimaPopolazione, String> {
+    let k = catture.len() as i32;
+    let totale: f64 = catture.iter().map(|c| *c as f64).sum();
+    if let Some(stima) = stima_banale(StimatorePopolazione::Zippin, catture, totale) {
+        return Ok(stima);
+    }
+
+    let ritardo: f64 = catture
+        .iter()
+        .enumerate()
+        .map(|(i, c)| i as f64 * *c as f64)
+        .sum();
+    let rapporto = ritardo / totale;
+    if rapporto >= (k - 1) as f64 / 2.0 {
+        // le catture non diminuiscono e la verosimiglianza non ha massimo: come lo
+        // stimatore classico, ripiego sulla somma delle catture
+        return Ok(StimaPopolazione::somma(
+            StimatorePopolazione::Zippin,
+            totale as u32,
+        ));
+    }
+
+    // la funzione cresce con q: bisezione su (0, 1)
+    let funzione = |q: f64| q / (1.0 - q) - k as f64 * q.powi(k) / (1.0 - q.powi(k)) - rapporto;
+    let mut q_min = 1e-12;
+    let mut q_max = 1.0 - 1e-12;
+    for _ in 0..200 {
+        let q = (q_min + q_max) / 2.0;
+        if funzione(q) < 0.0 {
+            q_min = q;
+        } else {
+            q_max = q;
+        }
+    }
+    let q = (q_min + q_max) / 2.0;
+    let popolazione = totale / (1.0 - q.powi(k));
+
+    Ok(stima_con_varianza(
+        StimatorePopolazione::Zippin,
+        popolazione,
+        1.0 - q,
+        k,
+        totale,
+    ))
+}
+
+/// Stima di Carle–Strub: N̂ è il più piccolo N ≥ T per cui
+/// (N+1)/(N-T+1) · Π (kN-X-T+β+k-i)/(kN-X+α+β+k-i) ≤ 1, con X = Σ(k-i)cᵢ e α = β = 1.
+/// L'errore standard usa la varianza di Zippin con p̂ = T / (kN̂ - X).
+pub fn stima_carle_strub(catture: &[u32]) -> Result<StimaPopolazione, String> {
+    let alfa = 1.0;
+    let beta = 1.0;
+    let k = catture.len() as i32;
+    let totale: f64 = catture.iter().map(|c| *c as f64).sum();
+    if let Some(stima) = stima_banale(StimatorePopolazione::CarleStrub, catture, totale) {
+        return Ok(stima);
+    }
+
+    let x: f64 = catture
+        .iter()
+        .enumerate()
+        .map(|(i, c)| (k - 1 - i as i32) as f64 * *c as f64)
+        .sum();
+    let kf = k as f64;
+
+    let rapporto = |n: f64| {
+        let mut rapporto = (n + 1.0) / (n - totale + 1.0);
+        for i in 1..=k {
+            let resto = (k - i) as f64;
+            rapporto *= (kf * n - x - totale + beta + resto) / (kf * n - x + alfa + beta + resto);
+        }
+        rapporto
+    };
+
+    // il rapporto decresce con N: raddoppio N finché il rapporto non scende sotto 1,
+    // poi bisezione tra l'ultimo N sopra 1 e il primo sotto
+    let mut sotto = totale;
+    let mut sopra = totale;
+    let limite = LIMITE_POPOLAZIONE_CARLE_STRUB as f64;
+    while rapporto(sopra) > 1.0 {
+        if sopra >= limite {
+            return Err(format!(
+                "stima di Carle–Strub non calcolabile: le catture per passaggio {:?} non convergono",
+                catture
+            ));
+        }
+        sotto = sopra;
+        sopra = (sopra * 2.0).max(sopra + 1.0).min(limite);
+    }
+    while sopra - sotto > 1.0 {
+        let medio = ((sotto + sopra) / 2.0).floor();
+        if rapporto(medio) > 1.0 {
+            sotto = medio;
+        } else {
+            sopra = medio;
+        }
+    }
+    let n = sopra;
+
+    let p = totale / (kf * n - x);
+    Ok(stima_con_varianza(
+        StimatorePopolazione::CarleStrub,
+        n,
+        p,
+        k,
+        totale,
+    ))
+}
+
+/// Con un solo passaggio la catturabilità non è stimabile, e senza catture la popolazione è nulla
+fn stima_banale(
+    stimatore: StimatorePopolazione,
+    catture: &[u32],
+    totale: f64,
+) -> Option<StimaPopolazione> {
+    if catture.len() < 2 || totale == 0.0 {
+        return Some(StimaPopolazione::puntuale(stimatore, totale as u32));
+    }
+    None
+}
+
+/// Var(N̂) = N̂·qᵏ·(1-qᵏ) / ((1-qᵏ)² - (kp)²·qᵏ⁻¹)
+fn stima_con_varianza(
+    stimatore: StimatorePopolazione,
+    popolazione: f64,
+    p: f64,
+    k: i32,
+    totale: f64,
+) -> StimaPopolazione {
+    let q = 1.0 - p;
+    let denominatore = (1.0 - q.powi(k)).powi(2) - (k as f64 * p).powi(2) * q.powi(k - 1);
+    let varianza = popolazione * q.powi(k) * (1.0 - q.powi(k)) / denominatore;
+
+    let (errore_standard, intervallo_confidenza) = if denominatore > 0.0 && varianza >= 0.0 {
+        let errore_standard = varianza.sqrt();
+        let margine = QUANTILE_NORMALE_95 * errore_standard;
+        (
+            Some(errore_standard as f32),
+            Some((
+                (popolazione - margine).max(totale) as f32,
+                (popolazione + margine) as f32,
+            )),
+        )
+    } else {
+        (None, None)
+    };
+
+    StimaPopolazione {
+        stimatore,
+        popolazione: popolazione as f32,
+        errore_standard,
+        intervallo_confidenza,
+        catturabilita: Some(p as f32),
+        somma_catture: false,
+        retta: None,
+    }
+}
+
+/// Ipotesi del campionamento per rimozione non rispettata da una specie.
+/// La densità che ne deriva è poco affidabile, o è calcolata dalla sola somma delle catture.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub enum AvvisoRimozione {
+    PrimoPassaggioSenzaCatture,
+    /// Il passaggio indicato non ha catturato meno esemplari del precedente
+    CattureNonDecrescenti {
+        passaggio: u8,
+        precedenti: u32,
+        catture: u32,
+    },
+    EsemplariInsufficienti {
+        catture: u32,
+        minimo: u32,
+    },
+    /// La stima non è calcolabile e al suo posto è usata la somma delle catture
+    SommaCatture,
+}
+
+impl AvvisoRimozione {
+    pub fn messaggio(&self) -> Messaggio {
+        match self {
+            AvvisoRimozione::PrimoPassaggioSenzaCatture => Messaggio::PrimoPassaggioSenzaCatture,
+            AvvisoRimozione::CattureNonDecrescenti {
+                passaggio,
+                precedenti,
+                catture,
+            } => Messaggio::CattureNonDecrescenti {
+                passaggio: *passaggio,
+                precedenti: *precedenti,
+                catture: *catture,
+            },
+            AvvisoRimozione::EsemplariInsufficienti { catture, minimo } => {
+                Messaggio::EsemplariInsufficienti {
+                    catture: *catture,
+                    minimo: *minimo,
+                }
+            }
+            AvvisoRimozione::SommaCatture => Messaggio::StimaSommaCatture,
+        }
+    }
+
+    pub fn descrizione(&self, lingua: Lingua) -> String {
+        self.messaggio().testo(lingua)
+    }
+}
+
+impl fmt::Display for AvvisoRimozione {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let string_representation = self.descrizione(Lingua::default());
+        write!(f, "{}", string_representation)
+    }
+}
+
+/// Controlla che le catture di una specie, in ordine di passaggio, diminuiscano come atteso
+/// dal campionamento per rimozione. Non dipende dalla stima: la sostituzione con la somma
+/// delle catture si ricava da [`StimaPopolazione::somma_catture`].
+pub fn verifica_rimozione(catture: &[u32]) -> Vec<AvvisoRimozione> {
+    let mut avvisi = Vec::new();
+
+    if catture.len() > 1 && catture[0] == 0 {
+        avvisi.push(AvvisoRimozione::PrimoPassaggioSenzaCatture);
+    }
+    for (i, coppia) in catture.windows(2).enumerate() {
+        // un primo passaggio vuoto è già segnalato, e dopo l'ultimo esemplare i passaggi
+        // restano vuoti come atteso
+        if coppia[1] >= coppia[0] && coppia[1] > 0 && !(i == 0 && coppia[0] == 0) {
+            avvisi.push(AvvisoRimozione::CattureNonDecrescenti {
+                passaggio: (i + 2) as u8,
+                precedenti: coppia[0],
+                catture: coppia[1],
+            });
+        }
+    }
+    let totale: u32 = catture.iter().sum();
+    if totale < ESEMPLARI_MINIMI_STIMA {
+        avvisi.push(AvvisoRimozione::EsemplariInsufficienti {
+            catture: totale,
+            minimo: ESEMPLARI_MINIMI_STIMA,
+        });
+    }
+
+    avvisi
+}
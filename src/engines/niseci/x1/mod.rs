@@ -17,9 +17,22 @@
 
 use std::collections::HashMap;
 
+use crate::domain::niseci::traccia::{EventoNISECI, TracciaNISECI};
 use crate::domain::niseci::{CampionamentoNISECI, RecordNISECI, RiferimentoNISECI, SpecieNISECI};
 
 pub fn calculate_x1(campionamento: &CampionamentoNISECI, riferimento: &RiferimentoNISECI) -> f32 {
+    calculate_x1_with_traccia(campionamento, riferimento, None)
+}
+
+/// Come calculate_x1(), registrando nella traccia, se presente, le specie contate in n_i, n_a,
+/// m_i e m_a
+pub fn calculate_x1_with_traccia(
+    campionamento: &CampionamentoNISECI,
+    riferimento: &RiferimentoNISECI,
+    traccia: Option<&mut TracciaNISECI>,
+) -> f32 {
+    // Gli elenchi delle specie servono solo alla traccia
+    let con_traccia = traccia.is_some();
     // n_i è il numero di specie autoctone di maggiore importanza ecologico-funzionale campionate
     // n_a è il numero di altre specie autoctone campionate
     // m_i è il numero di specie autoctone di maggiore importanza ecologico-funzionale attese
@@ -37,12 +50,20 @@ pub fn calculate_x1(campionamento: &CampionamentoNISECI, riferimento: &Riferimen
     let set_specie_campionate: Vec<&RecordNISECI> = specie_campionate_map.into_values().collect();
     let mut n_i: f32 = 0.0;
     let mut n_a: f32 = 0.0;
+    let mut campionate_importanti = Vec::new();
+    let mut campionate_altre = Vec::new();
     for spec in set_specie_campionate {
         if spec.specie.tipo_autoctono == 1 {
             // tipo_autoctono == 1 allora specie importante
             n_i += 1.0;
+            if con_traccia {
+                campionate_importanti.push(spec.specie.id.clone());
+            }
         } else if spec.specie.tipo_autoctono == 2 {
             n_a += 1.0;
+            if con_traccia {
+                campionate_altre.push(spec.specie.id.clone());
+            }
         }
     }
 
@@ -58,17 +79,44 @@ pub fn calculate_x1(campionamento: &CampionamentoNISECI, riferimento: &Riferimen
     let set_specie_attese: Vec<&SpecieNISECI> = specie_attese_map.into_values().collect();
     let mut m_i: f32 = 0.0;
     let mut m_a: f32 = 0.0;
+    let mut attese_importanti = Vec::new();
+    let mut attese_altre = Vec::new();
     for spec in set_specie_attese {
         if spec.tipo_autoctono == 1 {
             // tipo_autoctono == 1 allora specie importante
             m_i += 1.0;
+            if con_traccia {
+                attese_importanti.push(spec.id.clone());
+            }
         } else if spec.tipo_autoctono == 2 {
             m_a += 1.0;
+            if con_traccia {
+                attese_altre.push(spec.id.clone());
+            }
         }
     }
 
     // this is the formula use in the NISECI docs
     let x1 = (1.2 * n_i + 0.8 * n_a) / (1.2 * m_i + 0.8 * m_a);
 
-    (1000.0 * x1).round() / 1000.0
+    let x1 = (1000.0 * x1).round() / 1000.0;
+
+    if let Some(traccia) = traccia {
+        for specie in [
+            &mut campionate_importanti,
+            &mut campionate_altre,
+            &mut attese_importanti,
+            &mut attese_altre,
+        ] {
+            specie.sort();
+        }
+        traccia.registra(EventoNISECI::X1 {
+            campionate_importanti,
+            campionate_altre,
+            attese_importanti,
+            attese_altre,
+            x1,
+        });
+    }
+    x1
 }
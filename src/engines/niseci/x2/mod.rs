@@ -22,7 +22,13 @@ use crate::domain::niseci::{
     MetricheX2aB, RecordNISECI,
 };
 
-use super::linear_regression::{calculate_quantita_with_regression, Point};
+use super::linear_regression::{
+    stima_quantita_con_retta, AdattamentoRetta, MetodoRegressione, Point, SegnoPendenza,
+};
+use super::stimatori::{
+    catture_per_passaggio, stima_carle_strub, stima_zippin, verifica_rimozione, AvvisoRimozione,
+    StimaPopolazione, StimatorePopolazione,
+};
 
 #[derive(Clone)]
 pub struct SubmetricheX2 {
@@ -89,6 +95,8 @@ pub struct MetricheX2B {
     densita_stimata: f32,
     quantita_stimata: u32,
     x2_b: f32,
+    stima_popolazione: Option<StimaPopolazione>,
+    avvisi_rimozione: Vec<AvvisoRimozione>,
 }
 
 impl MetricheX2B {
@@ -98,8 +106,18 @@ impl MetricheX2B {
             densita_stimata,
             quantita_stimata,
             x2_b,
+            stima_popolazione: None,
+            avvisi_rimozione: Vec::new(),
         }
     }
+    pub fn stima_popolazione(mut self, stima: StimaPopolazione) -> Self {
+        self.stima_popolazione = Some(stima);
+        self
+    }
+    pub fn avvisi_rimozione(mut self, avvisi: Vec<AvvisoRimozione>) -> Self {
+        self.avvisi_rimozione = avvisi;
+        self
+    }
     pub fn get_id(&self) -> String {
         self.id_specie.clone()
     }
@@ -112,16 +130,37 @@ impl MetricheX2B {
     pub fn get_quantita_stimata(&self) -> u32 {
         self.quantita_stimata
     }
+    pub fn get_stima_popolazione(&self) -> Option<StimaPopolazione> {
+        self.stima_popolazione.clone()
+    }
+    pub fn get_avvisi_rimozione(&self) -> Vec<AvvisoRimozione> {
+        self.avvisi_rimozione.clone()
+    }
 }
 
 pub fn calculate_x2(
     campionamento: &CampionamentoNISECI,
     anagrafica: &AnagraficaNISECI,
     require_specie_attesa: bool,
+) -> Result<(Option<f32>, MetricheX2), Vec<String>> {
+    calculate_x2_with_stimatore(
+        campionamento,
+        anagrafica,
+        require_specie_attesa,
+        StimatorePopolazione::default(),
+    )
+}
+
+/// Come calculate_x2(), stimando la popolazione di ogni specie con lo stimatore indicato
+pub fn calculate_x2_with_stimatore(
+    campionamento: &CampionamentoNISECI,
+    anagrafica: &AnagraficaNISECI,
+    require_specie_attesa: bool,
+    stimatore: StimatorePopolazione,
 ) -> Result<(Option<f32>, MetricheX2), Vec<String>> {
     let (x2_a, criteri_vec) = calculate_sommatoria_x2_a(campionamento, require_specie_attesa)?;
     let (x2_b, densita_vec) =
-        calculate_sommatoria_x2_b(campionamento, anagrafica, require_specie_attesa)?;
+        calculate_sommatoria_x2_b(campionamento, anagrafica, require_specie_attesa, stimatore)?;
 
     let mut submetriche = HashMap::<String, SubmetricheX2>::new();
 
@@ -172,9 +211,23 @@ pub fn calculate_x2(
 pub fn calculate_x2_per_alloctone(
     campionamento: &CampionamentoNISECI,
     anagrafica: &AnagraficaNISECI,
+) -> Result<(Option<f32>, MetricheX2), Vec<String>> {
+    calculate_x2_per_alloctone_with_stimatore(
+        campionamento,
+        anagrafica,
+        StimatorePopolazione::default(),
+    )
+}
+
+/// Come calculate_x2_per_alloctone(), stimando la popolazione di ogni specie con lo stimatore indicato
+pub fn calculate_x2_per_alloctone_with_stimatore(
+    campionamento: &CampionamentoNISECI,
+    anagrafica: &AnagraficaNISECI,
+    stimatore: StimatorePopolazione,
 ) -> Result<(Option<f32>, MetricheX2), Vec<String>> {
     let (x2_a, criteri_vec) = calculate_sommatoria_x2_a_per_alloctone(campionamento)?;
-    let (x2_b, densita_vec) = calculate_sommatoria_x2_b_per_alloctone(campionamento, anagrafica)?;
+    let (x2_b, densita_vec) =
+        calculate_sommatoria_x2_b_per_alloctone(campionamento, anagrafica, stimatore)?;
 
     let mut submetriche = HashMap::<String, SubmetricheX2>::new();
 
@@ -220,6 +273,44 @@ pub fn calculate_x2_per_alloctone(
     calculate_x2_absolute(metriche_x2, x2_a, x2_b, &specie_campionate_set)
 }
 
+/// Stima popolazione, densità e x2_b di una sola specie del campionamento.
+/// Restituisce None se la specie non è stata campionata.
+pub fn calculate_metriche_x2_b_specie(
+    campionamento: &CampionamentoNISECI,
+    anagrafica: &AnagraficaNISECI,
+    codice_specie: &str,
+    stimatore: StimatorePopolazione,
+) -> Result<Option<MetricheX2B>, Vec<String>> {
+    let superficie = anagrafica.get_larghezza_media() * anagrafica.get_lunghezza_media();
+
+    let mut esemplari_per_cattura_map: HashMap<String, EsemplariPerCattura> = HashMap::new();
+    for cattura in &campionamento.campionamento {
+        if cattura.specie.id == codice_specie {
+            match esemplari_per_cattura_map.entry(cattura.specie.id.clone()) {
+                Entry::Occupied(mut occupied_entry) => {
+                    occupied_entry
+                        .get_mut()
+                        .fill_passaggio(cattura.passaggio_cattura);
+                }
+                Entry::Vacant(vacant_entry) => {
+                    vacant_entry.insert(EsemplariPerCattura::new_prevalorized(
+                        cattura.passaggio_cattura,
+                        &cattura.specie,
+                    ));
+                }
+            }
+        }
+    }
+
+    let (_, densita_vec) = calculate_sommatoria_x2_b_absolute(
+        esemplari_per_cattura_map,
+        superficie,
+        campionamento.get_numero_passaggi(),
+        stimatore,
+    )?;
+    Ok(densita_vec.into_iter().next())
+}
+
 struct RecordSubmetricheX2A {
     codice_specie: String,
     metriche_x2a: MetricheX2A,
@@ -301,6 +392,7 @@ fn calculate_sommatoria_x2_b(
     c: &CampionamentoNISECI,
     anagrafica: &AnagraficaNISECI,
     require_specie_attesa: bool,
+    stimatore: StimatorePopolazione,
 ) -> Result<(f32, Vec<MetricheX2B>), Vec<String>> {
     let superficie = anagrafica.get_larghezza_media() * anagrafica.get_lunghezza_media();
 
@@ -327,7 +419,12 @@ fn calculate_sommatoria_x2_b(
         }
     }
 
-    calculate_sommatoria_x2_b_absolute(esemplari_per_cattura_map, superficie)
+    calculate_sommatoria_x2_b_absolute(
+        esemplari_per_cattura_map,
+        superficie,
+        c.get_numero_passaggi(),
+        stimatore,
+    )
 }
 
 fn calculate_sommatoria_x2_a_per_alloctone(
@@ -358,6 +455,7 @@ fn calculate_sommatoria_x2_a_per_alloctone(
 fn calculate_sommatoria_x2_b_per_alloctone(
     c: &CampionamentoNISECI,
     anagrafica: &AnagraficaNISECI,
+    stimatore: StimatorePopolazione,
 ) -> Result<(f32, Vec<MetricheX2B>), Vec<String>> {
     let superficie = anagrafica.get_larghezza_media() * anagrafica.get_lunghezza_media();
 
@@ -382,7 +480,12 @@ fn calculate_sommatoria_x2_b_per_alloctone(
         }
     }
 
-    calculate_sommatoria_x2_b_absolute(esemplari_per_cattura_map, superficie)
+    calculate_sommatoria_x2_b_absolute(
+        esemplari_per_cattura_map,
+        superficie,
+        c.get_numero_passaggi(),
+        stimatore,
+    )
 }
 
 fn calculate_sommatoria_x2_a_absolute(
@@ -425,6 +528,8 @@ fn calculate_sommatoria_x2_a_absolute(
 fn calculate_sommatoria_x2_b_absolute(
     esemplari_per_cattura_map: HashMap<String, EsemplariPerCattura>,
     superficie: f32,
+    numero_passaggi: u8,
+    stimatore: StimatorePopolazione,
 ) -> Result<(f32, Vec<MetricheX2B>), Vec<String>> {
     // ora che abbiamo riempito la mappa con tutte le catture, possiamo andare
     // a calcolar x2b per ogni specie
@@ -432,17 +537,37 @@ fn calculate_sommatoria_x2_b_absolute(
     let mut errors: Vec<String> = Vec::with_capacity(esemplari_per_cattura_map.len()); // prenoto ora e poi restringo dopo
     let mut densita_vec: Vec<MetricheX2B> = Vec::with_capacity(esemplari_per_cattura_map.len());
     for catture in esemplari_per_cattura_map.values() {
-        match calculate_x2_b(catture, &superficie) {
-            Ok((x2_b, densita_stimata, quantita_stimata)) => {
+        // gli avvisi dipendono solo dalle catture: li ricavo prima della stima, così da
+        // riportarli anche quando la stima fallisce
+        let mut avvisi =
+            verifica_rimozione(&catture_per_passaggio(&catture.mappa, numero_passaggi));
+        match calculate_x2_b_with_stimatore(catture, &superficie, numero_passaggi, stimatore) {
+            Ok((x2_b, densita_stimata, stima)) => {
                 sommatoria_x2_b += x2_b;
-                densita_vec.push(MetricheX2B::new(
-                    catture.specie.id.clone(),
-                    densita_stimata,
-                    quantita_stimata,
-                    x2_b,
-                ));
+                if stima.somma_catture {
+                    avvisi.push(AvvisoRimozione::SommaCatture);
+                }
+                densita_vec.push(
+                    MetricheX2B::new(
+                        catture.specie.id.clone(),
+                        densita_stimata,
+                        stima.get_quantita(),
+                        x2_b,
+                    )
+                    .stima_popolazione(stima)
+                    .avvisi_rimozione(avvisi),
+                );
             }
-            Err(err_mess) => errors.push(err_mess),
+            Err(err_mess) if avvisi.is_empty() => errors.push(err_mess),
+            Err(err_mess) => errors.push(format!(
+                "{} ({})",
+                err_mess,
+                avvisi
+                    .iter()
+                    .map(|avviso| avviso.to_string())
+                    .collect::<Vec<_>>()
+                    .join("; ")
+            )),
         }
     }
 
@@ -476,28 +601,58 @@ fn calculate_x2_a(classe: &ClassiEtaSpecieNISECI) -> Result<(f32, MetricheX2A),
     classe.calculate_struttura_popolazione()
 }
 
-fn calculate_x2_b(e: &EsemplariPerCattura, superficie: &f32) -> Result<(f32, f32, u32), String> {
-    match get_quantita_stimata(&e.mappa) {
-        Ok(q_stimata) => {
-            // calcolo densita stimata
-            let densita_stimata = q_stimata as f32 / superficie;
+fn calculate_x2_b_with_stimatore(
+    e: &EsemplariPerCattura,
+    superficie: &f32,
+    numero_passaggi: u8,
+    stimatore: StimatorePopolazione,
+) -> Result<(f32, f32, StimaPopolazione), String> {
+    match get_stima_popolazione(&e.mappa, numero_passaggi, stimatore) {
+        Ok(stima) => {
+            // la densita usa la stima arrotondata, come la quantita
+            let densita_stimata = stima.get_quantita() as f32 / superficie;
 
             // trovo ora x2_b
             if densita_stimata > e.specie.dens_soglia2 {
-                return Ok((1.0, densita_stimata, q_stimata));
+                return Ok((1.0, densita_stimata, stima));
             }
             if densita_stimata > e.specie.dens_soglia1 {
-                return Ok((0.5, densita_stimata, q_stimata));
+                return Ok((0.5, densita_stimata, stima));
             }
-            Ok((0.0, densita_stimata, q_stimata))
+            Ok((0.0, densita_stimata, stima))
         }
-        Err(err_message) => Err(err_message),
+        Err(err_message) => Err(format!("{}: {}", e.specie.id, err_message)),
     }
 }
 
-fn get_quantita_stimata(passaggi: &HashMap<u8, u32>) -> Result<u32, String> {
+/// Lo stimatore classico usa solo i passaggi con catture della specie, gli stimatori di massima
+/// verosimiglianza tutti i `numero_passaggi` passaggi del campionamento
+fn get_stima_popolazione(
+    passaggi: &HashMap<u8, u32>,
+    numero_passaggi: u8,
+    stimatore: StimatorePopolazione,
+) -> Result<StimaPopolazione, String> {
+    match stimatore {
+        StimatorePopolazione::Classico(metodo) => stima_classica(passaggi, metodo),
+        StimatorePopolazione::Zippin => {
+            stima_zippin(&catture_per_passaggio(passaggi, numero_passaggi))
+        }
+        StimatorePopolazione::CarleStrub => {
+            stima_carle_strub(&catture_per_passaggio(passaggi, numero_passaggi))
+        }
+    }
+}
+
+/// Stima dello stimatore classico: con più passaggi ripiega sulla somma delle catture quando la
+/// rimozione non permette una stima, e lo segnala in StimaPopolazione::somma_catture
+fn stima_classica(
+    passaggi: &HashMap<u8, u32>,
+    metodo: MetodoRegressione,
+) -> Result<StimaPopolazione, String> {
+    let stimatore = StimatorePopolazione::Classico(metodo);
     if passaggi.len() == 1 {
-        return Ok(*passaggi.values().next().unwrap()); // sempre valorizzato
+        let quantita = *passaggi.values().next().unwrap(); // sempre valorizzato
+        return Ok(StimaPopolazione::puntuale(stimatore, quantita));
     }
 
     // passaggi viene creata in calculate_sommatoria_x2_b()
@@ -505,14 +660,28 @@ fn get_quantita_stimata(passaggi: &HashMap<u8, u32>) -> Result<u32, String> {
         let c1 = *passaggi.get(&1).unwrap();
         let c2 = *passaggi.get(&2).unwrap();
 
-        return calculate_passaggi_ripetuti(c1, c2);
-    }
-    calculate_q_stimata_regression(passaggi)
+        return calculate_passaggi_ripetuti(c1, c2).map(|(quantita, somma)| match somma {
+            true => StimaPopolazione::somma(stimatore, quantita),
+            false => StimaPopolazione::puntuale(stimatore, quantita),
+        });
+    }
+
+    let (quantita, retta) = calculate_q_stimata_regression_with_metodo(passaggi, metodo)?;
+    // stima_quantita_con_retta() usa la somma quando la retta manca o non scende
+    let somma = retta
+        .as_ref()
+        .is_none_or(|retta| retta.segno_pendenza() != SegnoPendenza::Negativa);
+    let stima = match somma {
+        true => StimaPopolazione::somma(stimatore, quantita),
+        false => StimaPopolazione::puntuale(stimatore, quantita),
+    };
+    Ok(StimaPopolazione { retta, ..stima })
 }
 
-fn calculate_passaggi_ripetuti(c1: u32, c2: u32) -> Result<u32, String> {
+/// Stima di Seber–LeCren con due passaggi; il flag indica che si è usata la somma delle catture
+fn calculate_passaggi_ripetuti(c1: u32, c2: u32) -> Result<(u32, bool), String> {
     if c1 == c2 || c1 == 0 || c2 == 0 {
-        return Ok(c1 + c2);
+        return Ok((c1 + c2, true));
     }
 
     let c = c1 + c2;
@@ -521,21 +690,19 @@ fn calculate_passaggi_ripetuti(c1: u32, c2: u32) -> Result<u32, String> {
     let result = (c as f32 / (1.0 - divisore.powf(2.0))).round() as i32;
 
     match result > 0 {
-        true => Ok(result as u32),
-        false => Ok(c1 + c2), // ritorno somma come da accordi
+        true => Ok((result as u32, false)),
+        false => Ok((c1 + c2, true)), // ritorno somma come da accordi
     }
 }
 
-fn calculate_q_stimata_regression(passaggi: &HashMap<u8, u32>) -> Result<u32, String> {
-    let ultimo_passaggio = *passaggi.keys().max().unwrap();
-
+fn calculate_q_stimata_regression_with_metodo(
+    passaggi: &HashMap<u8, u32>,
+    metodo: MetodoRegressione,
+) -> Result<(u32, Option<AdattamentoRetta>), String> {
     // dalla mappa non riesco a capire se ci siano o meno dei passaggi in cui non è stato trovato pesce
     // quindi mi creo un vettore che rappresenta i pesci trovati per ogni passaggio in ordine di passaggio
-    let mut esemplari_per_passaggio = vec![0_u32; ultimo_passaggio as usize];
-
-    for (key, value) in passaggi {
-        esemplari_per_passaggio[(*key - 1) as usize] = *value;
-    }
+    // la regressione usa i passaggi fino all'ultimo con catture della specie
+    let esemplari_per_passaggio = catture_per_passaggio(passaggi, 0);
 
     // ora creo i punti con x == esemplari catturati fino a quel passaggio
     // e y == esmplari catturati in quel passaggio
@@ -548,7 +715,7 @@ fn calculate_q_stimata_regression(passaggi: &HashMap<u8, u32>) -> Result<u32, St
         })
         .collect();
 
-    calculate_quantita_with_regression(points.as_slice())
+    stima_quantita_con_retta(points.as_slice(), metodo).map_err(|error| error.to_string())
 }
 
 fn fill_submetriche(
@@ -564,12 +731,7 @@ fn fill_submetriche(
                 *submetr = SubmetricheX2::new(
                     submetr.get_metriche_x2_a(),
                     submetr.get_classi_eta(),
-                    MetricheX2B::new(
-                        id,
-                        dens.get_densita_stimata(),
-                        dens.get_quantita_stimata(),
-                        dens.get_x2_b(),
-                    ),
+                    dens.clone(),
                 );
             }
             Entry::Vacant(_) => {
@@ -592,7 +754,9 @@ mod x2_private_tests {
         passaggi.insert(3, 20);
         passaggi.insert(4, 10);
 
-        let q_stimata = calculate_q_stimata_regression(&passaggi);
+        let q_stimata =
+            calculate_q_stimata_regression_with_metodo(&passaggi, MetodoRegressione::default())
+                .map(|(quantita, _)| quantita);
 
         assert!(q_stimata.is_ok());
         assert_eq!(190, q_stimata.unwrap());
@@ -605,7 +769,9 @@ mod x2_private_tests {
         passaggi.insert(2, 75);
         passaggi.insert(3, 100);
 
-        let q_stimata = calculate_q_stimata_regression(&passaggi);
+        let q_stimata =
+            calculate_q_stimata_regression_with_metodo(&passaggi, MetodoRegressione::default())
+                .map(|(quantita, _)| quantita);
 
         assert!(q_stimata.is_ok());
         assert_eq!(225, q_stimata.unwrap());
@@ -618,7 +784,9 @@ mod x2_private_tests {
         passaggi.insert(2, 50);
         passaggi.insert(3, 50);
 
-        let q_stimata = calculate_q_stimata_regression(&passaggi);
+        let q_stimata =
+            calculate_q_stimata_regression_with_metodo(&passaggi, MetodoRegressione::default())
+                .map(|(quantita, _)| quantita);
 
         assert!(q_stimata.is_ok());
         assert_eq!(150, q_stimata.unwrap());
@@ -629,12 +797,12 @@ mod x2_private_tests {
         let q_stimata_1 = calculate_passaggi_ripetuti(30, 12);
 
         assert!(q_stimata_1.is_ok());
-        assert_eq!(q_stimata_1.unwrap(), 50);
+        assert_eq!(q_stimata_1.unwrap(), (50, false));
 
         let q_stimata_2 = calculate_passaggi_ripetuti(30, 15);
 
         assert!(q_stimata_2.is_ok());
-        assert_eq!(q_stimata_2.unwrap(), 60);
+        assert_eq!(q_stimata_2.unwrap(), (60, false));
     }
 
     #[test]
@@ -642,7 +810,7 @@ mod x2_private_tests {
         let q_stimata = calculate_passaggi_ripetuti(15, 30);
 
         assert!(q_stimata.is_ok());
-        assert_eq!(q_stimata.unwrap(), 45);
+        assert_eq!(q_stimata.unwrap(), (45, true));
     }
 
     #[test]
@@ -650,7 +818,7 @@ mod x2_private_tests {
         let q_stimata = calculate_passaggi_ripetuti(30, 30);
 
         assert!(q_stimata.is_ok());
-        assert_eq!(q_stimata.unwrap(), 60);
+        assert_eq!(q_stimata.unwrap(), (60, true));
     }
 
     #[test]
@@ -661,7 +829,8 @@ mod x2_private_tests {
         passaggi.insert(3, 20);
         passaggi.insert(4, 10);
 
-        let q_stimata = get_quantita_stimata(&passaggi);
+        let q_stimata = stima_classica(&passaggi, MetodoRegressione::default())
+            .map(|stima| stima.get_quantita());
 
         assert!(q_stimata.is_ok());
         assert_eq!(190, q_stimata.unwrap());
@@ -673,7 +842,8 @@ mod x2_private_tests {
         passaggi.insert(1, 30);
         passaggi.insert(2, 12);
 
-        let q_stimata = get_quantita_stimata(&passaggi);
+        let q_stimata = stima_classica(&passaggi, MetodoRegressione::default())
+            .map(|stima| stima.get_quantita());
 
         assert!(q_stimata.is_ok());
         assert_eq!(50, q_stimata.unwrap());
@@ -682,7 +852,8 @@ mod x2_private_tests {
         passaggi2.insert(1, 30);
         passaggi2.insert(2, 15);
 
-        let q_stimata2 = get_quantita_stimata(&passaggi2);
+        let q_stimata2 = stima_classica(&passaggi2, MetodoRegressione::default())
+            .map(|stima| stima.get_quantita());
 
         assert!(q_stimata2.is_ok());
         assert_eq!(60, q_stimata2.unwrap());
@@ -694,7 +865,8 @@ mod x2_private_tests {
         passaggi.insert(1, 15);
         passaggi.insert(2, 30);
 
-        let q_stimata = get_quantita_stimata(&passaggi);
+        let q_stimata = stima_classica(&passaggi, MetodoRegressione::default())
+            .map(|stima| stima.get_quantita());
 
         assert!(q_stimata.is_ok());
         assert_eq!(q_stimata.unwrap(), 45);
@@ -707,12 +879,31 @@ mod x2_private_tests {
         passaggi.insert(2, 75);
         passaggi.insert(3, 100);
 
-        let q_stimata = get_quantita_stimata(&passaggi);
+        let q_stimata = stima_classica(&passaggi, MetodoRegressione::default())
+            .map(|stima| stima.get_quantita());
 
         assert!(q_stimata.is_ok());
         assert_eq!(q_stimata.unwrap(), 225);
     }
 
+    #[test]
+    fn stima_classica_somma_solo_da_ripiego() {
+        // la stima coincide con il totale ma non è un ripiego sulla somma
+        let passaggi: HashMap<u8, u32> = HashMap::from([(1, 100), (2, 1)]);
+        let stima = stima_classica(&passaggi, MetodoRegressione::default()).unwrap();
+        assert_eq!(stima.get_quantita(), 101);
+        assert!(!stima.somma_catture);
+
+        let passaggi: HashMap<u8, u32> = HashMap::from([(1, 15), (2, 30)]);
+        let stima = stima_classica(&passaggi, MetodoRegressione::default()).unwrap();
+        assert_eq!(stima.get_quantita(), 45);
+        assert!(stima.somma_catture);
+
+        let passaggi: HashMap<u8, u32> = HashMap::from([(1, 50), (2, 75), (3, 100)]);
+        let stima = stima_classica(&passaggi, MetodoRegressione::default()).unwrap();
+        assert!(stima.somma_catture);
+    }
+
     #[test]
     fn calculate_x2_b_buona() {
         let mut passaggi: HashMap<u8, u32> = HashMap::new();
@@ -722,11 +913,16 @@ mod x2_private_tests {
         let specie = get_ciaccio();
 
         let esemplari_per_cattura = EsemplariPerCattura {
-            specie: specie,
+            specie,
             mappa: passaggi,
         };
 
-        let x2_b = calculate_x2_b(&esemplari_per_cattura, &2.0);
+        let x2_b = calculate_x2_b_with_stimatore(
+            &esemplari_per_cattura,
+            &2.0,
+            2,
+            StimatorePopolazione::default(),
+        );
 
         assert!(x2_b.is_ok());
 
@@ -745,11 +941,16 @@ mod x2_private_tests {
         specie.dens_soglia2 = 30.0;
 
         let esemplari_per_cattura = EsemplariPerCattura {
-            specie: specie,
+            specie,
             mappa: passaggi,
         };
 
-        let x2_b = calculate_x2_b(&esemplari_per_cattura, &2.0);
+        let x2_b = calculate_x2_b_with_stimatore(
+            &esemplari_per_cattura,
+            &2.0,
+            2,
+            StimatorePopolazione::default(),
+        );
 
         assert!(x2_b.is_ok());
 
@@ -768,11 +969,16 @@ mod x2_private_tests {
         specie.dens_soglia2 = 40.0;
 
         let esemplari_per_cattura = EsemplariPerCattura {
-            specie: specie,
+            specie,
             mappa: passaggi,
         };
 
-        let x2_b = calculate_x2_b(&esemplari_per_cattura, &2.0);
+        let x2_b = calculate_x2_b_with_stimatore(
+            &esemplari_per_cattura,
+            &2.0,
+            2,
+            StimatorePopolazione::default(),
+        );
 
         assert!(x2_b.is_ok());
 
@@ -15,6 +15,7 @@
     along with this program.  If not, see <https://www.gnu.org/licenses/>.
 */
 
+use crate::domain::niseci::traccia::{CondizioneX3, EventoNISECI, TracciaNISECI};
 use crate::domain::niseci::{
     CampionamentoNISECI, ClassiEtaAlieniNISECI, ClassiEtaSpecieNISECI, InfoPopolazioniAlieneNISECI,
 };
@@ -87,15 +88,35 @@ impl MetricheX3 {
 }
 
 pub fn calculate_x3(c: &CampionamentoNISECI) -> Result<(f32, Option<MetricheX3>), Vec<String>> {
+    calculate_x3_with_traccia(c, None)
+}
+
+/// Come calculate_x3(), registrando nella traccia, se presente, la condizione che ha determinato x3
+pub fn calculate_x3_with_traccia(
+    c: &CampionamentoNISECI,
+    mut traccia: Option<&mut TracciaNISECI>,
+) -> Result<(f32, Option<MetricheX3>), Vec<String>> {
     let alieni_indigeni = c.get_numero_pesci_alieni_e_indigeni();
+    let mut registra = |condizione: CondizioneX3, x3: f32| {
+        if let Some(traccia) = traccia.as_deref_mut() {
+            traccia.registra(EventoNISECI::X3 {
+                alieni: alieni_indigeni.alieni,
+                indigeni: alieni_indigeni.indigeni,
+                condizione,
+                x3,
+            });
+        }
+    };
 
     // condizione 1
     if alieni_indigeni.alieni == 0 {
+        registra(CondizioneX3::NessunAlieno, 1.0);
         return Ok((1.0, None));
     }
 
     // condizione 2
     if alieni_indigeni.alieni >= alieni_indigeni.indigeni {
+        registra(CondizioneX3::AlieniNonMinoritari, 0.0);
         return Ok((0.0, None));
     }
 
@@ -112,6 +133,7 @@ pub fn calculate_x3(c: &CampionamentoNISECI) -> Result<(f32, Option<MetricheX3>)
     // condizione 3
     let epsilon: f32 = 1e-6;
     if (info_pop_aliene.tipo_1.popolazione_piu_strutt - 1.0).abs() < epsilon {
+        registra(CondizioneX3::AlloctonaTipo1Strutturata, 0.0);
         return Ok((0.0, None));
     }
 
@@ -240,6 +262,7 @@ pub fn calculate_x3(c: &CampionamentoNISECI) -> Result<(f32, Option<MetricheX3>)
         return Err(errors);
     }
 
+    registra(CondizioneX3::Formula { a, b }, rounded_x3);
     Ok((rounded_x3, Some(MetricheX3::new(a, b, submetriche))))
 }
 
@@ -0,0 +1,348 @@
+// SPDX-License-Identifier: GPL-3.0-only
+/*
+    Copyright (C) 2024-2026 jgabaut, gioninjo
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU General Public License as published by
+    the Free Software Foundation, version 3 of the License.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU General Public License for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use crate::csv::{RigaSorgente, TipoRecordCsv};
+use crate::domain::specie::SpecieSuggerita;
+use crate::i18n::{Etichetta, Lingua, Messaggio};
+use std::fmt;
+
+/// Posizione fisica di un errore all'interno di un file csv
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Posizione {
+    pub riga: u64,
+    pub record: u64,
+    pub byte: u64,
+}
+
+impl Posizione {
+    pub fn new(riga: u64, record: u64, byte: u64) -> Self {
+        Self { riga, record, byte }
+    }
+}
+
+impl From<&csv::Position> for Posizione {
+    fn from(p: &csv::Position) -> Self {
+        Self {
+            riga: p.line(),
+            record: p.record(),
+            byte: p.byte(),
+        }
+    }
+}
+
+impl Posizione {
+    pub fn descrizione(&self, lingua: Lingua) -> String {
+        format!("{}: {}", lingua.scegli("Riga", "Line"), self.riga)
+    }
+}
+
+impl fmt::Display for Posizione {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.descrizione(Lingua::default()))
+    }
+}
+
+/// Informazioni di contesto comuni a tutti i tipi di errore
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ContestoErrore {
+    /// Tipo di file/record in cui si è verificato l'errore
+    pub tipo_csv: Option<TipoRecordCsv>,
+    /// Posizione fisica nel file, se nota
+    pub posizione: Option<Posizione>,
+    /// Numero progressivo del record (a partire da 1), se noto
+    pub record: Option<usize>,
+    /// Nome del campo coinvolto
+    pub campo: Option<String>,
+    /// Indice (da 0) della colonna del campo nel file, se noto
+    pub colonna: Option<usize>,
+    /// Valore che ha causato l'errore
+    pub valore: Option<String>,
+    /// Messaggio in italiano
+    pub msg: String,
+    /// Voce del catalogo da cui è stato generato msg, per renderlo in altre lingue
+    pub messaggio: Option<Messaggio>,
+}
+
+impl ContestoErrore {
+    pub fn new(msg: impl Into<String>) -> Self {
+        Self {
+            msg: msg.into(),
+            ..Default::default()
+        }
+    }
+    /// Contesto con un messaggio del catalogo (vedi i18n::Messaggio)
+    pub fn da_messaggio(messaggio: Messaggio) -> Self {
+        Self::default().messaggio(messaggio)
+    }
+    pub fn messaggio(mut self, messaggio: Messaggio) -> Self {
+        self.msg = messaggio.testo(Lingua::Italiano);
+        self.messaggio = Some(messaggio);
+        self
+    }
+    pub fn tipo_csv(mut self, tipo_csv: TipoRecordCsv) -> Self {
+        self.tipo_csv = Some(tipo_csv);
+        self
+    }
+    pub fn posizione(mut self, posizione: Posizione) -> Self {
+        self.posizione = Some(posizione);
+        self
+    }
+    pub fn record(mut self, record: usize) -> Self {
+        self.record = Some(record);
+        self
+    }
+    pub fn campo(mut self, campo: impl Into<String>) -> Self {
+        self.campo = Some(campo.into());
+        self
+    }
+    pub fn colonna(mut self, colonna: usize) -> Self {
+        self.colonna = Some(colonna);
+        self
+    }
+    pub fn valore(mut self, valore: impl ToString) -> Self {
+        self.valore = Some(valore.to_string());
+        self
+    }
+}
+
+/// Errore unico della libreria: ogni variante identifica la causa,
+/// il contesto porta posizione, campo e valore quando disponibili
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    /// Errore di I/O durante la lettura dell'input
+    Io(Box<ContestoErrore>),
+    /// Il percorso passato non esiste, non è un file o non è un .csv
+    FileNonValido(Box<ContestoErrore>),
+    /// Un campo non può essere convertito nel tipo atteso
+    Deserializzazione(Box<ContestoErrore>),
+    /// Il record non ha il numero di campi atteso
+    NumeroCampi(Box<ContestoErrore>),
+    /// Una colonna attesa non è presente nell'intestazione
+    ColonnaMancante(Box<ContestoErrore>),
+    /// Sequenza UTF-8 non valida
+    Utf8(Box<ContestoErrore>),
+    /// Numero di record nel file diverso da quello atteso
+    NumeroRecord(Box<ContestoErrore>),
+    /// Un valore ha il tipo corretto ma non rispetta i vincoli di dominio
+    ValoreInvalido(Box<ContestoErrore>),
+    SoglieCLNonCrescenti(Box<ContestoErrore>),
+    SoglieADJUVNonCrescenti(Box<ContestoErrore>),
+    /// Errore durante il calcolo di un indice
+    Calcolo(Box<ContestoErrore>),
+}
+
+impl Error {
+    pub fn contesto(&self) -> &ContestoErrore {
+        match self {
+            Error::Io(c)
+            | Error::FileNonValido(c)
+            | Error::Deserializzazione(c)
+            | Error::NumeroCampi(c)
+            | Error::ColonnaMancante(c)
+            | Error::Utf8(c)
+            | Error::NumeroRecord(c)
+            | Error::ValoreInvalido(c)
+            | Error::SoglieCLNonCrescenti(c)
+            | Error::SoglieADJUVNonCrescenti(c)
+            | Error::Calcolo(c) => c,
+        }
+    }
+    fn contesto_mut(&mut self) -> &mut ContestoErrore {
+        match self {
+            Error::Io(c)
+            | Error::FileNonValido(c)
+            | Error::Deserializzazione(c)
+            | Error::NumeroCampi(c)
+            | Error::ColonnaMancante(c)
+            | Error::Utf8(c)
+            | Error::NumeroRecord(c)
+            | Error::ValoreInvalido(c)
+            | Error::SoglieCLNonCrescenti(c)
+            | Error::SoglieADJUVNonCrescenti(c)
+            | Error::Calcolo(c) => c,
+        }
+    }
+    /// Imposta il tipo di file a cui si riferisce l'errore
+    pub fn con_tipo_csv(mut self, tipo_csv: TipoRecordCsv) -> Self {
+        self.contesto_mut().tipo_csv = Some(tipo_csv);
+        self
+    }
+    /// Imposta il numero progressivo del record a cui si riferisce l'errore
+    pub fn con_record(mut self, record: usize) -> Self {
+        self.contesto_mut().record = Some(record);
+        self
+    }
+    /// Completa l'errore con la riga del file da cui proviene il record:
+    /// posizione fisica e, se il campo è noto, colonna e valore grezzo della cella
+    pub fn con_riga_sorgente(mut self, riga: &RigaSorgente) -> Self {
+        let c = self.contesto_mut();
+        if c.posizione.is_none() {
+            c.posizione = riga.posizione.clone();
+        }
+        let cella = c.campo.as_deref().and_then(|campo| riga.cella(campo));
+        if let Some((valore, colonna)) = cella {
+            c.valore = Some(valore.to_string());
+            c.colonna = colonna;
+        }
+        self
+    }
+    pub fn tipo_csv(&self) -> Option<TipoRecordCsv> {
+        self.contesto().tipo_csv
+    }
+    pub fn posizione(&self) -> Option<&Posizione> {
+        self.contesto().posizione.as_ref()
+    }
+    pub fn record(&self) -> Option<usize> {
+        self.contesto().record
+    }
+    pub fn campo(&self) -> Option<&str> {
+        self.contesto().campo.as_deref()
+    }
+    pub fn colonna(&self) -> Option<usize> {
+        self.contesto().colonna
+    }
+    pub fn valore(&self) -> Option<&str> {
+        self.contesto().valore.as_deref()
+    }
+    /// Specie suggerita per un codice specie non presente nel riferimento
+    pub fn suggerimento(&self) -> Option<&SpecieSuggerita> {
+        match &self.contesto().messaggio {
+            Some(Messaggio::CodiceSpecieSconosciuto {
+                suggerimento: Some(s),
+                ..
+            }) => Some(s),
+            _ => None,
+        }
+    }
+    /// Messaggio in italiano
+    pub fn msg(&self) -> &str {
+        &self.contesto().msg
+    }
+    /// Messaggio nella lingua richiesta (se l'errore proviene dal catalogo)
+    pub fn testo(&self, lingua: Lingua) -> String {
+        let c = self.contesto();
+        match &c.messaggio {
+            Some(m) => m.testo(lingua),
+            None => c.msg.clone(),
+        }
+    }
+    /// Descrizione completa dell'errore nella lingua richiesta (vedi Display)
+    pub fn descrizione(&self, lingua: Lingua) -> String {
+        let c = self.contesto();
+        let msg = self.testo(lingua);
+        let posizione_str = match &c.posizione {
+            Some(p) => p.descrizione(lingua),
+            None => "none".to_string(),
+        };
+        let campo_str = match &c.campo {
+            Some(campo) => campo.clone(),
+            None => "none".to_string(),
+        };
+        match self {
+            Error::Io(_) => format!("{}: {}", lingua.scegli("Errore di I/O", "I/O error"), msg),
+            Error::FileNonValido(_) => format!(
+                "{}: {}",
+                lingua.scegli("Errore file non valido", "Invalid file error"),
+                msg
+            ),
+            Error::Deserializzazione(_) => format!(
+                "{}: {}: {} {}: {}",
+                lingua.scegli(
+                    "Errore di deserializzazione alla posizione",
+                    "Deserialization error at position"
+                ),
+                posizione_str,
+                lingua.scegli("campo", "field"),
+                campo_str,
+                msg
+            ),
+            Error::NumeroCampi(_) => format!(
+                "{}: {}: {}",
+                lingua.scegli(
+                    "Errore numero campi alla posizione",
+                    "Field count error at position"
+                ),
+                posizione_str,
+                msg
+            ),
+            Error::ColonnaMancante(_) => {
+                let tipo_str = match c.tipo_csv {
+                    Some(tipo) => format!(" {}", tipo.etichetta(lingua)),
+                    None => "".to_string(),
+                };
+                format!(
+                    "{}{}: {}: {}",
+                    lingua.scegli("Errore intestazione", "Header error"),
+                    tipo_str,
+                    msg,
+                    campo_str
+                )
+            }
+            Error::Utf8(_) => format!(
+                "{}: {}: {}",
+                lingua.scegli("Errore UTF-8 alla posizione", "UTF-8 error at position"),
+                posizione_str,
+                msg
+            ),
+            Error::Calcolo(_) => format!(
+                "{}: {}",
+                lingua.scegli("Errore di calcolo", "Calculation error"),
+                msg
+            ),
+            Error::NumeroRecord(_)
+            | Error::ValoreInvalido(_)
+            | Error::SoglieCLNonCrescenti(_)
+            | Error::SoglieADJUVNonCrescenti(_) => {
+                let errore_record = lingua.scegli("Errore record", "Record error");
+                let prefisso = match c.tipo_csv {
+                    Some(tipo) => format!("{} {}: ", errore_record, tipo.etichetta(lingua)),
+                    None => format!("{}: ", errore_record),
+                };
+                // Riga e colonna (da 1) nel file, quando il record proviene da un csv
+                let sorgente_str = match (&c.posizione, c.colonna) {
+                    (Some(p), Some(col)) => format!(
+                        " ({}, {}: {})",
+                        p.descrizione(lingua),
+                        lingua.scegli("colonna", "column"),
+                        col + 1
+                    ),
+                    (Some(p), None) => format!(" ({})", p.descrizione(lingua)),
+                    _ => "".to_string(),
+                };
+                match c.record {
+                    Some(idx) => format!("{prefisso}Record {idx}: {msg}{sorgente_str}"),
+                    None => format!("{prefisso}{msg}{sorgente_str}"),
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let string_representation = self.descrizione(Lingua::default());
+        write!(f, "{}", string_representation)
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::Io(ContestoErrore::da_messaggio(Messaggio::Esterno(e.to_string())).into())
+    }
+}
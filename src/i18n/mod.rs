@@ -0,0 +1,656 @@
+// SPDX-License-Identifier: GPL-3.0-only
+/*
+    Copyright (C) 2024-2026 jgabaut, gioninjo
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU General Public License as published by
+    the Free Software Foundation, version 3 of the License.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU General Public License for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use crate::csv::deser::sniff::FormatoRilevato;
+use crate::csv::deser::translate_error_message;
+use crate::csv::TipoRecordCsv;
+use crate::domain::hfbi::CondizioniRiferimentoKeyHFBI;
+use crate::domain::specie::SpecieSuggerita;
+use chrono::format::ParseErrorKind;
+use std::fmt;
+use std::str::FromStr;
+
+/// Lingua dei messaggi e delle etichette prodotti dalla libreria
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Lingua {
+    #[default]
+    Italiano,
+    Inglese,
+}
+
+impl Lingua {
+    /// Sceglie il testo in questa lingua fra le due versioni date
+    pub fn scegli<'a>(self, italiano: &'a str, inglese: &'a str) -> &'a str {
+        match self {
+            Lingua::Italiano => italiano,
+            Lingua::Inglese => inglese,
+        }
+    }
+}
+
+impl fmt::Display for Lingua {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let string_representation = match self {
+            Lingua::Italiano => "it",
+            Lingua::Inglese => "en",
+        };
+        write!(f, "{}", string_representation)
+    }
+}
+
+impl FromStr for Lingua {
+    type Err = ();
+
+    /// Accetta i codici ISO 639-1 ("it", "en"), anche con regione (es. "en-GB")
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let codice = s.trim().to_ascii_lowercase();
+        match codice.split(['-', '_']).next() {
+            Some("it") => Ok(Lingua::Italiano),
+            Some("en") => Ok(Lingua::Inglese),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Etichetta leggibile di un valore (es. una classe di stato ecologico),
+/// usata da Display nella lingua predefinita (Italiano)
+pub trait Etichetta {
+    fn etichetta(&self, lingua: Lingua) -> &'static str;
+}
+
+/// Motivo per cui una data non è valida
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MotivoDataNonValida {
+    FuoriRange,
+    ValoriImpossibili,
+    SpecificaInsufficiente,
+    CaratteriNonAttesi,
+    InputTroppoCorto,
+    InputInEccesso,
+    FormatoErrato,
+    Sconosciuto,
+}
+
+impl From<ParseErrorKind> for MotivoDataNonValida {
+    fn from(kind: ParseErrorKind) -> Self {
+        match kind {
+            ParseErrorKind::OutOfRange => MotivoDataNonValida::FuoriRange,
+            ParseErrorKind::Impossible => MotivoDataNonValida::ValoriImpossibili,
+            ParseErrorKind::NotEnough => MotivoDataNonValida::SpecificaInsufficiente,
+            ParseErrorKind::Invalid => MotivoDataNonValida::CaratteriNonAttesi,
+            ParseErrorKind::TooShort => MotivoDataNonValida::InputTroppoCorto,
+            ParseErrorKind::TooLong => MotivoDataNonValida::InputInEccesso,
+            ParseErrorKind::BadFormat => MotivoDataNonValida::FormatoErrato,
+            _ => MotivoDataNonValida::Sconosciuto,
+        }
+    }
+}
+
+impl Etichetta for MotivoDataNonValida {
+    fn etichetta(&self, lingua: Lingua) -> &'static str {
+        match self {
+            MotivoDataNonValida::FuoriRange => lingua.scegli("fuori range", "out of range"),
+            MotivoDataNonValida::ValoriImpossibili => {
+                lingua.scegli("valori non possibili", "impossible values")
+            }
+            MotivoDataNonValida::SpecificaInsufficiente => {
+                lingua.scegli("specifica insufficiente", "not enough information")
+            }
+            MotivoDataNonValida::CaratteriNonAttesi => {
+                lingua.scegli("presenza di caratteri non attesi", "unexpected characters")
+            }
+            MotivoDataNonValida::InputTroppoCorto => lingua.scegli(
+                "terminazione prematura dell'input",
+                "premature end of input",
+            ),
+            MotivoDataNonValida::InputInEccesso => {
+                lingua.scegli("input in eccesso", "trailing input")
+            }
+            MotivoDataNonValida::FormatoErrato => lingua.scegli(
+                "errore nella specifica di formattazione",
+                "bad format specification",
+            ),
+            MotivoDataNonValida::Sconosciuto => {
+                lingua.scegli("errore sconosciuto", "unknown error")
+            }
+        }
+    }
+}
+
+/// Catalogo dei messaggi di errore della libreria.
+/// Ogni voce porta i propri argomenti e viene resa nella lingua richiesta da testo().
+#[derive(Debug, Clone, PartialEq)]
+pub enum Messaggio {
+    /// Testo prodotto da una libreria esterna (csv, serde, I/O), in inglese
+    Esterno(String),
+    PercorsoInesistente(String),
+    PercorsoNonFile(String),
+    FileNonCsv(String),
+    FileNonApribile {
+        percorso: String,
+        dettaglio: String,
+    },
+    ColonnaMancante,
+    FineRigaInattesa,
+    LunghezzaRecord {
+        attesa: u64,
+        trovata: u64,
+    },
+    TroppiRecord(usize),
+    NessunRecord,
+    OrigineNonValida(String),
+    TipoAutoctonoNonValido(String),
+    AlloNocivitaNonValida(String),
+    CodiceSpecieVuoto,
+    CodiceSpecieRidefinito,
+    CodiceSpecieSconosciuto {
+        codice: String,
+        /// Specie più simile nel riferimento, se ce n'è una abbastanza vicina
+        suggerimento: Option<SpecieSuggerita>,
+    },
+    /// Codice specie sostituito in base alle correzioni confermate (vedi CorrezioniSpecie)
+    CodiceSpecieCorretto {
+        record: usize,
+        errato: String,
+        corretto: String,
+    },
+    /// Soglia di densità (1 o 2) negativa
+    DensSogliaNegativa(u8),
+    /// Soglia di densità (1 o 2) nulla per una specie attesa
+    DensSogliaNulla(u8),
+    DensSoglieNonCrescenti,
+    SoglieCLNonCrescenti,
+    SoglieADJUVNonCrescenti,
+    /// Frazione di un gruppo trofico fuori da [0, 1] (campo = nome della colonna)
+    FrazioneTroficaNonValida {
+        campo: &'static str,
+        valore: String,
+    },
+    /// Le frazioni dei gruppi trofici di una specie non sommano a 1
+    SommaFrazioniTroficheNonValida(String),
+    NumPassaggioNonValido(String),
+    NumeroIndividuiNonValido(String),
+    PesoNonValido(String),
+    StazioneVuota,
+    DataNonValida(String),
+    DataFornitaNonValida(MotivoDataNonValida),
+    /// Campo di testo dell'anagrafica vuoto (campo = nome della colonna)
+    CampoTroppoCorto {
+        campo: &'static str,
+        valore: String,
+    },
+    /// Misura della stazione negativa (campo = nome della colonna)
+    MisuraTroppoBassa {
+        campo: &'static str,
+        valore: String,
+    },
+    /// Codice numerico fuori dall'intervallo ammesso per un tipo
+    CodiceFuoriIntervallo {
+        tipo: &'static str,
+        valore: String,
+        min: i32,
+        max: i32,
+    },
+    StazioneDuplicata {
+        data: String,
+        stazione: String,
+    },
+    CondizioniRiferimentoMancanti,
+    /// Valore di riferimento di una metrica non positivo (campo = nome della colonna)
+    CondizioneRiferimentoNonValida {
+        campo: &'static str,
+        valore: String,
+    },
+    /// Combinazione ripetuta in una tabella di condizioni di riferimento
+    CondizioniRiferimentoDuplicate(CondizioniRiferimentoKeyHFBI),
+    /// Combinazioni non coperte da una tabella di condizioni di riferimento
+    CondizioniRiferimentoIncomplete(Vec<CondizioniRiferimentoKeyHFBI>),
+    /// Sezioni dell'input JSON che non si possono indicare insieme (es. "riferimento", "comunita")
+    SezioniAlternative(&'static str, &'static str),
+    /// Errore interno nel calcolo di una metrica (es. "x2")
+    CalcoloMetrica {
+        metrica: &'static str,
+        dettaglio: String,
+    },
+    RisultatoNegativo(&'static str),
+    /// Livello di confidenza fuori da (0, 1)
+    LivelloConfidenzaNonValido(f32),
+    /// Campionamento per rimozione: nessun esemplare della specie al primo passaggio
+    PrimoPassaggioSenzaCatture,
+    /// Campionamento per rimozione: non meno esemplari che al passaggio precedente
+    CattureNonDecrescenti {
+        passaggio: u8,
+        precedenti: u32,
+        catture: u32,
+    },
+    /// Campionamento per rimozione: troppo pochi esemplari per una stima affidabile
+    EsemplariInsufficienti {
+        catture: u32,
+        minimo: u32,
+    },
+    /// Campionamento per rimozione: stima non calcolabile, sostituita dalla somma delle catture
+    StimaSommaCatture,
+    AnagraficaMancante(String),
+    /// Stazione del campionamento diversa da quella dell'anagrafica
+    StazioneNonCorrispondente {
+        campionamento: String,
+        anagrafica: String,
+    },
+    /// Data del campionamento diversa da quella dell'anagrafica
+    DataNonCorrispondente {
+        campionamento: String,
+        anagrafica: String,
+    },
+    /// Un campionamento che dovrebbe riguardare una sola stazione ne contiene più d'una
+    StazioniMultiple(Vec<String>),
+    /// File csv in un formato diverso da quello standard per il suo tipo
+    FormatoNonStandard(FormatoRilevato),
+    /// L'intestazione del file corrisponde a un tipo di file diverso da quello atteso
+    TipoFileDiverso {
+        trovato: TipoRecordCsv,
+        atteso: TipoRecordCsv,
+    },
+}
+
+fn nome_campo(campo: &str, lingua: Lingua) -> &str {
+    match campo {
+        "codiceStazione" => lingua.scegli("Codice stazione", "Station code"),
+        "corpoIdrico" => lingua.scegli("Corpo idrico", "Water body"),
+        "regione" => lingua.scegli("Regione", "Region"),
+        "provincia" => lingua.scegli("Provincia", "Province"),
+        "fonte" => lingua.scegli("Fonte", "Source"),
+        "numeroProtocollo" => lingua.scegli("Numero protocollo", "Protocol number"),
+        "nomeBacino" => lingua.scegli("Nome bacino", "Basin name"),
+        "lunghezzaStazione" => lingua.scegli("Lunghezza stazione", "Station length"),
+        "larghezzaStazione" => lingua.scegli("Larghezza stazione", "Station width"),
+        _ => campo,
+    }
+}
+
+/// Accordo del participio per i nomi femminili del catalogo
+fn is_femminile(campo: &str) -> bool {
+    matches!(campo, "regione" | "provincia" | "fonte")
+}
+
+impl Messaggio {
+    pub fn testo(&self, lingua: Lingua) -> String {
+        match lingua {
+            Lingua::Italiano => self.testo_italiano(),
+            Lingua::Inglese => self.testo_inglese(),
+        }
+    }
+
+    fn testo_italiano(&self) -> String {
+        let l = Lingua::Italiano;
+        match self {
+            Messaggio::Esterno(msg) => translate_error_message(msg),
+            Messaggio::PercorsoInesistente(p) => format!("{}: il percorso non esiste", p),
+            Messaggio::PercorsoNonFile(p) => format!("{}: il percorso non è un file regolare", p),
+            Messaggio::FileNonCsv(p) => format!("{}: il file non è un .csv", p),
+            Messaggio::FileNonApribile {
+                percorso,
+                dettaglio,
+            } => format!("{}: {}", percorso, translate_error_message(dettaglio)),
+            Messaggio::ColonnaMancante => "colonna mancante nell'intestazione".to_string(),
+            Messaggio::FineRigaInattesa => "Fine riga inatteso".to_string(),
+            Messaggio::LunghezzaRecord { attesa, trovata } => {
+                format!("lunghezza attesa {}, trovata {}", attesa, trovata)
+            }
+            Messaggio::TroppiRecord(n) => format!("Troppi record: {}, atteso 1", n),
+            Messaggio::NessunRecord => "Nessun record trovato: atteso 1".to_string(),
+            Messaggio::OrigineNonValida(v) => {
+                format!("origine invalida (non \"AUT\" o \"ALL\"): {}", v)
+            }
+            Messaggio::TipoAutoctonoNonValido(v) => {
+                format!("tipo_autoctono non valido (non 1 o 2): {}", v)
+            }
+            Messaggio::AlloNocivitaNonValida(v) => {
+                format!("allo_nocivita non valido (non [0..3]): {}", v)
+            }
+            Messaggio::CodiceSpecieVuoto => "codice_specie non valido (lunghezza < 1)".to_string(),
+            Messaggio::CodiceSpecieRidefinito => {
+                "codice_specie non valido (ridefinizione)".to_string()
+            }
+            Messaggio::CodiceSpecieSconosciuto {
+                codice,
+                suggerimento,
+            } => {
+                let msg = format!(
+                    "codice_specie non valido (non presente nel riferimento): {}",
+                    codice
+                );
+                match suggerimento {
+                    Some(s) => format!("{}, forse intendevi {}?", msg, s),
+                    None => msg,
+                }
+            }
+            Messaggio::CodiceSpecieCorretto {
+                record,
+                errato,
+                corretto,
+            } => format!(
+                "Record {}: codice specie {} sostituito con {}",
+                record, errato, corretto
+            ),
+            Messaggio::DensSogliaNegativa(n) => format!("dens_soglia{} non valido (< 0)", n),
+            Messaggio::DensSogliaNulla(n) => {
+                format!("dens_soglia{} non valido (== 0) per una specie attesa", n)
+            }
+            Messaggio::DensSoglieNonCrescenti => {
+                "dens_soglia1 maggiore di dens_soglia2 per una specie attesa".to_string()
+            }
+            Messaggio::SoglieCLNonCrescenti => "soglie CL non crescenti".to_string(),
+            Messaggio::SoglieADJUVNonCrescenti => "soglie AD/JUV non crescenti".to_string(),
+            Messaggio::FrazioneTroficaNonValida { campo, valore } => {
+                format!("{} non valido (non in [0, 1]): {}", campo, valore)
+            }
+            Messaggio::SommaFrazioniTroficheNonValida(v) => {
+                format!("somma delle frazioni trofiche diversa da 1: {}", v)
+            }
+            Messaggio::NumPassaggioNonValido(v) => format!("num_passaggio non valido (<1): {}", v),
+            Messaggio::NumeroIndividuiNonValido(v) => {
+                format!("numero_individui non valido (<1): {}", v)
+            }
+            Messaggio::PesoNonValido(v) => format!("peso non valido (not finite): {}", v),
+            Messaggio::StazioneVuota => "stazione non valida (lunghezza < 1)".to_string(),
+            Messaggio::DataNonValida(v) => format!("data non valida: {}", v),
+            Messaggio::DataFornitaNonValida(motivo) => {
+                format!("Data fornita non valida: {}", motivo.etichetta(l))
+            }
+            Messaggio::CampoTroppoCorto { campo, valore } => {
+                let accordo = if is_femminile(campo) { "a" } else { "o" };
+                format!(
+                    "{} troppo cort{}: {}",
+                    nome_campo(campo, l),
+                    accordo,
+                    valore
+                )
+            }
+            Messaggio::MisuraTroppoBassa { campo, valore } => {
+                format!("{} troppo bassa: {}", nome_campo(campo, l), valore)
+            }
+            Messaggio::CodiceFuoriIntervallo {
+                tipo,
+                valore,
+                min,
+                max,
+            } => format!("{} non valido: {}, atteso [{}, {}]", tipo, valore, min, max),
+            Messaggio::StazioneDuplicata { data, stazione } => {
+                format!(
+                    "codice stazione duplicato per la data {}: {}",
+                    data, stazione
+                )
+            }
+            Messaggio::CondizioniRiferimentoMancanti => {
+                "condizioni di riferimento non trovate".to_string()
+            }
+            Messaggio::CondizioneRiferimentoNonValida { campo, valore } => {
+                format!("{} non valido (deve essere > 0): {}", campo, valore)
+            }
+            Messaggio::CondizioniRiferimentoDuplicate(chiave) => format!(
+                "condizioni di riferimento ripetute per {}",
+                chiave.descrizione(l)
+            ),
+            Messaggio::CondizioniRiferimentoIncomplete(chiavi) => format!(
+                "condizioni di riferimento mancanti per: {}",
+                chiavi
+                    .iter()
+                    .map(|c| c.descrizione(l))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            Messaggio::SezioniAlternative(prima, seconda) => {
+                format!("indicare {} oppure {}, non entrambi", prima, seconda)
+            }
+            Messaggio::CalcoloMetrica { metrica, dettaglio } => {
+                format!("calcolo {}: {}", metrica, dettaglio)
+            }
+            Messaggio::RisultatoNegativo(metrica) => {
+                format!("risultato {}: valore negativo", metrica)
+            }
+            Messaggio::LivelloConfidenzaNonValido(livello) => {
+                format!(
+                    "livello di confidenza {} non valido, deve essere compreso tra 0 e 1",
+                    livello
+                )
+            }
+            Messaggio::PrimoPassaggioSenzaCatture => {
+                "nessun esemplare catturato al primo passaggio".to_string()
+            }
+            Messaggio::CattureNonDecrescenti {
+                passaggio,
+                precedenti,
+                catture,
+            } => format!(
+                "catture non in calo al passaggio {}: {} esemplari dopo {}",
+                passaggio, catture, precedenti
+            ),
+            Messaggio::EsemplariInsufficienti { catture, minimo } => format!(
+                "solo {} esemplari catturati, ne servono almeno {} per una stima affidabile",
+                catture, minimo
+            ),
+            Messaggio::StimaSommaCatture => {
+                "stima per rimozione non calcolabile, usata la somma delle catture".to_string()
+            }
+            Messaggio::AnagraficaMancante(stazione) => {
+                format!("anagrafica non trovata per la stazione {}", stazione)
+            }
+            Messaggio::StazioneNonCorrispondente {
+                campionamento,
+                anagrafica,
+            } => format!(
+                "stazione del campionamento diversa da quella dell'anagrafica: {} (anagrafica: {})",
+                campionamento, anagrafica
+            ),
+            Messaggio::DataNonCorrispondente {
+                campionamento,
+                anagrafica,
+            } => format!(
+                "data del campionamento diversa da quella dell'anagrafica: {} (anagrafica: {})",
+                campionamento, anagrafica
+            ),
+            Messaggio::StazioniMultiple(stazioni) => {
+                format!(
+                    "il campionamento contiene più stazioni: {}",
+                    stazioni.join(", ")
+                )
+            }
+            Messaggio::FormatoNonStandard(formato) => {
+                format!("Formato non standard rilevato: {}", formato.descrizione(l))
+            }
+            Messaggio::TipoFileDiverso { trovato, atteso } => format!(
+                "L'intestazione sembra di un file {}, atteso {}",
+                trovato.etichetta(l),
+                atteso.etichetta(l)
+            ),
+        }
+    }
+
+    fn testo_inglese(&self) -> String {
+        let l = Lingua::Inglese;
+        match self {
+            Messaggio::Esterno(msg) => msg.clone(),
+            Messaggio::PercorsoInesistente(p) => format!("{}: path does not exist", p),
+            Messaggio::PercorsoNonFile(p) => format!("{}: path is not a regular file", p),
+            Messaggio::FileNonCsv(p) => format!("{}: file is not a .csv", p),
+            Messaggio::FileNonApribile {
+                percorso,
+                dettaglio,
+            } => format!("{}: {}", percorso, dettaglio),
+            Messaggio::ColonnaMancante => "column missing from header".to_string(),
+            Messaggio::FineRigaInattesa => "Unexpected end of row".to_string(),
+            Messaggio::LunghezzaRecord { attesa, trovata } => {
+                format!("expected length {}, found {}", attesa, trovata)
+            }
+            Messaggio::TroppiRecord(n) => format!("Too many records: {}, expected 1", n),
+            Messaggio::NessunRecord => "No records found: expected 1".to_string(),
+            Messaggio::OrigineNonValida(v) => {
+                format!("invalid origine (not \"AUT\" or \"ALL\"): {}", v)
+            }
+            Messaggio::TipoAutoctonoNonValido(v) => {
+                format!("invalid tipo_autoctono (not 1 or 2): {}", v)
+            }
+            Messaggio::AlloNocivitaNonValida(v) => {
+                format!("invalid allo_nocivita (not [0..3]): {}", v)
+            }
+            Messaggio::CodiceSpecieVuoto => "invalid codice_specie (length < 1)".to_string(),
+            Messaggio::CodiceSpecieRidefinito => "invalid codice_specie (redefined)".to_string(),
+            Messaggio::CodiceSpecieSconosciuto {
+                codice,
+                suggerimento,
+            } => {
+                let msg = format!(
+                    "invalid codice_specie (not in the reference list): {}",
+                    codice
+                );
+                match suggerimento {
+                    Some(s) => format!("{}, did you mean {}?", msg, s),
+                    None => msg,
+                }
+            }
+            Messaggio::CodiceSpecieCorretto {
+                record,
+                errato,
+                corretto,
+            } => format!(
+                "Record {}: species code {} replaced with {}",
+                record, errato, corretto
+            ),
+            Messaggio::DensSogliaNegativa(n) => format!("invalid dens_soglia{} (< 0)", n),
+            Messaggio::DensSogliaNulla(n) => {
+                format!("invalid dens_soglia{} (== 0) for an expected species", n)
+            }
+            Messaggio::DensSoglieNonCrescenti => {
+                "dens_soglia1 greater than dens_soglia2 for an expected species".to_string()
+            }
+            Messaggio::SoglieCLNonCrescenti => "CL thresholds not increasing".to_string(),
+            Messaggio::SoglieADJUVNonCrescenti => "AD/JUV thresholds not increasing".to_string(),
+            Messaggio::FrazioneTroficaNonValida { campo, valore } => {
+                format!("invalid {} (not in [0, 1]): {}", campo, valore)
+            }
+            Messaggio::SommaFrazioniTroficheNonValida(v) => {
+                format!("trophic fractions do not sum to 1: {}", v)
+            }
+            Messaggio::NumPassaggioNonValido(v) => format!("invalid num_passaggio (<1): {}", v),
+            Messaggio::NumeroIndividuiNonValido(v) => {
+                format!("invalid numero_individui (<1): {}", v)
+            }
+            Messaggio::PesoNonValido(v) => format!("invalid peso (not finite): {}", v),
+            Messaggio::StazioneVuota => "invalid stazione (length < 1)".to_string(),
+            Messaggio::DataNonValida(v) => format!("invalid date: {}", v),
+            Messaggio::DataFornitaNonValida(motivo) => {
+                format!("Invalid date: {}", motivo.etichetta(l))
+            }
+            Messaggio::CampoTroppoCorto { campo, valore } => {
+                format!("{} too short: {}", nome_campo(campo, l), valore)
+            }
+            Messaggio::MisuraTroppoBassa { campo, valore } => {
+                format!("{} too low: {}", nome_campo(campo, l), valore)
+            }
+            Messaggio::CodiceFuoriIntervallo {
+                tipo,
+                valore,
+                min,
+                max,
+            } => format!("invalid {}: {}, expected [{}, {}]", tipo, valore, min, max),
+            Messaggio::StazioneDuplicata { data, stazione } => {
+                format!("duplicate station code for date {}: {}", data, stazione)
+            }
+            Messaggio::CondizioniRiferimentoMancanti => {
+                "reference conditions not found".to_string()
+            }
+            Messaggio::CondizioneRiferimentoNonValida { campo, valore } => {
+                format!("invalid {} (must be > 0): {}", campo, valore)
+            }
+            Messaggio::CondizioniRiferimentoDuplicate(chiave) => format!(
+                "reference conditions repeated for {}",
+                chiave.descrizione(l)
+            ),
+            Messaggio::CondizioniRiferimentoIncomplete(chiavi) => format!(
+                "missing reference conditions for: {}",
+                chiavi
+                    .iter()
+                    .map(|c| c.descrizione(l))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            Messaggio::SezioniAlternative(prima, seconda) => {
+                format!("give either {} or {}, not both", prima, seconda)
+            }
+            Messaggio::CalcoloMetrica { metrica, dettaglio } => {
+                format!("{} calculation: {}", metrica, dettaglio)
+            }
+            Messaggio::RisultatoNegativo(metrica) => format!("{} result: negative value", metrica),
+            Messaggio::LivelloConfidenzaNonValido(livello) => format!(
+                "invalid confidence level {}, must be between 0 and 1",
+                livello
+            ),
+            Messaggio::PrimoPassaggioSenzaCatture => "no fish caught in the first pass".to_string(),
+            Messaggio::CattureNonDecrescenti {
+                passaggio,
+                precedenti,
+                catture,
+            } => format!(
+                "catches do not decrease at pass {}: {} fish after {}",
+                passaggio, catture, precedenti
+            ),
+            Messaggio::EsemplariInsufficienti { catture, minimo } => format!(
+                "only {} fish caught, at least {} are needed for a reliable estimate",
+                catture, minimo
+            ),
+            Messaggio::StimaSommaCatture => {
+                "removal estimate not computable, the sum of the catches is used".to_string()
+            }
+            Messaggio::AnagraficaMancante(stazione) => {
+                format!("no anagrafica found for station {}", stazione)
+            }
+            Messaggio::StazioneNonCorrispondente {
+                campionamento,
+                anagrafica,
+            } => format!(
+                "campionamento station does not match the anagrafica: {} (anagrafica: {})",
+                campionamento, anagrafica
+            ),
+            Messaggio::DataNonCorrispondente {
+                campionamento,
+                anagrafica,
+            } => format!(
+                "campionamento date does not match the anagrafica: {} (anagrafica: {})",
+                campionamento, anagrafica
+            ),
+            Messaggio::StazioniMultiple(stazioni) => format!(
+                "campionamento contains more than one station: {}",
+                stazioni.join(", ")
+            ),
+            Messaggio::FormatoNonStandard(formato) => {
+                format!("Non-standard format detected: {}", formato.descrizione(l))
+            }
+            Messaggio::TipoFileDiverso { trovato, atteso } => format!(
+                "The header looks like a {} file, expected {}",
+                trovato.etichetta(l),
+                atteso.etichetta(l)
+            ),
+        }
+    }
+}
+
+impl fmt::Display for Messaggio {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let string_representation = self.testo(Lingua::default());
+        write!(f, "{}", string_representation)
+    }
+}
@@ -0,0 +1,227 @@
+// SPDX-License-Identifier: GPL-3.0-only
+/*
+    Copyright (C) 2024-2026 jgabaut, gioninjo
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU General Public License as published by
+    the Free Software Foundation, version 3 of the License.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU General Public License for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use crate::csv::deser::hfbi::{
+    PlainRecordCsvAnagraficaHFBI, PlainRecordCsvCampionamentoHFBI, PlainRecordCsvRiferimentoHFBI,
+};
+use crate::csv::deser::niseci::{
+    PlainRecordCsvAnagraficaNISECI, PlainRecordCsvCampionamentoNISECI,
+    PlainRecordCsvComunitaRiferimentoNISECI, PlainRecordCsvRiferimentoNISECI,
+};
+use crate::csv::parser::hfbi::{
+    check_records_anagrafica_hfbi, check_records_campionamento_hfbi, check_records_riferimento_hfbi,
+};
+use crate::csv::parser::niseci::{
+    check_coerenza_niseci, check_records_anagrafica_niseci, check_records_campionamento_niseci,
+    check_records_comunita_riferimento_niseci, check_records_riferimento_niseci,
+};
+use crate::csv::parser::OpzioniVerifica;
+use crate::diagnostics::Diagnostics;
+use crate::domain::hfbi::{AnagraficaHFBI, CampionamentoHFBI, RiferimentoHFBI};
+use crate::domain::niseci::{AnagraficaNISECI, CampionamentoNISECI, RiferimentoNISECI};
+use crate::error::{ContestoErrore, Error, Posizione};
+use crate::i18n::Messaggio;
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+
+/// Input JSON per il NISECI: anagrafica della stazione, comunità di riferimento e catture.
+/// I record hanno gli stessi nomi di campo dei file csv e vengono validati con le stesse regole di csv::parser.
+/// La comunità di riferimento si indica per esteso in `riferimento`, oppure in `comunita` scegliendo
+/// le specie da CATALOGO_NISECI.
+#[derive(Debug, Deserialize)]
+pub struct InputNISECI {
+    pub anagrafica: PlainRecordCsvAnagraficaNISECI,
+    #[serde(default)]
+    pub riferimento: Vec<PlainRecordCsvRiferimentoNISECI>,
+    #[serde(default)]
+    pub comunita: Option<Vec<PlainRecordCsvComunitaRiferimentoNISECI>>,
+    pub campionamento: Vec<PlainRecordCsvCampionamentoNISECI>,
+}
+
+/// Input JSON per l'HFBI: anagrafica della stazione, catture e, se non si usa RIFERIMENTO_HFBI,
+/// l'elenco delle specie
+#[derive(Debug, Deserialize)]
+pub struct InputHFBI {
+    pub anagrafica: PlainRecordCsvAnagraficaHFBI,
+    #[serde(default)]
+    pub riferimento: Option<Vec<PlainRecordCsvRiferimentoHFBI>>,
+    pub campionamento: Vec<PlainRecordCsvCampionamentoHFBI>,
+}
+
+/// Dati validati, pronti per calculate_niseci()
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ValutazioneNISECI {
+    pub anagrafica: AnagraficaNISECI,
+    pub riferimento: RiferimentoNISECI,
+    pub campionamento: CampionamentoNISECI,
+}
+
+/// Dati validati, pronti per calculate_hfbi()
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ValutazioneHFBI {
+    pub anagrafica: AnagraficaHFBI,
+    pub campionamento: CampionamentoHFBI,
+}
+
+fn converti_errore_json(e: serde_json::Error) -> Error {
+    let contesto = ContestoErrore::da_messaggio(Messaggio::Esterno(e.to_string()));
+    if e.is_io() {
+        return Error::Io(contesto.into());
+    }
+    Error::Deserializzazione(
+        contesto
+            .posizione(Posizione::new(e.line() as u64, 0, 0))
+            .into(),
+    )
+}
+
+fn leggi_json<R: Read, T: serde::de::DeserializeOwned>(reader: R) -> Result<T, Diagnostics> {
+    serde_json::from_reader(reader).map_err(|e| Diagnostics {
+        errori: vec![converti_errore_json(e)],
+        ..Default::default()
+    })
+}
+
+/// Accoda il resoconto di una fase al totale, restituendo il valore solo se valido
+fn raccogli<T>(
+    totale: &mut Diagnostics,
+    esito: Result<(T, Diagnostics), Diagnostics>,
+) -> Option<T> {
+    match esito {
+        Ok((v, diagnostics)) => {
+            totale.merge(diagnostics);
+            Some(v)
+        }
+        Err(diagnostics) => {
+            totale.merge(diagnostics);
+            None
+        }
+    }
+}
+
+/// Legge e valida un input NISECI in formato JSON.
+/// Gli errori di tutte le sezioni vengono riportati insieme; il campionamento viene validato
+/// solo se la comunità di riferimento è valida, e confrontato con l'anagrafica (vedi check_coerenza_niseci()).
+pub fn check_input_niseci_json<R: Read>(
+    reader: R,
+) -> Result<(ValutazioneNISECI, Diagnostics), Diagnostics> {
+    let input: InputNISECI = leggi_json(reader)?;
+    let mut diagnostics = Diagnostics::default();
+
+    let anagrafica = raccogli(
+        &mut diagnostics,
+        check_records_anagrafica_niseci(vec![input.anagrafica]),
+    );
+    let riferimento = match input.comunita {
+        Some(_) if !input.riferimento.is_empty() => {
+            diagnostics.errori.push(Error::ValoreInvalido(
+                ContestoErrore::da_messaggio(Messaggio::SezioniAlternative(
+                    "riferimento",
+                    "comunita",
+                ))
+                .campo("comunita")
+                .into(),
+            ));
+            None
+        }
+        Some(records) => raccogli(
+            &mut diagnostics,
+            check_records_comunita_riferimento_niseci(records, &OpzioniVerifica::default()),
+        ),
+        None => raccogli(
+            &mut diagnostics,
+            check_records_riferimento_niseci(input.riferimento, &OpzioniVerifica::default()),
+        ),
+    };
+    // Solo gli errori: i record sono già conteggiati dal resoconto del campionamento
+    if let Some(anagrafica) = &anagrafica {
+        if let Err(coerenza) = check_coerenza_niseci(anagrafica, &input.campionamento) {
+            diagnostics.errori.extend(coerenza.errori);
+        }
+    }
+    let campionamento = match &riferimento {
+        Some(specie) => raccogli(
+            &mut diagnostics,
+            check_records_campionamento_niseci(
+                input.campionamento,
+                specie.clone(),
+                &OpzioniVerifica::default(),
+            ),
+        ),
+        None => None,
+    };
+    // Il resoconto copre più sezioni
+    diagnostics.sorgente = None;
+
+    match (anagrafica, riferimento, campionamento) {
+        (Some(anagrafica), Some(riferimento), Some(campionamento)) if !diagnostics.has_errori() => {
+            Ok((
+                ValutazioneNISECI {
+                    anagrafica,
+                    riferimento: RiferimentoNISECI::new(riferimento),
+                    campionamento: CampionamentoNISECI::new(campionamento),
+                },
+                diagnostics,
+            ))
+        }
+        _ => Err(diagnostics),
+    }
+}
+
+/// Legge e valida un input HFBI in formato JSON.
+/// Senza `riferimento` le specie vengono cercate in RIFERIMENTO_HFBI; il campionamento viene
+/// validato solo se l'elenco delle specie è valido.
+pub fn check_input_hfbi_json<R: Read>(
+    reader: R,
+) -> Result<(ValutazioneHFBI, Diagnostics), Diagnostics> {
+    let input: InputHFBI = leggi_json(reader)?;
+    let mut diagnostics = Diagnostics::default();
+
+    let anagrafica = raccogli(
+        &mut diagnostics,
+        check_records_anagrafica_hfbi(vec![input.anagrafica]),
+    );
+    let riferimento = match input.riferimento {
+        Some(records) => raccogli(
+            &mut diagnostics,
+            check_records_riferimento_hfbi(records, &OpzioniVerifica::default()),
+        ),
+        None => Some(RiferimentoHFBI::default()),
+    };
+    let campionamento = match &riferimento {
+        Some(riferimento) => raccogli(
+            &mut diagnostics,
+            check_records_campionamento_hfbi(
+                input.campionamento,
+                &OpzioniVerifica::default().riferimento(riferimento),
+            ),
+        ),
+        None => None,
+    };
+    diagnostics.sorgente = None;
+
+    match (anagrafica, campionamento) {
+        (Some(anagrafica), Some(campionamento)) if !diagnostics.has_errori() => Ok((
+            ValutazioneHFBI {
+                anagrafica,
+                campionamento: CampionamentoHFBI::new(campionamento),
+            },
+            diagnostics,
+        )),
+        _ => Err(diagnostics),
+    }
+}
@@ -16,7 +16,13 @@
 */
 
 pub mod csv;
+pub mod diagnostics;
 pub mod domain;
 pub mod engines;
-#[cfg(test)]
+pub mod error;
+pub mod i18n;
+pub mod json;
+pub mod templates;
+pub use diagnostics::Diagnostics;
+pub use error::Error;
 mod tests;
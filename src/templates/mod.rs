@@ -0,0 +1,239 @@
+// SPDX-License-Identifier: GPL-3.0-only
+/*
+    Copyright (C) 2024-2026 jgabaut, gioninjo
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU General Public License as published by
+    the Free Software Foundation, version 3 of the License.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU General Public License for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use crate::csv::ser::FormatoOutput;
+use crate::csv::{
+    TipoRecordCsv, ANAGRAFICA_HFBI_HEADER_FIELDS, ANAGRAFICA_NISECI_HEADER_FIELDS,
+    CAMPIONAMENTO_HFBI_HEADER_FIELDS, CAMPIONAMENTO_NISECI_HEADER_FIELDS,
+    COMUNITA_RIFERIMENTO_NISECI_HEADER_FIELDS, CONDIZIONI_RIFERIMENTO_HFBI_HEADER_FIELDS,
+    RIFERIMENTO_HFBI_HEADER_FIELDS, RIFERIMENTO_NISECI_HEADER_FIELDS,
+};
+use crate::domain::hfbi::{
+    CondizioniRiferimentoKeyHFBI, HabitatHFBI, StagioneHFBI, TabellaCondizioniRiferimentoHFBI,
+    TipoLagunaCostieraHFBI,
+};
+use crate::error::{ContestoErrore, Error};
+use crate::i18n::Messaggio;
+use std::io::Write;
+
+// Righe di esempio, nell'ordine dei campi di *_HEADER_FIELDS e con il punto come separatore decimale.
+// Gli array hanno la lunghezza delle intestazioni, così un campo aggiunto o tolto non compila finché
+// gli esempi non vengono aggiornati. Le condizioni di riferimento HFBI vengono dalla tabella ufficiale.
+const ESEMPIO_RIFERIMENTO_NISECI: [[&str; RIFERIMENTO_NISECI_HEADER_FIELDS.len()]; 2] = [
+    [
+        "Alborella",
+        "Alburnus alborella",
+        "AL",
+        "AUT",
+        "2",
+        "0",
+        "1",
+        "45",
+        "90",
+        "130",
+        "150",
+        "0.5",
+        "0.67",
+        "1.5",
+        "2",
+        "0.052246487",
+        "0.086500523",
+    ],
+    [
+        "Barbo comune",
+        "Barbus plebejus",
+        "BA",
+        "AUT",
+        "2",
+        "0",
+        "1",
+        "80",
+        "170",
+        "210",
+        "300",
+        "0.5",
+        "0.67",
+        "1.5",
+        "2",
+        "0.00720942",
+        "0.036430372",
+    ],
+];
+const ESEMPIO_CAMPIONAMENTO_NISECI: [[&str; CAMPIONAMENTO_NISECI_HEADER_FIELDS.len()]; 3] = [
+    ["07/07/2019", "STAZIONE", "1", "BA", "275", "152"],
+    ["07/07/2019", "STAZIONE", "1", "AL", "62", "2.5"],
+    ["07/07/2019", "STAZIONE", "2", "BA", "206", "78"],
+];
+const ESEMPIO_ANAGRAFICA_NISECI: [[&str; ANAGRAFICA_NISECI_HEADER_FIELDS.len()]; 1] = [[
+    "STAZIONE",
+    "CORPO",
+    "REGIONE",
+    "PROVINCIA",
+    "07/07/2019",
+    "100",
+    "8.5",
+    "0",
+    "",
+    "",
+    "20",
+    "1",
+    "BACINO",
+]];
+const ESEMPIO_CAMPIONAMENTO_HFBI: [[&str; CAMPIONAMENTO_HFBI_HEADER_FIELDS.len()]; 2] =
+    [["AN", "25", "240"], ["CH", "41", "110.5"]];
+const ESEMPIO_ANAGRAFICA_HFBI: [[&str; ANAGRAFICA_HFBI_HEADER_FIELDS.len()]; 1] = [[
+    "STAZIONE",
+    "CORPO",
+    "REGIONE",
+    "PROVINCIA",
+    "07/07/2019",
+    "100",
+    "8.5",
+    "0",
+    "1",
+    "1",
+]];
+const ESEMPIO_RIFERIMENTO_HFBI: [[&str; RIFERIMENTO_HFBI_HEADER_FIELDS.len()]; 2] = [
+    ["Cheppia", "CH", "1", "1", "0", "0", "1", "0", "0", "0", "0"],
+    [
+        "Ghiozzo nero",
+        "GHN",
+        "1",
+        "2",
+        "0.4",
+        "0.4",
+        "0.2",
+        "0",
+        "0",
+        "0",
+        "0",
+    ],
+];
+
+// Le soglie vengono da CATALOGO_NISECI
+const ESEMPIO_COMUNITA_RIFERIMENTO_NISECI: [[&str;
+    COMUNITA_RIFERIMENTO_NISECI_HEADER_FIELDS.len()];
+    3] = [["AL", "1", "2"], ["BA", "1", "2"], ["AB", "0", "0"]];
+
+/// Contenuto del template generato
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ContenutoTemplate {
+    /// Solo l'intestazione
+    Vuoto,
+    /// Intestazione e alcune righe di esempio valide
+    Esempio,
+}
+
+fn righe<const N: usize>(esempio: &[[&str; N]]) -> Vec<Vec<String>> {
+    esempio
+        .iter()
+        .map(|r| r.iter().map(|v| v.to_string()).collect())
+        .collect()
+}
+
+/// Righe della tabella ufficiale (vedi TabellaCondizioniRiferimentoHFBI::ufficiale()),
+/// con i codici letti da csv::parser::hfbi::check_records_condizioni_riferimento_hfbi()
+fn righe_condizioni_riferimento_hfbi() -> Vec<Vec<String>> {
+    let tabella = TabellaCondizioniRiferimentoHFBI::ufficiale();
+    CondizioniRiferimentoKeyHFBI::tutte()
+        .into_iter()
+        .filter_map(|chiave| {
+            let condizioni = tabella.get(&chiave)?;
+            let tipo_laguna = match chiave.tipo_laguna {
+                TipoLagunaCostieraHFBI::MAt1 => 1,
+                TipoLagunaCostieraHFBI::MAt2 => 2,
+                TipoLagunaCostieraHFBI::MAt3 => 3,
+            };
+            let stagione = match chiave.stagione {
+                StagioneHFBI::Primavera => 0,
+                StagioneHFBI::Autunno => 1,
+            };
+            let habitat = match chiave.habitat_vegetato {
+                HabitatHFBI::Vegetato => 0,
+                HabitatHFBI::NonVegetato => 1,
+            };
+            let riga: [String; CONDIZIONI_RIFERIMENTO_HFBI_HEADER_FIELDS.len()] = [
+                tipo_laguna.to_string(),
+                stagione.to_string(),
+                habitat.to_string(),
+                condizioni.bn.to_string(),
+                condizioni.ddom.to_string(),
+                condizioni.dmig.to_string(),
+                condizioni.bbent.to_string(),
+                condizioni.dbent.to_string(),
+                condizioni.dhzp.to_string(),
+            ];
+            Some(riga.to_vec())
+        })
+        .collect()
+}
+
+fn righe_esempio(tipo: TipoRecordCsv) -> Vec<Vec<String>> {
+    match tipo {
+        TipoRecordCsv::RiferimentoNISECI => righe(&ESEMPIO_RIFERIMENTO_NISECI),
+        TipoRecordCsv::CampionamentoNISECI => righe(&ESEMPIO_CAMPIONAMENTO_NISECI),
+        TipoRecordCsv::AnagraficaNISECI => righe(&ESEMPIO_ANAGRAFICA_NISECI),
+        TipoRecordCsv::CampionamentoHFBI => righe(&ESEMPIO_CAMPIONAMENTO_HFBI),
+        TipoRecordCsv::AnagraficaHFBI => righe(&ESEMPIO_ANAGRAFICA_HFBI),
+        TipoRecordCsv::RiferimentoHFBI => righe(&ESEMPIO_RIFERIMENTO_HFBI),
+        TipoRecordCsv::CondizioniRiferimentoHFBI => righe_condizioni_riferimento_hfbi(),
+        TipoRecordCsv::ComunitaRiferimentoNISECI => righe(&ESEMPIO_COMUNITA_RIFERIMENTO_NISECI),
+    }
+}
+
+/// Scrive il template di un file di input, con l'intestazione presa da TipoRecordCsv::header_fields()
+pub fn write_template<W: Write>(
+    writer: W,
+    tipo: TipoRecordCsv,
+    formato: &FormatoOutput,
+    contenuto: ContenutoTemplate,
+) -> Result<(), Error> {
+    let converti_errore = |e: csv::Error| {
+        Error::Io(ContestoErrore::da_messaggio(Messaggio::Esterno(e.to_string())).into())
+    };
+    let mut wtr = csv::WriterBuilder::new()
+        .delimiter(formato.delimitatore)
+        .from_writer(writer);
+    wtr.write_record(tipo.header_fields())
+        .map_err(converti_errore)?;
+    if contenuto == ContenutoTemplate::Esempio {
+        let separatore = (formato.separatore_decimale as char).to_string();
+        for riga in righe_esempio(tipo) {
+            let campi = riga
+                .iter()
+                .zip(tipo.header_field_types())
+                .map(|(valore, tipo_campo)| match *tipo_campo {
+                    "f32" => valore.replace('.', &separatore),
+                    _ => valore.to_string(),
+                });
+            wtr.write_record(campi).map_err(converti_errore)?;
+        }
+    }
+    wtr.flush()?;
+    Ok(())
+}
+
+/// Come write_template(), restituendo il contenuto come stringa
+pub fn genera_template(
+    tipo: TipoRecordCsv,
+    formato: &FormatoOutput,
+    contenuto: ContenutoTemplate,
+) -> String {
+    let mut buf = Vec::new();
+    write_template(&mut buf, tipo, formato, contenuto).expect("writing to a Vec should not fail");
+    String::from_utf8(buf).expect("template fields are valid UTF-8")
+}
@@ -15,31 +15,71 @@
     along with this program.  If not, see <https://www.gnu.org/licenses/>.
 */
 
+use crate::csv::deser::colonne::AliasColonne;
+use crate::csv::deser::encoding::{rileva_codifica, CodificaTesto, Utf8Reader};
+use crate::csv::deser::sniff::{sniff_formato, LocaleCsv};
 use crate::csv::deser::{
     hfbi::{
         check_anagrafica_hfbi_reader, check_campionamento_hfbi_reader,
+        check_condizioni_riferimento_hfbi_reader, check_riferimento_hfbi_reader,
+        PlainRecordCsvAnagraficaHFBI, PlainRecordCsvCampionamentoHFBI,
+        PlainRecordCsvCondizioniRiferimentoHFBI, PlainRecordCsvRiferimentoHFBI,
         VeryItalianRecordCsvAnagraficaHFBI, VeryItalianRecordCsvCampionamentoHFBI,
+        VeryItalianRecordCsvCondizioniRiferimentoHFBI, VeryItalianRecordCsvRiferimentoHFBI,
     },
+    leggi_csv, leggi_csv_path,
     niseci::{
         check_anagrafica_niseci_reader, check_campionamento_niseci_reader,
-        check_riferimento_niseci_reader, VeryItalianRecordCsvAnagraficaNISECI,
-        VeryItalianRecordCsvCampionamentoNISECI, VeryItalianRecordCsvRiferimentoNISECI,
+        check_comunita_riferimento_niseci_reader, check_riferimento_niseci_reader,
+        PlainRecordCsvAnagraficaNISECI, PlainRecordCsvCampionamentoNISECI,
+        PlainRecordCsvComunitaRiferimentoNISECI, PlainRecordCsvRiferimentoNISECI,
+        VeryItalianRecordCsvAnagraficaNISECI, VeryItalianRecordCsvCampionamentoNISECI,
+        VeryItalianRecordCsvComunitaRiferimentoNISECI, VeryItalianRecordCsvRiferimentoNISECI,
     },
-    translate_error_message,
+    OpzioniLettura,
 };
 use crate::csv::parser::{
-    hfbi::{check_records_anagrafica_hfbi, check_records_campionamento_hfbi},
+    hfbi::{
+        check_records_anagrafica_hfbi, check_records_campionamento_hfbi,
+        check_records_condizioni_riferimento_hfbi, check_records_registro_anagrafica_hfbi,
+        check_records_riferimento_hfbi,
+    },
     niseci::{
-        check_records_anagrafica_niseci, check_records_campionamento_niseci,
-        check_records_riferimento_niseci,
+        check_coerenza_campionamenti_niseci, check_coerenza_niseci,
+        check_records_anagrafica_niseci, check_records_campionamenti_niseci,
+        check_records_campionamento_niseci, check_records_comunita_riferimento_niseci,
+        check_records_registro_anagrafica_niseci, check_records_riferimento_niseci,
     },
+    parse_date, OpzioniVerifica,
 };
+use crate::csv::ser::FormatoOutput;
 use crate::csv::{
-    ANAGRAFICA_HFBI_HEADER, ANAGRAFICA_NISECI_HEADER, CAMPIONAMENTO_HFBI_HEADER,
-    CAMPIONAMENTO_NISECI_HEADER, RIFERIMENTO_NISECI_HEADER,
+    TipoRecordCsv, ANAGRAFICA_HFBI_HEADER, ANAGRAFICA_NISECI_HEADER, CAMPIONAMENTO_HFBI_HEADER,
+    CAMPIONAMENTO_NISECI_HEADER, COMUNITA_RIFERIMENTO_NISECI_HEADER,
+    CONDIZIONI_RIFERIMENTO_HFBI_HEADER, RIFERIMENTO_HFBI_HEADER, RIFERIMENTO_NISECI_HEADER,
+};
+use crate::diagnostics::ValidationMode;
+use crate::domain::hfbi::{
+    cerca_specie_hfbi, indice_riferimento_hfbi, RiferimentoHFBI, TabellaCondizioniRiferimentoHFBI,
+    RIFERIMENTO_HFBI,
 };
-use crate::domain::niseci::SpecieNISECI;
-use std::io::Cursor;
+use crate::domain::niseci::catalogo::{cerca_specie_catalogo_niseci, CATALOGO_NISECI};
+use crate::domain::niseci::{
+    AnagraficaNISECI, CampionamentoNISECI, ChiaveCampionamentoNISECI, RiferimentoNISECI,
+    SpecieNISECI,
+};
+use crate::domain::niseci::{IdroEcoRegioneNISECI, StatoEcologicoNISECI};
+use crate::domain::registro::RegistroStazioni;
+use crate::domain::specie::{CorrezioniSpecie, IndiceSpecie};
+use crate::error::Error;
+use crate::i18n::{Etichetta, Lingua, Messaggio, MotivoDataNonValida};
+use crate::json::{check_input_hfbi_json, check_input_niseci_json, ValutazioneNISECI};
+use crate::templates::{genera_template, ContenutoTemplate};
+use crate::tests::test_utils::create_dummy_anagrafica;
+use chrono::NaiveDate;
+use std::collections::BTreeMap;
+use std::io::{Cursor, Read};
+use std::path::Path;
 
 #[test]
 fn test_csv_riferimento_niseci_found_string_expect_int() {
@@ -52,9 +92,9 @@ fn test_csv_riferimento_niseci_found_string_expect_int() {
         check_riferimento_niseci_reader::<_, VeryItalianRecordCsvRiferimentoNISECI>(reader, true);
 
     assert!(result.is_err());
-    let errors = result.err().unwrap();
+    let errors = result.err().unwrap().errori;
     assert_eq!(errors.len(), 1); // One invalid record
-    let translated_error = translate_error_message(&errors[0].to_string());
+    let translated_error = errors[0].to_string();
     assert!(translated_error.contains("tipo non valido"));
 }
 
@@ -69,9 +109,9 @@ fn test_csv_riferimento_niseci_found_string_expect_float() {
         check_riferimento_niseci_reader::<_, VeryItalianRecordCsvRiferimentoNISECI>(reader, true);
 
     assert!(result.is_err());
-    let errors = result.err().unwrap();
+    let errors = result.err().unwrap().errori;
     assert_eq!(errors.len(), 1); // One invalid record
-    let translated_error = translate_error_message(&errors[0].to_string());
+    let translated_error = errors[0].to_string();
     assert!(translated_error.contains("tipo non valido"));
 }
 
@@ -86,9 +126,9 @@ fn test_csv_riferimento_niseci_found_empty_string_expect_int() {
         check_riferimento_niseci_reader::<_, VeryItalianRecordCsvRiferimentoNISECI>(reader, true);
 
     assert!(result.is_err());
-    let errors = result.err().unwrap();
+    let errors = result.err().unwrap().errori;
     assert_eq!(errors.len(), 1); // One invalid record
-    let translated_error = translate_error_message(&errors[0].to_string());
+    let translated_error = errors[0].to_string();
     assert!(translated_error.contains("campo vuoto"));
 }
 
@@ -103,9 +143,9 @@ fn test_csv_riferimento_niseci_found_empty_string_expect_float() {
         check_riferimento_niseci_reader::<_, VeryItalianRecordCsvRiferimentoNISECI>(reader, true);
 
     assert!(result.is_err());
-    let errors = result.err().unwrap();
+    let errors = result.err().unwrap().errori;
     assert_eq!(errors.len(), 1); // One invalid record
-    let translated_error = translate_error_message(&errors[0].to_string());
+    let translated_error = errors[0].to_string();
     assert!(translated_error.contains("campo vuoto"));
 }
 
@@ -120,9 +160,9 @@ fn test_csv_riferimento_niseci_found_float_expect_int() {
         check_riferimento_niseci_reader::<_, VeryItalianRecordCsvRiferimentoNISECI>(reader, true);
 
     assert!(result.is_err());
-    let errors = result.err().unwrap();
+    let errors = result.err().unwrap().errori;
     assert_eq!(errors.len(), 1); // One invalid record
-    let translated_error = translate_error_message(&errors[0].to_string());
+    let translated_error = errors[0].to_string();
     assert!(translated_error.contains("tipo non valido"));
 }
 
@@ -139,9 +179,9 @@ fn test_csv_riferimento_niseci_err_found_cl_negative() {
         check_riferimento_niseci_reader::<_, VeryItalianRecordCsvRiferimentoNISECI>(reader, true);
 
     assert!(result.is_err());
-    let errors = result.err().unwrap();
+    let errors = result.err().unwrap().errori;
     assert_eq!(errors.len(), 1); // One invalid record
-    let translated_error = translate_error_message(&errors[0].to_string());
+    let translated_error = errors[0].to_string();
     assert!(translated_error.contains("tipo non valido"));
 }
 
@@ -156,10 +196,10 @@ fn test_csv_riferimento_niseci_lessfields() {
         check_riferimento_niseci_reader::<_, VeryItalianRecordCsvRiferimentoNISECI>(reader, true);
 
     assert!(result.is_err());
-    let errors = result.err().unwrap();
+    let errors = result.err().unwrap().errori;
     assert_eq!(errors.len(), 1); // One invalid record
 
-    let translated_error = translate_error_message(&errors[0].to_string());
+    let translated_error = errors[0].to_string();
     assert!(translated_error.contains("numero campi"));
 }
 
@@ -175,7 +215,7 @@ fn test_valid_csv_riferimento_niseci() {
     let result =
         check_riferimento_niseci_reader::<_, VeryItalianRecordCsvRiferimentoNISECI>(reader, true);
 
-    assert!(!result.is_err());
+    assert!(result.is_ok());
 }
 
 #[test]
@@ -186,7 +226,7 @@ fn test_empty_csv_riferimento_niseci() {
         check_riferimento_niseci_reader::<_, VeryItalianRecordCsvRiferimentoNISECI>(reader, true);
 
     assert!(result.is_ok());
-    let records = result.unwrap();
+    let (records, _) = result.unwrap();
     assert_eq!(records.len(), 0); // No records
 }
 
@@ -210,11 +250,12 @@ fn test_valid_recordcsv_riferimento_niseci() {
         ad_juv_soglia4: 0.4,
         dens_soglia1: 0.1,
         dens_soglia2: 0.2,
+        riga_sorgente: None,
     };
     let recordcsv_data = vec![record_1];
-    let result = check_records_riferimento_niseci(recordcsv_data);
+    let result = check_records_riferimento_niseci(recordcsv_data, &OpzioniVerifica::default());
 
-    assert!(!result.is_err());
+    assert!(result.is_ok());
 }
 
 #[test]
@@ -237,14 +278,17 @@ fn test_recordcsv_riferimento_niseci_soglie_cl_error() {
         ad_juv_soglia4: 0.4,
         dens_soglia1: 0.1,
         dens_soglia2: 0.2,
+        riga_sorgente: None,
     };
     let recordcsv_data = vec![record_1];
-    let result = check_records_riferimento_niseci(recordcsv_data);
+    let result = check_records_riferimento_niseci(recordcsv_data, &OpzioniVerifica::default());
 
     assert!(result.is_err());
 
-    let errors = result.err().unwrap();
+    let errors = result.err().unwrap().errori;
     assert_eq!(errors.len(), 1);
+    assert!(matches!(errors[0], Error::SoglieCLNonCrescenti(_)));
+    assert_eq!(errors[0].record(), Some(1));
 }
 
 #[test]
@@ -267,13 +311,14 @@ fn test_recordcsv_riferimento_niseci_soglie_ad_juv_error() {
         ad_juv_soglia4: 0.4,
         dens_soglia1: 0.1,
         dens_soglia2: 0.2,
+        riga_sorgente: None,
     };
     let recordcsv_data = vec![record_1];
-    let result = check_records_riferimento_niseci(recordcsv_data);
+    let result = check_records_riferimento_niseci(recordcsv_data, &OpzioniVerifica::default());
 
     assert!(result.is_err());
 
-    let errors = result.err().unwrap();
+    let errors = result.err().unwrap().errori;
     assert_eq!(errors.len(), 1);
 }
 
@@ -289,9 +334,9 @@ fn test_csv_campionamento_niseci_found_string_expect_int() {
     );
 
     assert!(result.is_err());
-    let errors = result.err().unwrap();
+    let errors = result.err().unwrap().errori;
     assert_eq!(errors.len(), 1); // One invalid record
-    let translated_error = translate_error_message(&errors[0].to_string());
+    let translated_error = errors[0].to_string();
     assert!(translated_error.contains("tipo non valido"));
 }
 
@@ -307,9 +352,9 @@ fn test_csv_campionamento_niseci_found_empty_string_expect_int() {
     );
 
     assert!(result.is_err());
-    let errors = result.err().unwrap();
+    let errors = result.err().unwrap().errori;
     assert_eq!(errors.len(), 1); // One invalid record
-    let translated_error = translate_error_message(&errors[0].to_string());
+    let translated_error = errors[0].to_string();
     assert!(translated_error.contains("campo vuoto"));
 }
 
@@ -325,9 +370,9 @@ fn test_csv_campionamento_niseci_found_float_expect_int() {
     );
 
     assert!(result.is_err());
-    let errors = result.err().unwrap();
+    let errors = result.err().unwrap().errori;
     assert_eq!(errors.len(), 1); // One invalid record
-    let translated_error = translate_error_message(&errors[0].to_string());
+    let translated_error = errors[0].to_string();
     assert!(translated_error.contains("tipo non valido"));
 }
 
@@ -343,10 +388,10 @@ fn test_csv_campionamento_niseci_lessfields() {
     );
 
     assert!(result.is_err());
-    let errors = result.err().unwrap();
+    let errors = result.err().unwrap().errori;
     assert_eq!(errors.len(), 1); // One invalid record
 
-    let translated_error = translate_error_message(&errors[0].to_string());
+    let translated_error = errors[0].to_string();
     assert!(translated_error.contains("numero campi"));
 }
 
@@ -363,7 +408,7 @@ fn test_valid_csv_campionamento_niseci() {
         reader, true,
     );
 
-    assert!(!result.is_err());
+    assert!(result.is_ok());
 }
 
 #[test]
@@ -375,7 +420,7 @@ fn test_empty_csv_campionamento_niseci() {
     );
 
     assert!(result.is_ok());
-    let records = result.unwrap();
+    let (records, _) = result.unwrap();
     assert_eq!(records.len(), 0); // No records
 }
 
@@ -384,6 +429,7 @@ fn test_valid_recordcsv_campionamento_niseci() {
     let specie_1 = SpecieNISECI {
         id: "1234".to_string(),
         nome: "Cervus elaphus".to_string(),
+        nome_comune: String::new(),
         tipo_autoctono: 1,
         tipo_alloctono: 0,
         specie_attesa: true,
@@ -408,11 +454,127 @@ fn test_valid_recordcsv_campionamento_niseci() {
         codice_specie: "1234".to_string(),
         lunghezza: 100,
         peso: 100.0,
+        riga_sorgente: None,
     };
     let recordcsv_data = vec![record_1];
-    let result = check_records_campionamento_niseci(recordcsv_data, riferimento_specie);
+    let result = check_records_campionamento_niseci(
+        recordcsv_data,
+        riferimento_specie,
+        &OpzioniVerifica::default(),
+    );
+
+    assert!(result.is_ok());
+}
+
+#[test]
+fn test_parse_date_formats() {
+    let data = NaiveDate::from_ymd_opt(2019, 7, 7).unwrap();
+    for s in [
+        "07/07/2019",
+        "7-7-2019",
+        "07.07.2019",
+        "07/07/19",
+        "2019-07-07",
+        "2019-07-07T10:30:00",
+        "07/07/2019 10:30",
+        " 07/07/2019 ",
+        "43653",
+        "43653,25",
+    ] {
+        assert_eq!(parse_date(s), Ok(data), "{s}");
+    }
+    // Anno a due cifre: 69 è ancora nel 2000, 70 nel 1900
+    assert_eq!(
+        parse_date("31/12/69").ok(),
+        NaiveDate::from_ymd_opt(2069, 12, 31)
+    );
+    assert_eq!(
+        parse_date("01/01/70").ok(),
+        NaiveDate::from_ymd_opt(1970, 1, 1)
+    );
+    assert_eq!(
+        parse_date("18264").ok(),
+        NaiveDate::from_ymd_opt(1950, 1, 1)
+    );
+    // Numeri troppo piccoli per essere una data di campionamento esportata da Excel
+    for s in ["2019", "1.5", "0", "60", "18263"] {
+        assert_eq!(parse_date(s), Err(MotivoDataNonValida::FuoriRange), "{s}");
+    }
+    assert_eq!(
+        parse_date("31/02/2019"),
+        Err(MotivoDataNonValida::FuoriRange)
+    );
+    assert!(parse_date("foo").is_err());
+    assert!(parse_date("").is_err());
+}
+
+#[test]
+fn test_recordcsv_campionamento_niseci_dates() {
+    let specie = SpecieNISECI {
+        id: "1234".to_string(),
+        nome: "Cervus elaphus".to_string(),
+        nome_comune: String::new(),
+        tipo_autoctono: 1,
+        tipo_alloctono: 0,
+        specie_attesa: true,
+        cl_soglia1: 0,
+        cl_soglia2: 0,
+        cl_soglia3: 0,
+        cl_soglia4: 0,
+        ad_juv_soglia1: 0.0,
+        ad_juv_soglia2: 0.0,
+        ad_juv_soglia3: 0.0,
+        ad_juv_soglia4: 0.0,
+        dens_soglia1: 1.0,
+        dens_soglia2: 2.0,
+    };
+    let record = |data: &str| VeryItalianRecordCsvCampionamentoNISECI {
+        data: data.to_string(),
+        stazione: "Foo".to_string(),
+        num_passaggio: 1,
+        codice_specie: "1234".to_string(),
+        lunghezza: 100,
+        peso: 100.0,
+        riga_sorgente: None,
+    };
+
+    let (records, _) = check_records_campionamento_niseci(
+        vec![record("08.07.2007"), record("7/7/07")],
+        vec![specie.clone()],
+        &OpzioniVerifica::default(),
+    )
+    .unwrap();
+    let campionamento = CampionamentoNISECI::new(records);
+    let data = NaiveDate::from_ymd_opt(2007, 7, 7).unwrap();
+    assert_eq!(campionamento.get_data(), Some(data));
+    assert_eq!(
+        campionamento.get_date(),
+        vec![data, data.succ_opt().unwrap()]
+    );
 
-    assert!(!result.is_err());
+    let errors = check_records_campionamento_niseci(
+        vec![record("foo")],
+        vec![specie.clone()],
+        &OpzioniVerifica::default(),
+    )
+    .err()
+    .unwrap()
+    .errori;
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].campo(), Some("data"));
+
+    // Stessa data scritta in formati diversi: un solo campionamento
+    let (campionamenti, _) = check_records_campionamenti_niseci(
+        vec![record("07/07/2007"), record("2007-07-07"), record("39270")],
+        vec![specie],
+        &OpzioniVerifica::default(),
+    )
+    .unwrap();
+    assert_eq!(campionamenti.len(), 1);
+    let (chiave, campionamento) = campionamenti.iter().next().unwrap();
+    assert_eq!(chiave.data, data);
+    assert_eq!(chiave.to_string(), "Foo (07/07/2007)");
+    assert_eq!(campionamento.campionamento.len(), 3);
 }
 
 #[test]
@@ -426,9 +588,9 @@ fn test_csv_anagrafica_niseci_found_string_expect_int() {
         check_anagrafica_niseci_reader::<_, VeryItalianRecordCsvAnagraficaNISECI>(reader, true);
 
     assert!(result.is_err());
-    let errors = result.err().unwrap();
+    let errors = result.err().unwrap().errori;
     assert_eq!(errors.len(), 1); // One invalid record
-    let translated_error = translate_error_message(&errors[0].to_string());
+    let translated_error = errors[0].to_string();
     assert!(translated_error.contains("tipo non valido"));
 }
 
@@ -443,9 +605,9 @@ fn test_csv_anagrafica_niseci_found_empty_string_expect_int() {
         check_anagrafica_niseci_reader::<_, VeryItalianRecordCsvAnagraficaNISECI>(reader, true);
 
     assert!(result.is_err());
-    let errors = result.err().unwrap();
+    let errors = result.err().unwrap().errori;
     assert_eq!(errors.len(), 1); // One invalid record
-    let translated_error = translate_error_message(&errors[0].to_string());
+    let translated_error = errors[0].to_string();
     assert!(translated_error.contains("campo vuoto"));
 }
 
@@ -460,9 +622,9 @@ fn test_csv_anagrafica_niseci_found_float_expect_int() {
         check_anagrafica_niseci_reader::<_, VeryItalianRecordCsvAnagraficaNISECI>(reader, true);
 
     assert!(result.is_err());
-    let errors = result.err().unwrap();
+    let errors = result.err().unwrap().errori;
     assert_eq!(errors.len(), 1); // One invalid record
-    let translated_error = translate_error_message(&errors[0].to_string());
+    let translated_error = errors[0].to_string();
     assert!(translated_error.contains("tipo non valido"));
 }
 
@@ -477,10 +639,10 @@ fn test_csv_anagrafica_niseci_lessfields() {
         check_anagrafica_niseci_reader::<_, VeryItalianRecordCsvAnagraficaNISECI>(reader, true);
 
     assert!(result.is_err());
-    let errors = result.err().unwrap();
+    let errors = result.err().unwrap().errori;
     assert_eq!(errors.len(), 1); // One invalid record
 
-    let translated_error = translate_error_message(&errors[0].to_string());
+    let translated_error = errors[0].to_string();
     assert!(translated_error.contains("numero campi"));
 }
 
@@ -494,7 +656,7 @@ fn test_valid_csv_anagrafica_niseci() {
     let result =
         check_anagrafica_niseci_reader::<_, VeryItalianRecordCsvAnagraficaNISECI>(reader, true);
 
-    assert!(!result.is_err());
+    assert!(result.is_ok());
 }
 
 #[test]
@@ -505,7 +667,7 @@ fn test_empty_csv_anagrafica_niseci() {
         check_anagrafica_niseci_reader::<_, VeryItalianRecordCsvAnagraficaNISECI>(reader, true);
 
     assert!(result.is_ok());
-    let records = result.unwrap();
+    let (records, _) = result.unwrap();
     assert_eq!(records.len(), 0); // No records
 }
 
@@ -525,11 +687,12 @@ fn test_valid_recordcsv_anagrafica_niseci() {
         idro_eco_regione: 0,
         area_alpina: 0,
         nome_bacino: "Foo".to_string(),
+        riga_sorgente: None,
     };
     let recordcsv_data = vec![record_1];
     let result = check_records_anagrafica_niseci(recordcsv_data);
 
-    assert!(!result.is_err());
+    assert!(result.is_ok());
 }
 
 #[test]
@@ -540,9 +703,9 @@ fn test_csv_campionamento_hfbi_found_string_expect_int() {
         check_campionamento_hfbi_reader::<_, VeryItalianRecordCsvCampionamentoHFBI>(reader, true);
 
     assert!(result.is_err());
-    let errors = result.err().unwrap();
+    let errors = result.err().unwrap().errori;
     assert_eq!(errors.len(), 1); // One invalid record
-    let translated_error = translate_error_message(&errors[0].to_string());
+    let translated_error = errors[0].to_string();
     assert!(translated_error.contains("tipo non valido"));
 }
 
@@ -554,9 +717,9 @@ fn test_csv_campionamento_hfbi_found_empty_string_expect_int() {
         check_campionamento_hfbi_reader::<_, VeryItalianRecordCsvCampionamentoHFBI>(reader, true);
 
     assert!(result.is_err());
-    let errors = result.err().unwrap();
+    let errors = result.err().unwrap().errori;
     assert_eq!(errors.len(), 1); // One invalid record
-    let translated_error = translate_error_message(&errors[0].to_string());
+    let translated_error = errors[0].to_string();
     assert!(translated_error.contains("campo vuoto"));
 }
 
@@ -568,9 +731,9 @@ fn test_csv_campionamento_hfbi_found_float_expect_int() {
         check_campionamento_hfbi_reader::<_, VeryItalianRecordCsvCampionamentoHFBI>(reader, true);
 
     assert!(result.is_err());
-    let errors = result.err().unwrap();
+    let errors = result.err().unwrap().errori;
     assert_eq!(errors.len(), 1); // One invalid record
-    let translated_error = translate_error_message(&errors[0].to_string());
+    let translated_error = errors[0].to_string();
     assert!(translated_error.contains("tipo non valido"));
 }
 
@@ -582,10 +745,10 @@ fn test_csv_campionamento_hfbi_lessfields() {
         check_campionamento_hfbi_reader::<_, VeryItalianRecordCsvCampionamentoHFBI>(reader, true);
 
     assert!(result.is_err());
-    let errors = result.err().unwrap();
+    let errors = result.err().unwrap().errori;
     assert_eq!(errors.len(), 1); // One invalid record
 
-    let translated_error = translate_error_message(&errors[0].to_string());
+    let translated_error = errors[0].to_string();
     assert!(translated_error.contains("numero campi"));
 }
 
@@ -596,7 +759,7 @@ fn test_valid_csv_campionamento_hfbi() {
     let result =
         check_campionamento_hfbi_reader::<_, VeryItalianRecordCsvCampionamentoHFBI>(reader, true);
 
-    assert!(!result.is_err());
+    assert!(result.is_ok());
 }
 
 #[test]
@@ -607,7 +770,7 @@ fn test_empty_csv_campionamento_hfbi() {
         check_campionamento_hfbi_reader::<_, VeryItalianRecordCsvCampionamentoHFBI>(reader, true);
 
     assert!(result.is_ok());
-    let records = result.unwrap();
+    let (records, _) = result.unwrap();
     assert_eq!(records.len(), 0); // No records
 }
 
@@ -617,11 +780,12 @@ fn test_valid_recordcsv_campionamento_hfbi() {
         codice_specie: "AN".to_string(),
         peso: 100.0,
         numero_individui: 10,
+        riga_sorgente: None,
     };
     let recordcsv_data = vec![record_1];
-    let result = check_records_campionamento_hfbi(recordcsv_data);
+    let result = check_records_campionamento_hfbi(recordcsv_data, &OpzioniVerifica::default());
 
-    assert!(!result.is_err());
+    assert!(result.is_ok());
 }
 
 #[test]
@@ -635,9 +799,9 @@ fn test_csv_anagrafica_hfbi_found_string_expect_int() {
         check_anagrafica_hfbi_reader::<_, VeryItalianRecordCsvAnagraficaHFBI>(reader, true);
 
     assert!(result.is_err());
-    let errors = result.err().unwrap();
+    let errors = result.err().unwrap().errori;
     assert_eq!(errors.len(), 1); // One invalid record
-    let translated_error = translate_error_message(&errors[0].to_string());
+    let translated_error = errors[0].to_string();
     assert!(translated_error.contains("tipo non valido"));
 }
 
@@ -652,9 +816,9 @@ fn test_csv_anagrafica_hfbi_found_empty_string_expect_int() {
         check_anagrafica_hfbi_reader::<_, VeryItalianRecordCsvAnagraficaHFBI>(reader, true);
 
     assert!(result.is_err());
-    let errors = result.err().unwrap();
+    let errors = result.err().unwrap().errori;
     assert_eq!(errors.len(), 1); // One invalid record
-    let translated_error = translate_error_message(&errors[0].to_string());
+    let translated_error = errors[0].to_string();
     assert!(translated_error.contains("campo vuoto"));
 }
 
@@ -669,9 +833,9 @@ fn test_csv_anagrafica_hfbi_found_float_expect_int() {
         check_anagrafica_hfbi_reader::<_, VeryItalianRecordCsvAnagraficaHFBI>(reader, true);
 
     assert!(result.is_err());
-    let errors = result.err().unwrap();
+    let errors = result.err().unwrap().errori;
     assert_eq!(errors.len(), 1); // One invalid record
-    let translated_error = translate_error_message(&errors[0].to_string());
+    let translated_error = errors[0].to_string();
     assert!(translated_error.contains("tipo non valido"));
 }
 
@@ -686,10 +850,10 @@ fn test_csv_anagrafica_hfbi_lessfields() {
         check_anagrafica_hfbi_reader::<_, VeryItalianRecordCsvAnagraficaHFBI>(reader, true);
 
     assert!(result.is_err());
-    let errors = result.err().unwrap();
+    let errors = result.err().unwrap().errori;
     assert_eq!(errors.len(), 1); // One invalid record
 
-    let translated_error = translate_error_message(&errors[0].to_string());
+    let translated_error = errors[0].to_string();
     assert!(translated_error.contains("numero campi"));
 }
 
@@ -703,7 +867,7 @@ fn test_valid_csv_anagrafica_hfbi() {
     let result =
         check_anagrafica_hfbi_reader::<_, VeryItalianRecordCsvAnagraficaHFBI>(reader, true);
 
-    assert!(!result.is_err());
+    assert!(result.is_ok());
 }
 
 #[test]
@@ -714,7 +878,7 @@ fn test_empty_csv_anagrafica_hfbi() {
         check_anagrafica_hfbi_reader::<_, VeryItalianRecordCsvAnagraficaHFBI>(reader, true);
 
     assert!(result.is_ok());
-    let records = result.unwrap();
+    let (records, _) = result.unwrap();
     assert_eq!(records.len(), 0); // No records
 }
 
@@ -731,9 +895,1503 @@ fn test_valid_recordcsv_anagrafica_hfbi() {
         stagione: 0,
         habitat: 1,
         tipo_laguna: 1,
+        riga_sorgente: None,
     };
     let recordcsv_data = vec![record_1];
     let result = check_records_anagrafica_hfbi(recordcsv_data);
 
-    assert!(!result.is_err());
+    assert!(result.is_ok());
+}
+
+#[test]
+fn test_csv_error_has_field_value_and_position() {
+    let csv_data = format!(
+        "{}\nCervo;Cervus elaphus;1234;Italia;abc;0;1;10;20;30;40;0.1;0.2;0.3;0.4;0.01;0.02",
+        RIFERIMENTO_NISECI_HEADER
+    );
+    let reader = Cursor::new(csv_data);
+    let result =
+        check_riferimento_niseci_reader::<_, VeryItalianRecordCsvRiferimentoNISECI>(reader, true);
+
+    let errors = result.err().unwrap().errori;
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(errors[0], Error::Deserializzazione(_)));
+    assert_eq!(errors[0].campo(), Some("tipoAutoctono"));
+    assert_eq!(errors[0].valore(), Some("abc"));
+    assert_eq!(errors[0].posizione().map(|p| p.riga), Some(2));
+}
+
+#[test]
+fn test_csv_error_lessfields_is_numero_campi() {
+    let csv_data = format!("{}\nAN;25", CAMPIONAMENTO_HFBI_HEADER);
+    let reader = Cursor::new(csv_data);
+    let result =
+        check_campionamento_hfbi_reader::<_, VeryItalianRecordCsvCampionamentoHFBI>(reader, true);
+
+    let errors = result.err().unwrap().errori;
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(errors[0], Error::NumeroCampi(_)));
+}
+
+#[test]
+fn test_record_error_has_field_and_value() {
+    let csv_data = format!("{}\nAN;0;240", CAMPIONAMENTO_HFBI_HEADER);
+    let reader = Cursor::new(csv_data);
+    let records =
+        check_campionamento_hfbi_reader::<_, VeryItalianRecordCsvCampionamentoHFBI>(reader, true)
+            .unwrap()
+            .0;
+    let errors = check_records_campionamento_hfbi(records, &OpzioniVerifica::default())
+        .err()
+        .unwrap()
+        .errori;
+
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(errors[0], Error::ValoreInvalido(_)));
+    assert_eq!(errors[0].campo(), Some("numeroIndividui"));
+    assert_eq!(errors[0].valore(), Some("0"));
+    assert_eq!(errors[0].record(), Some(1));
+    assert!(errors[0]
+        .to_string()
+        .starts_with("Errore record campionamento HFBI: Record 1: "));
+}
+
+#[test]
+fn test_csv_diagnostics_counts() {
+    let csv_data = format!(
+        "{}\nAN;25;240\nCH;abc;110\nCEC;2;15",
+        CAMPIONAMENTO_HFBI_HEADER
+    );
+    let reader = Cursor::new(csv_data);
+    let result =
+        check_campionamento_hfbi_reader::<_, VeryItalianRecordCsvCampionamentoHFBI>(reader, true);
+
+    let diagnostics = result.err().unwrap();
+    assert_eq!(diagnostics.sorgente, Some(TipoRecordCsv::CampionamentoHFBI));
+    assert_eq!(diagnostics.record_validi, 2);
+    assert_eq!(diagnostics.record_non_validi, 1);
+    assert_eq!(diagnostics.errori.len(), 1);
+    assert!(diagnostics.avvisi.is_empty());
+
+    let csv_data = format!("{}\nAN;25;240\nCEC;2;15", CAMPIONAMENTO_HFBI_HEADER);
+    let reader = Cursor::new(csv_data);
+    let (records, diagnostics) =
+        check_campionamento_hfbi_reader::<_, VeryItalianRecordCsvCampionamentoHFBI>(reader, true)
+            .unwrap();
+    assert_eq!(records.len(), 2);
+    assert_eq!(diagnostics.record_validi, 2);
+    assert!(!diagnostics.has_errori());
+}
+
+#[test]
+fn test_csv_lenient_mode_drops_invalid_rows() {
+    let csv_data = format!(
+        "{}\nAN;25;240\nCH;abc;110\nCEC;2;15",
+        CAMPIONAMENTO_HFBI_HEADER
+    );
+
+    let result = leggi_csv::<_, VeryItalianRecordCsvCampionamentoHFBI>(
+        Cursor::new(csv_data.clone()),
+        &OpzioniLettura::default().modalita(ValidationMode::Strict),
+    );
+    assert!(result.is_err());
+
+    let (records, diagnostics) = leggi_csv::<_, VeryItalianRecordCsvCampionamentoHFBI>(
+        Cursor::new(csv_data),
+        &OpzioniLettura::default().modalita(ValidationMode::Lenient),
+    )
+    .unwrap();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[1].codice_specie, "CEC");
+    assert!(!diagnostics.has_errori());
+    assert_eq!(diagnostics.record_validi, 2);
+    assert_eq!(diagnostics.record_non_validi, 1);
+    assert_eq!(diagnostics.scartati.len(), 1);
+    assert_eq!(diagnostics.scartati[0].record, 2);
+    assert_eq!(diagnostics.scartati[0].posizione.as_ref().unwrap().riga, 3);
+    assert_eq!(diagnostics.scartati[0].errori.len(), 1);
+}
+
+#[test]
+fn test_records_lenient_mode_drops_invalid_records() {
+    let records = || {
+        vec![
+            VeryItalianRecordCsvCampionamentoHFBI {
+                codice_specie: "AN".to_string(),
+                peso: 240.0,
+                numero_individui: 25,
+                riga_sorgente: None,
+            },
+            VeryItalianRecordCsvCampionamentoHFBI {
+                codice_specie: "XYZ".to_string(),
+                peso: 10.0,
+                numero_individui: 1,
+                riga_sorgente: None,
+            },
+        ]
+    };
+
+    assert!(check_records_campionamento_hfbi(records(), &OpzioniVerifica::default()).is_err());
+
+    let (records, diagnostics) = check_records_campionamento_hfbi(
+        records(),
+        &OpzioniVerifica::default().modalita(ValidationMode::Lenient),
+    )
+    .unwrap();
+    assert_eq!(records.len(), 1);
+    assert_eq!(diagnostics.scartati.len(), 1);
+    assert_eq!(diagnostics.scartati[0].record, 2);
+    assert_eq!(diagnostics.record_non_validi, 1);
+}
+
+#[test]
+fn test_lenient_mode_keeps_file_errors() {
+    let csv_data = "codiceSpecie;peso\nAN;240";
+    let result = leggi_csv::<_, VeryItalianRecordCsvCampionamentoHFBI>(
+        Cursor::new(csv_data),
+        &OpzioniLettura::default().modalita(ValidationMode::Lenient),
+    );
+
+    let diagnostics = result.err().unwrap();
+    assert!(matches!(diagnostics.errori[0], Error::ColonnaMancante(_)));
+    assert!(diagnostics.scartati.is_empty());
+}
+
+#[test]
+fn test_registro_anagrafica_niseci() {
+    let csv_data = format!(
+        "{}\nST1;CORPO;REGIONE;PROVINCIA;1/1/2020;100;8;0;;;20;1;BACINO\n\
+        ST1;CORPO;REGIONE;PROVINCIA;1/6/2020;100;8;0;;;20;1;BACINO\n\
+        ST2;CORPO;REGIONE;PROVINCIA;1/1/2020;100;8;0;;;20;1;BACINO",
+        ANAGRAFICA_NISECI_HEADER
+    );
+    let reader = Cursor::new(csv_data);
+    let (records, _) =
+        check_anagrafica_niseci_reader::<_, VeryItalianRecordCsvAnagraficaNISECI>(reader, true)
+            .unwrap();
+    let (registro, diagnostics) =
+        check_records_registro_anagrafica_niseci(records, &OpzioniVerifica::default()).unwrap();
+
+    assert_eq!(registro.len(), 3);
+    assert_eq!(diagnostics.record_validi, 3);
+    assert_eq!(registro.get("ST1").len(), 2);
+    assert_eq!(
+        registro.find("ST1", "01/06/2020").unwrap().date_string,
+        "1/6/2020"
+    );
+    // Data assente: con più anagrafiche della stazione non si sceglie
+    assert!(registro.find("ST1", "1/3/2020").is_none());
+    assert!(registro.find("ST3", "1/1/2020").is_none());
+    // Una sola anagrafica: vale per qualsiasi data, anche senza data
+    assert_eq!(
+        registro.find("ST2", "1/3/2020").unwrap().date_string,
+        "1/1/2020"
+    );
+    assert!(registro.find("ST2", "").is_some());
+
+    // Il codice stazione viene confrontato senza spazi iniziali e finali
+    let mut anagrafica = registro.find("ST1", "1/1/2020").unwrap().clone();
+    anagrafica.codice_stazione = "ST4 ".to_string();
+    let mut registro = registro.clone();
+    assert!(registro.inserisci(anagrafica).is_ok());
+    assert!(registro.contains_stazione("ST4"));
+    assert_eq!(registro.get(" ST4").len(), 1);
+    assert!(registro
+        .find_data("ST4", NaiveDate::from_ymd_opt(2020, 1, 1).unwrap())
+        .is_some());
+}
+
+#[test]
+fn test_registro_anagrafica_niseci_duplicate() {
+    let csv_data = format!(
+        "{}\nST1;CORPO;REGIONE;PROVINCIA;1/1/2020;100;8;0;;;20;1;BACINO\n\
+        ST2;CORPO;REGIONE;PROVINCIA;1/1/2020;100;8;0;;;20;1;BACINO\n\
+        ST1;CORPO;REGIONE;PROVINCIA;01/01/2020;100;8;0;;;20;1;BACINO",
+        ANAGRAFICA_NISECI_HEADER
+    );
+    let reader = Cursor::new(csv_data);
+    let (records, _) =
+        check_anagrafica_niseci_reader::<_, VeryItalianRecordCsvAnagraficaNISECI>(reader, true)
+            .unwrap();
+    let diagnostics =
+        check_records_registro_anagrafica_niseci(records, &OpzioniVerifica::default())
+            .err()
+            .unwrap();
+
+    assert_eq!(diagnostics.record_validi, 2);
+    assert_eq!(diagnostics.record_non_validi, 1);
+    assert_eq!(diagnostics.errori.len(), 1);
+    assert_eq!(diagnostics.errori[0].record(), Some(3));
+    assert_eq!(diagnostics.errori[0].campo(), Some("codiceStazione"));
+}
+
+#[test]
+fn test_registro_anagrafica_hfbi_invalid_record() {
+    let csv_data = format!(
+        "{}\nST1;CORPO;REGIONE;PROVINCIA;1/1/2020;100;8;0;1;1\n\
+        ST2;CORPO;REGIONE;PROVINCIA;1/1/2020;100;8;0;1;7",
+        ANAGRAFICA_HFBI_HEADER
+    );
+    let reader = Cursor::new(csv_data);
+    let (records, _) =
+        check_anagrafica_hfbi_reader::<_, VeryItalianRecordCsvAnagraficaHFBI>(reader, true)
+            .unwrap();
+    let diagnostics = check_records_registro_anagrafica_hfbi(records, &OpzioniVerifica::default())
+        .err()
+        .unwrap();
+
+    assert_eq!(diagnostics.record_validi, 1);
+    assert_eq!(diagnostics.errori.len(), 1);
+    assert_eq!(diagnostics.errori[0].record(), Some(2));
+    assert_eq!(diagnostics.errori[0].campo(), Some("tipoLaguna"));
+}
+
+fn campionamento_niseci_stazione(
+    stazione: &str,
+    data: &str,
+) -> VeryItalianRecordCsvCampionamentoNISECI {
+    VeryItalianRecordCsvCampionamentoNISECI {
+        data: data.to_string(),
+        stazione: stazione.to_string(),
+        num_passaggio: 1,
+        codice_specie: "BA".to_string(),
+        lunghezza: 100,
+        peso: 100.0,
+        riga_sorgente: None,
+    }
+}
+
+fn anagrafica_niseci_stazione(stazione: &str, data: &str) -> AnagraficaNISECI {
+    let mut anagrafica = create_dummy_anagrafica();
+    anagrafica.codice_stazione = stazione.to_string();
+    anagrafica.date_string = data.to_string();
+    anagrafica.data = parse_date(data).ok();
+    anagrafica
+}
+
+#[test]
+fn test_coerenza_anagrafica_campionamento_niseci() {
+    let anagrafica = anagrafica_niseci_stazione("ST1", "07/07/2019");
+
+    let records = vec![
+        campionamento_niseci_stazione("ST1", "07/07/2019"),
+        campionamento_niseci_stazione(" ST1", "2019-07-07"),
+    ];
+    let diagnostics = check_coerenza_niseci(&anagrafica, &records).unwrap();
+    assert_eq!(diagnostics.record_validi, 2);
+
+    let records = vec![
+        campionamento_niseci_stazione("ST1", "07/07/2019"),
+        campionamento_niseci_stazione("ST2", "07/07/2019"),
+        campionamento_niseci_stazione("ST1", "08/07/2019"),
+        campionamento_niseci_stazione("ST2", "07/07/2019"),
+    ];
+    let diagnostics = check_coerenza_niseci(&anagrafica, &records).err().unwrap();
+    assert_eq!(diagnostics.record_validi, 1);
+    assert_eq!(diagnostics.record_non_validi, 3);
+    let errori = &diagnostics.errori;
+    assert_eq!(errori.len(), 3);
+    assert_eq!(errori[0].campo(), Some("stazione"));
+    assert_eq!(errori[0].record(), Some(2));
+    assert_eq!(errori[0].valore(), Some("ST2"));
+    assert_eq!(errori[1].campo(), Some("data"));
+    assert_eq!(errori[1].record(), Some(3));
+    assert_eq!(
+        errori[1].testo(Lingua::Italiano),
+        "data del campionamento diversa da quella dell'anagrafica: 08/07/2019 (anagrafica: 07/07/2019)"
+    );
+    assert_eq!(errori[2].record(), None);
+    assert_eq!(
+        errori[2].testo(Lingua::Inglese),
+        "campionamento contains more than one station: ST1, ST2"
+    );
+}
+
+#[test]
+fn test_coerenza_registro_campionamenti_niseci() {
+    let registro: RegistroStazioni<_> = vec![
+        anagrafica_niseci_stazione("ST1", "07/07/2019"),
+        anagrafica_niseci_stazione("ST2", "01/06/2020"),
+    ]
+    .into_iter()
+    .collect();
+    let campionamenti: BTreeMap<_, _> = [("ST1", 7), ("ST1", 8), ("ST3", 7)]
+        .into_iter()
+        .map(|(stazione, giorno)| {
+            let chiave = ChiaveCampionamentoNISECI {
+                stazione: stazione.to_string(),
+                data: NaiveDate::from_ymd_opt(2019, 7, giorno).unwrap(),
+            };
+            (chiave, ())
+        })
+        .collect();
+
+    let diagnostics = check_coerenza_campionamenti_niseci(&registro, &campionamenti)
+        .err()
+        .unwrap();
+    assert_eq!(diagnostics.record_validi, 1);
+    assert_eq!(diagnostics.errori.len(), 2);
+    assert_eq!(diagnostics.errori[0].campo(), Some("data"));
+    assert_eq!(diagnostics.errori[0].valore(), Some("08/07/2019"));
+    assert_eq!(diagnostics.errori[1].campo(), Some("stazione"));
+    assert_eq!(diagnostics.errori[1].valore(), Some("ST3"));
+}
+
+#[test]
+fn test_json_input_niseci_stazione_diversa() {
+    let json = input_niseci_json("BA").replace("\"stazione\":\"ST1\"", "\"stazione\":\"ST9\"");
+    let diagnostics = check_input_niseci_json(json.as_bytes()).err().unwrap();
+
+    assert_eq!(diagnostics.errori.len(), 1);
+    assert_eq!(diagnostics.errori[0].campo(), Some("stazione"));
+    assert_eq!(diagnostics.errori[0].record(), Some(1));
+}
+
+#[test]
+fn test_sniff_formato_italiano() {
+    let formato = sniff_formato(include_bytes!("../../../templates/riferimento_niseci.csv"));
+
+    assert_eq!(formato.delimitatore, b';');
+    assert_eq!(formato.separatore_decimale, b',');
+    assert_eq!(formato.locale, LocaleCsv::Italiano);
+    assert_eq!(formato.tipo_record, Some(TipoRecordCsv::RiferimentoNISECI));
+
+    let formato = sniff_formato(include_bytes!(
+        "../../../templates/campionamento_niseci.csv"
+    ));
+    assert_eq!(formato.delimitatore, b';');
+    assert_eq!(
+        formato.tipo_record,
+        Some(TipoRecordCsv::CampionamentoNISECI)
+    );
+}
+
+#[test]
+fn test_sniff_formato_internazionale() {
+    let csv_data = "codiceSpecie,numeroIndividui,peso\nAN,25,240.5\nCH,41,110";
+    let formato = sniff_formato(csv_data.as_bytes());
+
+    assert_eq!(formato.delimitatore, b',');
+    assert_eq!(formato.separatore_decimale, b'.');
+    assert_eq!(formato.locale, LocaleCsv::Internazionale);
+    assert_eq!(formato.tipo_record, Some(TipoRecordCsv::CampionamentoHFBI));
+
+    let csv_data = "codicespecie\tnumeroindividui\tpeso\nAN\t25\t240,5";
+    let formato = sniff_formato(csv_data.as_bytes());
+    assert_eq!(formato.delimitatore, b'\t');
+    assert_eq!(formato.separatore_decimale, b',');
+    assert_eq!(formato.locale, LocaleCsv::NonStandard);
+    assert_eq!(formato.tipo_record, Some(TipoRecordCsv::CampionamentoHFBI));
+}
+
+#[test]
+fn test_csv_reader_auto() {
+    let reader = Cursor::new("codiceSpecie,numeroIndividui,peso\nAN,25,240.5\nCH,41,110");
+    let (records, diagnostics) =
+        leggi_csv::<_, PlainRecordCsvCampionamentoHFBI>(reader, &OpzioniLettura::default().auto())
+            .unwrap();
+
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].peso, 240.5);
+    assert_eq!(
+        diagnostics.formato.unwrap().locale,
+        LocaleCsv::Internazionale
+    );
+    assert!(diagnostics.avvisi.is_empty());
+
+    let reader = Cursor::new(include_bytes!("../../../templates/riferimento_niseci.csv"));
+    let (records, diagnostics) =
+        leggi_csv::<_, PlainRecordCsvRiferimentoNISECI>(reader, &OpzioniLettura::default().auto())
+            .unwrap();
+    assert!(!records.is_empty());
+    assert_eq!(diagnostics.formato.unwrap().locale, LocaleCsv::Italiano);
+    assert!(diagnostics.avvisi.is_empty());
+}
+
+#[test]
+fn test_csv_reader_auto_wrong_type() {
+    let reader = Cursor::new(include_bytes!("../../../templates/campionamento_hfbi.csv"));
+    let diagnostics =
+        leggi_csv::<_, PlainRecordCsvRiferimentoNISECI>(reader, &OpzioniLettura::default().auto())
+            .err()
+            .unwrap();
+
+    assert_eq!(
+        diagnostics.formato.unwrap().tipo_record,
+        Some(TipoRecordCsv::CampionamentoHFBI)
+    );
+    assert_eq!(
+        diagnostics.avvisi,
+        vec![Messaggio::TipoFileDiverso {
+            trovato: TipoRecordCsv::CampionamentoHFBI,
+            atteso: TipoRecordCsv::RiferimentoNISECI,
+        }]
+    );
+    // Gli avvisi sono scritti nella lingua richiesta, non in quella corrente alla lettura
+    assert!(diagnostics
+        .descrizione(Lingua::Italiano)
+        .contains("L'intestazione sembra di un file campionamento HFBI"));
+    assert!(diagnostics
+        .descrizione(Lingua::Inglese)
+        .contains("The header looks like a HFBI sampling file"));
+}
+
+#[test]
+fn test_leggi_csv_opzioni() {
+    // Il delimitatore indicato prevale su quello del tipo di record
+    let csv_data = "codiceSpecie,numeroIndividui,peso\nAN,25,240";
+    let opzioni = OpzioniLettura::default().delimitatore(b',');
+    let (records, _) =
+        leggi_csv::<_, VeryItalianRecordCsvCampionamentoHFBI>(Cursor::new(csv_data), &opzioni)
+            .unwrap();
+    assert_eq!(records[0].numero_individui, 25);
+
+    // Senza intestazione le colonne sono nell'ordine standard
+    let opzioni = OpzioniLettura::default().intestazione(false);
+    let (records, _) =
+        leggi_csv::<_, VeryItalianRecordCsvCampionamentoHFBI>(Cursor::new("AN;25;240,5"), &opzioni)
+            .unwrap();
+    assert_eq!(records[0].peso, 240.5);
+
+    let diagnostics = leggi_csv_path::<PlainRecordCsvCampionamentoHFBI>(
+        Path::new("non_esiste.csv"),
+        &OpzioniLettura::default(),
+    )
+    .err()
+    .unwrap();
+    assert!(matches!(diagnostics.errori[0], Error::FileNonValido(_)));
+}
+
+#[test]
+fn test_indice_specie_riferimento_niseci() {
+    let reader = Cursor::new(include_bytes!("../../../templates/riferimento_niseci.csv"));
+    let (records, _) =
+        leggi_csv::<_, PlainRecordCsvRiferimentoNISECI>(reader, &OpzioniLettura::default().auto())
+            .unwrap();
+    let (specie, _) =
+        check_records_riferimento_niseci(records, &OpzioniVerifica::default()).unwrap();
+    let riferimento = RiferimentoNISECI::new(specie);
+    let indice = riferimento.indice();
+
+    assert_eq!(indice.len(), riferimento.elenco_specie.len());
+    for chiave in [
+        "AL",
+        " al ",
+        "Alburnus alborella",
+        "ALBURNUS  ALBORELLA",
+        "alborella",
+    ] {
+        let specie = indice.cerca(&riferimento.elenco_specie, chiave);
+        assert_eq!(specie.map(|s| s.id.as_str()), Some("AL"), "{chiave}");
+    }
+    assert_eq!(
+        riferimento.cerca_specie("anguilla").map(|s| s.id.as_str()),
+        Some("AN")
+    );
+    assert!(riferimento.cerca_specie("XYZ").is_none());
+
+    // Il campionamento può indicare la specie anche per nome
+    let campionamento = VeryItalianRecordCsvCampionamentoNISECI {
+        data: "07/07/2019".to_string(),
+        stazione: "ST1".to_string(),
+        num_passaggio: 1,
+        codice_specie: "Alburnus alborella".to_string(),
+        lunghezza: 100,
+        peso: 10.0,
+        riga_sorgente: None,
+    };
+    let (records, _) = check_records_campionamento_niseci(
+        vec![campionamento],
+        riferimento.elenco_specie,
+        &OpzioniVerifica::default(),
+    )
+    .unwrap();
+    assert_eq!(records[0].specie.id, "AL");
+}
+
+#[test]
+fn test_indice_specie_duplicati() {
+    let mut riga_minuscola = RIGA_MUGGINE.replace(";MUG;", ";mug ;");
+    riga_minuscola.insert(0, '\n');
+    let csv_data = format!(
+        "{}\n{}{}",
+        RIFERIMENTO_NISECI_HEADER, RIGA_MUGGINE, riga_minuscola
+    );
+    let (records, _) = check_riferimento_niseci_reader::<_, VeryItalianRecordCsvRiferimentoNISECI>(
+        Cursor::new(csv_data),
+        true,
+    )
+    .unwrap();
+    let errors = check_records_riferimento_niseci(records, &OpzioniVerifica::default())
+        .err()
+        .unwrap()
+        .errori;
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].record(), Some(2));
+    assert_eq!(errors[0].msg(), "codice_specie non valido (ridefinizione)");
+
+    // Un nome comune a più specie è ambiguo: si trovano solo per codice
+    let mut elenco = vec![
+        SpecieNISECI::new_dummy_specie(),
+        SpecieNISECI::new_dummy_specie(),
+    ];
+    elenco[1].id = "1".to_string();
+    assert_eq!(IndiceSpecie::duplicati(&elenco), vec![]);
+    let indice = IndiceSpecie::da_elenco(&elenco);
+    assert_eq!(indice.posizione("dummy"), None);
+    assert_eq!(indice.posizione("1"), Some(1));
+    elenco[1].id = "0".to_string();
+    assert_eq!(IndiceSpecie::duplicati(&elenco), vec![(1, 0)]);
+
+    // Nell'elenco predefinito ogni specie ha il suo codice
+    assert_eq!(IndiceSpecie::duplicati(&RIFERIMENTO_HFBI), vec![]);
+    assert_eq!(indice_riferimento_hfbi().len(), RIFERIMENTO_HFBI.len());
+    assert_eq!(
+        cerca_specie_hfbi("DIC").map(|s| s.nome_comune.as_ref()),
+        Some("Spigola branzino")
+    );
+    assert_eq!(
+        cerca_specie_hfbi("ENG").map(|s| s.nome_comune.as_ref()),
+        Some("Alice (Acciuga Europea)")
+    );
+    assert_eq!(
+        cerca_specie_hfbi(" cheppia").map(|s| s.codice_specie.as_ref()),
+        Some("CH")
+    );
+}
+
+#[test]
+fn test_suggerimento_codice_specie_sconosciuto() {
+    let reader = Cursor::new(include_bytes!("../../../templates/riferimento_niseci.csv"));
+    let (records, _) =
+        leggi_csv::<_, PlainRecordCsvRiferimentoNISECI>(reader, &OpzioniLettura::default().auto())
+            .unwrap();
+    let (specie, _) =
+        check_records_riferimento_niseci(records, &OpzioniVerifica::default()).unwrap();
+    let campionamento = |codice: &str| {
+        let mut record = campionamento_niseci_stazione("ST1", "07/07/2019");
+        record.codice_specie = codice.to_string();
+        record
+    };
+
+    let records = vec![campionamento("ANG"), campionamento("QWERTYUIOP")];
+    let errors =
+        check_records_campionamento_niseci(records, specie.clone(), &OpzioniVerifica::default())
+            .err()
+            .unwrap()
+            .errori;
+    assert_eq!(errors.len(), 2);
+    assert_eq!(
+        errors[0].testo(Lingua::Italiano),
+        "codice_specie non valido (non presente nel riferimento): ANG, forse intendevi AN (Anguilla)?"
+    );
+    assert!(errors[0]
+        .testo(Lingua::Inglese)
+        .ends_with("did you mean AN (Anguilla)?"));
+    assert_eq!(
+        errors[0].suggerimento().map(|s| s.codice.as_str()),
+        Some("AN")
+    );
+    assert!(errors[1].suggerimento().is_none());
+
+    // Il suggerimento confermato viene applicato alle importazioni successive
+    let mut correzioni = CorrezioniSpecie::new();
+    assert!(correzioni.conferma_suggerimento(&errors[0]));
+    assert!(!correzioni.conferma_suggerimento(&errors[1]));
+    assert_eq!(correzioni.get(" ang"), Some("AN"));
+
+    let json = serde_json::to_string(&correzioni).unwrap();
+    assert_eq!(json, r#"{"ang":"AN"}"#);
+    let correzioni: CorrezioniSpecie = serde_json::from_str(&json).unwrap();
+    // Un file scritto a mano viene normalizzato al caricamento
+    let a_mano: CorrezioniSpecie = serde_json::from_str(r#"{" ANG ":" AN"}"#).unwrap();
+    assert_eq!(a_mano, correzioni);
+    assert_eq!(a_mano.get("ang"), Some("AN"));
+
+    let records = vec![campionamento("BA"), campionamento("ANG")];
+    let (records, diagnostics) = check_records_campionamento_niseci(
+        records,
+        specie,
+        &OpzioniVerifica::default().correzioni(&correzioni),
+    )
+    .unwrap();
+    assert_eq!(records[1].specie.id, "AN");
+    assert_eq!(diagnostics.avvisi.len(), 1);
+    assert!(diagnostics.avvisi[0]
+        .testo(Lingua::Italiano)
+        .contains("ANG"));
+
+    // Anche per HFBI, sia per codice che per nome
+    let record_hfbi = |codice: &str| VeryItalianRecordCsvCampionamentoHFBI {
+        codice_specie: codice.to_string(),
+        peso: 10.0,
+        numero_individui: 1,
+        riga_sorgente: None,
+    };
+    let errors = check_records_campionamento_hfbi(
+        vec![record_hfbi("Cheppiaa")],
+        &OpzioniVerifica::default(),
+    )
+    .err()
+    .unwrap()
+    .errori;
+    assert_eq!(
+        errors[0].suggerimento().map(|s| s.codice.as_str()),
+        Some("CH")
+    );
+    let mut correzioni = CorrezioniSpecie::new();
+    correzioni.conferma("Cheppiaa", "CH");
+    let (records, diagnostics) = check_records_campionamento_hfbi(
+        vec![record_hfbi("cheppiaa")],
+        &OpzioniVerifica::default().correzioni(&correzioni),
+    )
+    .unwrap();
+    assert_eq!(records[0].specie.codice_specie, "CH");
+    assert_eq!(diagnostics.avvisi.len(), 1);
+}
+
+const RIGA_MUGGINE: &str =
+    "Muggine calamità;Liza ramada;MUG;AUT;2;0;0;45;90;130;150;0,5;0,67;1,5;2;0.0;0.0";
+
+fn check_nome_comune_riferimento(csv_data: Vec<u8>, codifica: CodificaTesto) {
+    assert_eq!(rileva_codifica(&csv_data), codifica);
+    let reader = Cursor::new(csv_data);
+    let (records, _) =
+        check_riferimento_niseci_reader::<_, VeryItalianRecordCsvRiferimentoNISECI>(reader, true)
+            .unwrap();
+
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].nome_comune, "Muggine calamità");
+}
+
+#[test]
+fn test_csv_encoding_utf8() {
+    let csv_data = format!("{}\n{}", RIFERIMENTO_NISECI_HEADER, RIGA_MUGGINE);
+    check_nome_comune_riferimento(csv_data.into_bytes(), CodificaTesto::Utf8);
+
+    let mut csv_data = vec![0xEF, 0xBB, 0xBF];
+    csv_data.extend(format!("{}\n{}", RIFERIMENTO_NISECI_HEADER, RIGA_MUGGINE).bytes());
+    check_nome_comune_riferimento(csv_data, CodificaTesto::Utf8Bom);
+}
+
+#[test]
+fn test_csv_encoding_windows_1252() {
+    let csv_data: Vec<u8> = format!("{}\n{}", RIFERIMENTO_NISECI_HEADER, RIGA_MUGGINE)
+        .chars()
+        .map(|c| c as u32 as u8) // Latin-1
+        .collect();
+    check_nome_comune_riferimento(csv_data, CodificaTesto::Windows1252);
+}
+
+#[test]
+fn test_csv_encoding_utf16() {
+    let testo = format!("{}\n{}", RIFERIMENTO_NISECI_HEADER, RIGA_MUGGINE);
+
+    let mut csv_data = vec![0xFF, 0xFE];
+    csv_data.extend(testo.encode_utf16().flat_map(|c| c.to_le_bytes()));
+    check_nome_comune_riferimento(csv_data, CodificaTesto::Utf16Le);
+
+    let mut csv_data = vec![0xFE, 0xFF];
+    csv_data.extend(testo.encode_utf16().flat_map(|c| c.to_be_bytes()));
+    check_nome_comune_riferimento(csv_data, CodificaTesto::Utf16Be);
+
+    // Senza BOM
+    let csv_data = testo.encode_utf16().flat_map(|c| c.to_le_bytes()).collect();
+    check_nome_comune_riferimento(csv_data, CodificaTesto::Utf16Le);
+}
+
+#[test]
+fn test_utf8_reader_windows_1252_oltre_il_campione() {
+    // Le prime righe sono ASCII: la codifica viene riconosciuta come UTF-8, ma un carattere
+    // Windows-1252 oltre i primi 16KB non diventa U+FFFD
+    let mut csv_data = format!("{}\n", RIFERIMENTO_NISECI_HEADER).into_bytes();
+    while csv_data.len() < 20 * 1024 {
+        csv_data
+            .extend(b"Barbo;Barbus plebejus;BA;AUT;2;0;0;45;90;130;150;0,5;0,67;1,5;2;0.0;0.0\n");
+    }
+    csv_data.extend(RIGA_MUGGINE.chars().map(|c| c as u32 as u8));
+    assert_eq!(rileva_codifica(&csv_data[..16 * 1024]), CodificaTesto::Utf8);
+
+    let mut reader = Utf8Reader::new(Cursor::new(csv_data.clone()));
+    let mut testo = String::new();
+    reader.read_to_string(&mut testo).unwrap();
+    assert_eq!(reader.codifica(), Some(CodificaTesto::Windows1252));
+    assert!(testo.ends_with(RIGA_MUGGINE));
+    assert!(!testo.contains('\u{FFFD}'));
+
+    let (records, _) = check_riferimento_niseci_reader::<_, VeryItalianRecordCsvRiferimentoNISECI>(
+        Cursor::new(csv_data),
+        true,
+    )
+    .unwrap();
+    assert_eq!(records.last().unwrap().nome_comune, "Muggine calamità");
+}
+
+#[test]
+fn test_utf8_reader_sequenza_tra_due_letture() {
+    // "à" in UTF-8 (2 byte) a cavallo della fine del campione resta UTF-8
+    let mut csv_data = vec![b'a'; 16 * 1024 - 1];
+    csv_data.extend("à fine".as_bytes());
+    let mut reader = Utf8Reader::new(Cursor::new(csv_data));
+    let mut testo = String::new();
+    reader.read_to_string(&mut testo).unwrap();
+    assert_eq!(reader.codifica(), Some(CodificaTesto::Utf8));
+    assert!(testo.ends_with("aà fine"));
+}
+
+#[test]
+fn test_utf8_reader_strips_bom() {
+    let mut reader = Utf8Reader::new(Cursor::new(include_bytes!(
+        "../../../templates/riferimento_niseci.csv"
+    )));
+    let mut testo = String::new();
+    reader.read_to_string(&mut testo).unwrap();
+
+    assert_eq!(reader.codifica(), Some(CodificaTesto::Utf8Bom));
+    assert!(testo.starts_with("nomeComune;"));
+    assert!(testo.contains("Ghiozzo Gò;"));
+}
+
+#[test]
+fn test_csv_columns_by_name() {
+    // Colonne riordinate, maiuscole diverse, snake_case, colonne sconosciute e vuote in coda
+    let csv_data = "PESO;Codice_Specie;note;numeroindividui;;;\n240;AN;foo;25;;;\n110,5;CH;;41;;;";
+    let reader = Cursor::new(csv_data);
+    let (records, _) =
+        check_campionamento_hfbi_reader::<_, VeryItalianRecordCsvCampionamentoHFBI>(reader, true)
+            .unwrap();
+
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].codice_specie, "AN");
+    assert_eq!(records[0].numero_individui, 25);
+    assert_eq!(records[1].peso, 110.5);
+}
+
+#[test]
+fn test_record_error_has_source_position() {
+    // Colonne riordinate: numeroIndividui è la seconda colonna del file
+    let csv_data = "peso;numeroIndividui;codiceSpecie\n240;25;AN\n110;00;CH";
+    let reader = Cursor::new(csv_data);
+    let (records, _) =
+        check_campionamento_hfbi_reader::<_, VeryItalianRecordCsvCampionamentoHFBI>(reader, true)
+            .unwrap();
+    let riga = records[1].riga_sorgente.as_ref().unwrap();
+    assert_eq!(riga.cella("codiceSpecie"), Some(("CH", Some(2))));
+
+    let errors = check_records_campionamento_hfbi(records, &OpzioniVerifica::default())
+        .err()
+        .unwrap()
+        .errori;
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].record(), Some(2));
+    assert_eq!(errors[0].campo(), Some("numeroIndividui"));
+    assert_eq!(errors[0].colonna(), Some(1));
+    // Valore grezzo della cella, non quello convertito
+    assert_eq!(errors[0].valore(), Some("00"));
+    let posizione = errors[0].posizione().unwrap();
+    assert_eq!(posizione.riga, 3);
+    assert_eq!(posizione.byte, 44);
+    assert!(errors[0].to_string().ends_with("(Riga: 3, colonna: 2)"));
+}
+
+#[test]
+fn test_error_message_languages() {
+    let csv_data = format!("{}\nAN;0;240", CAMPIONAMENTO_HFBI_HEADER);
+    let reader = Cursor::new(csv_data);
+    let records =
+        check_campionamento_hfbi_reader::<_, VeryItalianRecordCsvCampionamentoHFBI>(reader, true)
+            .unwrap()
+            .0;
+    let errors = check_records_campionamento_hfbi(records, &OpzioniVerifica::default())
+        .err()
+        .unwrap()
+        .errori;
+
+    assert_eq!(errors[0].testo(Lingua::Italiano), errors[0].msg());
+    assert_eq!(
+        errors[0].descrizione(Lingua::Italiano),
+        "Errore record campionamento HFBI: Record 1: numero_individui non valido (<1): 0 (Riga: 2, colonna: 2)"
+    );
+    assert_eq!(
+        errors[0].descrizione(Lingua::Inglese),
+        "Record error HFBI sampling: Record 1: invalid numero_individui (<1): 0 (Line: 2, column: 2)"
+    );
+}
+
+#[test]
+fn test_labels_languages() {
+    assert_eq!("en-GB".parse::<Lingua>(), Ok(Lingua::Inglese));
+    assert_eq!("IT".parse::<Lingua>(), Ok(Lingua::Italiano));
+    assert!("fr".parse::<Lingua>().is_err());
+
+    assert_eq!(
+        StatoEcologicoNISECI::Moderato.etichetta(Lingua::Italiano),
+        "Moderato"
+    );
+    assert_eq!(
+        StatoEcologicoNISECI::Moderato.etichetta(Lingua::Inglese),
+        "Moderate"
+    );
+    assert_eq!(
+        IdroEcoRegioneNISECI::Toscana.etichetta(Lingua::Inglese),
+        "Tuscany"
+    );
+    assert_eq!(
+        TipoRecordCsv::AnagraficaHFBI.etichetta(Lingua::Inglese),
+        "HFBI station data"
+    );
+}
+
+#[test]
+fn test_csv_columns_alias() {
+    let csv_data = "Specie;n;peso\nAN;25;240";
+    let reader = Cursor::new(csv_data);
+    let diagnostics =
+        check_campionamento_hfbi_reader::<_, VeryItalianRecordCsvCampionamentoHFBI>(reader, true)
+            .err()
+            .unwrap();
+    assert_eq!(diagnostics.colonne_mancanti(), vec!["numeroIndividui"]);
+
+    let alias = AliasColonne::default().con_alias("n", "numeroIndividui");
+    let reader = Cursor::new(csv_data);
+    let (records, _) = leggi_csv::<_, VeryItalianRecordCsvCampionamentoHFBI>(
+        reader,
+        &OpzioniLettura::default().alias(alias),
+    )
+    .unwrap();
+    assert_eq!(records[0].codice_specie, "AN");
+    assert_eq!(records[0].numero_individui, 25);
+}
+
+#[test]
+fn test_csv_columns_missing() {
+    let csv_data = "data;codiceSpecie;lunghezza\n07/07/2019;BA;275";
+    let reader = Cursor::new(csv_data);
+    let diagnostics =
+        check_campionamento_niseci_reader::<_, VeryItalianRecordCsvCampionamentoNISECI>(
+            reader, true,
+        )
+        .err()
+        .unwrap();
+
+    assert_eq!(
+        diagnostics.colonne_mancanti(),
+        vec!["stazione", "numPassaggio", "peso"]
+    );
+    assert!(matches!(diagnostics.errori[0], Error::ColonnaMancante(_)));
+    assert_eq!(
+        diagnostics.errori[0].to_string(),
+        "Errore intestazione campionamento NISECI: colonna mancante nell'intestazione: stazione"
+    );
+}
+
+fn input_niseci_json(codice_specie_catturata: &str) -> String {
+    serde_json::json!({
+        "anagrafica": {
+            "codiceStazione": "ST1", "corpoIdrico": "CORPO", "regione": "REGIONE",
+            "provincia": "PROVINCIA", "data": "07/07/2019", "lunghezzaStazione": 100.0,
+            "larghezzaStazione": 8.0, "tipoComunita": 0, "fonte": "", "numeroProtocollo": "",
+            "idroEcoRegione": 20, "areaAlpina": 1, "nomeBacino": "BACINO"
+        },
+        "riferimento": [{
+            "nomeComune": "Barbo", "nomeLatino": "Barbus plebejus", "codiceSpecie": "BA",
+            "origine": "AUT", "tipoAutoctono": 2, "alloNocivita": 0, "specieAttesa": 1,
+            "clSoglia1": 80, "clSoglia2": 160, "clSoglia3": 240, "clSoglia4": 320,
+            "adJuvSoglia1": 0.5, "adJuvSoglia2": 0.67, "adJuvSoglia3": 1.5, "adJuvSoglia4": 2.0,
+            "densSoglia1": 0.05, "densSoglia2": 0.08
+        }],
+        "campionamento": [
+            { "data": "07/07/2019", "stazione": "ST1", "numPassaggio": 1,
+              "codiceSpecie": "BA", "lunghezza": 275, "peso": 152.0 },
+            { "data": "07/07/2019", "stazione": "ST1", "numPassaggio": 1,
+              "codiceSpecie": codice_specie_catturata, "lunghezza": 206, "peso": 78.5 }
+        ]
+    })
+    .to_string()
+}
+
+#[test]
+fn test_json_input_niseci() {
+    let json = input_niseci_json("BA");
+    let (valutazione, diagnostics) = check_input_niseci_json(json.as_bytes()).unwrap();
+
+    assert!(!diagnostics.has_errori());
+    assert_eq!(diagnostics.record_validi, 4);
+    assert_eq!(valutazione.anagrafica.codice_stazione, "ST1");
+    assert_eq!(valutazione.riferimento.elenco_specie.len(), 1);
+    assert_eq!(valutazione.campionamento.campionamento.len(), 2);
+    assert_eq!(valutazione.campionamento.campionamento[1].peso, 78.5);
+
+    // Il risultato della validazione è a sua volta serializzabile
+    let serializzata = serde_json::to_string(&valutazione).unwrap();
+    let deserializzata: ValutazioneNISECI = serde_json::from_str(&serializzata).unwrap();
+    assert_eq!(deserializzata.campionamento.campionamento.len(), 2);
+}
+
+#[test]
+fn test_json_input_niseci_invalid() {
+    let json = input_niseci_json("XYZ");
+    let diagnostics = check_input_niseci_json(json.as_bytes()).err().unwrap();
+
+    assert_eq!(diagnostics.errori.len(), 1);
+    assert_eq!(
+        diagnostics.errori[0].tipo_csv(),
+        Some(TipoRecordCsv::CampionamentoNISECI)
+    );
+    assert_eq!(diagnostics.errori[0].record(), Some(2));
+    assert_eq!(diagnostics.errori[0].campo(), Some("codiceSpecie"));
+
+    let json = "{\n  \"anagrafica\": {\n    \"codiceStazione\": \"ST1\",,\n  }\n}";
+    let diagnostics = check_input_niseci_json(json.as_bytes()).err().unwrap();
+    assert!(matches!(diagnostics.errori[0], Error::Deserializzazione(_)));
+    assert_eq!(diagnostics.errori[0].posizione().unwrap().riga, 3);
+}
+
+#[test]
+fn test_json_input_hfbi() {
+    let json = serde_json::json!({
+        "anagrafica": {
+            "codiceStazione": "ST1", "corpoIdrico": "CORPO", "regione": "REGIONE",
+            "provincia": "PROVINCIA", "data": "1/1/2020", "lunghezzaStazione": 100.0,
+            "larghezzaStazione": 8.0, "stagione": 0, "habitat": 1, "tipoLaguna": 1
+        },
+        "campionamento": [
+            { "codiceSpecie": "AN", "numeroIndividui": 25, "peso": 240.0 },
+            { "codiceSpecie": "CH", "numeroIndividui": 41, "peso": 110.0 }
+        ]
+    })
+    .to_string();
+    let (valutazione, _) = check_input_hfbi_json(json.as_bytes()).unwrap();
+
+    assert_eq!(valutazione.campionamento.campionamento.len(), 2);
+    assert_eq!(
+        valutazione.campionamento.campionamento[0]
+            .specie
+            .nome_comune,
+        "Anguilla"
+    );
+
+    let json = json.replace("\"tipoLaguna\":1", "\"tipoLaguna\":7");
+    let diagnostics = check_input_hfbi_json(json.as_bytes()).err().unwrap();
+    assert_eq!(diagnostics.errori.len(), 1);
+    assert_eq!(diagnostics.errori[0].campo(), Some("tipoLaguna"));
+}
+
+#[test]
+fn test_riferimento_hfbi_da_file() {
+    // Il template contiene l'elenco predefinito
+    let reader = Cursor::new(include_bytes!("../../../templates/riferimento_hfbi.csv"));
+    let (records, diagnostics) =
+        leggi_csv::<_, PlainRecordCsvRiferimentoHFBI>(reader, &OpzioniLettura::default().auto())
+            .unwrap();
+    assert_eq!(
+        diagnostics.formato.unwrap().tipo_record,
+        Some(TipoRecordCsv::RiferimentoHFBI)
+    );
+    let (riferimento, _) =
+        check_records_riferimento_hfbi(records, &OpzioniVerifica::default()).unwrap();
+    let predefinito = RiferimentoHFBI::default();
+    assert_eq!(
+        riferimento.elenco_specie.len(),
+        predefinito.elenco_specie.len()
+    );
+    for specie in &riferimento.elenco_specie {
+        let attesa = predefinito.cerca_specie(&specie.codice_specie).unwrap();
+        assert_eq!(specie.nome_comune, attesa.nome_comune);
+        assert_eq!(specie.autoctono, attesa.autoctono);
+        assert_eq!(
+            format!("{:?}", specie.gruppo_eco),
+            format!("{:?}", attesa.gruppo_eco)
+        );
+        assert_eq!(
+            specie.gruppo_trofico.frazioni(),
+            attesa.gruppo_trofico.frazioni()
+        );
+    }
+    for specie in &predefinito.elenco_specie {
+        assert!(
+            (specie.gruppo_trofico.somma() - 1.0).abs() < 1e-6,
+            "{}",
+            specie.codice_specie
+        );
+    }
+
+    // Una specie nuova è riconosciuta solo con l'elenco che la contiene
+    let csv_data = format!(
+        "{}\nNuova specie;NUO;0;3;0,25;0,25;0;0;0;0,5;0",
+        RIFERIMENTO_HFBI_HEADER
+    );
+    let (records, _) = check_riferimento_hfbi_reader::<_, VeryItalianRecordCsvRiferimentoHFBI>(
+        Cursor::new(csv_data),
+        true,
+    )
+    .unwrap();
+    let (riferimento, _) =
+        check_records_riferimento_hfbi(records, &OpzioniVerifica::default()).unwrap();
+    let campionamento = || {
+        vec![VeryItalianRecordCsvCampionamentoHFBI {
+            codice_specie: "NUO".to_string(),
+            peso: 10.0,
+            numero_individui: 2,
+            riga_sorgente: None,
+        }]
+    };
+    assert!(
+        check_records_campionamento_hfbi(campionamento(), &OpzioniVerifica::default()).is_err()
+    );
+    let (records, _) = check_records_campionamento_hfbi(
+        campionamento(),
+        &OpzioniVerifica::default().riferimento(&riferimento),
+    )
+    .unwrap();
+    assert!(!records[0].specie.autoctono);
+    assert_eq!(records[0].specie.gruppo_trofico.planctivori, 0.5);
+}
+
+#[test]
+fn test_riferimento_hfbi_predefinito_valido() {
+    let records = RIFERIMENTO_HFBI
+        .iter()
+        .map(|specie| PlainRecordCsvRiferimentoHFBI {
+            nome_comune: specie.nome_comune.to_string(),
+            codice_specie: specie.codice_specie.to_string(),
+            autoctono: specie.autoctono as u32,
+            gruppo_eco: specie.gruppo_eco.clone() as u32,
+            microbentivori: specie.gruppo_trofico.microbentivori,
+            macrobentivori: specie.gruppo_trofico.macrobentivori,
+            iperbentivori: specie.gruppo_trofico.iperbentivori,
+            erbivori: specie.gruppo_trofico.erbivori,
+            detritivori: specie.gruppo_trofico.detritivori,
+            planctivori: specie.gruppo_trofico.planctivori,
+            onnivori: specie.gruppo_trofico.onnivori,
+            riga_sorgente: None,
+        })
+        .collect();
+    let (riferimento, diagnostics) =
+        check_records_riferimento_hfbi(records, &OpzioniVerifica::default()).unwrap();
+    assert_eq!(riferimento.elenco_specie.len(), RIFERIMENTO_HFBI.len());
+    assert_eq!(diagnostics.record_validi, RIFERIMENTO_HFBI.len());
+}
+
+#[test]
+fn test_riferimento_hfbi_non_valido() {
+    let csv_data = format!(
+        "{}\n{}\n{}\n{}\n{}\n{}",
+        RIFERIMENTO_HFBI_HEADER,
+        "Cheppia;CH;1;1;0;0;1;0;0;0;0",
+        "Cheppia bis;ch ;1;1;0;0;1;0;0;0;0",
+        "Specie A;SPA;1;7;0;0;1;0;0;0;0",
+        "Specie B;SPB;1;2;0,5;0,4;0;0;0;0;0",
+        "Specie C;SPC;2;2;1,5;-0,5;0;0;0;0;0",
+    );
+    let (records, _) = check_riferimento_hfbi_reader::<_, VeryItalianRecordCsvRiferimentoHFBI>(
+        Cursor::new(csv_data),
+        true,
+    )
+    .unwrap();
+    let diagnostics = check_records_riferimento_hfbi(records, &OpzioniVerifica::default())
+        .err()
+        .unwrap();
+    assert_eq!(diagnostics.record_validi, 1);
+    let errori: Vec<_> = diagnostics
+        .errori
+        .iter()
+        .map(|e| (e.record(), e.campo()))
+        .collect();
+    assert_eq!(
+        errori,
+        vec![
+            (Some(2), Some("codiceSpecie")),
+            (Some(3), Some("gruppoEco")),
+            (Some(4), None),
+            (Some(5), Some("autoctono")),
+        ]
+    );
+    assert_eq!(
+        diagnostics.errori[2].testo(Lingua::Inglese),
+        "trophic fractions do not sum to 1: 0.9"
+    );
+
+    let csv_data = format!(
+        "{}\nSpecie C;SPC;1;2;1,5;-0,5;0;0;0;0;0",
+        RIFERIMENTO_HFBI_HEADER
+    );
+    let (records, _) = check_riferimento_hfbi_reader::<_, VeryItalianRecordCsvRiferimentoHFBI>(
+        Cursor::new(csv_data),
+        true,
+    )
+    .unwrap();
+    let errori = check_records_riferimento_hfbi(records, &OpzioniVerifica::default())
+        .err()
+        .unwrap()
+        .errori;
+    assert_eq!(errori.len(), 2);
+    assert_eq!(errori[0].campo(), Some("microbentivori"));
+    assert_eq!(errori[1].campo(), Some("macrobentivori"));
+}
+
+#[test]
+fn test_json_input_hfbi_riferimento() {
+    let json = serde_json::json!({
+        "anagrafica": {
+            "codiceStazione": "ST1", "corpoIdrico": "CORPO", "regione": "REGIONE",
+            "provincia": "PROVINCIA", "data": "1/1/2020", "lunghezzaStazione": 100.0,
+            "larghezzaStazione": 8.0, "stagione": 0, "habitat": 1, "tipoLaguna": 1
+        },
+        "riferimento": [
+            {
+                "nomeComune": "Anguilla", "codiceSpecie": "AN", "autoctono": 1, "gruppoEco": 1,
+                "microbentivori": 0.0, "macrobentivori": 0.5, "iperbentivori": 0.5,
+                "erbivori": 0.0, "detritivori": 0.0, "planctivori": 0.0, "onnivori": 0.0
+            }
+        ],
+        "campionamento": [
+            { "codiceSpecie": "AN", "numeroIndividui": 25, "peso": 240.0 }
+        ]
+    })
+    .to_string();
+    let (valutazione, _) = check_input_hfbi_json(json.as_bytes()).unwrap();
+    let specie = &valutazione.campionamento.campionamento[0].specie;
+    assert_eq!(specie.gruppo_trofico.macrobentivori, 0.5);
+
+    // CH è nell'elenco predefinito ma non in quello fornito
+    let json = json.replace(
+        "\"codiceSpecie\":\"AN\",\"numeroIndividui\"",
+        "\"codiceSpecie\":\"CH\",\"numeroIndividui\"",
+    );
+    let diagnostics = check_input_hfbi_json(json.as_bytes()).err().unwrap();
+    assert_eq!(diagnostics.errori.len(), 1);
+    assert_eq!(diagnostics.errori[0].campo(), Some("codiceSpecie"));
+}
+
+#[test]
+fn test_condizioni_riferimento_hfbi_da_file() {
+    let reader = Cursor::new(include_bytes!(
+        "../../../templates/condizioni_riferimento_hfbi.csv"
+    ));
+    let (records, diagnostics) = leggi_csv::<_, PlainRecordCsvCondizioniRiferimentoHFBI>(
+        reader,
+        &OpzioniLettura::default().auto(),
+    )
+    .unwrap();
+    assert_eq!(
+        diagnostics.formato.unwrap().tipo_record,
+        Some(TipoRecordCsv::CondizioniRiferimentoHFBI)
+    );
+    let (tabella, diagnostics) =
+        check_records_condizioni_riferimento_hfbi(records, &OpzioniVerifica::default()).unwrap();
+    assert_eq!(diagnostics.record_validi, 12);
+    assert!(tabella.is_completa());
+    assert_eq!(&tabella, TabellaCondizioniRiferimentoHFBI::ufficiale());
+}
+
+#[test]
+fn test_condizioni_riferimento_hfbi_non_valide() {
+    let righe = include_str!("../../../templates/condizioni_riferimento_hfbi.csv")
+        .lines()
+        .collect::<Vec<_>>();
+    // Prima riga mancante, seconda ripetuta, terza con un valore nullo
+    let csv_data = format!(
+        "{}\n{}\n{}\n{}\n{}",
+        CONDIZIONI_RIFERIMENTO_HFBI_HEADER,
+        righe[2],
+        righe[2],
+        "1;1;0;1,932;0;2,014;7,572;2,329;2,083",
+        righe[4..].join("\n"),
+    );
+    let leggi = || {
+        check_condizioni_riferimento_hfbi_reader::<_, VeryItalianRecordCsvCondizioniRiferimentoHFBI>(
+            Cursor::new(csv_data.clone()),
+            true,
+        )
+        .unwrap()
+        .0
+    };
+    let diagnostics =
+        check_records_condizioni_riferimento_hfbi(leggi(), &OpzioniVerifica::default())
+            .err()
+            .unwrap();
+    assert_eq!(diagnostics.record_validi, 10);
+    let errori: Vec<_> = diagnostics
+        .errori
+        .iter()
+        .map(|e| (e.record(), e.campo()))
+        .collect();
+    assert_eq!(
+        errori,
+        vec![(Some(2), None), (Some(3), Some("ddom")), (None, None)]
+    );
+    assert_eq!(
+        diagnostics.errori[0].testo(Lingua::Inglese),
+        "reference conditions repeated for M-AT-1/Spring/Unvegetated"
+    );
+    assert_eq!(
+        diagnostics.errori[2].testo(Lingua::Inglese),
+        "missing reference conditions for: M-AT-1/Spring/Vegetated, M-AT-1/Autumn/Vegetated"
+    );
+
+    // Una tabella incompleta non è valida nemmeno scartando le righe errate
+    let diagnostics = check_records_condizioni_riferimento_hfbi(
+        leggi(),
+        &OpzioniVerifica::default().modalita(ValidationMode::Lenient),
+    )
+    .err()
+    .unwrap();
+    assert_eq!(diagnostics.scartati.len(), 2);
+    assert_eq!(diagnostics.errori.len(), 1);
+}
+
+#[test]
+fn test_comunita_riferimento_niseci_da_file() {
+    let reader = Cursor::new(include_bytes!(
+        "../../../templates/comunita_riferimento_niseci.csv"
+    ));
+    let (records, diagnostics) = leggi_csv::<_, PlainRecordCsvComunitaRiferimentoNISECI>(
+        reader,
+        &OpzioniLettura::default().auto(),
+    )
+    .unwrap();
+    assert_eq!(
+        diagnostics.formato.unwrap().tipo_record,
+        Some(TipoRecordCsv::ComunitaRiferimentoNISECI)
+    );
+    let (comunita, _) =
+        check_records_comunita_riferimento_niseci(records, &OpzioniVerifica::default()).unwrap();
+
+    // Le soglie sono quelle del file di riferimento completo da cui è tratto il catalogo
+    let reader = Cursor::new(include_bytes!("../../../templates/riferimento_niseci.csv"));
+    let (records, _) =
+        leggi_csv::<_, PlainRecordCsvRiferimentoNISECI>(reader, &OpzioniLettura::default().auto())
+            .unwrap();
+    let (riferimento, _) =
+        check_records_riferimento_niseci(records, &OpzioniVerifica::default()).unwrap();
+    assert_eq!(riferimento.len(), CATALOGO_NISECI.len());
+    assert_eq!(comunita.len(), 10);
+    for specie in &comunita {
+        let attesa = riferimento.iter().find(|s| s.id == specie.id).unwrap();
+        assert_eq!(specie.nome.trim(), attesa.nome.trim());
+        assert_eq!(specie.specie_attesa, attesa.specie_attesa);
+        assert_eq!(specie.tipo_autoctono, attesa.tipo_autoctono);
+        assert_eq!(specie.cl_soglia4, attesa.cl_soglia4);
+        assert_eq!(specie.ad_juv_soglia2, attesa.ad_juv_soglia2);
+        assert_eq!(specie.dens_soglia1, attesa.dens_soglia1);
+        assert_eq!(specie.dens_soglia2, attesa.dens_soglia2);
+    }
+
+    let voce = cerca_specie_catalogo_niseci("abramis brama").unwrap();
+    assert_eq!(voce.codice_specie, "AB");
+    assert!(!voce.autoctona);
+    assert_eq!(voce.allo_nocivita, 2);
+}
+
+#[test]
+fn test_comunita_riferimento_niseci_soglie_solo_json() {
+    // Le colonne delle soglie non fanno parte del file csv: valgono quelle del catalogo
+    let csv_data = "codiceSpecie;specieAttesa;tipoAutoctono;clSoglia1\nAL;1;2;10";
+    let (records, _) = leggi_csv::<_, PlainRecordCsvComunitaRiferimentoNISECI>(
+        Cursor::new(csv_data),
+        &OpzioniLettura::default().auto(),
+    )
+    .unwrap();
+    assert!(records[0].soglie.is_none());
+    let (comunita, _) =
+        check_records_comunita_riferimento_niseci(records, &OpzioniVerifica::default()).unwrap();
+    assert_eq!(comunita[0].cl_soglia1, 45);
+}
+
+#[test]
+fn test_comunita_riferimento_niseci_non_valida() {
+    let csv_data = format!(
+        "{}\n{}\n{}\n{}\n{}\n{}\n{}",
+        COMUNITA_RIFERIMENTO_NISECI_HEADER,
+        "AL;1;2",
+        "Alborella;0;2",
+        "ALBX;1;2",
+        "BA;1;3",
+        "ALM;1;2",
+        "AB;0;7",
+    );
+    let (records, _) = check_comunita_riferimento_niseci_reader::<
+        _,
+        VeryItalianRecordCsvComunitaRiferimentoNISECI,
+    >(Cursor::new(csv_data), true)
+    .unwrap();
+    let diagnostics =
+        check_records_comunita_riferimento_niseci(records, &OpzioniVerifica::default())
+            .err()
+            .unwrap();
+    assert_eq!(diagnostics.record_validi, 2);
+    let errori: Vec<_> = diagnostics
+        .errori
+        .iter()
+        .map(|e| (e.record(), e.campo()))
+        .collect();
+    assert_eq!(
+        errori,
+        vec![
+            (Some(2), Some("codiceSpecie")),
+            (Some(3), Some("codiceSpecie")),
+            (Some(4), Some("tipoAutoctono")),
+            (Some(5), Some("densSoglia1")),
+        ]
+    );
+    assert_eq!(
+        diagnostics.errori[1]
+            .suggerimento()
+            .map(|s| s.codice.as_str()),
+        Some("AL")
+    );
+}
+
+#[test]
+fn test_json_input_niseci_comunita() {
+    let mut json: serde_json::Value = serde_json::from_str(&input_niseci_json("BA")).unwrap();
+    json["comunita"] = serde_json::json!([
+        { "codiceSpecie": "BA", "specieAttesa": 1, "tipoAutoctono": 2 },
+        {
+            "codiceSpecie": "ALM", "specieAttesa": 1, "tipoAutoctono": 2,
+            "soglie": { "densSoglie": [0.01, 0.02] }
+        }
+    ]);
+    let diagnostics = check_input_niseci_json(json.to_string().as_bytes())
+        .err()
+        .unwrap();
+    assert_eq!(diagnostics.errori.len(), 1);
+    assert_eq!(diagnostics.errori[0].campo(), Some("comunita"));
+    assert_eq!(
+        diagnostics.errori[0].testo(Lingua::Inglese),
+        "give either riferimento or comunita, not both"
+    );
+
+    json.as_object_mut().unwrap().remove("riferimento");
+    let (valutazione, _) = check_input_niseci_json(json.to_string().as_bytes()).unwrap();
+    let elenco = &valutazione.riferimento.elenco_specie;
+    assert_eq!(elenco.len(), 2);
+    assert_eq!(elenco[0].cl_soglia2, 170);
+    assert_eq!(elenco[1].cl_soglia2, 90);
+    assert_eq!(elenco[1].dens_soglia2, 0.02);
+}
+
+#[test]
+fn test_templates_header() {
+    let formato = FormatoOutput::italiano();
+    for (tipo, header) in [
+        (TipoRecordCsv::RiferimentoNISECI, RIFERIMENTO_NISECI_HEADER),
+        (
+            TipoRecordCsv::CampionamentoNISECI,
+            CAMPIONAMENTO_NISECI_HEADER,
+        ),
+        (TipoRecordCsv::AnagraficaNISECI, ANAGRAFICA_NISECI_HEADER),
+        (TipoRecordCsv::CampionamentoHFBI, CAMPIONAMENTO_HFBI_HEADER),
+        (TipoRecordCsv::AnagraficaHFBI, ANAGRAFICA_HFBI_HEADER),
+        (TipoRecordCsv::RiferimentoHFBI, RIFERIMENTO_HFBI_HEADER),
+        (
+            TipoRecordCsv::CondizioniRiferimentoHFBI,
+            CONDIZIONI_RIFERIMENTO_HFBI_HEADER,
+        ),
+        (
+            TipoRecordCsv::ComunitaRiferimentoNISECI,
+            COMUNITA_RIFERIMENTO_NISECI_HEADER,
+        ),
+    ] {
+        let template = genera_template(tipo, &formato, ContenutoTemplate::Vuoto);
+        assert_eq!(template, format!("{}\n", header));
+    }
+
+    let template = genera_template(
+        TipoRecordCsv::CampionamentoHFBI,
+        &FormatoOutput::internazionale(),
+        ContenutoTemplate::Esempio,
+    );
+    assert_eq!(
+        template,
+        "codiceSpecie,numeroIndividui,peso\nAN,25,240\nCH,41,110.5\n"
+    );
+}
+
+#[test]
+fn test_templates_esempio_valid() {
+    for formato in [FormatoOutput::italiano(), FormatoOutput::internazionale()] {
+        let template = |tipo| genera_template(tipo, &formato, ContenutoTemplate::Esempio);
+
+        let (records, diagnostics) = leggi_csv::<_, PlainRecordCsvRiferimentoNISECI>(
+            Cursor::new(template(TipoRecordCsv::RiferimentoNISECI)),
+            &OpzioniLettura::default().auto(),
+        )
+        .unwrap();
+        assert_eq!(
+            diagnostics.formato.unwrap().delimitatore,
+            formato.delimitatore
+        );
+        let (riferimento_specie, _) =
+            check_records_riferimento_niseci(records, &OpzioniVerifica::default()).unwrap();
+
+        let (records, _) = leggi_csv::<_, PlainRecordCsvCampionamentoNISECI>(
+            Cursor::new(template(TipoRecordCsv::CampionamentoNISECI)),
+            &OpzioniLettura::default().auto(),
+        )
+        .unwrap();
+        let (campionamento, _) = check_records_campionamento_niseci(
+            records,
+            riferimento_specie,
+            &OpzioniVerifica::default(),
+        )
+        .unwrap();
+        assert_eq!(campionamento[1].peso, 2.5);
+
+        let (records, _) = leggi_csv::<_, PlainRecordCsvAnagraficaNISECI>(
+            Cursor::new(template(TipoRecordCsv::AnagraficaNISECI)),
+            &OpzioniLettura::default().auto(),
+        )
+        .unwrap();
+        let (anagrafica, _) = check_records_anagrafica_niseci(records).unwrap();
+        assert_eq!(anagrafica.larghezza_media_stazione, 8.5);
+
+        let (records, _) = leggi_csv::<_, PlainRecordCsvCampionamentoHFBI>(
+            Cursor::new(template(TipoRecordCsv::CampionamentoHFBI)),
+            &OpzioniLettura::default().auto(),
+        )
+        .unwrap();
+        assert!(check_records_campionamento_hfbi(records, &OpzioniVerifica::default()).is_ok());
+
+        let (records, _) = leggi_csv::<_, PlainRecordCsvAnagraficaHFBI>(
+            Cursor::new(template(TipoRecordCsv::AnagraficaHFBI)),
+            &OpzioniLettura::default().auto(),
+        )
+        .unwrap();
+        assert!(check_records_anagrafica_hfbi(records).is_ok());
+
+        let (records, _) = leggi_csv::<_, PlainRecordCsvRiferimentoHFBI>(
+            Cursor::new(template(TipoRecordCsv::RiferimentoHFBI)),
+            &OpzioniLettura::default().auto(),
+        )
+        .unwrap();
+        let (riferimento, _) =
+            check_records_riferimento_hfbi(records, &OpzioniVerifica::default()).unwrap();
+        assert_eq!(
+            riferimento.elenco_specie[1].gruppo_trofico.microbentivori,
+            0.4
+        );
+
+        let (records, _) = leggi_csv::<_, PlainRecordCsvCondizioniRiferimentoHFBI>(
+            Cursor::new(template(TipoRecordCsv::CondizioniRiferimentoHFBI)),
+            &OpzioniLettura::default().auto(),
+        )
+        .unwrap();
+        let (tabella, _) =
+            check_records_condizioni_riferimento_hfbi(records, &OpzioniVerifica::default())
+                .unwrap();
+        assert_eq!(&tabella, TabellaCondizioniRiferimentoHFBI::ufficiale());
+
+        let (records, _) = leggi_csv::<_, PlainRecordCsvComunitaRiferimentoNISECI>(
+            Cursor::new(template(TipoRecordCsv::ComunitaRiferimentoNISECI)),
+            &OpzioniLettura::default().auto(),
+        )
+        .unwrap();
+        let (comunita, _) =
+            check_records_comunita_riferimento_niseci(records, &OpzioniVerifica::default())
+                .unwrap();
+        assert_eq!(comunita[2].tipo_alloctono, 2);
+    }
 }
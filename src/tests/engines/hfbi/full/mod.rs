@@ -19,7 +19,9 @@ use crate::csv::deser::hfbi::{
     VeryItalianRecordCsvAnagraficaHFBI, VeryItalianRecordCsvCampionamentoHFBI,
 };
 use crate::csv::parser::hfbi::{check_records_anagrafica_hfbi, check_records_campionamento_hfbi};
-use crate::domain::hfbi::CampionamentoHFBI;
+use crate::csv::parser::OpzioniVerifica;
+use crate::csv::ser::{write_riepilogo_hfbi, FormatoOutput};
+use crate::domain::hfbi::{CampionamentoHFBI, RisultatoHFBI};
 use crate::engines::hfbi::full::calculate_hfbi;
 use std::io::Cursor;
 
@@ -39,13 +41,15 @@ fn calculate_hfbi_template() {
 
     assert!(campionamento_csv_check.is_ok());
 
-    let campionamento_csv_records = campionamento_csv_check.expect("is_ok() was checked before");
+    let (campionamento_csv_records, _) =
+        campionamento_csv_check.expect("is_ok() was checked before");
 
-    let campionamento_value_check = check_records_campionamento_hfbi(campionamento_csv_records);
+    let campionamento_value_check =
+        check_records_campionamento_hfbi(campionamento_csv_records, &OpzioniVerifica::default());
 
     assert!(campionamento_value_check.is_ok());
 
-    let campionamento_specie = campionamento_value_check.expect("is_ok() was checked before");
+    let (campionamento_specie, _) = campionamento_value_check.expect("is_ok() was checked before");
 
     let anagrafica_reader = Cursor::new(ANAGRAFICA_HFBI_TEMPLATE_DATA);
 
@@ -56,13 +60,13 @@ fn calculate_hfbi_template() {
 
     assert!(anagrafica_csv_check.is_ok());
 
-    let anagrafica_csv_records = anagrafica_csv_check.expect("is_ok() was checked before");
+    let (anagrafica_csv_records, _) = anagrafica_csv_check.expect("is_ok() was checked before");
 
     let anagrafica_value_check = check_records_anagrafica_hfbi(anagrafica_csv_records);
 
     assert!(anagrafica_value_check.is_ok());
 
-    let anagrafica = anagrafica_value_check.expect("is_ok() was checked before");
+    let (anagrafica, _) = anagrafica_value_check.expect("is_ok() was checked before");
 
     let campionamento = CampionamentoHFBI {
         campionamento: campionamento_specie,
@@ -82,3 +86,51 @@ fn calculate_hfbi_template() {
     assert_eq!(intermediates.dhzp, 0.417);
     assert_eq!(intermediates.dmig, 0.421);
 }
+
+#[test]
+fn write_hfbi_template() {
+    let campionamento_reader = Cursor::new(CAMPIONAMENTO_HFBI_TEMPLATE_DATA);
+    let (campionamento_csv_records, _) = check_campionamento_hfbi_reader::<
+        _,
+        VeryItalianRecordCsvCampionamentoHFBI,
+    >(campionamento_reader, true)
+    .expect("template should be valid");
+    let (campionamento_specie, _) =
+        check_records_campionamento_hfbi(campionamento_csv_records, &OpzioniVerifica::default())
+            .expect("template should be valid");
+
+    let anagrafica_reader = Cursor::new(ANAGRAFICA_HFBI_TEMPLATE_DATA);
+    let (anagrafica_csv_records, _) = check_anagrafica_hfbi_reader::<
+        _,
+        VeryItalianRecordCsvAnagraficaHFBI,
+    >(anagrafica_reader, true)
+    .expect("template should be valid");
+    let (anagrafica, _) =
+        check_records_anagrafica_hfbi(anagrafica_csv_records).expect("template should be valid");
+
+    let campionamento = CampionamentoHFBI {
+        campionamento: campionamento_specie,
+    };
+    let (hfbi, intermediates) =
+        calculate_hfbi(&campionamento, &anagrafica).expect("template is valid");
+    let risultato = RisultatoHFBI::new(Some(hfbi), intermediates);
+
+    let mut riepilogo = Vec::new();
+    write_riepilogo_hfbi(
+        &mut riepilogo,
+        &[(&anagrafica, &risultato)],
+        &FormatoOutput::italiano(),
+    )
+    .expect("writing to a Vec should not fail");
+    let riepilogo = String::from_utf8(riepilogo).unwrap();
+    let righe: Vec<&str> = riepilogo.lines().collect();
+
+    assert_eq!(
+        righe[0],
+        "codiceStazione;data;hfbi;statoEcologico;mmi;bn;ddom;dmig;bbent;dbent;dhzp"
+    );
+    assert_eq!(
+        righe[1],
+        "CODICE;1/1/1111;1,3;Eccellente;0,362;1,587;0,551;0,421;3,638;0,154;0,417"
+    );
+}
@@ -0,0 +1,123 @@
+// SPDX-License-Identifier: GPL-3.0-only
+/*
+    Copyright (C) 2024-2026 jgabaut, gioninjo
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU General Public License as published by
+    the Free Software Foundation, version 3 of the License.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU General Public License for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use super::full::leggi_template_niseci;
+use crate::domain::niseci::CampionamentoNISECI;
+use crate::engines::niseci::bootstrap::{calculate_niseci_bootstrap, OpzioniBootstrapNISECI};
+use crate::engines::niseci::full::calculate_stato_ecologico_niseci;
+use crate::error::Error;
+use crate::i18n::Lingua;
+
+#[test]
+fn test_bootstrap_niseci_template() {
+    let (campionamento, riferimento, anagrafica) = leggi_template_niseci();
+    let opzioni = OpzioniBootstrapNISECI {
+        repliche: 200,
+        ..Default::default()
+    };
+
+    let bootstrap = calculate_niseci_bootstrap(&campionamento, &riferimento, &anagrafica, &opzioni)
+        .expect("template is valid");
+
+    assert_eq!(bootstrap.niseci, Some(0.209));
+    assert_eq!(
+        bootstrap.distribuzione_niseci.len() + bootstrap.repliche_non_calcolabili,
+        200
+    );
+    assert_eq!(
+        bootstrap.distribuzione_rqe.len(),
+        bootstrap.distribuzione_niseci.len()
+    );
+    let (inferiore, superiore) = bootstrap.intervallo_niseci.expect("some replicates");
+    assert!(inferiore <= superiore);
+    let (inferiore_rqe, superiore_rqe) = bootstrap.intervallo_rqe.expect("some replicates");
+    assert!(inferiore_rqe <= superiore_rqe);
+
+    let totale: f32 = bootstrap
+        .probabilita_stato
+        .iter()
+        .map(|p| p.probabilita)
+        .sum();
+    assert!((totale - 1.0).abs() < 1e-4);
+    let stato = calculate_stato_ecologico_niseci(bootstrap.niseci, &anagrafica.area)
+        .expect("niseci is Some");
+    assert!(bootstrap.probabilita(stato) > 0.0);
+
+    // Lo stesso seme dà lo stesso risultato
+    let ripetuto = calculate_niseci_bootstrap(&campionamento, &riferimento, &anagrafica, &opzioni)
+        .expect("template is valid");
+    assert_eq!(
+        ripetuto.distribuzione_niseci,
+        bootstrap.distribuzione_niseci
+    );
+
+    let con_passaggi = calculate_niseci_bootstrap(
+        &campionamento,
+        &riferimento,
+        &anagrafica,
+        &OpzioniBootstrapNISECI {
+            ricampiona_passaggi: true,
+            ..opzioni
+        },
+    )
+    .expect("template is valid");
+    assert!(con_passaggi.intervallo_niseci.is_some());
+}
+
+#[test]
+fn test_bootstrap_niseci_esemplari_identici() {
+    // Tutti gli esemplari sono uguali: ogni replica coincide con il campionamento
+    let (campionamento, riferimento, anagrafica) = leggi_template_niseci();
+    let barbo = campionamento.campionamento[0].clone();
+    let campionamento = CampionamentoNISECI {
+        campionamento: vec![barbo; 12],
+    };
+    let opzioni = OpzioniBootstrapNISECI {
+        repliche: 50,
+        ..Default::default()
+    };
+
+    let bootstrap = calculate_niseci_bootstrap(&campionamento, &riferimento, &anagrafica, &opzioni)
+        .expect("campionamento is valid");
+
+    let niseci = bootstrap.niseci.expect("BA is expected");
+    assert_eq!(bootstrap.intervallo_niseci, Some((niseci, niseci)));
+    assert_eq!(bootstrap.repliche_non_calcolabili, 0);
+    let stato =
+        calculate_stato_ecologico_niseci(Some(niseci), &anagrafica.area).expect("niseci is Some");
+    assert_eq!(bootstrap.probabilita(stato), 1.0);
+    assert_eq!(bootstrap.stato_piu_probabile(), Some(stato));
+}
+
+#[test]
+fn test_bootstrap_niseci_livello_non_valido() {
+    let (campionamento, riferimento, anagrafica) = leggi_template_niseci();
+    let opzioni = OpzioniBootstrapNISECI {
+        livello_confidenza: 95.0,
+        ..Default::default()
+    };
+
+    let res = calculate_niseci_bootstrap(&campionamento, &riferimento, &anagrafica, &opzioni);
+    let Err(errors) = res else {
+        panic!("95 is not a valid confidence level");
+    };
+    assert!(matches!(errors[0], Error::ValoreInvalido(_)));
+    assert_eq!(
+        errors[0].testo(Lingua::Inglese),
+        "invalid confidence level 95, must be between 0 and 1"
+    );
+}
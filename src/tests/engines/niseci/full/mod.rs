@@ -17,21 +17,38 @@
 
 use crate::csv::deser::niseci::{
     check_anagrafica_niseci_reader, check_campionamento_niseci_reader,
-    check_riferimento_niseci_reader, VeryItalianRecordCsvAnagraficaNISECI,
-    VeryItalianRecordCsvCampionamentoNISECI, VeryItalianRecordCsvRiferimentoNISECI,
+    check_comunita_riferimento_niseci_reader, check_riferimento_niseci_reader,
+    VeryItalianRecordCsvAnagraficaNISECI, VeryItalianRecordCsvCampionamentoNISECI,
+    VeryItalianRecordCsvComunitaRiferimentoNISECI, VeryItalianRecordCsvRiferimentoNISECI,
 };
 use crate::csv::parser::niseci::{
-    check_records_anagrafica_niseci, check_records_campionamento_niseci,
+    check_records_anagrafica_niseci, check_records_campionamenti_niseci,
+    check_records_campionamento_niseci, check_records_comunita_riferimento_niseci,
     check_records_riferimento_niseci,
 };
-use crate::domain::niseci::{CampionamentoNISECI, RiferimentoNISECI};
+use crate::csv::parser::OpzioniVerifica;
+use crate::csv::ser::{write_riepilogo_niseci, write_specie_niseci, FormatoOutput};
+use crate::domain::niseci::traccia::{CondizioneX3, EventoNISECI, GruppoSpecieNISECI};
+use crate::domain::niseci::{
+    AnagraficaNISECI, CampionamentoNISECI, ChiaveCampionamentoNISECI, RiferimentoNISECI,
+    RisultatoNISECI, StatoEcologicoNISECI,
+};
+use crate::domain::registro::RegistroStazioni;
+use crate::error::Error;
 use crate::{
-    engines::niseci::full::calculate_niseci,
+    engines::niseci::full::{
+        calculate_niseci, calculate_niseci_per_campionamento, calculate_niseci_with_stimatore,
+        calculate_niseci_with_traccia, calculate_rqe_niseci,
+    },
+    engines::niseci::linear_regression::{MetodoRegressione, SegnoPendenza},
+    engines::niseci::stimatori::{AvvisoRimozione, StimatorePopolazione, ESEMPLARI_MINIMI_STIMA},
+    i18n::Lingua,
     tests::test_utils::{
         create_dummy_anagrafica, create_dummy_campionamento_chopped,
         create_dummy_campionamento_full, create_dummy_riferimento,
     },
 };
+use chrono::NaiveDate;
 use std::io::Cursor;
 
 const RIFERIMENTO_NISECI_TEMPLATE_DATA: &[u8] =
@@ -40,6 +57,8 @@ const CAMPIONAMENTO_NISECI_TEMPLATE_DATA: &[u8] =
     include_bytes!("../../../../../templates/campionamento_niseci.csv");
 const ANAGRAFICA_NISECI_TEMPLATE_DATA: &[u8] =
     include_bytes!("../../../../../templates/anagrafica_niseci.csv");
+const COMUNITA_RIFERIMENTO_NISECI_TEMPLATE_DATA: &[u8] =
+    include_bytes!("../../../../../templates/comunita_riferimento_niseci.csv");
 
 #[test]
 fn calculate_dummy_niseci_campionamento_full() {
@@ -69,56 +88,106 @@ fn calculate_dummy_niseci_campionamento_chopped() {
 
 #[test]
 fn calculate_niseci_template() {
-    let riferimento_reader = Cursor::new(RIFERIMENTO_NISECI_TEMPLATE_DATA);
-    let riferimento_csv_check = check_riferimento_niseci_reader::<
-        _,
-        VeryItalianRecordCsvRiferimentoNISECI,
-    >(riferimento_reader, true);
+    let (campionamento, riferimento, anagrafica) = leggi_template_niseci();
 
-    assert!(riferimento_csv_check.is_ok());
+    let calc_niseci_res = calculate_niseci(&campionamento, &riferimento, &anagrafica);
 
-    let riferimento_csv_records = riferimento_csv_check.expect("is_ok() was checked before");
+    assert!(calc_niseci_res.is_ok());
 
-    let riferimento_value_check = check_records_riferimento_niseci(riferimento_csv_records);
+    let (niseci, intermediates) = calc_niseci_res.expect("is_ok() was checked before");
 
-    assert!(riferimento_value_check.is_ok());
+    assert_eq!(niseci, Some(0.209));
+    assert_eq!(intermediates.x1, 0.429);
+    assert_eq!(intermediates.x2, Some(0.267));
+    assert_eq!(intermediates.x3, 1.0);
+}
 
-    let riferimento_specie = riferimento_value_check.expect("is_ok() was checked before");
+#[test]
+fn calculate_niseci_template_comunita_da_catalogo() {
+    // La comunità elenca solo le specie attese e quelle catturate: il risultato è lo stesso
+    // ottenuto con il file di riferimento completo
+    let comunita_reader = Cursor::new(COMUNITA_RIFERIMENTO_NISECI_TEMPLATE_DATA);
+    let (comunita_csv_records, _) = check_comunita_riferimento_niseci_reader::<
+        _,
+        VeryItalianRecordCsvComunitaRiferimentoNISECI,
+    >(comunita_reader, true)
+    .expect("template should be valid");
+    let (riferimento_specie, _) = check_records_comunita_riferimento_niseci(
+        comunita_csv_records,
+        &OpzioniVerifica::default(),
+    )
+    .expect("template should be valid");
 
     let campionamento_reader = Cursor::new(CAMPIONAMENTO_NISECI_TEMPLATE_DATA);
-
-    let campionamento_csv_check = check_campionamento_niseci_reader::<
+    let (campionamento_csv_records, _) = check_campionamento_niseci_reader::<
         _,
         VeryItalianRecordCsvCampionamentoNISECI,
-    >(campionamento_reader, true);
-
-    assert!(campionamento_csv_check.is_ok());
-
-    let campionamento_csv_records = campionamento_csv_check.expect("is_ok() was checked before");
-
-    let campionamento_value_check =
-        check_records_campionamento_niseci(campionamento_csv_records, riferimento_specie.clone());
-
-    assert!(campionamento_value_check.is_ok());
-
-    let campionamento_specie = campionamento_value_check.expect("is_ok() was checked before");
+    >(campionamento_reader, true)
+    .expect("template should be valid");
+    let (campionamento_specie, _) = check_records_campionamento_niseci(
+        campionamento_csv_records,
+        riferimento_specie.clone(),
+        &OpzioniVerifica::default(),
+    )
+    .expect("template should be valid");
 
     let anagrafica_reader = Cursor::new(ANAGRAFICA_NISECI_TEMPLATE_DATA);
-
-    let anagrafica_csv_check = check_anagrafica_niseci_reader::<
+    let (anagrafica_csv_records, _) = check_anagrafica_niseci_reader::<
         _,
         VeryItalianRecordCsvAnagraficaNISECI,
-    >(anagrafica_reader, true);
+    >(anagrafica_reader, true)
+    .expect("template should be valid");
+    let (anagrafica, _) =
+        check_records_anagrafica_niseci(anagrafica_csv_records).expect("template should be valid");
 
-    assert!(anagrafica_csv_check.is_ok());
+    let campionamento = CampionamentoNISECI {
+        campionamento: campionamento_specie,
+    };
+    let riferimento = RiferimentoNISECI {
+        elenco_specie: riferimento_specie,
+    };
+    let (niseci, intermediates) =
+        calculate_niseci(&campionamento, &riferimento, &anagrafica).expect("template is valid");
 
-    let anagrafica_csv_records = anagrafica_csv_check.expect("is_ok() was checked before");
+    assert_eq!(niseci, Some(0.209));
+    assert_eq!(intermediates.x1, 0.429);
+    assert_eq!(intermediates.x2, Some(0.267));
+    assert_eq!(intermediates.x3, 1.0);
+}
 
-    let anagrafica_value_check = check_records_anagrafica_niseci(anagrafica_csv_records);
+pub(super) fn leggi_template_niseci() -> (CampionamentoNISECI, RiferimentoNISECI, AnagraficaNISECI)
+{
+    let riferimento_reader = Cursor::new(RIFERIMENTO_NISECI_TEMPLATE_DATA);
+    let (riferimento_csv_records, _) = check_riferimento_niseci_reader::<
+        _,
+        VeryItalianRecordCsvRiferimentoNISECI,
+    >(riferimento_reader, true)
+    .expect("template should be valid");
+    let (riferimento_specie, _) =
+        check_records_riferimento_niseci(riferimento_csv_records, &OpzioniVerifica::default())
+            .expect("template should be valid");
 
-    assert!(anagrafica_value_check.is_ok());
+    let campionamento_reader = Cursor::new(CAMPIONAMENTO_NISECI_TEMPLATE_DATA);
+    let (campionamento_csv_records, _) = check_campionamento_niseci_reader::<
+        _,
+        VeryItalianRecordCsvCampionamentoNISECI,
+    >(campionamento_reader, true)
+    .expect("template should be valid");
+    let (campionamento_specie, _) = check_records_campionamento_niseci(
+        campionamento_csv_records,
+        riferimento_specie.clone(),
+        &OpzioniVerifica::default(),
+    )
+    .expect("template should be valid");
 
-    let anagrafica = anagrafica_value_check.expect("is_ok() was checked before");
+    let anagrafica_reader = Cursor::new(ANAGRAFICA_NISECI_TEMPLATE_DATA);
+    let (anagrafica_csv_records, _) = check_anagrafica_niseci_reader::<
+        _,
+        VeryItalianRecordCsvAnagraficaNISECI,
+    >(anagrafica_reader, true)
+    .expect("template should be valid");
+    let (anagrafica, _) =
+        check_records_anagrafica_niseci(anagrafica_csv_records).expect("template should be valid");
 
     let campionamento = CampionamentoNISECI {
         campionamento: campionamento_specie,
@@ -127,14 +196,333 @@ fn calculate_niseci_template() {
         elenco_specie: riferimento_specie,
     };
 
-    let calc_niseci_res = calculate_niseci(&campionamento, &riferimento, &anagrafica);
+    (campionamento, riferimento, anagrafica)
+}
 
-    assert!(calc_niseci_res.is_ok());
+#[test]
+fn calculate_niseci_template_stimatori() {
+    let (campionamento, riferimento, anagrafica) = leggi_template_niseci();
+
+    // Lo stimatore classico con la regressione storica dà lo stesso risultato
+    let (niseci, intermediates) = calculate_niseci_with_stimatore(
+        &campionamento,
+        &riferimento,
+        &anagrafica,
+        StimatorePopolazione::Classico(MetodoRegressione::DiscesaGradiente),
+    )
+    .expect("template is valid");
+    assert_eq!(niseci, Some(0.209));
+    let barbo = &intermediates.specie_specifici["BA"];
+    assert_eq!(barbo.quantita_stimata, 75);
+    let stima = barbo.stima_popolazione.as_ref().expect("BA was sampled");
+    assert_eq!(stima.errore_standard, None);
+    // tre passaggi: la stima viene dalla retta di rimozione, con la sua diagnostica
+    let retta = stima.retta.as_ref().expect("three passes");
+    assert_eq!(retta.metodo, MetodoRegressione::DiscesaGradiente);
+    assert_eq!(retta.segno_pendenza(), SegnoPendenza::Negativa);
+    assert!(retta.r_quadro > 0.9 && retta.r_quadro <= 1.0);
+    assert_eq!(retta.residui.len(), 3);
+
+    // BA: 43, 21 e 5 esemplari nei tre passaggi
+    let (_, intermediates) = calculate_niseci_with_stimatore(
+        &campionamento,
+        &riferimento,
+        &anagrafica,
+        StimatorePopolazione::CarleStrub,
+    )
+    .expect("template is valid");
+    let barbo = &intermediates.specie_specifici["BA"];
+    assert_eq!(barbo.quantita_stimata, 72);
+    let stima = barbo.stima_popolazione.as_ref().expect("BA was sampled");
+    assert_eq!(stima.stimatore, StimatorePopolazione::CarleStrub);
+    assert!(stima.errore_standard.is_some());
+    let (inferiore, superiore) = stima.intervallo_confidenza.expect("three passes");
+    assert!(inferiore <= 72.0 && 72.0 <= superiore);
+
+    // BA: la stima di Zippin delle stesse catture
+    let (_, intermediates) = calculate_niseci_with_stimatore(
+        &campionamento,
+        &riferimento,
+        &anagrafica,
+        StimatorePopolazione::Zippin,
+    )
+    .expect("template is valid");
+    assert_eq!(intermediates.specie_specifici["BA"].quantita_stimata, 73);
+    // CV: 1, 1 e 0 esemplari, i tre passaggi del campionamento valgono anche per la specie
+    let cavedano = &intermediates.specie_specifici["CV"];
+    assert_eq!(cavedano.quantita_stimata, 2);
+    let stima = cavedano.stima_popolazione.as_ref().expect("CV was sampled");
+    assert!(!stima.somma_catture);
+    assert!((stima.popolazione - 2.178).abs() < 1e-3);
+    assert!(stima.errore_standard.is_some());
+    assert!(!cavedano
+        .avvisi_rimozione
+        .contains(&AvvisoRimozione::SommaCatture));
+    // GH: 20 esemplari tutti al primo passaggio, catturabilità 1 e nessuna incertezza
+    let stima = intermediates.specie_specifici["GH"]
+        .stima_popolazione
+        .as_ref()
+        .expect("GH was sampled");
+    assert_eq!(stima.get_quantita(), 20);
+    assert!(stima.errore_standard.expect("three passes") < 1e-6);
+    assert_eq!(stima.intervallo_confidenza, Some((20.0, 20.0)));
+}
 
-    let (niseci, intermediates) = calc_niseci_res.expect("is_ok() was checked before");
+#[test]
+fn calculate_niseci_template_avvisi_rimozione() {
+    let (campionamento, riferimento, anagrafica) = leggi_template_niseci();
+    let (_, intermediates) =
+        calculate_niseci(&campionamento, &riferimento, &anagrafica).expect("template is valid");
+
+    // BA: 43, 21 e 5 esemplari, la rimozione è regolare
+    assert!(intermediates.specie_specifici["BA"]
+        .avvisi_rimozione
+        .is_empty());
+    // CV: un esemplare in ciascuno dei due passaggi, la stima è la somma delle catture
+    assert_eq!(
+        intermediates.specie_specifici["CV"].avvisi_rimozione,
+        vec![
+            AvvisoRimozione::CattureNonDecrescenti {
+                passaggio: 2,
+                precedenti: 1,
+                catture: 1,
+            },
+            AvvisoRimozione::EsemplariInsufficienti {
+                catture: 2,
+                minimo: ESEMPLARI_MINIMI_STIMA,
+            },
+            AvvisoRimozione::SommaCatture,
+        ]
+    );
+    assert_eq!(
+        intermediates.specie_specifici["CV"].avvisi_rimozione[1].descrizione(Lingua::Inglese),
+        "only 2 fish caught, at least 10 are needed for a reliable estimate"
+    );
+    assert!(
+        intermediates.specie_specifici["CV"]
+            .stima_popolazione
+            .as_ref()
+            .unwrap()
+            .somma_catture
+    );
+}
 
+#[test]
+fn calculate_niseci_template_per_campionamento() {
+    let (_, riferimento, anagrafica) = leggi_template_niseci();
+
+    // Stesso campionamento del template, ripetuto per un'altra stazione e un'altra data
+    let template = String::from_utf8_lossy(CAMPIONAMENTO_NISECI_TEMPLATE_DATA).to_string();
+    let mut campionamento_data = template.clone();
+    for line in template.lines().skip(1) {
+        campionamento_data.push_str(&line.replace("2190627 Reno 390", "STAZ2"));
+        campionamento_data.push_str("\r\n");
+        campionamento_data.push_str(&line.replace("07/07/2019", "08/07/2019"));
+        campionamento_data.push_str("\r\n");
+    }
+    let campionamento_reader = Cursor::new(campionamento_data);
+    let (campionamento_csv_records, _) = check_campionamento_niseci_reader::<
+        _,
+        VeryItalianRecordCsvCampionamentoNISECI,
+    >(campionamento_reader, true)
+    .expect("template should be valid");
+    let (campionamenti, diagnostics) = check_records_campionamenti_niseci(
+        campionamento_csv_records,
+        riferimento.elenco_specie.clone(),
+        &OpzioniVerifica::default(),
+    )
+    .expect("template should be valid");
+
+    assert_eq!(campionamenti.len(), 3);
+    assert_eq!(diagnostics.record_validi, 390 * 3);
+
+    // Un'anagrafica per ciascuna data di Reno 390, nessuna per STAZ2
+    let anagrafiche: RegistroStazioni<_> = ["07/07/2019", "08/07/2019"]
+        .iter()
+        .map(|data| {
+            let mut anagrafica_reno = anagrafica.clone();
+            anagrafica_reno.codice_stazione = "2190627 Reno 390".to_string();
+            anagrafica_reno.date_string = data.to_string();
+            anagrafica_reno.data = None;
+            anagrafica_reno
+        })
+        .collect();
+
+    let risultati = calculate_niseci_per_campionamento(&campionamenti, &riferimento, &anagrafiche);
+
+    assert_eq!(risultati.len(), 3);
+    for giorno in [7, 8] {
+        let chiave = ChiaveCampionamentoNISECI {
+            stazione: "2190627 Reno 390".to_string(),
+            data: NaiveDate::from_ymd_opt(2019, 7, giorno).unwrap(),
+        };
+        let (niseci, _) = risultati[&chiave]
+            .as_ref()
+            .expect("anagrafica was provided");
+        assert_eq!(*niseci, Some(0.209));
+    }
+    let chiave = ChiaveCampionamentoNISECI {
+        stazione: "STAZ2".to_string(),
+        data: NaiveDate::from_ymd_opt(2019, 7, 7).unwrap(),
+    };
+    assert!(risultati[&chiave].is_err());
+
+    // Con la sola anagrafica del 7 luglio, questa vale anche per l'8
+    let solo_7: RegistroStazioni<_> = anagrafiche
+        .iter()
+        .filter(|a| a.date_string == "07/07/2019")
+        .cloned()
+        .collect();
+    let risultati = calculate_niseci_per_campionamento(&campionamenti, &riferimento, &solo_7);
+    let chiave = ChiaveCampionamentoNISECI {
+        stazione: "2190627 Reno 390".to_string(),
+        data: NaiveDate::from_ymd_opt(2019, 7, 8).unwrap(),
+    };
+    assert!(risultati[&chiave].is_ok());
+
+    // Con più anagrafiche della stazione, nessuna dell'8 luglio, non se ne sceglie una
+    let altre_date: RegistroStazioni<_> = ["07/07/2019", "09/07/2019"]
+        .iter()
+        .map(|data| {
+            let mut anagrafica_reno = solo_7.iter().next().unwrap().clone();
+            anagrafica_reno.date_string = data.to_string();
+            anagrafica_reno
+        })
+        .collect();
+    let risultati = calculate_niseci_per_campionamento(&campionamenti, &riferimento, &altre_date);
+    let Err(errors) = &risultati[&chiave] else {
+        panic!("no anagrafica for 08/07/2019");
+    };
+    assert!(matches!(errors[0], Error::Calcolo(_)));
+}
+
+#[test]
+fn write_niseci_template() {
+    let (campionamento, riferimento, anagrafica) = leggi_template_niseci();
+    let (niseci, intermediates) =
+        calculate_niseci(&campionamento, &riferimento, &anagrafica).expect("template is valid");
+    let n_specie = intermediates.specie_specifici.len();
+    let risultato = RisultatoNISECI::new(niseci, calculate_rqe_niseci(niseci), intermediates);
+
+    let mut riepilogo = Vec::new();
+    write_riepilogo_niseci(
+        &mut riepilogo,
+        &[(&anagrafica, &risultato)],
+        &FormatoOutput::italiano(),
+    )
+    .expect("writing to a Vec should not fail");
+    let riepilogo = String::from_utf8(riepilogo).unwrap();
+    let righe: Vec<&str> = riepilogo.lines().collect();
+
+    assert_eq!(righe.len(), 2);
+    assert_eq!(
+        righe[0],
+        "codiceStazione;data;niseci;rqe;statoEcologico;x1;x2;x3"
+    );
+    assert_eq!(
+        righe[1],
+        "CODICE;1/1/1111;0,209;0,42;Moderato;0,429;0,267;1"
+    );
+
+    let mut riepilogo = Vec::new();
+    write_riepilogo_niseci(
+        &mut riepilogo,
+        &[(&anagrafica, &risultato)],
+        &FormatoOutput::internazionale(),
+    )
+    .expect("writing to a Vec should not fail");
+    let riepilogo = String::from_utf8(riepilogo).unwrap();
+    assert_eq!(
+        riepilogo.lines().nth(1),
+        Some("CODICE,1/1/1111,0.209,0.42,Moderato,0.429,0.267,1")
+    );
+
+    let mut specie = Vec::new();
+    write_specie_niseci(
+        &mut specie,
+        &[(&anagrafica, &risultato)],
+        &FormatoOutput::italiano(),
+    )
+    .expect("writing to a Vec should not fail");
+    let specie = String::from_utf8(specie).unwrap();
+    let righe: Vec<&str> = specie.lines().collect();
+
+    assert_eq!(righe.len(), n_specie + 1);
+    assert!(righe[0].starts_with("codiceStazione;data;codiceSpecie;nomeLatino;"));
+    assert!(righe[1..]
+        .iter()
+        .all(|r| r.starts_with("CODICE;1/1/1111;") && r.split(';').count() == 18));
+}
+
+#[test]
+fn calculate_niseci_template_traccia() {
+    let (campionamento, riferimento, anagrafica) = leggi_template_niseci();
+    let (res, traccia) = calculate_niseci_with_traccia(
+        &campionamento,
+        &riferimento,
+        &anagrafica,
+        StimatorePopolazione::default(),
+    );
+    let (niseci, _) = res.expect("template is valid");
     assert_eq!(niseci, Some(0.209));
-    assert_eq!(intermediates.x1, 0.429);
-    assert_eq!(intermediates.x2, Some(0.267));
-    assert_eq!(intermediates.x3, 1.0);
+
+    assert_eq!(
+        traccia.eventi[0],
+        EventoNISECI::Stimatore {
+            stimatore: StimatorePopolazione::default()
+        }
+    );
+    let EventoNISECI::X1 {
+        campionate_altre,
+        attese_altre,
+        x1,
+        ..
+    } = &traccia.eventi[1]
+    else {
+        panic!("x1 follows the estimator");
+    };
+    assert_eq!(campionate_altre, &vec!["BA", "CV", "GH"]);
+    assert_eq!(attese_altre.len(), 7);
+    assert_eq!(*x1, 0.429);
+
+    assert!(traccia.eventi.contains(&EventoNISECI::MediaX2 {
+        sommatoria_x2_a: 0.0,
+        sommatoria_x2_b: 2.0,
+        specie: 3,
+        x2: Some(0.267),
+    }));
+    assert!(traccia.eventi.iter().any(|e| matches!(
+        e,
+        EventoNISECI::Densita { gruppo: GruppoSpecieNISECI::NonAttese, specie, catture_per_passaggio, .. }
+            if specie == "VA" && catture_per_passaggio == &vec![124, 109, 40]
+    )));
+    assert!(traccia.eventi.contains(&EventoNISECI::X3 {
+        alieni: 0,
+        indigeni: 390,
+        condizione: CondizioneX3::NessunAlieno,
+        x3: 1.0,
+    }));
+    assert_eq!(
+        traccia.eventi.last(),
+        Some(&EventoNISECI::Classificazione {
+            area: anagrafica.area,
+            rqe: Some(0.42),
+            stato: Some(StatoEcologicoNISECI::Moderato),
+        })
+    );
+
+    let testo = traccia.testo(Lingua::Inglese);
+    assert_eq!(testo.lines().count(), traccia.eventi.len());
+    assert!(testo.contains("x1 = 0.429, x2 = 0.267, x3 = 1 => 0.209"));
+    // la retta di rimozione di BA, stimata su tre passaggi
+    let barbo = testo
+        .lines()
+        .find(|riga| riga.starts_with("BA ") && riga.contains("density"))
+        .expect("BA density event");
+    assert!(barbo.contains("(line y = "));
+    assert!(barbo.contains("R² = "));
+
+    let json: serde_json::Value = serde_json::from_str(&traccia.json()).expect("valid json");
+    assert_eq!(json["eventi"][1]["evento"], "x1");
+    assert_eq!(json["eventi"][1]["x1"], 0.429);
 }
@@ -16,7 +16,9 @@
 */
 
 use crate::engines::niseci::linear_regression::{
-    calculate_quantita_with_regression, gradient_descent_iterate, Point,
+    adatta_retta, calculate_quantita_with_metodo, calculate_quantita_with_regression,
+    gradient_descent_iterate, minimi_quadrati, stima_quantita_con_retta, LinearRegressionError,
+    MetodoRegressione, Point, SegnoPendenza,
 };
 
 #[test]
@@ -64,3 +66,95 @@ fn test_quantita_stimata_m_positive() {
     assert!(quantita_stimata.is_ok());
     assert_eq!(quantita_stimata.unwrap(), 225);
 }
+
+#[test]
+fn test_minimi_quadrati() {
+    let records = [Point::new(1, 100), Point::new(2, 75), Point::new(3, 50)];
+    let (m, b) = minimi_quadrati(&records).unwrap();
+    assert_eq!(m, -25.0);
+    assert_eq!(b, 125.0);
+
+    let adattamento = adatta_retta(&records, MetodoRegressione::MinimiQuadrati).unwrap();
+    assert_eq!(adattamento.r_quadro, 1.0);
+    assert_eq!(adattamento.residui, vec![0.0, 0.0, 0.0]);
+    assert_eq!(adattamento.segno_pendenza(), SegnoPendenza::Negativa);
+    assert_eq!(adattamento.intersezione_asse_x(), Some(5.0));
+}
+
+#[test]
+fn test_adatta_retta_diagnostica() {
+    let passaggi = [
+        Point::new(70, 70),
+        Point::new(130, 60),
+        Point::new(150, 20),
+        Point::new(160, 10),
+    ];
+
+    let adattamento = adatta_retta(&passaggi, MetodoRegressione::MinimiQuadrati).unwrap();
+    // Sxy = -3100, Sxx = 4875, Syy = 2600
+    assert!((adattamento.m - (-3100.0 / 4875.0)).abs() < 1e-5);
+    assert!((adattamento.r_quadro - 3100.0 * 3100.0 / (4875.0 * 2600.0)).abs() < 1e-5);
+    assert!(adattamento.residui.iter().sum::<f32>().abs() < 1e-3);
+
+    // La discesa del gradiente non arriva all'ottimo: il suo R² è più basso
+    let storico = adatta_retta(&passaggi, MetodoRegressione::DiscesaGradiente).unwrap();
+    assert_eq!(storico.metodo, MetodoRegressione::DiscesaGradiente);
+    assert!(storico.r_quadro < adattamento.r_quadro);
+    assert_eq!(
+        calculate_quantita_with_metodo(&passaggi, MetodoRegressione::DiscesaGradiente),
+        Ok(190)
+    );
+
+    let passaggi = [Point::new(1, 50), Point::new(2, 75), Point::new(3, 100)];
+    let adattamento = adatta_retta(&passaggi, MetodoRegressione::MinimiQuadrati).unwrap();
+    assert_eq!(adattamento.segno_pendenza(), SegnoPendenza::Positiva);
+}
+
+#[test]
+fn test_minimi_quadrati_x_uguali() {
+    // Dopo la prima cattura non viene preso nulla: il totale cumulato non cambia
+    let passaggi = [Point::new(8, 8), Point::new(8, 0), Point::new(8, 0)];
+    assert!(matches!(
+        minimi_quadrati(&passaggi),
+        Err(LinearRegressionError::SameXValues)
+    ));
+    assert_eq!(calculate_quantita_with_regression(&passaggi), Ok(8));
+}
+
+#[test]
+fn test_regressione_senza_punti() {
+    assert_eq!(minimi_quadrati(&[]), Err(LinearRegressionError::NoPoints));
+    assert!(matches!(
+        gradient_descent_iterate(&[]),
+        Err(LinearRegressionError::NoPoints)
+    ));
+    assert_eq!(
+        calculate_quantita_with_metodo(&[], MetodoRegressione::MinimiQuadrati),
+        Err(LinearRegressionError::NoPoints)
+    );
+}
+
+#[test]
+fn test_stima_quantita_con_retta() {
+    let passaggi = [Point::new(1, 100), Point::new(2, 75), Point::new(3, 50)];
+    let (quantita, retta) =
+        stima_quantita_con_retta(&passaggi, MetodoRegressione::MinimiQuadrati).unwrap();
+    assert_eq!(quantita, 5);
+    let retta = retta.expect("la retta è stimabile");
+    assert_eq!(retta.r_quadro, 1.0);
+    assert_eq!(retta.segno_pendenza(), SegnoPendenza::Negativa);
+
+    // Pendenza positiva: si usa la somma, ma la retta resta disponibile per la diagnosi
+    let passaggi = [Point::new(1, 50), Point::new(2, 75), Point::new(3, 100)];
+    let (quantita, retta) =
+        stima_quantita_con_retta(&passaggi, MetodoRegressione::MinimiQuadrati).unwrap();
+    assert_eq!(quantita, 225);
+    assert_eq!(retta.unwrap().segno_pendenza(), SegnoPendenza::Positiva);
+
+    // Catture cumulate tutte uguali: nessuna retta
+    let passaggi = [Point::new(8, 8), Point::new(8, 0), Point::new(8, 0)];
+    assert_eq!(
+        stima_quantita_con_retta(&passaggi, MetodoRegressione::MinimiQuadrati),
+        Ok((8, None))
+    );
+}
@@ -15,11 +15,15 @@
     along with this program.  If not, see <https://www.gnu.org/licenses/>.
 */
 
+#[cfg(test)]
+mod bootstrap;
 #[cfg(test)]
 mod full;
 #[cfg(test)]
 mod linear_regression;
 #[cfg(test)]
+mod stimatori;
+#[cfg(test)]
 mod x1;
 #[cfg(test)]
 mod x2;
@@ -0,0 +1,162 @@
+// SPDX-License-Identifier: GPL-3.0-only
+/*
+    Copyright (C) 2024-2026 jgabaut, gioninjo
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU General Public License as published by
+    the Free Software Foundation, version 3 of the License.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU General Public License for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use crate::engines::niseci::stimatori::{
+    catture_per_passaggio, stima_carle_strub, stima_zippin, verifica_rimozione, AvvisoRimozione,
+    StimaPopolazione, StimatorePopolazione, ESEMPLARI_MINIMI_STIMA,
+};
+use std::collections::HashMap;
+
+fn assert_circa(valore: f32, atteso: f32) {
+    assert!(
+        (valore - atteso).abs() < 1e-3,
+        "atteso {atteso}, ottenuto {valore}"
+    );
+}
+
+#[test]
+fn test_catture_per_passaggio() {
+    let mut passaggi: HashMap<u8, u32> = HashMap::new();
+    passaggi.insert(3, 5);
+    passaggi.insert(1, 40);
+
+    assert_eq!(catture_per_passaggio(&passaggi, 3), vec![40, 0, 5]);
+    // i passaggi finali senza catture della specie restano nel vettore
+    assert_eq!(catture_per_passaggio(&passaggi, 4), vec![40, 0, 5, 0]);
+}
+
+#[test]
+fn test_stima_zippin() {
+    // Con due passaggi coincide con Seber–LeCren: N = c1² / (c1 - c2)
+    let stima = stima_zippin(&[30, 12]).expect("catture decrescenti");
+
+    assert_eq!(stima.stimatore, StimatorePopolazione::Zippin);
+    assert_circa(stima.popolazione, 50.0);
+    assert_eq!(stima.get_quantita(), 50);
+    assert_circa(stima.catturabilita.unwrap(), 0.6);
+    assert_circa(stima.errore_standard.unwrap(), 7.2008);
+    // 50 - 1.96 · 7.2 sarebbe minore dei 42 esemplari catturati
+    let (inferiore, superiore) = stima.intervallo_confidenza.unwrap();
+    assert_eq!(inferiore, 42.0);
+    assert_circa(superiore, 64.1134);
+
+    let stima = stima_zippin(&[70, 60, 20, 10]).expect("catture decrescenti");
+    assert_circa(stima.popolazione, 175.6085);
+    assert_circa(stima.errore_standard.unwrap(), 6.9629);
+    assert_circa(stima.catturabilita.unwrap(), 0.454);
+}
+
+#[test]
+fn test_stima_zippin_catture_non_decrescenti() {
+    // Come lo stimatore classico, ripiega sulla somma delle catture
+    assert_eq!(
+        stima_zippin(&[15, 30]).unwrap(),
+        StimaPopolazione::somma(StimatorePopolazione::Zippin, 45)
+    );
+    let stima = stima_zippin(&[1, 1]).unwrap();
+    assert_eq!(stima.get_quantita(), 2);
+    assert!(stima.somma_catture);
+    assert_eq!(stima.errore_standard, None);
+    assert_eq!(stima.intervallo_confidenza, None);
+}
+
+#[test]
+fn test_stima_carle_strub() {
+    let stima = stima_carle_strub(&[70, 60, 20, 10]).expect("catture decrescenti");
+
+    assert_eq!(stima.stimatore, StimatorePopolazione::CarleStrub);
+    assert_eq!(stima.popolazione, 174.0);
+    assert_circa(stima.catturabilita.unwrap(), 160.0 / 346.0);
+    assert_circa(stima.errore_standard.unwrap(), 6.5710);
+
+    // Catture in aumento: la stima è molto oltre le catture totali
+    let stima = stima_carle_strub(&[50, 75, 100]).expect("stima oltre le catture");
+    assert_eq!(stima.popolazione, 4040.0);
+    let stima = stima_carle_strub(&[43, 21, 5]).expect("catture decrescenti");
+    assert_eq!(stima.popolazione, 72.0);
+
+    // Il prior sulla catturabilità permette una stima anche con catture uguali
+    let stima = stima_carle_strub(&[1, 1]).expect("stima sempre definita");
+    assert_eq!(stima.popolazione, 2.0);
+    let (inferiore, _) = stima.intervallo_confidenza.unwrap();
+    assert_eq!(inferiore, 2.0);
+}
+
+#[test]
+fn test_stima_senza_incertezza() {
+    // Un solo passaggio non permette di stimare la catturabilità
+    assert_eq!(
+        stima_zippin(&[12]).unwrap(),
+        StimaPopolazione::puntuale(StimatorePopolazione::Zippin, 12)
+    );
+    assert_eq!(
+        stima_carle_strub(&[12]).unwrap(),
+        StimaPopolazione::puntuale(StimatorePopolazione::CarleStrub, 12)
+    );
+
+    // Tutti gli esemplari catturati al primo passaggio
+    let stima = stima_zippin(&[10, 0, 0]).unwrap();
+    assert_eq!(stima.get_quantita(), 10);
+    assert_circa(stima.errore_standard.unwrap(), 0.0);
+}
+
+#[test]
+fn test_verifica_rimozione() {
+    assert!(verifica_rimozione(&[43, 21, 5]).is_empty());
+    // Dopo l'ultimo esemplare i passaggi vuoti sono attesi
+    assert!(verifica_rimozione(&[30, 0, 0]).is_empty());
+
+    // Catture uguali non permettono la stima quanto catture in aumento
+    assert_eq!(
+        verifica_rimozione(&[30, 30]),
+        vec![AvvisoRimozione::CattureNonDecrescenti {
+            passaggio: 2,
+            precedenti: 30,
+            catture: 30,
+        }]
+    );
+
+    assert_eq!(
+        verifica_rimozione(&[50, 75, 100]),
+        vec![
+            AvvisoRimozione::CattureNonDecrescenti {
+                passaggio: 2,
+                precedenti: 50,
+                catture: 75,
+            },
+            AvvisoRimozione::CattureNonDecrescenti {
+                passaggio: 3,
+                precedenti: 75,
+                catture: 100,
+            },
+        ]
+    );
+
+    // Il primo passaggio vuoto non conta anche come aumento delle catture
+    assert_eq!(
+        verifica_rimozione(&[0, 12, 3]),
+        vec![AvvisoRimozione::PrimoPassaggioSenzaCatture]
+    );
+
+    assert_eq!(
+        verifica_rimozione(&[4, 1]),
+        vec![AvvisoRimozione::EsemplariInsufficienti {
+            catture: 5,
+            minimo: ESEMPLARI_MINIMI_STIMA,
+        }]
+    );
+}
@@ -179,7 +179,7 @@ fn calculate_x2_test_1() {
         area: AreaNISECI::Alpina,
         codice_stazione: "Foo".to_string(),
         date_string: "Never".to_string(),
-        comunita: comunita,
+        comunita,
         idro_eco_regione: IdroEcoRegioneNISECI::AlpiCentroOrientali,
         larghezza_media_stazione: 1.0,
         lunghezza_media_stazione: 10.0,
@@ -188,6 +188,7 @@ fn calculate_x2_test_1() {
             regione: "sardninaia".to_string(),
             provincia: "oristano".to_string(),
         },
+        data: None,
     };
 
     let x2 = calculate_x2(&campionamento, &anagrafica, true);
@@ -212,7 +213,7 @@ fn calculate_x2_test_1() {
         area: AreaNISECI::Alpina,
         codice_stazione: "Foo".to_string(),
         date_string: "Never".to_string(),
-        comunita: comunita,
+        comunita,
         idro_eco_regione: IdroEcoRegioneNISECI::AlpiCentroOrientali,
         larghezza_media_stazione: 1.0,
         lunghezza_media_stazione: 10.0,
@@ -221,6 +222,7 @@ fn calculate_x2_test_1() {
             regione: "sardninaia".to_string(),
             provincia: "oristano".to_string(),
         },
+        data: None,
     };
 
     let x2 = calculate_x2(&campionamento, &anagrafica, true);
@@ -68,6 +68,7 @@ fn calculate_x3_alieni_tipo_1_strutt() {
         passaggio_cattura: 2,
         lunghezza: 2,
         peso: 2.0,
+        data: None,
     };
     c.campionamento.push(ciaccio);
 
@@ -87,6 +88,7 @@ fn calculate_x3_alieni_tipo_2_strutt() {
         passaggio_cattura: 2,
         lunghezza: 2,
         peso: 2.0,
+        data: None,
     };
     c.campionamento.push(ciaccio);
 
@@ -106,6 +108,7 @@ fn calculate_x3_alieni_tipo_3_strutt() {
         passaggio_cattura: 2,
         lunghezza: 2,
         peso: 2.0,
+        data: None,
     };
     c.campionamento.push(ciaccio);
 
@@ -125,6 +128,7 @@ fn calculate_x3_alieni_tipo_3_destrutt() {
         passaggio_cattura: 2,
         lunghezza: 2,
         peso: 2.0,
+        data: None,
     };
     c.campionamento.push(ciaccio);
 
@@ -144,6 +148,7 @@ fn calculate_x3_alieni_tipo_3_mediam_strutt() {
         passaggio_cattura: 2,
         lunghezza: 2,
         peso: 2.0,
+        data: None,
     };
     c.campionamento.push(ciaccio);
 
@@ -25,6 +25,7 @@ fn test_calcolo_pesci_per_passaggio() {
     let specie_1 = SpecieNISECI {
         id: "1234".to_string(),
         nome: "Cervus elaphus".to_string(),
+        nome_comune: String::new(),
         tipo_autoctono: 1,
         tipo_alloctono: 0,
         specie_attesa: true,
@@ -45,6 +46,7 @@ fn test_calcolo_pesci_per_passaggio() {
         passaggio_cattura: 1,
         lunghezza: 100, // in millimetri
         peso: 100.0,    // in grammi
+        data: None,
     };
 
     let record_2 = RecordNISECI {
@@ -52,6 +54,7 @@ fn test_calcolo_pesci_per_passaggio() {
         passaggio_cattura: 2,
         lunghezza: 100, // in millimetri
         peso: 100.0,    // in grammi
+        data: None,
     };
 
     let record_3 = RecordNISECI {
@@ -59,6 +62,7 @@ fn test_calcolo_pesci_per_passaggio() {
         passaggio_cattura: 3,
         lunghezza: 100, // in millimetri
         peso: 100.0,    // in grammi
+        data: None,
     };
 
     let mut c1 = vec![record_1; 20];
@@ -24,7 +24,7 @@ use crate::domain::{
 };
 
 pub fn create_dummy_anagrafica() -> AnagraficaNISECI {
-    return AnagraficaNISECI {
+    AnagraficaNISECI {
         comunita: ComunitaNISECI {
             tipo: TipoComunitaNISECI::Redatta,
             fonte: None,
@@ -42,7 +42,8 @@ pub fn create_dummy_anagrafica() -> AnagraficaNISECI {
         },
         lunghezza_media_stazione: 0.0,
         larghezza_media_stazione: 0.0,
-    };
+        data: None,
+    }
 }
 
 pub fn create_dummy_riferimento() -> RiferimentoNISECI {
@@ -50,6 +51,7 @@ pub fn create_dummy_riferimento() -> RiferimentoNISECI {
         id: 1.to_string(),
         specie_attesa: true,
         nome: "Ciaccio ciaccensis".to_string(),
+        nome_comune: String::new(),
         tipo_autoctono: 2,
         tipo_alloctono: 0,
         cl_soglia1: 1,
@@ -67,6 +69,7 @@ pub fn create_dummy_riferimento() -> RiferimentoNISECI {
         id: 2.to_string(),
         specie_attesa: true,
         nome: "Ciaccio sbribbrensis".to_string(),
+        nome_comune: String::new(),
         tipo_autoctono: 2,
         tipo_alloctono: 0,
         cl_soglia1: 1,
@@ -84,6 +87,7 @@ pub fn create_dummy_riferimento() -> RiferimentoNISECI {
         id: 3.to_string(),
         specie_attesa: true,
         nome: "Ciaccio cozzensis".to_string(),
+        nome_comune: String::new(),
         tipo_autoctono: 2,
         tipo_alloctono: 0,
         cl_soglia1: 1,
@@ -101,6 +105,7 @@ pub fn create_dummy_riferimento() -> RiferimentoNISECI {
         id: 4.to_string(),
         specie_attesa: true,
         nome: "Normus sempliciottum".to_string(),
+        nome_comune: String::new(),
         tipo_autoctono: 1,
         tipo_alloctono: 0,
         cl_soglia1: 1,
@@ -118,6 +123,7 @@ pub fn create_dummy_riferimento() -> RiferimentoNISECI {
         id: 5.to_string(),
         specie_attesa: true,
         nome: "Normus qualunquis".to_string(),
+        nome_comune: String::new(),
         tipo_autoctono: 1,
         tipo_alloctono: 0,
         cl_soglia1: 1,
@@ -135,6 +141,7 @@ pub fn create_dummy_riferimento() -> RiferimentoNISECI {
         id: 6.to_string(),
         specie_attesa: true,
         nome: "Disturbus infognatus".to_string(),
+        nome_comune: String::new(),
         tipo_autoctono: 0,
         tipo_alloctono: 1,
         cl_soglia1: 1,
@@ -152,6 +159,7 @@ pub fn create_dummy_riferimento() -> RiferimentoNISECI {
         id: 7.to_string(),
         specie_attesa: true,
         nome: "Disturbus sotterfugius".to_string(),
+        nome_comune: String::new(),
         tipo_autoctono: 0,
         tipo_alloctono: 1,
         cl_soglia1: 1,
@@ -169,6 +177,7 @@ pub fn create_dummy_riferimento() -> RiferimentoNISECI {
         id: 7.to_string(),
         specie_attesa: false,
         nome: "Sorprendo sorprendentes".to_string(),
+        nome_comune: String::new(),
         tipo_autoctono: 0,
         tipo_alloctono: 1,
         cl_soglia1: 1,
@@ -186,6 +195,7 @@ pub fn create_dummy_riferimento() -> RiferimentoNISECI {
         id: 8.to_string(),
         specie_attesa: false,
         nome: "Sorprendo improvvisus".to_string(),
+        nome_comune: String::new(),
         tipo_autoctono: 2,
         tipo_alloctono: 0,
         cl_soglia1: 1,
@@ -200,20 +210,19 @@ pub fn create_dummy_riferimento() -> RiferimentoNISECI {
         dens_soglia2: 2.0,
     };
 
-    let mut elenco_specie = Vec::with_capacity(9);
-    elenco_specie.push(importante_1);
-    elenco_specie.push(importante_2);
-    elenco_specie.push(importante_3);
-    elenco_specie.push(normale_1);
-    elenco_specie.push(normale_2);
-    elenco_specie.push(alloctono_1);
-    elenco_specie.push(alloctono_2);
-    elenco_specie.push(inatteso_1);
-    elenco_specie.push(inatteso_2);
-
-    RiferimentoNISECI {
-        elenco_specie: elenco_specie,
-    }
+    let elenco_specie = vec![
+        importante_1,
+        importante_2,
+        importante_3,
+        normale_1,
+        normale_2,
+        alloctono_1,
+        alloctono_2,
+        inatteso_1,
+        inatteso_2,
+    ];
+
+    RiferimentoNISECI { elenco_specie }
 }
 
 /// campionamento che ha dentro tutte le specie autoctone attese
@@ -226,6 +235,7 @@ pub fn create_dummy_campionamento_full() -> CampionamentoNISECI {
         id: 1.to_string(),
         specie_attesa: true,
         nome: "Ciaccio ciaccensis".to_string(),
+        nome_comune: String::new(),
         tipo_autoctono: 2,
         tipo_alloctono: 0,
         cl_soglia1: 1,
@@ -243,6 +253,7 @@ pub fn create_dummy_campionamento_full() -> CampionamentoNISECI {
         id: 2.to_string(),
         specie_attesa: true,
         nome: "Ciaccio sbribbrensis".to_string(),
+        nome_comune: String::new(),
         tipo_autoctono: 2,
         tipo_alloctono: 0,
         cl_soglia1: 1,
@@ -260,6 +271,7 @@ pub fn create_dummy_campionamento_full() -> CampionamentoNISECI {
         id: 3.to_string(),
         specie_attesa: true,
         nome: "Ciaccio cozzensis".to_string(),
+        nome_comune: String::new(),
         tipo_autoctono: 2,
         tipo_alloctono: 0,
         cl_soglia1: 1,
@@ -277,6 +289,7 @@ pub fn create_dummy_campionamento_full() -> CampionamentoNISECI {
         id: 4.to_string(),
         specie_attesa: true,
         nome: "Normus sempliciottum".to_string(),
+        nome_comune: String::new(),
         tipo_autoctono: 1,
         tipo_alloctono: 0,
         cl_soglia1: 1,
@@ -294,6 +307,7 @@ pub fn create_dummy_campionamento_full() -> CampionamentoNISECI {
         id: 5.to_string(),
         specie_attesa: true,
         nome: "Normus qualunquis".to_string(),
+        nome_comune: String::new(),
         tipo_autoctono: 1,
         tipo_alloctono: 0,
         cl_soglia1: 1,
@@ -311,6 +325,7 @@ pub fn create_dummy_campionamento_full() -> CampionamentoNISECI {
         id: 6.to_string(),
         specie_attesa: true,
         nome: "Disturbus infognatus".to_string(),
+        nome_comune: String::new(),
         tipo_autoctono: 0,
         tipo_alloctono: 1,
         cl_soglia1: 1,
@@ -328,6 +343,7 @@ pub fn create_dummy_campionamento_full() -> CampionamentoNISECI {
         id: 7.to_string(),
         specie_attesa: true,
         nome: "Disturbus sotterfugius".to_string(),
+        nome_comune: String::new(),
         tipo_autoctono: 0,
         tipo_alloctono: 1,
         cl_soglia1: 1,
@@ -345,6 +361,7 @@ pub fn create_dummy_campionamento_full() -> CampionamentoNISECI {
         id: 7.to_string(),
         specie_attesa: false,
         nome: "Sorprendo sorprendentes".to_string(),
+        nome_comune: String::new(),
         tipo_autoctono: 0,
         tipo_alloctono: 1,
         cl_soglia1: 1,
@@ -362,6 +379,7 @@ pub fn create_dummy_campionamento_full() -> CampionamentoNISECI {
         id: 8.to_string(),
         specie_attesa: false,
         nome: "Sorprendo improvvisus".to_string(),
+        nome_comune: String::new(),
         tipo_autoctono: 2,
         tipo_alloctono: 0,
         cl_soglia1: 1,
@@ -381,70 +399,70 @@ pub fn create_dummy_campionamento_full() -> CampionamentoNISECI {
         lunghezza: 5,
         peso: 5.0,
         passaggio_cattura: 1,
+        data: None,
     };
     let record_2 = RecordNISECI {
         specie: importante_2,
         lunghezza: 5,
         peso: 5.0,
         passaggio_cattura: 1,
+        data: None,
     };
     let record_3 = RecordNISECI {
         specie: importante_3,
         lunghezza: 5,
         peso: 5.0,
         passaggio_cattura: 1,
+        data: None,
     };
     let record_4 = RecordNISECI {
         specie: normale_2,
         lunghezza: 5,
         peso: 5.0,
         passaggio_cattura: 1,
+        data: None,
     };
     let record_5 = RecordNISECI {
         specie: normale_1,
         lunghezza: 5,
         peso: 5.0,
         passaggio_cattura: 1,
+        data: None,
     };
     let record_6 = RecordNISECI {
         specie: inatteso_1,
         lunghezza: 5,
         peso: 5.0,
         passaggio_cattura: 1,
+        data: None,
     };
     let record_7 = RecordNISECI {
         specie: inatteso_2,
         lunghezza: 5,
         peso: 5.0,
         passaggio_cattura: 1,
+        data: None,
     };
     let record_8 = RecordNISECI {
         specie: alloctono_1,
         lunghezza: 5,
         peso: 5.0,
         passaggio_cattura: 1,
+        data: None,
     };
     let record_9 = RecordNISECI {
         specie: alloctono_2,
         lunghezza: 5,
         peso: 5.0,
         passaggio_cattura: 1,
+        data: None,
     };
 
-    let mut campionamento = Vec::with_capacity(9);
-    campionamento.push(record_1);
-    campionamento.push(record_2);
-    campionamento.push(record_3);
-    campionamento.push(record_4);
-    campionamento.push(record_5);
-    campionamento.push(record_6);
-    campionamento.push(record_7);
-    campionamento.push(record_8);
-    campionamento.push(record_9);
+    let campionamento = vec![
+        record_1, record_2, record_3, record_4, record_5, record_6, record_7, record_8, record_9,
+    ];
 
-    CampionamentoNISECI {
-        campionamento: campionamento,
-    }
+    CampionamentoNISECI { campionamento }
 }
 
 /// campionamento che non contiene tutte le specie
@@ -480,6 +498,7 @@ pub fn create_massive_campionamento_ciacci() -> CampionamentoNISECI {
         passaggio_cattura: 2,
         lunghezza: 2,
         peso: 2.0,
+        data: None,
     };
     c.campionamento.push(trocchio);
 
@@ -505,6 +524,7 @@ pub fn create_massive_campionamento_ciacci_2() -> CampionamentoNISECI {
         passaggio_cattura: 2,
         lunghezza: 2,
         peso: 2.0,
+        data: None,
     };
     c.campionamento.push(trocchio);
 
@@ -533,6 +553,7 @@ pub fn create_massive_campionamento_ciacci_solo_autoctoni_2() -> CampionamentoNI
         lunghezza: 13,
         passaggio_cattura: 1,
         peso: 10.0,
+        data: None,
     };
     for _ in 0..10 {
         campionamento.push(ciaccio_cl5_c1.clone());
@@ -544,6 +565,7 @@ pub fn create_massive_campionamento_ciacci_solo_autoctoni_2() -> CampionamentoNI
         lunghezza: 10,
         passaggio_cattura: 1,
         peso: 10.0,
+        data: None,
     };
     for _ in 0..10 {
         campionamento.push(ciaccio_cl4_c1.clone());
@@ -555,6 +577,7 @@ pub fn create_massive_campionamento_ciacci_solo_autoctoni_2() -> CampionamentoNI
         lunghezza: 7,
         passaggio_cattura: 1,
         peso: 10.0,
+        data: None,
     };
     for _ in 0..10 {
         campionamento.push(ciaccio_cl3_c1.clone());
@@ -566,6 +589,7 @@ pub fn create_massive_campionamento_ciacci_solo_autoctoni_2() -> CampionamentoNI
         lunghezza: 10,
         passaggio_cattura: 2,
         peso: 10.0,
+        data: None,
     };
     for _ in 0..10 {
         campionamento.push(ciaccio_cl4_c2.clone());
@@ -577,14 +601,13 @@ pub fn create_massive_campionamento_ciacci_solo_autoctoni_2() -> CampionamentoNI
         lunghezza: 2,
         passaggio_cattura: 2,
         peso: 10.0,
+        data: None,
     };
     for _ in 0..5 {
         campionamento.push(ciaccio_cl1_c2.clone());
     }
 
-    CampionamentoNISECI {
-        campionamento: campionamento,
-    }
+    CampionamentoNISECI { campionamento }
 }
 
 /// In questo campionamento troverai:
@@ -609,6 +632,7 @@ pub fn create_massive_campionamento_ciacci_solo_autoctoni_1() -> CampionamentoNI
         lunghezza: 13,
         passaggio_cattura: 1,
         peso: 10.0,
+        data: None,
     };
     for _ in 0..10 {
         campionamento.push(ciaccio_cl5_c1.clone());
@@ -620,6 +644,7 @@ pub fn create_massive_campionamento_ciacci_solo_autoctoni_1() -> CampionamentoNI
         lunghezza: 10,
         passaggio_cattura: 1,
         peso: 10.0,
+        data: None,
     };
     for _ in 0..10 {
         campionamento.push(ciaccio_cl4_c1.clone());
@@ -631,6 +656,7 @@ pub fn create_massive_campionamento_ciacci_solo_autoctoni_1() -> CampionamentoNI
         lunghezza: 7,
         passaggio_cattura: 1,
         peso: 10.0,
+        data: None,
     };
     for _ in 0..10 {
         campionamento.push(ciaccio_cl3_c1.clone());
@@ -642,6 +668,7 @@ pub fn create_massive_campionamento_ciacci_solo_autoctoni_1() -> CampionamentoNI
         lunghezza: 4,
         passaggio_cattura: 2,
         peso: 10.0,
+        data: None,
     };
     for _ in 0..10 {
         campionamento.push(ciaccio_cl2_c2.clone());
@@ -653,14 +680,13 @@ pub fn create_massive_campionamento_ciacci_solo_autoctoni_1() -> CampionamentoNI
         lunghezza: 4,
         passaggio_cattura: 2,
         peso: 10.0,
+        data: None,
     };
     for _ in 0..5 {
         campionamento.push(ciaccio_cl1_c2.clone());
     }
 
-    CampionamentoNISECI {
-        campionamento: campionamento,
-    }
+    CampionamentoNISECI { campionamento }
 }
 
 /// In questo campionamento troverai:
@@ -864,6 +890,7 @@ fn create_campionamento_strutturato_data_una_specie(specie: SpecieNISECI) -> Cam
         lunghezza: 13,
         passaggio_cattura: 1,
         peso: 10.0,
+        data: None,
     };
     for _ in 0..10 {
         campionamento.push(cl5_c1.clone());
@@ -875,6 +902,7 @@ fn create_campionamento_strutturato_data_una_specie(specie: SpecieNISECI) -> Cam
         lunghezza: 10,
         passaggio_cattura: 1,
         peso: 10.0,
+        data: None,
     };
     for _ in 0..10 {
         campionamento.push(cl4_c1.clone());
@@ -886,6 +914,7 @@ fn create_campionamento_strutturato_data_una_specie(specie: SpecieNISECI) -> Cam
         lunghezza: 7,
         passaggio_cattura: 1,
         peso: 10.0,
+        data: None,
     };
     for _ in 0..10 {
         campionamento.push(cl3_c1.clone());
@@ -897,6 +926,7 @@ fn create_campionamento_strutturato_data_una_specie(specie: SpecieNISECI) -> Cam
         lunghezza: 4,
         passaggio_cattura: 2,
         peso: 10.0,
+        data: None,
     };
     for _ in 0..10 {
         campionamento.push(cl2_c2.clone());
@@ -908,14 +938,13 @@ fn create_campionamento_strutturato_data_una_specie(specie: SpecieNISECI) -> Cam
         lunghezza: 4,
         passaggio_cattura: 2,
         peso: 10.0,
+        data: None,
     };
     for _ in 0..5 {
         campionamento.push(cl1_c2.clone());
     }
 
-    CampionamentoNISECI {
-        campionamento: campionamento,
-    }
+    CampionamentoNISECI { campionamento }
 }
 
 /// In questo campionamento troverai:
@@ -933,6 +962,7 @@ fn create_campionamento_destrutturato_data_una_specie(specie: SpecieNISECI) -> C
         lunghezza: 13,
         passaggio_cattura: 1,
         peso: 10.0,
+        data: None,
     };
     for _ in 0..10 {
         campionamento.push(cl5_c1.clone());
@@ -944,6 +974,7 @@ fn create_campionamento_destrutturato_data_una_specie(specie: SpecieNISECI) -> C
         lunghezza: 10,
         passaggio_cattura: 1,
         peso: 10.0,
+        data: None,
     };
     for _ in 0..20 {
         campionamento.push(cl4_c1.clone());
@@ -955,14 +986,13 @@ fn create_campionamento_destrutturato_data_una_specie(specie: SpecieNISECI) -> C
         lunghezza: 4,
         passaggio_cattura: 2,
         peso: 10.0,
+        data: None,
     };
     for _ in 0..10 {
         campionamento.push(cl2_c2.clone());
     }
 
-    CampionamentoNISECI {
-        campionamento: campionamento,
-    }
+    CampionamentoNISECI { campionamento }
 }
 
 /// In questo campionamento troverai:
@@ -983,6 +1013,7 @@ fn create_campionamento_mediam_strutturato_data_una_specie(
         lunghezza: 13,
         passaggio_cattura: 1,
         peso: 10.0,
+        data: None,
     };
     for _ in 0..10 {
         campionamento.push(cl5_c1.clone());
@@ -994,6 +1025,7 @@ fn create_campionamento_mediam_strutturato_data_una_specie(
         lunghezza: 10,
         passaggio_cattura: 1,
         peso: 10.0,
+        data: None,
     };
     for _ in 0..20 {
         campionamento.push(cl4_c1.clone());
@@ -1005,6 +1037,7 @@ fn create_campionamento_mediam_strutturato_data_una_specie(
         lunghezza: 4,
         passaggio_cattura: 2,
         peso: 10.0,
+        data: None,
     };
     for _ in 0..10 {
         campionamento.push(cl2_c2.clone());
@@ -1016,14 +1049,13 @@ fn create_campionamento_mediam_strutturato_data_una_specie(
         lunghezza: 4,
         passaggio_cattura: 2,
         peso: 10.0,
+        data: None,
     };
     for _ in 0..5 {
         campionamento.push(cl1_c2.clone());
     }
 
-    CampionamentoNISECI {
-        campionamento: campionamento,
-    }
+    CampionamentoNISECI { campionamento }
 }
 
 /// id == 1
@@ -1032,6 +1064,7 @@ pub fn get_ciaccio() -> SpecieNISECI {
         id: 1.to_string(),
         specie_attesa: true,
         nome: "Ciaccio ciaccensis".to_string(),
+        nome_comune: String::new(),
         tipo_autoctono: 2,
         tipo_alloctono: 0,
         cl_soglia1: 3,
@@ -1053,6 +1086,7 @@ pub fn get_trocchio() -> SpecieNISECI {
         id: 2.to_string(),
         specie_attesa: true,
         nome: "Trocchio trocchiensis".to_string(),
+        nome_comune: String::new(),
         tipo_autoctono: 0,
         tipo_alloctono: 1,
         cl_soglia1: 3,
@@ -1074,6 +1108,7 @@ pub fn get_bronzo() -> SpecieNISECI {
         id: 3.to_string(),
         specie_attesa: true,
         nome: "Bronzo bronzensis".to_string(),
+        nome_comune: String::new(),
         tipo_autoctono: 0,
         tipo_alloctono: 2,
         cl_soglia1: 3,
@@ -1095,6 +1130,7 @@ pub fn get_tappo() -> SpecieNISECI {
         id: 4.to_string(),
         specie_attesa: true,
         nome: "Tappo sugheribus".to_string(),
+        nome_comune: String::new(),
         tipo_autoctono: 0,
         tipo_alloctono: 3,
         cl_soglia1: 3,